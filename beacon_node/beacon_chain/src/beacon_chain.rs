@@ -4,13 +4,17 @@ use crate::fork_choice::{Error as ForkChoiceError, ForkChoice};
 use crate::iter::{ReverseBlockRootIterator, ReverseStateRootIterator};
 use crate::metrics;
 use crate::persisted_beacon_chain::{PersistedBeaconChain, BEACON_CHAIN_DB_KEY};
+use crate::slasher::{Error as SlasherError, Slasher, SlasherConfig};
+use crate::validator_monitor::ValidatorMonitor;
+use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use lmd_ghost::LmdGhost;
 use log::trace;
 use operation_pool::DepositInsertStatus;
-use operation_pool::{OperationPool, PersistedOperationPool};
+use operation_pool::{OperationPool, OperationPoolLimits, PersistedOperationPool};
 use parking_lot::{RwLock, RwLockReadGuard};
 use slog::{error, info, warn, Logger};
 use slot_clock::SlotClock;
+use ssz::{Decode, Encode};
 use state_processing::per_block_processing::{
     errors::{
         AttestationValidationError, AttesterSlashingValidationError, DepositValidationError,
@@ -19,12 +23,14 @@ use state_processing::per_block_processing::{
     verify_attestation_for_state, VerifySignatures,
 };
 use state_processing::{
-    per_block_processing, per_block_processing_without_verifying_block_signature,
-    per_slot_processing, BlockProcessingError,
+    common::get_indexed_attestation, fast_forward_state, per_block_processing_with_pubkeys,
+    per_block_processing_without_verifying_block_signature, BlockProcessingError,
+    EpochRewardReport, SkipSlotProcessor,
 };
+use std::borrow::Cow;
 use std::sync::Arc;
 use store::iter::{BlockRootsIterator, StateRootsIterator};
-use store::{Error as DBError, Store};
+use store::{DBColumn, Error as DBError, Store};
 use tree_hash::TreeHash;
 use types::*;
 
@@ -106,6 +112,19 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     /// A state-machine that is updated with information from the network and chooses a canonical
     /// head block.
     pub fork_choice: ForkChoice<T>,
+    /// Detects slashable attestations and block headers, if enabled.
+    slasher: Option<Slasher<T::Store, T::EthSpec>>,
+    /// Tracks the performance of a set of validators, if enabled.
+    validator_monitor: Option<ValidatorMonitor<T::EthSpec>>,
+    /// The validators for which an `EpochRewardReport` is stored at each epoch transition.
+    reward_report_validators: Vec<usize>,
+    /// Decompressed public keys for all known validators, used to verify block signatures without
+    /// decompressing the keys of each state.
+    validator_pubkey_cache: RwLock<ValidatorPubkeyCache<T::Store>>,
+    /// The most recently imported block and its post-state. Kept so that the caches of the state
+    /// (e.g., the tree hash cache) are not lost when it becomes the head or the parent of the
+    /// next block, as they would be if it were read from the database.
+    last_imported_block: RwLock<Option<CheckPoint<T::EthSpec>>>,
     /// Logging to CLI, etc.
     log: Logger,
 }
@@ -118,6 +137,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         mut genesis_state: BeaconState<T::EthSpec>,
         mut genesis_block: BeaconBlock<T::EthSpec>,
         spec: ChainSpec,
+        op_pool_limits: OperationPoolLimits,
         log: Logger,
     ) -> Result<Self, Error> {
         genesis_state.build_all_caches(&spec)?;
@@ -147,23 +167,34 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
               "genesis_block_root" => format!("{}", genesis_block_root),
         );
 
+        let validator_pubkey_cache = ValidatorPubkeyCache::new(&genesis_state, store.clone())?;
+
         Ok(Self {
             spec,
             slot_clock,
-            op_pool: OperationPool::new(),
+            op_pool: OperationPool::with_limits(op_pool_limits),
             state: RwLock::new(genesis_state),
             canonical_head,
             genesis_block_root,
             fork_choice: ForkChoice::new(store.clone(), &genesis_block, genesis_block_root),
+            slasher: None,
+            validator_monitor: None,
+            reward_report_validators: vec![],
+            validator_pubkey_cache: RwLock::new(validator_pubkey_cache),
+            last_imported_block: RwLock::new(None),
             store,
             log,
         })
     }
 
     /// Attempt to load an existing instance from the given `store`.
+    ///
+    /// The operation pool is restored with `op_pool_limits`, which may differ from the limits it
+    /// was persisted with.
     pub fn from_store(
         store: Arc<T::Store>,
         spec: ChainSpec,
+        op_pool_limits: OperationPoolLimits,
         log: Logger,
     ) -> Result<Option<BeaconChain<T>>, Error> {
         let key = Hash256::from_slice(&BEACON_CHAIN_DB_KEY.as_bytes());
@@ -182,7 +213,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let last_finalized_root = p.canonical_head.beacon_state.finalized_checkpoint.root;
         let last_finalized_block = &p.canonical_head.beacon_block;
 
-        let op_pool = p.op_pool.into_operation_pool(&p.state, &spec);
+        let op_pool = p
+            .op_pool
+            .into_operation_pool(op_pool_limits, &p.state, &spec);
+
+        let validator_pubkey_cache = ValidatorPubkeyCache::new(&p.state, store.clone())?;
 
         Ok(Some(BeaconChain {
             spec,
@@ -192,11 +227,66 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             canonical_head: RwLock::new(p.canonical_head),
             state: RwLock::new(p.state),
             genesis_block_root: p.genesis_block_root,
+            slasher: None,
+            validator_monitor: None,
+            reward_report_validators: vec![],
+            validator_pubkey_cache: RwLock::new(validator_pubkey_cache),
+            last_imported_block: RwLock::new(None),
             store,
             log,
         }))
     }
 
+    /// Start recording all attestations and block headers processed by `self`, adding any
+    /// slashings found to the op pool.
+    ///
+    /// The slasher records are kept in `self.store`, so they persist across restarts.
+    pub fn enable_slasher(&mut self, config: SlasherConfig) -> Result<(), SlasherError> {
+        self.slasher = Some(Slasher::new(self.store.clone(), config)?);
+        Ok(())
+    }
+
+    /// Start tracking the attestations, proposals and balances of the validators with the given
+    /// `pubkeys`, exporting the results as metrics and logs.
+    pub fn enable_validator_monitor(&mut self, pubkeys: Vec<PublicKey>) {
+        let monitor = ValidatorMonitor::new(pubkeys, self.log.clone());
+        monitor.process_valid_state(&self.state.read(), &self.spec);
+        self.validator_monitor = Some(monitor);
+    }
+
+    /// Start storing a report of the rewards and penalties applied to each of the given
+    /// validators at each epoch transition.
+    ///
+    /// Reports are read with `Self::get_reward_report`.
+    pub fn enable_reward_reports(&mut self, validator_indices: Vec<usize>) {
+        self.reward_report_validators = validator_indices;
+    }
+
+    /// Returns the report of the rewards and penalties for the attestations made during `epoch`
+    /// by the validators given to `Self::enable_reward_reports`.
+    ///
+    /// Returns `None` if the canonical chain has not yet rewarded `epoch`, or if no report was
+    /// stored for it.
+    pub fn get_reward_report(&self, epoch: Epoch) -> Result<Option<EpochRewardReport>, Error> {
+        let block_root = {
+            let head_state = &self.head().beacon_state;
+            match head_state.get_block_root(reward_report_slot::<T::EthSpec>(epoch)) {
+                Ok(block_root) => *block_root,
+                Err(_) => return Ok(None),
+            }
+        };
+
+        match self.store.get_bytes(
+            DBColumn::RewardReport.into(),
+            &reward_report_key(epoch, block_root),
+        )? {
+            Some(bytes) => Ok(Some(
+                EpochRewardReport::from_ssz_bytes(&bytes).map_err(DBError::from)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
     /// Attempt to save this instance to `self.store`.
     pub fn persist(&self) -> Result<(), Error> {
         let timer = metrics::start_timer(&metrics::PERSIST_CHAIN);
@@ -307,6 +397,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(self.state.read())
     }
 
+    /// Returns the decompressed public key of the validator with the given index, if it is known.
+    ///
+    /// The key is read from the pubkey cache, which contains every validator in the states that
+    /// have been imported by this chain.
+    pub fn validator_pubkey(&self, validator_index: usize) -> Option<PublicKey> {
+        self.validator_pubkey_cache
+            .read()
+            .get(validator_index)
+            .cloned()
+    }
+
     /// Returns a read-lock guarded `CheckPoint` struct for reading the head (as chosen by the
     /// fork-choice rule).
     ///
@@ -335,14 +436,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             let mut state = self.state.write();
 
             // If required, transition the new state to the present slot.
+            let mut processor = SkipSlotProcessor::new(None);
             for _ in state.slot.as_u64()..present_slot.as_u64() {
                 // Ensure the next epoch state caches are built in case of an epoch transition.
                 state.build_committee_cache(RelativeEpoch::Next, spec)?;
 
-                per_slot_processing(&mut *state, spec)?;
+                processor.per_slot_processing(&mut *state, spec)?;
             }
 
             state.build_all_caches(spec)?;
+
+            if let Some(monitor) = &self.validator_monitor {
+                monitor.process_valid_state(&state, spec);
+            }
         }
 
         Ok(())
@@ -616,16 +722,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             // `data.beacon_block_root` isn't necessarily in a nearby epoch to the attestation
             // (e.g., if there were lots of skip slots since the head of the chain and the
             // epoch creation epoch).
-            for _ in state.slot.as_u64()
-                ..attestation
+            fast_forward_state(
+                &mut state,
+                attestation
                     .data
                     .target
                     .epoch
-                    .start_slot(T::EthSpec::slots_per_epoch())
-                    .as_u64()
-            {
-                per_slot_processing(&mut state, &self.spec)?;
-            }
+                    .start_slot(T::EthSpec::slots_per_epoch()),
+                Some(attestation_head_block.state_root),
+                &self.spec,
+            )?;
 
             state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
 
@@ -742,6 +848,25 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             self.fork_choice
                 .process_attestation(&state, &attestation, block)?;
 
+            // Observe the attestation (e.g., check for slashable behaviour) before it is moved
+            // into the op pool.
+            self.observe_attestation(&attestation, state, None);
+
+            // Attestations signed by a single validator are also aggregated naively, so that the
+            // aggregate of every signer seen is available for block production.
+            if attestation.aggregation_bits.num_set_bits() == 1 {
+                if let Err(e) =
+                    self.op_pool
+                        .insert_unaggregated_attestation(&attestation, state, &self.spec)
+                {
+                    warn!(
+                        self.log,
+                        "Failed to aggregate attestation";
+                        "error" => format!("{:?}", e),
+                    );
+                }
+            }
+
             // Provide the valid attestation to op pool, which may choose to retain the
             // attestation for inclusion in a future block.
             self.op_pool
@@ -862,8 +987,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // It is an error because if know the parent block we should also know the parent state.
         let parent_state_root = parent_block.state_root;
         let parent_state = self
-            .store
-            .get(&parent_state_root)?
+            .get_state_with_caches(&parent_state_root)?
             .ok_or_else(|| Error::DBInconsistent(format!("Missing state {}", parent_state_root)))?;
 
         metrics::stop_timer(db_read_timer);
@@ -874,13 +998,25 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // slot and the block slot. These will need to be stored in the database.
         let mut intermediate_states = vec![];
 
+        // Keep the reports for any epoch transitions between the parent state and the block slot.
+        let mut reward_reports = vec![];
+
         // Transition the parent state to the block slot.
         let mut state: BeaconState<T::EthSpec> = parent_state;
+        let mut processor = SkipSlotProcessor::new(Some(parent_state_root));
         for i in state.slot.as_u64()..block.slot.as_u64() {
             if i > 0 {
                 intermediate_states.push(state.clone());
             }
-            per_slot_processing(&mut state, &self.spec)?;
+            if let Some(report) = processor.per_slot_processing_with_reward_report(
+                &mut state,
+                &self.reward_report_validators,
+                &self.spec,
+            )? {
+                if !self.reward_report_validators.is_empty() {
+                    reward_reports.push(report);
+                }
+            }
         }
 
         metrics::stop_timer(catchup_timer);
@@ -896,7 +1032,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         // Apply the received block to its parent state (which has been transitioned into this
         // slot).
-        match per_block_processing(&mut state, &block, &self.spec) {
+        //
+        // The signatures are verified in a single batch, using the keys in the pubkey cache. The
+        // parent state may contain validators that are not yet in the cache (e.g., deposits
+        // included by the parent block), so they are added first.
+        self.validator_pubkey_cache
+            .write()
+            .import_new_pubkeys(&state)?;
+        let pubkey_cache = self.validator_pubkey_cache.read();
+        let result = per_block_processing_with_pubkeys(
+            &mut state,
+            &block,
+            |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
+            &self.spec,
+        );
+        drop(pubkey_cache);
+
+        match result {
             Err(BlockProcessingError::BeaconStateError(e)) => {
                 return Err(Error::BeaconStateError(e))
             }
@@ -908,7 +1060,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         let state_root_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_STATE_ROOT);
 
-        let state_root = state.canonical_root();
+        let state_root = state.update_tree_hash_cache()?;
 
         if block.state_root != state_root {
             return Ok(BlockProcessingOutcome::StateRootMismatch);
@@ -938,8 +1090,29 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.store.put(&block_root, &block)?;
         self.store.put(&state_root, &state)?;
 
+        *self.last_imported_block.write() = Some(CheckPoint::new(
+            block.clone(),
+            block_root,
+            state.clone(),
+            state_root,
+        ));
+
+        // Store the reward reports, keyed by the block that preceded the epoch transition so
+        // that reports from different forks are kept separate.
+        for report in reward_reports {
+            let block_root =
+                *state.get_block_root(reward_report_slot::<T::EthSpec>(report.epoch))?;
+            self.store.put_bytes(
+                DBColumn::RewardReport.into(),
+                &reward_report_key(report.epoch, block_root),
+                &report.as_ssz_bytes(),
+            )?;
+        }
+
         metrics::stop_timer(db_write_timer);
 
+        self.observe_block(&block, &state);
+
         let fork_choice_register_timer =
             metrics::start_timer(&metrics::BLOCK_PROCESSING_FORK_CHOICE_REGISTER);
 
@@ -983,6 +1156,160 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(BlockProcessingOutcome::Processed { block_root })
     }
 
+    /// Provides `attestation` to the validator monitor and queues it for the slasher (if
+    /// enabled).
+    ///
+    /// `inclusion_slot` is the slot of the block containing `attestation`, or `None` if it was
+    /// received outside of a block. `state` must be suitable for computing the committee of
+    /// `attestation`.
+    fn observe_attestation(
+        &self,
+        attestation: &Attestation<T::EthSpec>,
+        state: &BeaconState<T::EthSpec>,
+        inclusion_slot: Option<Slot>,
+    ) {
+        if self.slasher.is_none() && self.validator_monitor.is_none() {
+            return;
+        }
+
+        let indexed_attestation = match get_indexed_attestation(state, attestation) {
+            Ok(indexed_attestation) => indexed_attestation,
+            Err(e) => {
+                error!(
+                    self.log,
+                    "Unable to index observed attestation";
+                    "error" => format!("{:?}", e)
+                );
+                return;
+            }
+        };
+
+        if let Some(monitor) = &self.validator_monitor {
+            match inclusion_slot {
+                Some(inclusion_slot) => {
+                    if let Ok(attestation_slot) = state.get_attestation_data_slot(&attestation.data)
+                    {
+                        monitor.register_attestation_in_block(
+                            &indexed_attestation,
+                            (inclusion_slot - attestation_slot).as_u64(),
+                        );
+                    }
+                }
+                None => monitor.register_gossip_attestation(&indexed_attestation),
+            }
+        }
+
+        if let Some(slasher) = &self.slasher {
+            slasher.queue_indexed_attestation(indexed_attestation);
+        }
+    }
+
+    /// Provides the proposer and attestations of `block` to the validator monitor and queues the
+    /// header and attestations of `block` for the slasher (if enabled).
+    ///
+    /// `state` must be the post-state of `block`.
+    fn observe_block(&self, block: &BeaconBlock<T::EthSpec>, state: &BeaconState<T::EthSpec>) {
+        if self.slasher.is_none() && self.validator_monitor.is_none() {
+            return;
+        }
+
+        let proposer_index =
+            match state.get_beacon_proposer_index(block.slot, RelativeEpoch::Current, &self.spec) {
+                Ok(proposer_index) => proposer_index as u64,
+                Err(e) => {
+                    error!(
+                        self.log,
+                        "Unable to determine proposer of observed block";
+                        "error" => format!("{:?}", e)
+                    );
+                    return;
+                }
+            };
+
+        if let Some(monitor) = &self.validator_monitor {
+            monitor.register_block(proposer_index, block.slot);
+        }
+
+        if let Some(slasher) = &self.slasher {
+            slasher.queue_block_header(proposer_index, block.block_header());
+        }
+
+        for attestation in block.body.attestations.iter() {
+            self.observe_attestation(attestation, state, Some(block.slot));
+        }
+    }
+
+    /// Processes the attestations and block headers queued for the slasher (if enabled) in a
+    /// single batch, adding any resulting slashings to the op pool. Also prunes the slasher
+    /// records that are older than its history length.
+    ///
+    /// Intended to be called once per epoch, away from block and attestation processing.
+    pub fn process_slasher_queue(&self) {
+        let slasher = match &self.slasher {
+            Some(slasher) => slasher,
+            None => return,
+        };
+
+        let current_epoch = match self.read_slot_clock() {
+            Some(slot) => slot.epoch(T::EthSpec::slots_per_epoch()),
+            None => return,
+        };
+
+        let output = match slasher.process_queued(current_epoch) {
+            Ok(output) => output,
+            Err(e) => {
+                error!(
+                    self.log,
+                    "Slasher failed to process queue";
+                    "error" => format!("{:?}", e)
+                );
+                return;
+            }
+        };
+
+        if output.num_dropped > 0 {
+            warn!(
+                self.log,
+                "Slasher queue was full";
+                "dropped" => output.num_dropped,
+            );
+        }
+
+        for slashing in output.proposer_slashings {
+            warn!(
+                self.log,
+                "Slasher found proposer slashing";
+                "proposer_index" => slashing.proposer_index,
+                "slot" => slashing.header_1.slot,
+            );
+
+            if let Err(e) = self.process_proposer_slashing(slashing) {
+                warn!(
+                    self.log,
+                    "Slasher produced invalid proposer slashing";
+                    "error" => format!("{:?}", e)
+                );
+            }
+        }
+
+        for slashing in output.attester_slashings {
+            warn!(
+                self.log,
+                "Slasher found attester slashing";
+                "attestation_1" => format!("{:?}", slashing.attestation_1.data),
+                "attestation_2" => format!("{:?}", slashing.attestation_2.data),
+            );
+
+            if let Err(e) = self.process_attester_slashing(slashing) {
+                warn!(
+                    self.log,
+                    "Slasher produced invalid attester slashing";
+                    "error" => format!("{:?}", e)
+                );
+            }
+        }
+    }
+
     /// Produce a new block at the present slot.
     ///
     /// The produced block will not be inherently valid, it must be signed by a block producer.
@@ -1017,9 +1344,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_TIMES);
 
         // If required, transition the new state to the present slot.
-        while state.slot < produce_at_slot {
-            per_slot_processing(&mut state, &self.spec)?;
-        }
+        fast_forward_state(&mut state, produce_at_slot, None, &self.spec)?;
 
         state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
 
@@ -1034,8 +1359,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let mut graffiti: [u8; 32] = [0; 32];
         graffiti.copy_from_slice(GRAFFITI.as_bytes());
 
-        let (proposer_slashings, attester_slashings) =
-            self.op_pool.get_slashings(&state, &self.spec);
+        let (proposer_slashings, attester_slashings, voluntary_exits) =
+            self.op_pool.get_slashings_and_exits(&state, &self.spec);
 
         let mut block = BeaconBlock {
             slot: state.slot,
@@ -1053,16 +1378,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 graffiti,
                 proposer_slashings: proposer_slashings.into(),
                 attester_slashings: attester_slashings.into(),
-                attestations: self.op_pool.get_attestations(&state, &self.spec).into(),
+                attestations: self.op_pool.get_attestations(&state, &self.spec)?.into(),
                 deposits: self.op_pool.get_deposits(&state).into(),
-                voluntary_exits: self.op_pool.get_voluntary_exits(&state, &self.spec).into(),
+                voluntary_exits: voluntary_exits.into(),
                 transfers: self.op_pool.get_transfers(&state, &self.spec).into(),
             },
         };
 
         per_block_processing_without_verifying_block_signature(&mut state, &block, &self.spec)?;
 
-        let state_root = state.canonical_root();
+        let state_root = state.update_tree_hash_cache()?;
 
         block.state_root = state_root;
 
@@ -1093,8 +1418,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
             let beacon_state_root = beacon_block.state_root;
             let beacon_state: BeaconState<T::EthSpec> = self
-                .store
-                .get(&beacon_state_root)?
+                .get_state_with_caches(&beacon_state_root)?
                 .ok_or_else(|| Error::MissingBeaconState(beacon_state_root))?;
 
             let previous_slot = self.head().beacon_block.slot;
@@ -1158,6 +1482,31 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         result
     }
 
+    /// Returns the state with root `state_root`.
+    ///
+    /// If it is the state of the most recently imported block or the canonical head, it is cloned
+    /// from memory along with its caches (cloning a state is cheap, as its large fields are shared
+    /// with the clone). Otherwise, it is read from the database without caches.
+    fn get_state_with_caches(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<Option<BeaconState<T::EthSpec>>, Error> {
+        if let Some(checkpoint) = self.last_imported_block.read().as_ref() {
+            if checkpoint.beacon_state_root == *state_root {
+                return Ok(Some(checkpoint.beacon_state.clone()));
+            }
+        }
+
+        {
+            let head = self.canonical_head.read();
+            if head.beacon_state_root == *state_root {
+                return Ok(Some(head.beacon_state.clone()));
+            }
+        }
+
+        Ok(self.store.get(state_root)?)
+    }
+
     /// Update the canonical head to `new_head`.
     fn update_canonical_head(&self, new_head: CheckPoint<T::EthSpec>) -> Result<(), Error> {
         let timer = metrics::start_timer(&metrics::UPDATE_HEAD_TIMES);
@@ -1168,7 +1517,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         // Update the always-at-the-present-slot state we keep around for performance gains.
         *self.state.write() = {
-            let mut state = self.canonical_head.read().beacon_state.clone();
+            let (mut state, state_root) = {
+                let head = self.canonical_head.read();
+                (head.beacon_state.clone(), head.beacon_state_root)
+            };
 
             let present_slot = match self.slot_clock.present_slot() {
                 Ok(Some(slot)) => slot,
@@ -1176,15 +1528,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             };
 
             // If required, transition the new state to the present slot.
-            for _ in state.slot.as_u64()..present_slot.as_u64() {
-                per_slot_processing(&mut state, &self.spec)?;
-            }
+            fast_forward_state(&mut state, present_slot, Some(state_root), &self.spec)?;
 
             state.build_all_caches(&self.spec)?;
 
             state
         };
 
+        if let Some(monitor) = &self.validator_monitor {
+            monitor.process_valid_state(&self.state.read(), &self.spec);
+        }
+
         // Save `self` to `self.store`.
         self.persist()?;
 
@@ -1301,3 +1655,16 @@ impl From<BeaconStateError> for Error {
         Error::BeaconStateError(e)
     }
 }
+
+/// Returns the slot prior to the epoch transition which rewards the attestations of `epoch`.
+fn reward_report_slot<E: EthSpec>(epoch: Epoch) -> Slot {
+    (epoch + 2).start_slot(E::slots_per_epoch()) - 1
+}
+
+/// Returns the database key for the `EpochRewardReport` of `epoch`, where `block_root` is the root
+/// of the block at `reward_report_slot(epoch)`.
+fn reward_report_key(epoch: Epoch, block_root: Hash256) -> Vec<u8> {
+    let mut key = epoch.as_u64().to_be_bytes().to_vec();
+    key.extend_from_slice(block_root.as_bytes());
+    key
+}
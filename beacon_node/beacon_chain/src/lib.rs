@@ -9,15 +9,22 @@ mod fork_choice;
 mod iter;
 mod metrics;
 mod persisted_beacon_chain;
+mod slasher;
 pub mod test_utils;
+mod validator_monitor;
+mod validator_pubkey_cache;
 
 pub use self::beacon_chain::{
     AttestationProcessingOutcome, BeaconChain, BeaconChainTypes, BlockProcessingOutcome,
 };
 pub use self::checkpoint::CheckPoint;
 pub use self::errors::{BeaconChainError, BlockProductionError};
+pub use self::slasher::{Error as SlasherError, Slasher, SlasherConfig, SlasherOutput};
+pub use self::validator_monitor::{EpochSummary, ValidatorMonitor};
+pub use self::validator_pubkey_cache::ValidatorPubkeyCache;
 pub use lmd_ghost;
 pub use metrics::scrape_for_metrics;
+pub use operation_pool::OperationPoolLimits;
 pub use parking_lot;
 pub use slot_clock;
 pub use state_processing::per_block_processing::errors::{
@@ -186,6 +186,50 @@ lazy_static! {
         try_create_int_gauge("beacon_head_state_withdrawn_validators_total", "Sum of all validator balances at the head of the chain");
     pub static ref HEAD_STATE_ETH1_DEPOSIT_INDEX: Result<IntGauge> =
         try_create_int_gauge("beacon_head_state_eth1_deposit_index", "Eth1 deposit index at the head of the chain");
+
+    /*
+     * Validator Monitor
+     */
+    pub static ref VALIDATOR_MONITOR_GOSSIP_ATTESTATIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "validator_monitor_gossip_attestations_total",
+        "Count of attestations from the monitored validator received outside of a block",
+        &["validator"]
+    );
+    pub static ref VALIDATOR_MONITOR_INCLUDED_ATTESTATIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "validator_monitor_included_attestations_total",
+        "Count of epochs in which an attestation from the monitored validator was included in a block",
+        &["validator"]
+    );
+    pub static ref VALIDATOR_MONITOR_MISSED_ATTESTATIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "validator_monitor_missed_attestations_total",
+        "Count of epochs in which no attestation from the monitored validator was included in a block",
+        &["validator"]
+    );
+    pub static ref VALIDATOR_MONITOR_INCLUSION_DISTANCE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "validator_monitor_inclusion_distance",
+        "Minimum inclusion distance of the most recently summarized attestation of the monitored validator",
+        &["validator"]
+    );
+    pub static ref VALIDATOR_MONITOR_BLOCKS_PROPOSED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "validator_monitor_blocks_proposed_total",
+        "Count of blocks proposed by the monitored validator",
+        &["validator"]
+    );
+    pub static ref VALIDATOR_MONITOR_MISSED_PROPOSALS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "validator_monitor_missed_proposals_total",
+        "Count of slots in which the monitored validator was the proposer but no block is in the canonical chain",
+        &["validator"]
+    );
+    pub static ref VALIDATOR_MONITOR_BALANCE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "validator_monitor_balance_gwei",
+        "Balance of the monitored validator at the start of the current epoch",
+        &["validator"]
+    );
+    pub static ref VALIDATOR_MONITOR_BALANCE_CHANGE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "validator_monitor_balance_change_gwei",
+        "Change in balance of the monitored validator during the previous epoch",
+        &["validator"]
+    );
 }
 
 /// Scrape the `beacon_chain` for metrics that are not constantly updated (e.g., the present slot,
@@ -0,0 +1,1112 @@
+//! Detects slashable behaviour in the attestations and block headers seen by the beacon node.
+//!
+//! Attestations and block headers are queued as they are seen and processed later in a batch
+//! (see `Slasher::process_queued`), keeping the slasher's database work off the block and
+//! attestation import paths.
+//!
+//! Double proposals and double votes are found by recording the block header each validator
+//! proposed at each slot and the attestation each validator made for each target epoch.
+//!
+//! Surround votes are found using the min-max span method. For each validator and epoch `e` we
+//! store:
+//!
+//! - `min_span[e]`: the minimum `target - e` over all attestations with `source > e`.
+//! - `max_span[e]`: the maximum `target - e` over all attestations with `source < e`.
+//!
+//! An attestation `(s, t)` surrounds a prior attestation if `min_span[s] < t - s` and is
+//! surrounded by a prior attestation if `max_span[s] > t - s`. The target of the conflicting
+//! attestation is `s + min_span[s]` or `s + max_span[s]`, respectively.
+//!
+//! Spans are stored as `u16` distances in chunks of `SPAN_CHUNK_SIZE` epochs, so each
+//! validator costs a few bytes per epoch of history.
+//!
+//! The keys of the records written for each epoch are kept in the `SlasherEpochIndex` column so
+//! that records older than the history length can be deleted. The lowest epoch that has not been
+//! pruned is kept in the same column, so pruning resumes where it left off after a restart.
+use parking_lot::Mutex;
+use serde_derive::{Deserialize, Serialize};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use store::{DBColumn, Error as StoreError, Store};
+use tree_hash::{SignedRoot, TreeHash};
+use types::*;
+
+/// The number of epochs stored in each chunk of the min/max span indexes.
+pub const SPAN_CHUNK_SIZE: u64 = 16;
+
+/// The length of a record in the `SlasherTarget` column: an `AttestationData` root followed by
+/// an `IndexedAttestation` root.
+const TARGET_RECORD_LEN: usize = 64;
+
+/// The key of the lowest epoch that has not been pruned in the `SlasherEpochIndex` column. It
+/// cannot collide with the 8 byte epoch keys of that column.
+const PRUNED_EPOCH_KEY: &[u8] = b"pruned_epoch";
+
+/// The maximum number of attestations that may be queued between batches. Further attestations
+/// are dropped until the queue is processed.
+const MAX_QUEUED_ATTESTATIONS: usize = 131_072;
+
+/// The maximum number of block headers that may be queued between batches. Further headers are
+/// dropped until the queue is processed.
+const MAX_QUEUED_BLOCK_HEADERS: usize = 4_096;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    StoreError(StoreError),
+    /// A record in the `SlasherTarget` column did not have the expected length.
+    InvalidTargetRecord(usize),
+    /// A chunk in a span column did not have the expected length.
+    InvalidSpanChunk(usize),
+    /// A record in the `SlasherEpochIndex` column listed a different number of proposers and
+    /// proposal slots.
+    InvalidEpochRecords {
+        proposers: usize,
+        slots: usize,
+    },
+    /// The span index referred to an attestation that is not in the store.
+    MissingAttestation {
+        validator_index: u64,
+        target_epoch: Epoch,
+    },
+}
+
+impl From<StoreError> for Error {
+    fn from(e: StoreError) -> Error {
+        Error::StoreError(e)
+    }
+}
+
+impl From<ssz::DecodeError> for Error {
+    fn from(e: ssz::DecodeError) -> Error {
+        Error::StoreError(e.into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlasherConfig {
+    /// The number of epochs of history kept by the slasher. Records older than this are pruned
+    /// and surround votes spanning more epochs than this may go undetected.
+    pub history_length: u64,
+}
+
+impl Default for SlasherConfig {
+    fn default() -> Self {
+        Self {
+            history_length: 4_096,
+        }
+    }
+}
+
+/// The slashings found whilst processing a batch of queued attestations and block headers.
+#[derive(Debug, PartialEq)]
+pub struct SlasherOutput<E: EthSpec> {
+    pub proposer_slashings: Vec<ProposerSlashing>,
+    pub attester_slashings: Vec<AttesterSlashing<E>>,
+    /// The number of attestations and block headers that were dropped because the queue was full.
+    pub num_dropped: usize,
+}
+
+/// Attestations and block headers that have been seen but not yet processed.
+struct Queue<E: EthSpec> {
+    attestations: Vec<IndexedAttestation<E>>,
+    block_headers: Vec<(u64, BeaconBlockHeader)>,
+    /// The number of items that were not queued because the queue was full.
+    num_dropped: usize,
+}
+
+impl<E: EthSpec> Default for Queue<E> {
+    fn default() -> Self {
+        Self {
+            attestations: vec![],
+            block_headers: vec![],
+            num_dropped: 0,
+        }
+    }
+}
+
+/// The keys of the slasher records belonging to a single epoch, stored in the
+/// `SlasherEpochIndex` column.
+#[derive(Debug, Default, PartialEq, Encode, Decode)]
+struct EpochRecords {
+    /// Validators with a `SlasherTarget` record for this target epoch.
+    target_validators: Vec<u64>,
+    /// Roots of the `SlasherAttestation` records with this target epoch.
+    attestation_roots: Vec<Hash256>,
+    /// Proposers with a `SlasherProposal` record in this epoch, with their slots in
+    /// `proposal_slots`.
+    proposers: Vec<u64>,
+    proposal_slots: Vec<u64>,
+    /// Validators with span chunks starting at this epoch.
+    span_validators: Vec<u64>,
+}
+
+impl EpochRecords {
+    fn append(&mut self, other: EpochRecords) {
+        self.target_validators.extend(other.target_validators);
+        self.attestation_roots.extend(other.attestation_roots);
+        self.proposers.extend(other.proposers);
+        self.proposal_slots.extend(other.proposal_slots);
+        self.span_validators.extend(other.span_validators);
+    }
+}
+
+/// Records attestations and block headers, producing slashings for any that conflict.
+pub struct Slasher<U: Store, E: EthSpec> {
+    store: Arc<U>,
+    config: SlasherConfig,
+    queue: Mutex<Queue<E>>,
+    /// The lowest epoch whose records have not been pruned.
+    ///
+    /// Held whilst processing a batch, so that the read-modify-write of the span indexes is not
+    /// interleaved.
+    pruned_epoch: Mutex<Epoch>,
+    _phantom: PhantomData<E>,
+}
+
+impl<U: Store, E: EthSpec> Slasher<U, E> {
+    /// Instantiate a slasher which keeps its records in `store`, resuming from any records that
+    /// are already there.
+    pub fn new(store: Arc<U>, config: SlasherConfig) -> Result<Self, Error> {
+        let pruned_epoch =
+            match store.get_bytes(DBColumn::SlasherEpochIndex.into(), PRUNED_EPOCH_KEY)? {
+                Some(bytes) => Epoch::from_ssz_bytes(&bytes)?,
+                None => Epoch::new(0),
+            };
+
+        Ok(Self {
+            store,
+            config,
+            queue: Mutex::new(Queue::default()),
+            pruned_epoch: Mutex::new(pruned_epoch),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Queues `header`, proposed by `proposer_index`, for the next call to `process_queued`.
+    ///
+    /// The header is dropped if `MAX_QUEUED_BLOCK_HEADERS` are already queued.
+    pub fn queue_block_header(&self, proposer_index: u64, header: BeaconBlockHeader) {
+        let mut queue = self.queue.lock();
+
+        if queue.block_headers.len() < MAX_QUEUED_BLOCK_HEADERS {
+            queue.block_headers.push((proposer_index, header));
+        } else {
+            queue.num_dropped += 1;
+        }
+    }
+
+    /// Queues `attestation` for the next call to `process_queued`.
+    ///
+    /// The attestation is dropped if `MAX_QUEUED_ATTESTATIONS` are already queued.
+    pub fn queue_indexed_attestation(&self, attestation: IndexedAttestation<E>) {
+        let mut queue = self.queue.lock();
+
+        if queue.attestations.len() < MAX_QUEUED_ATTESTATIONS {
+            queue.attestations.push(attestation);
+        } else {
+            queue.num_dropped += 1;
+        }
+    }
+
+    /// Records all queued block headers and attestations, returning the slashings found. Then
+    /// deletes the records that are more than `history_length` epochs older than
+    /// `current_epoch`.
+    ///
+    /// Attestations are processed in batches of the same target epoch, so the span chunks of
+    /// each validator are read and written once per batch. Queued items older than the pruned
+    /// epochs are ignored.
+    pub fn process_queued(&self, current_epoch: Epoch) -> Result<SlasherOutput<E>, Error> {
+        let queue = std::mem::replace(&mut *self.queue.lock(), Queue::default());
+
+        let mut pruned_epoch = self.pruned_epoch.lock();
+
+        let mut new_records = BTreeMap::new();
+        let mut output = SlasherOutput {
+            proposer_slashings: vec![],
+            attester_slashings: vec![],
+            num_dropped: queue.num_dropped,
+        };
+
+        for (proposer_index, header) in &queue.block_headers {
+            if header.slot.epoch(E::slots_per_epoch()) < *pruned_epoch {
+                continue;
+            }
+
+            if let Some(slashing) =
+                self.process_block_header(*proposer_index, header, &mut new_records)?
+            {
+                output.proposer_slashings.push(slashing);
+            }
+        }
+
+        let mut batches = BTreeMap::new();
+        for attestation in queue.attestations {
+            let target = attestation.data.target.epoch;
+            if target >= *pruned_epoch {
+                batches
+                    .entry(target)
+                    .or_insert_with(Vec::new)
+                    .push(attestation);
+            }
+        }
+
+        let mut seen_pairs = HashSet::new();
+        for batch in batches.values() {
+            for slashing in
+                self.process_attestation_batch(batch, *pruned_epoch, &mut new_records)?
+            {
+                let pair = (
+                    slashing.attestation_1.tree_hash_root(),
+                    slashing.attestation_2.tree_hash_root(),
+                );
+                if seen_pairs.insert(pair) {
+                    output.attester_slashings.push(slashing);
+                }
+            }
+        }
+
+        for (epoch, records) in new_records {
+            self.append_epoch_records(epoch, records)?;
+        }
+
+        *pruned_epoch = self.prune(current_epoch, *pruned_epoch)?;
+
+        Ok(output)
+    }
+
+    /// Records that `proposer_index` proposed `header`.
+    ///
+    /// Returns a `ProposerSlashing` if a different header has already been seen from the same
+    /// proposer at the same slot.
+    fn process_block_header(
+        &self,
+        proposer_index: u64,
+        header: &BeaconBlockHeader,
+        new_records: &mut BTreeMap<Epoch, EpochRecords>,
+    ) -> Result<Option<ProposerSlashing>, Error> {
+        let column = DBColumn::SlasherProposal.into();
+        let key = validator_epoch_key(proposer_index, header.slot.as_u64());
+
+        match self.store.get_bytes(column, &key)? {
+            Some(bytes) => {
+                let existing = BeaconBlockHeader::from_ssz_bytes(&bytes)?;
+
+                if existing.signed_root() == header.signed_root() {
+                    Ok(None)
+                } else {
+                    Ok(Some(ProposerSlashing {
+                        proposer_index,
+                        header_1: existing,
+                        header_2: header.clone(),
+                    }))
+                }
+            }
+            None => {
+                self.store.put_bytes(column, &key, &header.as_ssz_bytes())?;
+
+                let records = new_records
+                    .entry(header.slot.epoch(E::slots_per_epoch()))
+                    .or_default();
+                records.proposers.push(proposer_index);
+                records.proposal_slots.push(header.slot.as_u64());
+
+                Ok(None)
+            }
+        }
+    }
+
+    /// Records each attestation in `batch`, which must all have the same target epoch, for each
+    /// of its attesting validators.
+    ///
+    /// Returns an `AttesterSlashing` for each attestation and validator where the attestation
+    /// is a double vote or surround vote with a previously seen attestation.
+    fn process_attestation_batch(
+        &self,
+        batch: &[IndexedAttestation<E>],
+        pruned_epoch: Epoch,
+        new_records: &mut BTreeMap<Epoch, EpochRecords>,
+    ) -> Result<Vec<AttesterSlashing<E>>, Error> {
+        let mut spans = HashMap::new();
+        let mut slashings = vec![];
+
+        for attestation in batch {
+            let attestation_root = Hash256::from_slice(&attestation.tree_hash_root());
+            let data_root = Hash256::from_slice(&attestation.data.tree_hash_root());
+            let target = attestation.data.target.epoch;
+
+            let column = DBColumn::SlasherAttestation.into();
+            if !self.store.key_exists(column, attestation_root.as_bytes())? {
+                self.store.put_bytes(
+                    column,
+                    attestation_root.as_bytes(),
+                    &attestation.as_ssz_bytes(),
+                )?;
+                new_records
+                    .entry(target)
+                    .or_default()
+                    .attestation_roots
+                    .push(attestation_root);
+            }
+
+            for &validator_index in attestation
+                .custody_bit_0_indices
+                .iter()
+                .chain(attestation.custody_bit_1_indices.iter())
+            {
+                let validator_spans = spans.entry(validator_index).or_insert_with(|| {
+                    ValidatorSpans::new(&*self.store, validator_index, pruned_epoch)
+                });
+
+                let slashing = self.process_validator_attestation(
+                    validator_index,
+                    attestation,
+                    attestation_root,
+                    data_root,
+                    validator_spans,
+                    new_records,
+                )?;
+
+                slashings.extend(slashing);
+            }
+        }
+
+        for (validator_index, validator_spans) in spans {
+            let created_chunks = validator_spans
+                .min
+                .commit()?
+                .into_iter()
+                .chain(validator_spans.max.commit()?)
+                .collect::<BTreeSet<_>>();
+
+            for chunk_index in created_chunks {
+                new_records
+                    .entry(Epoch::new(chunk_index * SPAN_CHUNK_SIZE))
+                    .or_default()
+                    .span_validators
+                    .push(validator_index);
+            }
+        }
+
+        Ok(slashings)
+    }
+
+    /// Checks `attestation` against the history of `validator_index`, then records it.
+    fn process_validator_attestation(
+        &self,
+        validator_index: u64,
+        attestation: &IndexedAttestation<E>,
+        attestation_root: Hash256,
+        data_root: Hash256,
+        spans: &mut ValidatorSpans<U>,
+        new_records: &mut BTreeMap<Epoch, EpochRecords>,
+    ) -> Result<Option<AttesterSlashing<E>>, Error> {
+        let source = attestation.data.source.epoch;
+        let target = attestation.data.target.epoch;
+
+        // Check for a double vote.
+        match self.get_target_record(validator_index, target)? {
+            Some((existing_data_root, _)) if existing_data_root == data_root => {
+                // This validator has already been processed for this `AttestationData`.
+                return Ok(None);
+            }
+            Some((_, existing_root)) => {
+                let existing = self.get_attestation(existing_root, validator_index, target)?;
+                return Ok(Some(AttesterSlashing {
+                    attestation_1: existing,
+                    attestation_2: attestation.clone(),
+                }));
+            }
+            None => {
+                let mut record = Vec::with_capacity(TARGET_RECORD_LEN);
+                record.extend_from_slice(data_root.as_bytes());
+                record.extend_from_slice(attestation_root.as_bytes());
+                self.store.put_bytes(
+                    DBColumn::SlasherTarget.into(),
+                    &validator_epoch_key(validator_index, target.as_u64()),
+                    &record,
+                )?;
+                new_records
+                    .entry(target)
+                    .or_default()
+                    .target_validators
+                    .push(validator_index);
+            }
+        }
+
+        let distance = span_distance(target, source);
+
+        // Check if this attestation surrounds, or is surrounded by, a prior attestation.
+        let min_span = spans.min.get(source)?;
+        let max_span = spans.max.get(source)?;
+
+        let slashing = if min_span != SpanKind::Min.default_value() && min_span < distance {
+            let existing_target = source + Epoch::new(u64::from(min_span));
+            Some(AttesterSlashing {
+                attestation_1: attestation.clone(),
+                attestation_2: self.get_attestation_for_target(validator_index, existing_target)?,
+            })
+        } else if max_span > distance {
+            let existing_target = source + Epoch::new(u64::from(max_span));
+            Some(AttesterSlashing {
+                attestation_1: self.get_attestation_for_target(validator_index, existing_target)?,
+                attestation_2: attestation.clone(),
+            })
+        } else {
+            None
+        };
+
+        // Update the min spans for the epochs prior to the source.
+        let lowest_epoch = std::cmp::max(
+            target.as_u64().saturating_sub(self.config.history_length),
+            spans.pruned_epoch.as_u64(),
+        );
+        let mut epoch = source.as_u64();
+        while epoch > lowest_epoch {
+            epoch -= 1;
+            let new_span = span_distance(target, Epoch::new(epoch));
+            if new_span < spans.min.get(Epoch::new(epoch))? {
+                spans.min.set(Epoch::new(epoch), new_span)?;
+            } else {
+                break;
+            }
+        }
+
+        // Update the max spans for the epochs between the source and the target.
+        let lowest_epoch = std::cmp::max(source.as_u64() + 1, spans.pruned_epoch.as_u64());
+        for epoch in lowest_epoch..target.as_u64() {
+            let new_span = span_distance(target, Epoch::new(epoch));
+            if new_span > spans.max.get(Epoch::new(epoch))? {
+                spans.max.set(Epoch::new(epoch), new_span)?;
+            } else {
+                break;
+            }
+        }
+
+        Ok(slashing)
+    }
+
+    /// Adds `records` to the records of `epoch` in the `SlasherEpochIndex` column.
+    fn append_epoch_records(&self, epoch: Epoch, records: EpochRecords) -> Result<(), Error> {
+        let column = DBColumn::SlasherEpochIndex.into();
+        let key = epoch.as_u64().to_be_bytes();
+
+        let mut existing = match self.store.get_bytes(column, &key)? {
+            Some(bytes) => EpochRecords::from_ssz_bytes(&bytes)?,
+            None => EpochRecords::default(),
+        };
+        existing.append(records);
+
+        self.store
+            .put_bytes(column, &key, &existing.as_ssz_bytes())?;
+        Ok(())
+    }
+
+    /// Deletes the records of all epochs from `pruned_epoch` up to `current_epoch -
+    /// history_length`, rounded down to a chunk boundary so that no span chunk is partially
+    /// deleted.
+    ///
+    /// Returns the new lowest epoch that has not been pruned, which is also persisted.
+    fn prune(&self, current_epoch: Epoch, pruned_epoch: Epoch) -> Result<Epoch, Error> {
+        let prune_to = current_epoch
+            .as_u64()
+            .saturating_sub(self.config.history_length)
+            / SPAN_CHUNK_SIZE
+            * SPAN_CHUNK_SIZE;
+
+        for epoch in pruned_epoch.as_u64()..prune_to {
+            let column = DBColumn::SlasherEpochIndex.into();
+            let key = epoch.to_be_bytes();
+
+            let records = match self.store.get_bytes(column, &key)? {
+                Some(bytes) => EpochRecords::from_ssz_bytes(&bytes)?,
+                None => continue,
+            };
+
+            if records.proposers.len() != records.proposal_slots.len() {
+                return Err(Error::InvalidEpochRecords {
+                    proposers: records.proposers.len(),
+                    slots: records.proposal_slots.len(),
+                });
+            }
+
+            for validator_index in records.target_validators {
+                self.store.key_delete(
+                    DBColumn::SlasherTarget.into(),
+                    &validator_epoch_key(validator_index, epoch),
+                )?;
+            }
+
+            for attestation_root in records.attestation_roots {
+                self.store.key_delete(
+                    DBColumn::SlasherAttestation.into(),
+                    attestation_root.as_bytes(),
+                )?;
+            }
+
+            for (proposer_index, slot) in records.proposers.into_iter().zip(records.proposal_slots)
+            {
+                self.store.key_delete(
+                    DBColumn::SlasherProposal.into(),
+                    &validator_epoch_key(proposer_index, slot),
+                )?;
+            }
+
+            let (chunk_index, _) = chunk_position(Epoch::new(epoch));
+            for validator_index in records.span_validators {
+                let chunk_key = validator_epoch_key(validator_index, chunk_index);
+                self.store
+                    .key_delete(SpanKind::Min.column().into(), &chunk_key)?;
+                self.store
+                    .key_delete(SpanKind::Max.column().into(), &chunk_key)?;
+            }
+
+            self.store.key_delete(column, &key)?;
+        }
+
+        let new_pruned_epoch = std::cmp::max(pruned_epoch, Epoch::new(prune_to));
+        if new_pruned_epoch != pruned_epoch {
+            self.store.put_bytes(
+                DBColumn::SlasherEpochIndex.into(),
+                PRUNED_EPOCH_KEY,
+                &new_pruned_epoch.as_ssz_bytes(),
+            )?;
+        }
+
+        Ok(new_pruned_epoch)
+    }
+
+    /// Returns the attestation data root and attestation root that `validator_index` attested
+    /// to with `target`, if any.
+    fn get_target_record(
+        &self,
+        validator_index: u64,
+        target: Epoch,
+    ) -> Result<Option<(Hash256, Hash256)>, Error> {
+        let key = validator_epoch_key(validator_index, target.as_u64());
+
+        match self.store.get_bytes(DBColumn::SlasherTarget.into(), &key)? {
+            Some(bytes) => {
+                if bytes.len() != TARGET_RECORD_LEN {
+                    return Err(Error::InvalidTargetRecord(bytes.len()));
+                }
+                Ok(Some((
+                    Hash256::from_slice(&bytes[0..32]),
+                    Hash256::from_slice(&bytes[32..64]),
+                )))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the attestation that `validator_index` made with `target`.
+    fn get_attestation_for_target(
+        &self,
+        validator_index: u64,
+        target: Epoch,
+    ) -> Result<IndexedAttestation<E>, Error> {
+        let (_, attestation_root) = self
+            .get_target_record(validator_index, target)?
+            .ok_or_else(|| Error::MissingAttestation {
+                validator_index,
+                target_epoch: target,
+            })?;
+
+        self.get_attestation(attestation_root, validator_index, target)
+    }
+
+    fn get_attestation(
+        &self,
+        attestation_root: Hash256,
+        validator_index: u64,
+        target: Epoch,
+    ) -> Result<IndexedAttestation<E>, Error> {
+        let bytes = self
+            .store
+            .get_bytes(
+                DBColumn::SlasherAttestation.into(),
+                attestation_root.as_bytes(),
+            )?
+            .ok_or_else(|| Error::MissingAttestation {
+                validator_index,
+                target_epoch: target,
+            })?;
+
+        Ok(IndexedAttestation::from_ssz_bytes(&bytes)?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SpanKind {
+    Min,
+    Max,
+}
+
+impl SpanKind {
+    fn column(self) -> DBColumn {
+        match self {
+            SpanKind::Min => DBColumn::SlasherMinSpan,
+            SpanKind::Max => DBColumn::SlasherMaxSpan,
+        }
+    }
+
+    /// The span of an epoch for which no relevant attestation has been seen.
+    fn default_value(self) -> u16 {
+        match self {
+            SpanKind::Min => u16::max_value(),
+            SpanKind::Max => 0,
+        }
+    }
+}
+
+/// The span indexes of a single validator, cached whilst processing a batch of attestations.
+struct ValidatorSpans<'a, U: Store> {
+    min: SpanChunks<'a, U>,
+    max: SpanChunks<'a, U>,
+    /// Spans prior to this epoch have been pruned, so are not updated.
+    pruned_epoch: Epoch,
+}
+
+impl<'a, U: Store> ValidatorSpans<'a, U> {
+    fn new(store: &'a U, validator_index: u64, pruned_epoch: Epoch) -> Self {
+        Self {
+            min: SpanChunks::new(store, SpanKind::Min, validator_index),
+            max: SpanChunks::new(store, SpanKind::Max, validator_index),
+            pruned_epoch,
+        }
+    }
+}
+
+/// Caches the chunks of a single validator's span index that are read or modified whilst
+/// processing a batch of attestations.
+struct SpanChunks<'a, U: Store> {
+    store: &'a U,
+    kind: SpanKind,
+    validator_index: u64,
+    chunks: BTreeMap<u64, Vec<u16>>,
+    dirty: HashSet<u64>,
+    /// Chunks that were not in the store when they were first read.
+    created: HashSet<u64>,
+}
+
+impl<'a, U: Store> SpanChunks<'a, U> {
+    fn new(store: &'a U, kind: SpanKind, validator_index: u64) -> Self {
+        Self {
+            store,
+            kind,
+            validator_index,
+            chunks: BTreeMap::new(),
+            dirty: HashSet::new(),
+            created: HashSet::new(),
+        }
+    }
+
+    fn get(&mut self, epoch: Epoch) -> Result<u16, Error> {
+        let (chunk_index, offset) = chunk_position(epoch);
+        Ok(self.chunk(chunk_index)?[offset])
+    }
+
+    fn set(&mut self, epoch: Epoch, span: u16) -> Result<(), Error> {
+        let (chunk_index, offset) = chunk_position(epoch);
+        self.chunk(chunk_index)?[offset] = span;
+        self.dirty.insert(chunk_index);
+        Ok(())
+    }
+
+    fn chunk(&mut self, chunk_index: u64) -> Result<&mut Vec<u16>, Error> {
+        if !self.chunks.contains_key(&chunk_index) {
+            let key = validator_epoch_key(self.validator_index, chunk_index);
+            let chunk = match self.store.get_bytes(self.kind.column().into(), &key)? {
+                Some(bytes) => {
+                    if bytes.len() != SPAN_CHUNK_SIZE as usize * 2 {
+                        return Err(Error::InvalidSpanChunk(bytes.len()));
+                    }
+                    bytes
+                        .chunks(2)
+                        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                        .collect()
+                }
+                None => {
+                    self.created.insert(chunk_index);
+                    vec![self.kind.default_value(); SPAN_CHUNK_SIZE as usize]
+                }
+            };
+            self.chunks.insert(chunk_index, chunk);
+        }
+
+        Ok(self
+            .chunks
+            .get_mut(&chunk_index)
+            .expect("chunk was inserted above"))
+    }
+
+    /// Writes all modified chunks to the store, returning the indices of those that were not
+    /// previously in the store.
+    fn commit(self) -> Result<Vec<u64>, Error> {
+        for chunk_index in &self.dirty {
+            let bytes = self.chunks[chunk_index]
+                .iter()
+                .flat_map(|span| span.to_le_bytes().to_vec())
+                .collect::<Vec<u8>>();
+            self.store.put_bytes(
+                self.kind.column().into(),
+                &validator_epoch_key(self.validator_index, *chunk_index),
+                &bytes,
+            )?;
+        }
+
+        Ok(self.dirty.intersection(&self.created).cloned().collect())
+    }
+}
+
+/// Returns `target - epoch` as a span, saturating at the largest non-default min span.
+fn span_distance(target: Epoch, epoch: Epoch) -> u16 {
+    std::cmp::min(
+        target.as_u64().saturating_sub(epoch.as_u64()),
+        u64::from(u16::max_value() - 1),
+    ) as u16
+}
+
+/// Returns the index of the chunk containing `epoch` and the position of `epoch` in that chunk.
+fn chunk_position(epoch: Epoch) -> (u64, usize) {
+    (
+        epoch.as_u64() / SPAN_CHUNK_SIZE,
+        (epoch.as_u64() % SPAN_CHUNK_SIZE) as usize,
+    )
+}
+
+/// A database key for a `(validator_index, epoch)` pair.
+///
+/// Big-endian encoding keeps the keys for a single validator ordered by epoch.
+fn validator_epoch_key(validator_index: u64, epoch: u64) -> Vec<u8> {
+    let mut key = validator_index.to_be_bytes().to_vec();
+    key.extend_from_slice(&epoch.to_be_bytes());
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::MemoryStore;
+
+    type E = MinimalEthSpec;
+
+    fn slasher() -> Slasher<MemoryStore, E> {
+        Slasher::new(Arc::new(MemoryStore::open()), SlasherConfig::default())
+            .expect("should create slasher")
+    }
+
+    /// Queues `attestation` and processes it in a batch of its own.
+    fn process_attestation(
+        slasher: &Slasher<MemoryStore, E>,
+        attestation: &IndexedAttestation<E>,
+    ) -> Result<Vec<AttesterSlashing<E>>, Error> {
+        slasher.queue_indexed_attestation(attestation.clone());
+        slasher
+            .process_queued(attestation.data.target.epoch)
+            .map(|output| output.attester_slashings)
+    }
+
+    /// Queues `header` and processes it in a batch of its own.
+    fn process_block_header(
+        slasher: &Slasher<MemoryStore, E>,
+        proposer_index: u64,
+        header: &BeaconBlockHeader,
+    ) -> Result<Option<ProposerSlashing>, Error> {
+        slasher.queue_block_header(proposer_index, header.clone());
+        slasher
+            .process_queued(header.slot.epoch(E::slots_per_epoch()))
+            .map(|output| output.proposer_slashings.into_iter().next())
+    }
+
+    fn block_header(slot: u64) -> BeaconBlockHeader {
+        BeaconBlockHeader {
+            slot: Slot::new(slot),
+            parent_root: Hash256::zero(),
+            state_root: Hash256::zero(),
+            body_root: Hash256::zero(),
+            signature: Signature::empty_signature(),
+        }
+    }
+
+    fn attestation(
+        indices: &[u64],
+        source: u64,
+        target: u64,
+        block_root: u64,
+    ) -> IndexedAttestation<E> {
+        let data = AttestationData {
+            beacon_block_root: Hash256::from_low_u64_be(block_root),
+            source: Checkpoint {
+                epoch: Epoch::new(source),
+                root: Hash256::zero(),
+            },
+            target: Checkpoint {
+                epoch: Epoch::new(target),
+                root: Hash256::zero(),
+            },
+            crosslink: Crosslink::default(),
+        };
+
+        IndexedAttestation {
+            custody_bit_0_indices: VariableList::new(indices.to_vec()).unwrap(),
+            custody_bit_1_indices: VariableList::empty(),
+            data,
+            signature: AggregateSignature::new(),
+        }
+    }
+
+    #[test]
+    fn detects_double_vote() {
+        let slasher = slasher();
+
+        let first = attestation(&[0, 1], 0, 1, 1);
+        assert_eq!(process_attestation(&slasher, &first), Ok(vec![]));
+        // Processing the same attestation again is not slashable.
+        assert_eq!(process_attestation(&slasher, &first), Ok(vec![]));
+
+        let second = attestation(&[1, 2], 0, 1, 2);
+        assert_eq!(
+            process_attestation(&slasher, &second),
+            Ok(vec![AttesterSlashing {
+                attestation_1: first,
+                attestation_2: second,
+            }])
+        );
+    }
+
+    #[test]
+    fn detects_surrounding_vote() {
+        let slasher = slasher();
+
+        let surrounded = attestation(&[3], 5, 6, 1);
+        assert_eq!(process_attestation(&slasher, &surrounded), Ok(vec![]));
+
+        let surrounding = attestation(&[3], 4, 7, 2);
+        assert_eq!(
+            process_attestation(&slasher, &surrounding),
+            Ok(vec![AttesterSlashing {
+                attestation_1: surrounding,
+                attestation_2: surrounded,
+            }])
+        );
+    }
+
+    #[test]
+    fn detects_surrounded_vote() {
+        let slasher = slasher();
+
+        let surrounding = attestation(&[3], 10, 40, 1);
+        assert_eq!(process_attestation(&slasher, &surrounding), Ok(vec![]));
+
+        let surrounded = attestation(&[3], 20, 30, 2);
+        assert_eq!(
+            process_attestation(&slasher, &surrounded),
+            Ok(vec![AttesterSlashing {
+                attestation_1: surrounding,
+                attestation_2: surrounded,
+            }])
+        );
+    }
+
+    #[test]
+    fn ignores_non_conflicting_votes() {
+        let slasher = slasher();
+
+        for (source, target) in &[(0, 1), (1, 2), (2, 4), (4, 5), (5, 40), (40, 41)] {
+            assert_eq!(
+                process_attestation(&slasher, &attestation(&[7], *source, *target, 1)),
+                Ok(vec![]),
+                "({}, {}) should not be slashable",
+                source,
+                target
+            );
+        }
+
+        // Other validators are unaffected by validator 7's history.
+        assert_eq!(
+            process_attestation(&slasher, &attestation(&[8], 3, 42, 1)),
+            Ok(vec![])
+        );
+        assert_eq!(
+            process_attestation(&slasher, &attestation(&[7], 3, 42, 1))
+                .map(|slashings| slashings.len()),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn detects_double_proposal() {
+        let slasher = slasher();
+
+        let header = block_header(3);
+        assert_eq!(process_block_header(&slasher, 1, &header), Ok(None));
+        assert_eq!(process_block_header(&slasher, 1, &header), Ok(None));
+
+        // The same header from a different proposer, or at a different slot, is not slashable.
+        assert_eq!(process_block_header(&slasher, 2, &header), Ok(None));
+        let mut later_header = header.clone();
+        later_header.slot = Slot::new(4);
+        assert_eq!(process_block_header(&slasher, 1, &later_header), Ok(None));
+
+        let mut other_header = header.clone();
+        other_header.body_root = Hash256::from_low_u64_be(1);
+        assert_eq!(
+            process_block_header(&slasher, 1, &other_header),
+            Ok(Some(ProposerSlashing {
+                proposer_index: 1,
+                header_1: header,
+                header_2: other_header,
+            }))
+        );
+    }
+
+    #[test]
+    fn processes_queue_in_batches() {
+        let slasher = slasher();
+
+        let first = attestation(&[0], 0, 1, 1);
+        let double = attestation(&[0], 0, 1, 2);
+        let surrounded = attestation(&[1], 5, 6, 1);
+        let surrounding = attestation(&[1], 4, 7, 2);
+        for attestation in &[&first, &double, &surrounded, &surrounding] {
+            slasher.queue_indexed_attestation((*attestation).clone());
+        }
+
+        let header = block_header(3);
+        let mut other_header = header.clone();
+        other_header.body_root = Hash256::from_low_u64_be(1);
+        slasher.queue_block_header(1, header.clone());
+        slasher.queue_block_header(1, other_header.clone());
+
+        // Nothing is recorded until the queue is processed.
+        assert_eq!(
+            slasher
+                .store
+                .key_exists(DBColumn::SlasherTarget.into(), &validator_epoch_key(0, 1)),
+            Ok(false)
+        );
+
+        assert_eq!(
+            slasher.process_queued(Epoch::new(7)),
+            Ok(SlasherOutput {
+                proposer_slashings: vec![ProposerSlashing {
+                    proposer_index: 1,
+                    header_1: header,
+                    header_2: other_header,
+                }],
+                attester_slashings: vec![
+                    AttesterSlashing {
+                        attestation_1: first,
+                        attestation_2: double,
+                    },
+                    AttesterSlashing {
+                        attestation_1: surrounding,
+                        attestation_2: surrounded,
+                    },
+                ],
+                num_dropped: 0,
+            })
+        );
+
+        // The queue is empty once processed.
+        assert_eq!(
+            slasher.process_queued(Epoch::new(7)).map(|output| (
+                output.proposer_slashings.len(),
+                output.attester_slashings.len()
+            )),
+            Ok((0, 0))
+        );
+    }
+
+    #[test]
+    fn drops_items_when_queue_is_full() {
+        let slasher = slasher();
+
+        let header = block_header(3);
+        for _ in 0..MAX_QUEUED_BLOCK_HEADERS + 2 {
+            slasher.queue_block_header(1, header.clone());
+        }
+        assert_eq!(
+            slasher
+                .process_queued(Epoch::new(0))
+                .map(|output| output.num_dropped),
+            Ok(2)
+        );
+
+        // The queue accepts items again once processed.
+        slasher.queue_block_header(1, header);
+        assert_eq!(
+            slasher
+                .process_queued(Epoch::new(0))
+                .map(|output| output.num_dropped),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn prunes_old_records() {
+        let store = Arc::new(MemoryStore::open());
+        let config = SlasherConfig {
+            history_length: SPAN_CHUNK_SIZE,
+        };
+        let slasher =
+            Slasher::<_, E>::new(store.clone(), config.clone()).expect("should create slasher");
+
+        let old_attestation = attestation(&[0], 2, 5, 1);
+        let old_header = block_header(3);
+        let recent_attestation = attestation(&[0], 30, 31, 1);
+        slasher.queue_indexed_attestation(old_attestation.clone());
+        slasher.queue_indexed_attestation(recent_attestation);
+        slasher.queue_block_header(0, old_header.clone());
+        assert!(slasher.process_queued(Epoch::new(31)).is_ok());
+
+        let old_keys: Vec<(&str, Vec<u8>)> = vec![
+            (DBColumn::SlasherTarget.into(), validator_epoch_key(0, 5)),
+            (
+                DBColumn::SlasherAttestation.into(),
+                old_attestation.tree_hash_root(),
+            ),
+            (DBColumn::SlasherProposal.into(), validator_epoch_key(0, 3)),
+            (DBColumn::SlasherMinSpan.into(), validator_epoch_key(0, 0)),
+            (DBColumn::SlasherMaxSpan.into(), validator_epoch_key(0, 0)),
+            (
+                DBColumn::SlasherEpochIndex.into(),
+                5u64.to_be_bytes().to_vec(),
+            ),
+        ];
+        let key_exists = |(column, key): &(&str, Vec<u8>)| {
+            slasher
+                .store
+                .key_exists(column, key)
+                .expect("should read store")
+        };
+
+        // 31 - 16 rounds down to epoch 0, so nothing is pruned.
+        assert!(old_keys.iter().all(key_exists));
+
+        // 40 - 16 rounds down to epoch 16, so epochs 0 to 15 are pruned.
+        assert!(slasher.process_queued(Epoch::new(40)).is_ok());
+        assert!(!old_keys.iter().any(key_exists));
+        assert!(key_exists(&(
+            DBColumn::SlasherTarget.into(),
+            validator_epoch_key(0, 31)
+        )));
+
+        // Conflicting attestations and headers from pruned epochs are ignored.
+        let mut other_header = old_header.clone();
+        other_header.body_root = Hash256::from_low_u64_be(1);
+        assert_eq!(process_block_header(&slasher, 0, &other_header), Ok(None));
+        assert_eq!(
+            process_attestation(&slasher, &attestation(&[0], 2, 5, 2)),
+            Ok(vec![])
+        );
+        assert!(!old_keys.iter().any(key_exists));
+
+        // The pruned epoch is restored after a restart.
+        let restarted = Slasher::<_, E>::new(store, config).expect("should create slasher");
+        assert_eq!(*restarted.pruned_epoch.lock(), Epoch::new(16));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{BeaconChain, BeaconChainTypes, BlockProcessingOutcome};
+use crate::{BeaconChain, BeaconChainTypes, BlockProcessingOutcome, OperationPoolLimits};
 use lmd_ghost::LmdGhost;
 use sloggers::{null::NullLoggerBuilder, Build};
 use slot_clock::SlotClock;
@@ -127,6 +127,7 @@ where
             genesis_state,
             genesis_block,
             spec.clone(),
+            OperationPoolLimits::default(),
             log,
         )
         .expect("Terminate if beacon chain generation fails");
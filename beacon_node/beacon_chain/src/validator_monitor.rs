@@ -0,0 +1,510 @@
+use crate::metrics;
+use parking_lot::RwLock;
+use slog::{info, warn, Logger};
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
+use types::{
+    BeaconState, ChainSpec, Epoch, EthSpec, IndexedAttestation, PublicKey, RelativeEpoch, Slot,
+};
+
+/// What was observed about the attestations of a single validator for a single epoch.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EpochSummary {
+    /// The number of attestations received outside of a block (e.g., from gossip or the API).
+    pub gossip_attestations: usize,
+    /// The number of blocks which included an attestation from the validator.
+    pub block_inclusions: usize,
+    /// The smallest distance between the slot of an attestation and the slot of a block that
+    /// included it.
+    pub min_inclusion_distance: Option<u64>,
+}
+
+impl EpochSummary {
+    fn register_inclusion(&mut self, inclusion_distance: u64) {
+        self.block_inclusions += 1;
+        self.min_inclusion_distance = Some(
+            self.min_inclusion_distance
+                .map_or(inclusion_distance, |min| {
+                    std::cmp::min(min, inclusion_distance)
+                }),
+        );
+    }
+}
+
+/// A validator that is being monitored.
+struct MonitoredValidator {
+    pubkey: PublicKey,
+    /// The index of the validator, once it has been seen in a `BeaconState`.
+    index: Option<u64>,
+    /// The balance of the validator when the previous epoch was processed.
+    last_balance: Option<u64>,
+    /// Summaries of epochs which have not yet been reported.
+    summaries: BTreeMap<Epoch, EpochSummary>,
+}
+
+impl MonitoredValidator {
+    /// The value of the `validator` label on the Prometheus metrics and logs.
+    ///
+    /// The validator index is used when it is known, otherwise the public key.
+    fn id(&self) -> String {
+        self.index
+            .map(|index| index.to_string())
+            .unwrap_or_else(|| self.pubkey.as_hex_string())
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    validators: Vec<MonitoredValidator>,
+    /// Maps a validator index to its position in `validators`.
+    indices: HashMap<u64, usize>,
+    /// The first epoch for which every block and attestation has been observed, set when the
+    /// first state is processed.
+    first_complete_epoch: Option<Epoch>,
+    /// The epoch of the most recently processed state.
+    last_processed_epoch: Option<Epoch>,
+}
+
+impl Inner {
+    /// Returns the summary for the validator with `index` at `epoch`, if that validator is
+    /// monitored.
+    fn summary_mut(&mut self, index: u64, epoch: Epoch) -> Option<(&mut EpochSummary, String)> {
+        let position = *self.indices.get(&index)?;
+        let validator = &mut self.validators[position];
+        let id = validator.id();
+
+        Some((validator.summaries.entry(epoch).or_default(), id))
+    }
+
+    /// Finds the index of each validator which does not yet have one.
+    fn resolve_indices<T: EthSpec>(&mut self, state: &BeaconState<T>) {
+        let mut unresolved: HashMap<&PublicKey, usize> = self
+            .validators
+            .iter()
+            .enumerate()
+            .filter(|(_, validator)| validator.index.is_none())
+            .map(|(position, validator)| (&validator.pubkey, position))
+            .collect();
+
+        if unresolved.is_empty() {
+            return;
+        }
+
+        let mut resolved = vec![];
+        for (index, validator) in state.validators.iter().enumerate() {
+            if let Some(position) = unresolved.remove(&validator.pubkey) {
+                resolved.push((position, index as u64));
+            }
+        }
+
+        for (position, index) in resolved {
+            self.validators[position].index = Some(index);
+            self.indices.insert(index, position);
+        }
+    }
+}
+
+/// Tracks the performance of a set of validators, identified by their public keys.
+///
+/// For each monitored validator and each epoch, the monitor records:
+///
+/// - Whether an attestation from the validator was received outside of a block.
+/// - Whether an attestation from the validator was included in a block, and the inclusion
+/// distance.
+/// - Blocks proposed, and proposals missing from the canonical chain.
+/// - Changes to the validator's balance.
+///
+/// The results are exported as Prometheus metrics (labelled by validator index) and logged each
+/// time a state in a new epoch is given to `Self::process_valid_state`.
+///
+/// ## Note
+///
+/// Inclusions are recorded from every processed block, including those which do not become
+/// canonical. Missed proposals are determined from the canonical chain.
+pub struct ValidatorMonitor<T: EthSpec> {
+    inner: RwLock<Inner>,
+    log: Logger,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: EthSpec> ValidatorMonitor<T> {
+    /// Instantiate a monitor for the validators with the given `pubkeys`.
+    pub fn new(pubkeys: Vec<PublicKey>, log: Logger) -> Self {
+        let validators = pubkeys
+            .into_iter()
+            .map(|pubkey| MonitoredValidator {
+                pubkey,
+                index: None,
+                last_balance: None,
+                summaries: BTreeMap::new(),
+            })
+            .collect();
+
+        Self {
+            inner: RwLock::new(Inner {
+                validators,
+                ..Inner::default()
+            }),
+            log,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the index of each monitored validator, or `None` if the validator has not yet been
+    /// seen in a processed state.
+    pub fn validator_indices(&self) -> Vec<(PublicKey, Option<u64>)> {
+        self.inner
+            .read()
+            .validators
+            .iter()
+            .map(|validator| (validator.pubkey.clone(), validator.index))
+            .collect()
+    }
+
+    /// Returns the summary of `epoch` for the validator with `index`, if it is monitored and the
+    /// summary has not yet been reported.
+    pub fn get_summary(&self, index: u64, epoch: Epoch) -> Option<EpochSummary> {
+        let inner = self.inner.read();
+        let position = *inner.indices.get(&index)?;
+        inner.validators[position].summaries.get(&epoch).cloned()
+    }
+
+    /// Register a valid attestation received outside of a block (e.g., from gossip or the API).
+    pub fn register_gossip_attestation(&self, indexed_attestation: &IndexedAttestation<T>) {
+        let epoch = indexed_attestation.data.target.epoch;
+        let mut inner = self.inner.write();
+
+        for index in attesting_indices(indexed_attestation) {
+            if let Some((summary, id)) = inner.summary_mut(index, epoch) {
+                summary.gossip_attestations += 1;
+                metrics::inc_counter_vec(&metrics::VALIDATOR_MONITOR_GOSSIP_ATTESTATIONS, &[&id]);
+            }
+        }
+    }
+
+    /// Register an attestation included in a block, `inclusion_distance` slots after the slot of
+    /// the attestation.
+    pub fn register_attestation_in_block(
+        &self,
+        indexed_attestation: &IndexedAttestation<T>,
+        inclusion_distance: u64,
+    ) {
+        let epoch = indexed_attestation.data.target.epoch;
+        let mut inner = self.inner.write();
+
+        for index in attesting_indices(indexed_attestation) {
+            if let Some((summary, _)) = inner.summary_mut(index, epoch) {
+                summary.register_inclusion(inclusion_distance);
+            }
+        }
+    }
+
+    /// Register a valid block proposed by the validator with `proposer_index` at `slot`.
+    pub fn register_block(&self, proposer_index: u64, slot: Slot) {
+        let inner = self.inner.read();
+
+        if let Some(position) = inner.indices.get(&proposer_index) {
+            let id = inner.validators[*position].id();
+
+            info!(
+                self.log,
+                "Block from monitored validator";
+                "validator" => &id,
+                "slot" => slot.as_u64(),
+            );
+            metrics::inc_counter_vec(&metrics::VALIDATOR_MONITOR_BLOCKS_PROPOSED, &[&id]);
+        }
+    }
+
+    /// Reports on the monitored validators, if `state` is in a later epoch than the previously
+    /// processed state.
+    ///
+    /// Reports the attestations from two epochs prior to `state` (which may no longer be
+    /// included in a block), the proposals of the previous epoch and the balances at `state`.
+    ///
+    /// `state` must be a valid state with its previous epoch committee cache built.
+    pub fn process_valid_state(&self, state: &BeaconState<T>, spec: &ChainSpec) {
+        let current_epoch = state.current_epoch();
+        let mut inner = self.inner.write();
+
+        if inner
+            .last_processed_epoch
+            .map_or(false, |epoch| epoch >= current_epoch)
+        {
+            return;
+        }
+        inner.last_processed_epoch = Some(current_epoch);
+        let first_complete_epoch = *inner.first_complete_epoch.get_or_insert(current_epoch + 1);
+
+        inner.resolve_indices(state);
+
+        if current_epoch >= first_complete_epoch + 2 {
+            self.report_attestations(&mut inner, state, current_epoch - 2);
+        }
+
+        // There is no previous epoch to report on at genesis.
+        if state.previous_epoch() < current_epoch {
+            self.report_proposals(&inner, state, spec);
+        }
+
+        self.report_balances(&mut inner, state);
+    }
+
+    /// Report on the inclusion of the attestations for `epoch`, then discard the summaries of
+    /// `epoch` and any earlier epochs.
+    fn report_attestations(&self, inner: &mut Inner, state: &BeaconState<T>, epoch: Epoch) {
+        for validator in inner.validators.iter_mut() {
+            let index = match validator.index {
+                Some(index) => index,
+                None => continue,
+            };
+            let id = validator.id();
+
+            let summary = validator.summaries.remove(&epoch).unwrap_or_default();
+            validator.summaries = validator.summaries.split_off(&(epoch + 1));
+
+            let is_active = state
+                .validators
+                .get(index as usize)
+                .map_or(false, |v| v.is_active_at(epoch));
+            if !is_active {
+                continue;
+            }
+
+            if let Some(inclusion_distance) = summary.min_inclusion_distance {
+                info!(
+                    self.log,
+                    "Attestation from monitored validator included";
+                    "validator" => &id,
+                    "epoch" => epoch.as_u64(),
+                    "inclusion_distance" => inclusion_distance,
+                    "gossip_attestations" => summary.gossip_attestations,
+                );
+                metrics::inc_counter_vec(&metrics::VALIDATOR_MONITOR_INCLUDED_ATTESTATIONS, &[&id]);
+                metrics::set_gauge_vec(
+                    &metrics::VALIDATOR_MONITOR_INCLUSION_DISTANCE,
+                    &[&id],
+                    inclusion_distance as i64,
+                );
+            } else {
+                warn!(
+                    self.log,
+                    "Attestation from monitored validator not included";
+                    "validator" => &id,
+                    "epoch" => epoch.as_u64(),
+                    "gossip_attestations" => summary.gossip_attestations,
+                );
+                metrics::inc_counter_vec(&metrics::VALIDATOR_MONITOR_MISSED_ATTESTATIONS, &[&id]);
+            }
+        }
+    }
+
+    /// Report the slots in the previous epoch of `state` where a monitored validator was the
+    /// proposer but there is no block in the chain of `state`.
+    fn report_proposals(&self, inner: &Inner, state: &BeaconState<T>, spec: &ChainSpec) {
+        let previous_epoch = state.previous_epoch();
+
+        for slot in previous_epoch.slot_iter(T::slots_per_epoch()) {
+            // The genesis block has no proposer.
+            if slot == spec.genesis_slot {
+                continue;
+            }
+
+            let proposer_index =
+                match state.get_beacon_proposer_index(slot, RelativeEpoch::Previous, spec) {
+                    Ok(index) => index as u64,
+                    Err(_) => continue,
+                };
+            let position = match inner.indices.get(&proposer_index) {
+                Some(position) => *position,
+                None => continue,
+            };
+
+            // A skipped slot has the same block root as the slot prior.
+            let skipped = match (state.get_block_root(slot), state.get_block_root(slot - 1)) {
+                (Ok(root), Ok(prior_root)) => root == prior_root,
+                _ => continue,
+            };
+
+            if skipped {
+                let id = inner.validators[position].id();
+
+                warn!(
+                    self.log,
+                    "Missed block from monitored validator";
+                    "validator" => &id,
+                    "slot" => slot.as_u64(),
+                );
+                metrics::inc_counter_vec(&metrics::VALIDATOR_MONITOR_MISSED_PROPOSALS, &[&id]);
+            }
+        }
+    }
+
+    /// Report the balance of each monitored validator in `state`, and the change since the
+    /// previously processed state.
+    fn report_balances(&self, inner: &mut Inner, state: &BeaconState<T>) {
+        let epoch = state.current_epoch();
+
+        for validator in inner.validators.iter_mut() {
+            let balance = match validator
+                .index
+                .and_then(|index| state.balances.get(index as usize))
+            {
+                Some(balance) => *balance,
+                None => continue,
+            };
+            let id = validator.id();
+            let change = validator
+                .last_balance
+                .map(|last_balance| balance as i64 - last_balance as i64);
+
+            info!(
+                self.log,
+                "Monitored validator balance";
+                "validator" => &id,
+                "epoch" => epoch.as_u64(),
+                "balance_gwei" => balance,
+                "change_gwei" => change.map_or_else(|| "unknown".to_string(), |c| c.to_string()),
+            );
+
+            metrics::set_gauge_vec(&metrics::VALIDATOR_MONITOR_BALANCE, &[&id], balance as i64);
+            if let Some(change) = change {
+                metrics::set_gauge_vec(&metrics::VALIDATOR_MONITOR_BALANCE_CHANGE, &[&id], change);
+            }
+
+            validator.last_balance = Some(balance);
+        }
+    }
+}
+
+/// Returns the indices of all validators who signed `indexed_attestation`.
+fn attesting_indices<T: EthSpec>(
+    indexed_attestation: &IndexedAttestation<T>,
+) -> impl Iterator<Item = u64> + '_ {
+    indexed_attestation
+        .custody_bit_0_indices
+        .iter()
+        .chain(indexed_attestation.custody_bit_1_indices.iter())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::TestingBeaconStateBuilder;
+    use types::{
+        AggregateSignature, AttestationData, Checkpoint, Crosslink, Hash256, MinimalEthSpec,
+        VariableList,
+    };
+
+    type E = MinimalEthSpec;
+
+    const VALIDATOR_COUNT: usize = 16;
+
+    fn null_logger() -> Logger {
+        Logger::root(slog::Discard, slog::o!())
+    }
+
+    fn state_at_epoch(epoch: u64, spec: &ChainSpec) -> BeaconState<E> {
+        let mut builder =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(VALIDATOR_COUNT, spec);
+        builder.teleport_to_slot(Epoch::new(epoch).start_slot(E::slots_per_epoch()));
+        builder.build_caches(spec).unwrap();
+        builder.build().0
+    }
+
+    fn attestation(indices: &[u64], target: u64) -> IndexedAttestation<E> {
+        IndexedAttestation {
+            custody_bit_0_indices: VariableList::new(indices.to_vec()).unwrap(),
+            custody_bit_1_indices: VariableList::empty(),
+            data: AttestationData {
+                beacon_block_root: Hash256::zero(),
+                source: Checkpoint::default(),
+                target: Checkpoint {
+                    epoch: Epoch::new(target),
+                    root: Hash256::zero(),
+                },
+                crosslink: Crosslink::default(),
+            },
+            signature: AggregateSignature::new(),
+        }
+    }
+
+    #[test]
+    fn resolves_indices_of_monitored_validators() {
+        let spec = E::default_spec();
+        let state = state_at_epoch(1, &spec);
+        let unknown = PublicKey::default();
+
+        let monitor = ValidatorMonitor::<E>::new(
+            vec![state.validators[3].pubkey.clone(), unknown.clone()],
+            null_logger(),
+        );
+        monitor.process_valid_state(&state, &spec);
+
+        assert_eq!(
+            monitor.validator_indices(),
+            vec![
+                (state.validators[3].pubkey.clone(), Some(3)),
+                (unknown, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn summarizes_monitored_attestations_only() {
+        let spec = E::default_spec();
+        let state = state_at_epoch(1, &spec);
+
+        let monitor = ValidatorMonitor::<E>::new(
+            vec![
+                state.validators[1].pubkey.clone(),
+                state.validators[2].pubkey.clone(),
+            ],
+            null_logger(),
+        );
+        monitor.process_valid_state(&state, &spec);
+
+        monitor.register_gossip_attestation(&attestation(&[1, 5], 2));
+        monitor.register_attestation_in_block(&attestation(&[1, 5], 2), 3);
+        monitor.register_attestation_in_block(&attestation(&[1, 2], 2), 1);
+
+        assert_eq!(
+            monitor.get_summary(1, Epoch::new(2)),
+            Some(EpochSummary {
+                gossip_attestations: 1,
+                block_inclusions: 2,
+                min_inclusion_distance: Some(1),
+            })
+        );
+        assert_eq!(
+            monitor.get_summary(2, Epoch::new(2)),
+            Some(EpochSummary {
+                gossip_attestations: 0,
+                block_inclusions: 1,
+                min_inclusion_distance: Some(1),
+            })
+        );
+        assert_eq!(monitor.get_summary(5, Epoch::new(2)), None);
+    }
+
+    #[test]
+    fn discards_summaries_once_reported() {
+        let spec = E::default_spec();
+        let monitor = {
+            let state = state_at_epoch(1, &spec);
+            ValidatorMonitor::<E>::new(vec![state.validators[1].pubkey.clone()], null_logger())
+        };
+
+        monitor.process_valid_state(&state_at_epoch(1, &spec), &spec);
+        monitor.register_attestation_in_block(&attestation(&[1], 2), 1);
+        monitor.register_attestation_in_block(&attestation(&[1], 3), 1);
+
+        monitor.process_valid_state(&state_at_epoch(3, &spec), &spec);
+        assert!(monitor.get_summary(1, Epoch::new(2)).is_some());
+
+        monitor.process_valid_state(&state_at_epoch(4, &spec), &spec);
+        assert!(monitor.get_summary(1, Epoch::new(2)).is_none());
+        assert!(monitor.get_summary(1, Epoch::new(3)).is_some());
+    }
+}
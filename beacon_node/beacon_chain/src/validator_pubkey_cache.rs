@@ -0,0 +1,133 @@
+use crate::errors::BeaconChainError as Error;
+use std::sync::Arc;
+use store::{DBColumn, Error as StoreError, Store};
+use types::{BeaconState, EthSpec, PublicKey};
+
+/// Provides a mapping of `validator_index -> validator_pubkey`, shared across all states.
+///
+/// Each `BeaconState` stores a public key for every validator, however those keys are
+/// decompressed again each time a state is read from the store. Decompression is expensive, so
+/// this cache keeps a single decompressed copy of each key for signature verification.
+///
+/// Validator indices are determined by the order of deposits, so the same index refers to the
+/// same public key in all states on all forks. Therefore the cache is append-only. Each key is
+/// persisted in the store (in uncompressed form) when it is added, so the cache does not need to
+/// be rebuilt from the state when the node restarts.
+pub struct ValidatorPubkeyCache<U: Store> {
+    pubkeys: Vec<PublicKey>,
+    store: Arc<U>,
+}
+
+impl<U: Store> ValidatorPubkeyCache<U> {
+    /// Load the public keys persisted in `store`, then add any of the validators in `state` that
+    /// were not yet persisted.
+    pub fn new<E: EthSpec>(state: &BeaconState<E>, store: Arc<U>) -> Result<Self, Error> {
+        let mut cache = Self::load_from_store(store)?;
+        cache.import_new_pubkeys(state)?;
+        Ok(cache)
+    }
+
+    /// Load all of the public keys persisted in `store`.
+    pub fn load_from_store(store: Arc<U>) -> Result<Self, Error> {
+        let mut pubkeys = vec![];
+
+        while let Some(bytes) =
+            store.get_bytes(DBColumn::PubkeyCache.into(), &key(pubkeys.len()))?
+        {
+            pubkeys.push(PublicKey::from_uncompressed_bytes(&bytes).map_err(StoreError::from)?);
+        }
+
+        Ok(Self { pubkeys, store })
+    }
+
+    /// Add the public keys of any validators in `state` that are not yet in `self`, persisting
+    /// them in the store.
+    pub fn import_new_pubkeys<E: EthSpec>(&mut self, state: &BeaconState<E>) -> Result<(), Error> {
+        for validator in state.validators.iter().skip(self.pubkeys.len()) {
+            self.store.put_bytes(
+                DBColumn::PubkeyCache.into(),
+                &key(self.pubkeys.len()),
+                &validator.pubkey.as_uncompressed_bytes(),
+            )?;
+            self.pubkeys.push(validator.pubkey.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Get the public key for a validator with index `i`.
+    pub fn get(&self, i: usize) -> Option<&PublicKey> {
+        self.pubkeys.get(i)
+    }
+
+    /// Returns the number of validators in the cache.
+    pub fn len(&self) -> usize {
+        self.pubkeys.len()
+    }
+
+    /// Returns `true` if there are no validators in the cache.
+    pub fn is_empty(&self) -> bool {
+        self.pubkeys.is_empty()
+    }
+}
+
+/// The key of the public key of the validator with the given index in the `PubkeyCache` column.
+fn key(validator_index: usize) -> [u8; 8] {
+    (validator_index as u64).to_be_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use store::MemoryStore;
+    use types::test_utils::TestingBeaconStateBuilder;
+    use types::{MinimalEthSpec, Validator};
+
+    type E = MinimalEthSpec;
+
+    fn get_state(validator_count: usize) -> BeaconState<E> {
+        let spec = E::default_spec();
+        let builder =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(validator_count, &spec);
+        let (state, _keypairs) = builder.build();
+        state
+    }
+
+    fn check_cache(cache: &ValidatorPubkeyCache<MemoryStore>, state: &BeaconState<E>) {
+        assert_eq!(cache.len(), state.validators.len());
+        for (i, validator) in state.validators.iter().enumerate() {
+            assert_eq!(cache.get(i), Some(&validator.pubkey));
+        }
+        assert_eq!(cache.get(state.validators.len()), None);
+    }
+
+    #[test]
+    fn import_and_persist() {
+        let store = Arc::new(MemoryStore::open());
+        let mut state = get_state(8);
+
+        let mut cache = ValidatorPubkeyCache::new(&state, store.clone()).unwrap();
+        check_cache(&cache, &state);
+
+        // Importing the same state again is a no-op.
+        cache.import_new_pubkeys(&state).unwrap();
+        check_cache(&cache, &state);
+
+        // Add a new validator to the state.
+        let pubkey = get_state(9).validators[8].pubkey.clone();
+        state
+            .validators
+            .push(Validator {
+                pubkey,
+                ..Validator::default()
+            })
+            .unwrap();
+
+        cache.import_new_pubkeys(&state).unwrap();
+        check_cache(&cache, &state);
+
+        // The cache can be restored from the store alone.
+        let restored = ValidatorPubkeyCache::load_from_store(store).unwrap();
+        check_cache(&restored, &state);
+    }
+}
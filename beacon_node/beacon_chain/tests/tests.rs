@@ -7,7 +7,7 @@ use beacon_chain::test_utils::{
     AttestationStrategy, BeaconChainHarness, BlockStrategy, CommonTypes, PersistedBeaconChain,
     BEACON_CHAIN_DB_KEY,
 };
-use beacon_chain::AttestationProcessingOutcome;
+use beacon_chain::{AttestationProcessingOutcome, OperationPoolLimits};
 use lmd_ghost::ThreadSafeReducedTree;
 use rand::Rng;
 use store::{MemoryStore, Store};
@@ -322,7 +322,9 @@ fn roundtrip_operation_pool() {
     let p: PersistedBeaconChain<CommonTypes<TestForkChoice, MinimalEthSpec>> =
         harness.chain.store.get(&key).unwrap().unwrap();
 
-    let restored_op_pool = p.op_pool.into_operation_pool(&p.state, &harness.spec);
+    let restored_op_pool =
+        p.op_pool
+            .into_operation_pool(OperationPoolLimits::default(), &p.state, &harness.spec);
 
     assert_eq!(harness.chain.op_pool, restored_op_pool);
 }
@@ -9,7 +9,8 @@ use beacon_chain::{
 };
 use slog::{crit, info, Logger};
 use slot_clock::SlotClock;
-use std::fs::File;
+use ssz::Decode;
+use std::fs::{self, File};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::SystemTime;
@@ -79,6 +80,14 @@ where
             serde_yaml::from_reader(file)
                 .map_err(|e| format!("Unable to parse YAML genesis state file: {:?}", e))?
         }
+        GenesisState::SszFile { file } => {
+            let bytes = fs::read(file).map_err(|e| {
+                format!("Unable to read SSZ genesis state file {:?}: {:?}", file, e)
+            })?;
+
+            BeaconState::from_ssz_bytes(&bytes)
+                .map_err(|e| format!("Unable to parse SSZ genesis state file: {:?}", e))?
+        }
         GenesisState::HttpBootstrap { server } => {
             let bootstrapper = Bootstrapper::from_server_string(server.to_string())
                 .map_err(|e| format!("Failed to initialize bootstrap client: {}", e))?;
@@ -103,9 +112,12 @@ where
     );
 
     // Try load an existing `BeaconChain` from the store. If unable, create a new one.
-    if let Ok(Some(beacon_chain)) =
-        BeaconChain::from_store(store.clone(), spec.clone(), log.clone())
-    {
+    if let Ok(Some(beacon_chain)) = BeaconChain::from_store(
+        store.clone(),
+        spec.clone(),
+        config.op_pool_limits.clone(),
+        log.clone(),
+    ) {
         // Here we check to ensure that the `BeaconChain` loaded from store has the expected
         // genesis block.
         //
@@ -135,6 +147,7 @@ where
             genesis_state,
             genesis_block,
             spec,
+            config.op_pool_limits.clone(),
             log.clone(),
         )
         .map_err(|e| format!("Failed to initialize new beacon chain: {:?}", e).into())
@@ -1,11 +1,14 @@
 use crate::{Bootstrapper, Eth2Config};
+use beacon_chain::{OperationPoolLimits, SlasherConfig};
 use clap::ArgMatches;
+use eth2_config::Eth2TestnetDir;
 use network::NetworkConfig;
 use serde_derive::{Deserialize, Serialize};
 use slog::{info, o, warn, Drain};
 use std::fs::{self, OpenOptions};
 use std::path::PathBuf;
 use std::sync::Mutex;
+use types::PublicKey;
 
 /// The number initial validators when starting the `Minimal`.
 const TESTNET_VALIDATOR_COUNT: usize = 16;
@@ -25,6 +28,15 @@ pub struct Config {
     pub network: network::NetworkConfig,
     pub rpc: rpc::RPCConfig,
     pub rest_api: rest_api::ApiConfig,
+    /// If `Some`, run a slasher which adds any slashings it detects to the op pool.
+    pub slasher: Option<SlasherConfig>,
+    /// The indices of the validators for which reports of the rewards and penalties applied at
+    /// each epoch transition are stored.
+    pub reward_report_validators: Vec<usize>,
+    /// The public keys of the validators tracked by the validator monitor.
+    pub monitor_validators: Vec<PublicKey>,
+    /// The maximum number of each type of operation stored in the operation pool.
+    pub op_pool_limits: OperationPoolLimits,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +58,8 @@ pub enum GenesisState {
     },
     /// Load a YAML-encoded genesis state from a file.
     Yaml { file: PathBuf },
+    /// Load a SSZ-encoded genesis state from a file.
+    SszFile { file: PathBuf },
     /// Use a HTTP server (running our REST-API) to load genesis and finalized states and blocks.
     HttpBootstrap { server: String },
 }
@@ -60,6 +74,10 @@ impl Default for Config {
             network: NetworkConfig::new(),
             rpc: rpc::RPCConfig::default(),
             rest_api: rest_api::ApiConfig::default(),
+            slasher: None,
+            reward_report_validators: vec![],
+            monitor_validators: vec![],
+            op_pool_limits: OperationPoolLimits::default(),
             spec_constants: TESTNET_SPEC_CONSTANTS.into(),
             genesis_state: GenesisState::RecentGenesis {
                 validator_count: TESTNET_VALIDATOR_COUNT,
@@ -136,6 +154,11 @@ impl Config {
             }
         }
 
+        // The testnet directory may be overridden by the `--bootstrap` flag, below.
+        if let Some(dir) = args.value_of("testnet-dir") {
+            apply_testnet_dir(self, PathBuf::from(dir))?;
+        }
+
         if let Some(dir) = args.value_of("db") {
             self.db_type = dir.to_string();
         };
@@ -144,6 +167,35 @@ impl Config {
         self.rpc.apply_cli_args(args)?;
         self.rest_api.apply_cli_args(args)?;
 
+        if args.is_present("slasher") {
+            let mut slasher = self.slasher.take().unwrap_or_default();
+
+            if let Some(history_length) = args.value_of("slasher-history-length") {
+                slasher.history_length = history_length
+                    .parse::<u64>()
+                    .map_err(|_| "slasher-history-length is not a valid u64.")?;
+            }
+
+            self.slasher = Some(slasher);
+        }
+
+        if let Some(indices) = args.value_of("reward-report-validators") {
+            self.reward_report_validators = indices
+                .split(',')
+                .map(|index| index.trim().parse::<usize>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| {
+                    "reward-report-validators must be a comma-separated list of indices."
+                })?;
+        }
+
+        if let Some(pubkeys) = args.value_of("monitor-validators") {
+            self.monitor_validators = pubkeys
+                .split(',')
+                .map(|pubkey| parse_pubkey(pubkey.trim()))
+                .collect::<Result<_, _>>()?;
+        }
+
         if let Some(log_file) = args.value_of("logfile") {
             self.log_file = PathBuf::from(log_file);
             self.update_logger(log)?;
@@ -158,6 +210,27 @@ impl Config {
     }
 }
 
+/// Reads the testnet in `dir`, setting the spec constants, genesis state and boot nodes of the
+/// `config`.
+fn apply_testnet_dir(config: &mut Config, dir: PathBuf) -> Result<(), String> {
+    let testnet_dir = Eth2TestnetDir::load(dir.clone())?;
+
+    config.spec_constants = testnet_dir.spec_constants()?;
+
+    if let Some(file) = testnet_dir.genesis_state {
+        config.genesis_state = GenesisState::SszFile { file };
+    }
+
+    for enr in &testnet_dir.boot_enr {
+        config.network.boot_nodes.push(
+            enr.parse()
+                .map_err(|_| format!("Invalid ENR in testnet directory {:?}: {}", dir, enr))?,
+        );
+    }
+
+    Ok(())
+}
+
 /// Perform the HTTP bootstrapping procedure, reading an ENR and multiaddr from the HTTP server and
 /// adding them to the `config`.
 fn do_bootstrapping(config: &mut Config, server: String, log: &slog::Logger) -> Result<(), String> {
@@ -186,3 +259,17 @@ fn do_bootstrapping(config: &mut Config, server: String, log: &slog::Logger) ->
 
     Ok(())
 }
+
+/// Parses a `0x`-prefixed, hex-encoded public key.
+fn parse_pubkey(string: &str) -> Result<PublicKey, String> {
+    const PREFIX: &str = "0x";
+
+    if !string.starts_with(PREFIX) {
+        return Err(format!("Public key must have a '0x' prefix: {}", string));
+    }
+
+    let bytes = hex::decode(string.trim_start_matches(PREFIX))
+        .map_err(|e| format!("Invalid hex in public key {}: {:?}", string, e))?;
+
+    PublicKey::from_bytes(&bytes).map_err(|e| format!("Invalid public key {}: {:?}", string, e))
+}
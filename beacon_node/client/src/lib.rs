@@ -18,6 +18,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::runtime::TaskExecutor;
 use tokio::timer::Interval;
+use types::{Epoch, EthSpec};
 
 pub use beacon_chain::BeaconChainTypes;
 pub use beacon_chain_types::ClientType;
@@ -63,12 +64,38 @@ where
         let seconds_per_slot = eth2_config.spec.seconds_per_slot;
 
         // Load a `BeaconChain` from the store, or create a new one if it does not exist.
-        let beacon_chain = Arc::new(T::initialise_beacon_chain(
+        let mut beacon_chain = T::initialise_beacon_chain(
             store,
             &client_config,
             eth2_config.spec.clone(),
             log.clone(),
-        )?);
+        )?;
+
+        if let Some(slasher_config) = &client_config.slasher {
+            info!(
+                log,
+                "Slasher enabled";
+                "history_length" => slasher_config.history_length,
+            );
+            beacon_chain
+                .enable_slasher(slasher_config.clone())
+                .map_err(|e| format!("Unable to start slasher: {:?}", e))?;
+        }
+
+        if !client_config.reward_report_validators.is_empty() {
+            beacon_chain.enable_reward_reports(client_config.reward_report_validators.clone());
+        }
+
+        if !client_config.monitor_validators.is_empty() {
+            info!(
+                log,
+                "Validator monitor enabled";
+                "validator_count" => client_config.monitor_validators.len(),
+            );
+            beacon_chain.enable_validator_monitor(client_config.monitor_validators.clone());
+        }
+
+        let beacon_chain = Arc::new(beacon_chain);
 
         if beacon_chain.read_slot_clock().is_none() {
             panic!("Cannot start client before genesis!")
@@ -95,11 +122,12 @@ where
 
         // Start the network service, libp2p and syncing threads
         // TODO: Add beacon_chain reference to network parameters
-        let network_config = &client_config.network;
+        let mut network_config = client_config.network.clone();
+        network_config.fork_versions = eth2_config.spec.fork_versions();
         let network_logger = log.new(o!("Service" => "Network"));
         let (network, network_send) = NetworkService::new(
             beacon_chain.clone(),
-            network_config,
+            &network_config,
             executor,
             network_logger,
         )?;
@@ -149,11 +177,13 @@ where
 
             let chain = beacon_chain.clone();
             let log = log.new(o!("Service" => "SlotTimer"));
+            let mut slasher_epoch = None;
             executor.spawn(
                 exit.until(
                     interval
                         .for_each(move |_| {
                             do_state_catchup(&chain, &log);
+                            do_slasher_batch(&chain, &mut slasher_epoch, &log);
 
                             Ok(())
                         })
@@ -219,3 +249,33 @@ fn do_state_catchup<T: BeaconChainTypes>(chain: &Arc<BeaconChain<T>>, log: &slog
         );
     };
 }
+
+/// Processes the attestations and block headers queued for the slasher once the epoch has
+/// advanced past `slasher_epoch`, the epoch of the previous batch.
+///
+/// The batch is processed on its own thread so that it does not delay the slot timer.
+fn do_slasher_batch<T: BeaconChainTypes>(
+    chain: &Arc<BeaconChain<T>>,
+    slasher_epoch: &mut Option<Epoch>,
+    log: &slog::Logger,
+) {
+    if let Some(current_slot) = chain.read_slot_clock() {
+        let current_epoch = current_slot.epoch(T::EthSpec::slots_per_epoch());
+
+        if slasher_epoch.map_or(true, |epoch| current_epoch > epoch) {
+            *slasher_epoch = Some(current_epoch);
+
+            let chain = chain.clone();
+            if let Err(e) = std::thread::Builder::new()
+                .name("slasher".into())
+                .spawn(move || chain.process_slasher_queue())
+            {
+                error!(
+                    log,
+                    "Unable to start slasher batch";
+                    "error" => format!("{:?}", e)
+                );
+            }
+        }
+    }
+}
@@ -1,13 +1,14 @@
 use crate::discovery::Discovery;
-use crate::rpc::{RPCEvent, RPCMessage, RPC};
+use crate::peer_manager::{PeerAction, PeerManager};
+use crate::rpc::{RPCError, RPCEvent, RPCMessage, RPC};
+use crate::{base_topic, BEACON_ATTESTATION_TOPIC, BEACON_BLOCK_TOPIC};
 use crate::{error, NetworkConfig};
 use crate::{Topic, TopicHash};
-use crate::{BEACON_ATTESTATION_TOPIC, BEACON_BLOCK_TOPIC};
 use futures::prelude::*;
 use libp2p::{
     core::identity::Keypair,
     discv5::Discv5Event,
-    gossipsub::{Gossipsub, GossipsubEvent},
+    gossipsub::{Gossipsub, GossipsubEvent, MessageId},
     identify::{Identify, IdentifyEvent},
     ping::{Ping, PingConfig, PingEvent},
     swarm::{NetworkBehaviourAction, NetworkBehaviourEventProcess},
@@ -17,9 +18,12 @@ use libp2p::{
 use slog::{debug, o, trace};
 use ssz::{ssz_encode, Encode};
 use std::num::NonZeroU32;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio_timer::Interval;
 
 const MAX_IDENTIFY_ADDRESSES: usize = 20;
+/// The interval between peer manager heartbeats, which lift expired bans.
+const PEER_MANAGER_HEARTBEAT_SECS: u64 = 30;
 
 /// Builds the network behaviour that manages the core protocols of eth2.
 /// This core behaviour is managed by `Behaviour` which adds peer management to all core
@@ -42,6 +46,12 @@ pub struct Behaviour<TSubstream: AsyncRead + AsyncWrite> {
     #[behaviour(ignore)]
     /// The events generated by this behaviour to be consumed in the swarm poll.
     events: Vec<BehaviourEvent>,
+    #[behaviour(ignore)]
+    /// Keeps the scores of peers and decides which peers are banned.
+    peer_manager: PeerManager,
+    #[behaviour(ignore)]
+    /// Triggers the peer manager heartbeat.
+    peer_manager_heartbeat: Interval,
     /// Logger for behaviour actions.
     #[behaviour(ignore)]
     log: slog::Logger,
@@ -75,6 +85,11 @@ impl<TSubstream: AsyncRead + AsyncWrite> Behaviour<TSubstream> {
             ping: Ping::new(ping_config),
             identify,
             events: Vec::new(),
+            peer_manager: PeerManager::new(&net_conf.network_dir, log),
+            peer_manager_heartbeat: Interval::new(
+                Instant::now(),
+                Duration::from_secs(PEER_MANAGER_HEARTBEAT_SECS),
+            ),
             log: behaviour_log,
         })
     }
@@ -90,13 +105,16 @@ impl<TSubstream: AsyncRead + AsyncWrite> NetworkBehaviourEventProcess<GossipsubE
 {
     fn inject_event(&mut self, event: GossipsubEvent) {
         match event {
-            GossipsubEvent::Message(gs_msg) => {
+            GossipsubEvent::Message(propagation_source, id, gs_msg) => {
                 trace!(self.log, "Received GossipEvent"; "msg" => format!("{:?}", gs_msg));
 
                 let msg = PubsubMessage::from_topics(&gs_msg.topics, gs_msg.data);
 
+                // The message is not propagated until it has been validated, see
+                // `Self::validate_gossip`.
                 self.events.push(BehaviourEvent::GossipMessage {
-                    source: gs_msg.source,
+                    id,
+                    source: propagation_source,
                     topics: gs_msg.topics,
                     message: msg,
                 });
@@ -119,6 +137,28 @@ impl<TSubstream: AsyncRead + AsyncWrite> NetworkBehaviourEventProcess<RPCMessage
                 self.events.push(BehaviourEvent::PeerDisconnected(peer_id))
             }
             RPCMessage::RPC(peer_id, rpc_event) => {
+                match &rpc_event {
+                    RPCEvent::Request(..) => {
+                        if self.peer_manager.on_rpc_request(&peer_id) {
+                            self.events
+                                .push(BehaviourEvent::PeerBanned(peer_id.clone()));
+                        }
+                    }
+                    RPCEvent::Error(_, error) => {
+                        let action = match error {
+                            // The peer sent bytes that are not valid for the protocol.
+                            RPCError::SSZDecodeError(_)
+                            | RPCError::SnappyError(_)
+                            | RPCError::InvalidProtocol(_) => PeerAction::MidToleranceError,
+                            RPCError::ReadError(_)
+                            | RPCError::IoError(_)
+                            | RPCError::StreamTimeout
+                            | RPCError::Custom(_) => PeerAction::HighToleranceError,
+                        };
+                        self.report_peer(&peer_id, action);
+                    }
+                    RPCEvent::Response(..) => {}
+                }
                 self.events.push(BehaviourEvent::RPC(peer_id, rpc_event))
             }
         }
@@ -138,6 +178,13 @@ impl<TSubstream: AsyncRead + AsyncWrite> Behaviour<TSubstream> {
     fn poll<TBehaviourIn>(
         &mut self,
     ) -> Async<NetworkBehaviourAction<TBehaviourIn, BehaviourEvent>> {
+        // Lift any expired bans.
+        while let Ok(Async::Ready(Some(_))) = self.peer_manager_heartbeat.poll() {
+            for peer_id in self.peer_manager.heartbeat() {
+                self.events.push(BehaviourEvent::PeerUnbanned(peer_id));
+            }
+        }
+
         if !self.events.is_empty() {
             return Async::Ready(NetworkBehaviourAction::GenerateEvent(self.events.remove(0)));
         }
@@ -199,6 +246,32 @@ impl<TSubstream: AsyncRead + AsyncWrite> Behaviour<TSubstream> {
         }
     }
 
+    /// Informs gossipsub of the result of validating the message with `message_id`, which was
+    /// received from `propagation_source`.
+    ///
+    /// Only accepted messages are propagated to our peers. Rejected messages reduce the score of
+    /// `propagation_source`.
+    pub fn validate_gossip(
+        &mut self,
+        message_id: &MessageId,
+        propagation_source: &PeerId,
+        result: GossipValidationResult,
+    ) {
+        match result {
+            GossipValidationResult::Accept => {
+                self.gossipsub
+                    .propagate_message(message_id, propagation_source);
+            }
+            GossipValidationResult::Ignore => {}
+            GossipValidationResult::Reject => {
+                debug!(self.log, "Rejected gossip message";
+                    "peer" => format!("{:?}", propagation_source),
+                );
+                self.report_peer(propagation_source, PeerAction::LowToleranceError);
+            }
+        }
+    }
+
     /* Eth2 RPC behaviour functions */
 
     /// Sends an RPC Request/Response via the RPC protocol.
@@ -210,6 +283,25 @@ impl<TSubstream: AsyncRead + AsyncWrite> Behaviour<TSubstream> {
     pub fn connected_peers(&self) -> usize {
         self.discovery.connected_peers()
     }
+
+    /// Updates the score of `peer_id` due to `action`, banning the peer if its score becomes too
+    /// low.
+    pub fn report_peer(&mut self, peer_id: &PeerId, action: PeerAction) {
+        if self.peer_manager.report_peer(peer_id, action) {
+            self.events
+                .push(BehaviourEvent::PeerBanned(peer_id.clone()));
+        }
+    }
+
+    /// Returns the current score of `peer_id`.
+    pub fn peer_score(&self, peer_id: &PeerId) -> f64 {
+        self.peer_manager.score(peer_id)
+    }
+
+    /// Returns the peers that are currently banned.
+    pub fn banned_peers(&self) -> Vec<PeerId> {
+        self.peer_manager.banned_peers()
+    }
 }
 
 /// The types of events than can be obtained from polling the behaviour.
@@ -217,13 +309,32 @@ pub enum BehaviourEvent {
     RPC(PeerId, RPCEvent),
     PeerDialed(PeerId),
     PeerDisconnected(PeerId),
+    /// The peer's score has become too low. It must be disconnected and banned.
+    PeerBanned(PeerId),
+    /// The peer's ban has expired.
+    PeerUnbanned(PeerId),
     GossipMessage {
+        /// The identifier used to report the validation result to gossipsub.
+        id: MessageId,
+        /// The peer that forwarded the message to us.
         source: PeerId,
         topics: Vec<TopicHash>,
         message: PubsubMessage,
     },
 }
 
+/// The result of validating a gossip message, which determines whether it is propagated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GossipValidationResult {
+    /// The message is valid and is propagated to our peers.
+    Accept,
+    /// The message could not be validated, or is not useful (e.g., it is a duplicate). It is not
+    /// propagated, but the sending peer is not penalized.
+    Ignore,
+    /// The message is invalid. It is not propagated and counts against the sending peer.
+    Reject,
+}
+
 /// Messages that are passed to and from the pubsub (Gossipsub) behaviour.
 #[derive(Debug, Clone, PartialEq)]
 pub enum PubsubMessage {
@@ -244,7 +355,7 @@ impl PubsubMessage {
      */
     fn from_topics(topics: &Vec<TopicHash>, data: Vec<u8>) -> Self {
         for topic in topics {
-            match topic.as_str() {
+            match base_topic(topic.as_str()) {
                 BEACON_BLOCK_TOPIC => return PubsubMessage::Block(data),
                 BEACON_ATTESTATION_TOPIC => return PubsubMessage::Attestation(data),
                 _ => {}
@@ -10,6 +10,34 @@ use std::time::Duration;
 pub const BEACON_BLOCK_TOPIC: &str = "beacon_block";
 pub const BEACON_ATTESTATION_TOPIC: &str = "beacon_attestation";
 pub const SHARD_TOPIC_PREFIX: &str = "shard";
+/// Separates a topic from the fork version it applies to.
+pub const TOPIC_FORK_VERSION_SEPARATOR: &str = "/";
+
+/// Returns the name of `topic` for the chain with `fork_version`.
+///
+/// Topics for the genesis fork version are unchanged, so that nodes without a fork schedule
+/// remain compatible. Topics for any later version have the version appended (e.g.,
+/// `beacon_block/01000000`), so that messages from each side of a fork are kept separate.
+pub fn fork_topic(topic: &str, fork_version: [u8; 4]) -> String {
+    if fork_version == [0; 4] {
+        topic.to_string()
+    } else {
+        format!(
+            "{}{}{}",
+            topic,
+            TOPIC_FORK_VERSION_SEPARATOR,
+            hex::encode(fork_version)
+        )
+    }
+}
+
+/// Returns `topic` without any fork version added by `fork_topic`.
+pub fn base_topic(topic: &str) -> &str {
+    topic
+        .split(TOPIC_FORK_VERSION_SEPARATOR)
+        .next()
+        .unwrap_or(topic)
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
@@ -48,6 +76,10 @@ pub struct Config {
 
     /// List of extra topics to initially subscribe to as strings.
     pub topics: Vec<String>,
+
+    /// The fork versions for which the block and attestation topics are subscribed to. Should
+    /// include every version in the fork schedule, so no messages are missed during a fork.
+    pub fork_versions: Vec<[u8; 4]>,
 }
 
 impl Default for Config {
@@ -68,11 +100,14 @@ impl Default for Config {
             gs_config: GossipsubConfigBuilder::new()
                 .max_transmit_size(1_000_000)
                 .heartbeat_interval(Duration::from_secs(20))
+                // Messages are only propagated once they have been validated.
+                .manual_propagation()
                 .build(),
             boot_nodes: vec![],
             libp2p_nodes: vec![],
             client_version: version::version(),
             topics: Vec::new(),
+            fork_versions: vec![[0; 4]],
         }
     }
 }
@@ -153,3 +188,24 @@ impl Config {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fork_topic_round_trip() {
+        assert_eq!(fork_topic(BEACON_BLOCK_TOPIC, [0; 4]), BEACON_BLOCK_TOPIC);
+        assert_eq!(
+            fork_topic(BEACON_BLOCK_TOPIC, [1, 0, 0, 0]),
+            "beacon_block/01000000"
+        );
+
+        for version in &[[0; 4], [1, 0, 0, 0]] {
+            assert_eq!(
+                base_topic(&fork_topic(BEACON_ATTESTATION_TOPIC, *version)),
+                BEACON_ATTESTATION_TOPIC
+            );
+        }
+    }
+}
@@ -10,21 +10,24 @@ mod config;
 mod discovery;
 pub mod error;
 mod metrics;
+mod peer_manager;
 pub mod rpc;
 mod service;
 
-pub use behaviour::PubsubMessage;
+pub use behaviour::{GossipValidationResult, PubsubMessage};
 pub use config::{
-    Config as NetworkConfig, BEACON_ATTESTATION_TOPIC, BEACON_BLOCK_TOPIC, SHARD_TOPIC_PREFIX,
+    base_topic, fork_topic, Config as NetworkConfig, BEACON_ATTESTATION_TOPIC, BEACON_BLOCK_TOPIC,
+    SHARD_TOPIC_PREFIX, TOPIC_FORK_VERSION_SEPARATOR,
 };
 pub use libp2p::enr::Enr;
-pub use libp2p::gossipsub::{Topic, TopicHash};
+pub use libp2p::gossipsub::{MessageId, Topic, TopicHash};
 pub use libp2p::multiaddr;
 pub use libp2p::Multiaddr;
 pub use libp2p::{
     gossipsub::{GossipsubConfig, GossipsubConfigBuilder},
     PeerId, Swarm,
 };
+pub use peer_manager::PeerAction;
 pub use rpc::RPCEvent;
 pub use service::Libp2pEvent;
 pub use service::Service;
@@ -0,0 +1,431 @@
+//! Maintains a reputation score for each peer, banning peers whose score becomes too low.
+//!
+//! Peers start with a score of zero. Their score is reduced whenever they misbehave (see
+//! `PeerAction`) and decays back towards zero over time, so that infrequent faults are forgiven.
+//! Peers with a score at or below `MIN_SCORE_BEFORE_BAN` are banned for `BAN_DURATION`.
+//!
+//! Bans are written to disk, so they persist across restarts.
+use libp2p::PeerId;
+use slog::{debug, warn};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The score of a peer that has not misbehaved.
+pub const DEFAULT_SCORE: f64 = 0.0;
+/// The lowest score a peer may have.
+const MIN_SCORE: f64 = -100.0;
+/// Peers with a score at or below this value are disconnected and banned.
+const MIN_SCORE_BEFORE_BAN: f64 = -50.0;
+/// The number of seconds it takes for a score to decay half way back to `DEFAULT_SCORE`.
+const SCORE_HALFLIFE_SECS: f64 = 600.0;
+/// Scores closer than this to `DEFAULT_SCORE` are forgotten.
+const SCORE_PRUNE_THRESHOLD: f64 = 0.1;
+/// The duration for which a peer is banned.
+const BAN_DURATION: Duration = Duration::from_secs(30 * 60);
+/// The length of the window in which the RPC requests of a peer are counted.
+const RPC_REQUEST_WINDOW: Duration = Duration::from_secs(10);
+/// A peer that sends more than this many RPC requests in a `RPC_REQUEST_WINDOW` is spamming.
+const MAX_RPC_REQUESTS_PER_WINDOW: u32 = 50;
+/// The file in the network directory that stores the banned peers.
+const BANNED_PEERS_FILENAME: &str = "banned_peers";
+
+/// An action by a peer that affects its score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PeerAction {
+    /// The peer did something which is always malicious. It is banned immediately.
+    Fatal,
+    /// The peer did something that is very likely to be malicious (e.g., sent an invalid block).
+    /// The peer is banned after a few of these.
+    LowToleranceError,
+    /// The peer did something that may be malicious or may be the result of a bug (e.g., sent a
+    /// message that could not be decoded).
+    MidToleranceError,
+    /// The peer did something that is usually benign, but is malicious if repeated often (e.g., a
+    /// request timed out or the peer sent too many requests).
+    HighToleranceError,
+}
+
+impl PeerAction {
+    /// The amount by which the action changes the score of a peer.
+    fn score_delta(self) -> f64 {
+        match self {
+            PeerAction::Fatal => MIN_SCORE,
+            PeerAction::LowToleranceError => -10.0,
+            PeerAction::MidToleranceError => -5.0,
+            PeerAction::HighToleranceError => -1.0,
+        }
+    }
+}
+
+/// The score of a single peer and the count of its recent RPC requests.
+struct PeerInfo {
+    /// The score as of `last_updated`.
+    score: f64,
+    last_updated: Instant,
+    /// The number of RPC requests received since `rpc_window_start`.
+    rpc_requests: u32,
+    rpc_window_start: Instant,
+}
+
+impl PeerInfo {
+    fn new(now: Instant) -> Self {
+        Self {
+            score: DEFAULT_SCORE,
+            last_updated: now,
+            rpc_requests: 0,
+            rpc_window_start: now,
+        }
+    }
+
+    /// Returns the score at `now`, after it has decayed towards `DEFAULT_SCORE`.
+    fn score_at(&self, now: Instant) -> f64 {
+        let elapsed = now.duration_since(self.last_updated);
+        let elapsed_secs = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_millis()) / 1000.0;
+
+        DEFAULT_SCORE
+            + (self.score - DEFAULT_SCORE) * 0.5_f64.powf(elapsed_secs / SCORE_HALFLIFE_SECS)
+    }
+
+    /// Applies `action` at `now`, returning the new score.
+    fn apply(&mut self, action: PeerAction, now: Instant) -> f64 {
+        self.score = (self.score_at(now) + action.score_delta()).max(MIN_SCORE);
+        self.last_updated = now;
+        self.score
+    }
+}
+
+/// Keeps the scores of peers and decides which peers should be banned.
+///
+/// The `PeerManager` does not connect to or disconnect from peers; it informs the caller which
+/// peers have been banned or unbanned.
+pub struct PeerManager {
+    peers: HashMap<PeerId, PeerInfo>,
+    /// Banned peers and the unix time (in seconds) at which their ban expires.
+    banned_peers: HashMap<PeerId, u64>,
+    /// The file that `banned_peers` is persisted to.
+    banned_peers_file: PathBuf,
+    log: slog::Logger,
+}
+
+impl PeerManager {
+    /// Instantiates a new manager, loading any unexpired bans from `network_dir`.
+    pub fn new(network_dir: &Path, log: &slog::Logger) -> Self {
+        let banned_peers_file = network_dir.join(BANNED_PEERS_FILENAME);
+        let banned_peers = load_banned_peers(&banned_peers_file, unix_time_now(), log);
+
+        Self {
+            peers: HashMap::new(),
+            banned_peers,
+            banned_peers_file,
+            log: log.clone(),
+        }
+    }
+
+    /// Returns the current score of `peer_id`.
+    pub fn score(&self, peer_id: &PeerId) -> f64 {
+        self.peers
+            .get(peer_id)
+            .map(|info| info.score_at(Instant::now()))
+            .unwrap_or(DEFAULT_SCORE)
+    }
+
+    /// Returns `true` if `peer_id` is currently banned.
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.banned_peers.contains_key(peer_id)
+    }
+
+    /// Returns all of the currently banned peers.
+    pub fn banned_peers(&self) -> Vec<PeerId> {
+        self.banned_peers.keys().cloned().collect()
+    }
+
+    /// Updates the score of `peer_id` due to `action`.
+    ///
+    /// Returns `true` if the peer has become banned and should be disconnected.
+    pub fn report_peer(&mut self, peer_id: &PeerId, action: PeerAction) -> bool {
+        self.report_peer_at(peer_id, action, Instant::now(), unix_time_now())
+    }
+
+    /// Records that `peer_id` has sent an RPC request, penalizing it if it has sent too many.
+    ///
+    /// Returns `true` if the peer has become banned and should be disconnected.
+    pub fn on_rpc_request(&mut self, peer_id: &PeerId) -> bool {
+        self.on_rpc_request_at(peer_id, Instant::now(), unix_time_now())
+    }
+
+    /// Forgets the scores that have decayed back to `DEFAULT_SCORE` and lifts expired bans.
+    ///
+    /// Returns the peers which are no longer banned.
+    pub fn heartbeat(&mut self) -> Vec<PeerId> {
+        self.heartbeat_at(Instant::now(), unix_time_now())
+    }
+
+    fn report_peer_at(
+        &mut self,
+        peer_id: &PeerId,
+        action: PeerAction,
+        now: Instant,
+        unix_now: u64,
+    ) -> bool {
+        if self.is_banned(peer_id) {
+            return false;
+        }
+
+        let score = self
+            .peers
+            .entry(peer_id.clone())
+            .or_insert_with(|| PeerInfo::new(now))
+            .apply(action, now);
+
+        debug!(self.log, "Peer score updated";
+            "peer" => format!("{:?}", peer_id),
+            "action" => format!("{:?}", action),
+            "score" => score,
+        );
+
+        if score <= MIN_SCORE_BEFORE_BAN {
+            warn!(self.log, "Banning peer";
+                "peer" => format!("{:?}", peer_id),
+                "score" => score,
+                "duration_secs" => BAN_DURATION.as_secs(),
+            );
+
+            self.banned_peers
+                .insert(peer_id.clone(), unix_now + BAN_DURATION.as_secs());
+            self.save_banned_peers();
+
+            true
+        } else {
+            false
+        }
+    }
+
+    fn on_rpc_request_at(&mut self, peer_id: &PeerId, now: Instant, unix_now: u64) -> bool {
+        let info = self
+            .peers
+            .entry(peer_id.clone())
+            .or_insert_with(|| PeerInfo::new(now));
+
+        if now.duration_since(info.rpc_window_start) >= RPC_REQUEST_WINDOW {
+            info.rpc_requests = 0;
+            info.rpc_window_start = now;
+        }
+        info.rpc_requests += 1;
+
+        if info.rpc_requests > MAX_RPC_REQUESTS_PER_WINDOW {
+            self.report_peer_at(peer_id, PeerAction::HighToleranceError, now, unix_now)
+        } else {
+            false
+        }
+    }
+
+    fn heartbeat_at(&mut self, now: Instant, unix_now: u64) -> Vec<PeerId> {
+        self.peers.retain(|_, info| {
+            (info.score_at(now) - DEFAULT_SCORE).abs() >= SCORE_PRUNE_THRESHOLD
+                || now.duration_since(info.rpc_window_start) < RPC_REQUEST_WINDOW
+        });
+
+        let unbanned: Vec<PeerId> = self
+            .banned_peers
+            .iter()
+            .filter(|(_, unban_time)| **unban_time <= unix_now)
+            .map(|(peer_id, _)| peer_id.clone())
+            .collect();
+
+        if !unbanned.is_empty() {
+            for peer_id in &unbanned {
+                debug!(self.log, "Unbanning peer"; "peer" => format!("{:?}", peer_id));
+                self.banned_peers.remove(peer_id);
+                // The peer starts afresh.
+                self.peers.remove(peer_id);
+            }
+            self.save_banned_peers();
+        }
+
+        unbanned
+    }
+
+    /// Writes the banned peers to disk, one `<peer_id> <unban_time>` pair per line.
+    fn save_banned_peers(&self) {
+        let contents: String = self
+            .banned_peers
+            .iter()
+            .map(|(peer_id, unban_time)| format!("{} {}\n", peer_id.to_base58(), unban_time))
+            .collect();
+
+        if let Some(dir) = self.banned_peers_file.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+
+        if let Err(e) =
+            File::create(&self.banned_peers_file).and_then(|mut f| f.write_all(contents.as_bytes()))
+        {
+            warn!(
+                self.log,
+                "Could not write banned peers to file: {:?}. Error: {}", self.banned_peers_file, e
+            );
+        }
+    }
+}
+
+/// Reads the banned peers from `path`, ignoring any bans that expired before `unix_now`.
+fn load_banned_peers(path: &Path, unix_now: u64, log: &slog::Logger) -> HashMap<PeerId, u64> {
+    let mut contents = String::new();
+    if File::open(path)
+        .and_then(|mut f| f.read_to_string(&mut contents))
+        .is_err()
+    {
+        return HashMap::new();
+    }
+
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let peer_id = parts.next()?.parse::<PeerId>().ok();
+            let unban_time = parts.next()?.parse::<u64>().ok();
+
+            match (peer_id, unban_time) {
+                (Some(peer_id), Some(unban_time)) => Some((peer_id, unban_time)),
+                _ => {
+                    warn!(log, "Invalid banned peer entry"; "entry" => line);
+                    None
+                }
+            }
+        })
+        .filter(|(_, unban_time)| *unban_time > unix_now)
+        .collect()
+}
+
+/// Returns the number of seconds since the unix epoch.
+fn unix_time_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slog::{o, Discard, Logger};
+    use tempfile::TempDir;
+
+    fn manager(dir: &TempDir) -> PeerManager {
+        PeerManager::new(dir.path(), &Logger::root(Discard, o!()))
+    }
+
+    #[test]
+    fn ban_after_repeated_errors() {
+        let dir = TempDir::new().unwrap();
+        let mut manager = manager(&dir);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        for _ in 0..4 {
+            assert!(!manager.report_peer_at(&peer_id, PeerAction::LowToleranceError, now, 0));
+        }
+        assert!(!manager.is_banned(&peer_id));
+
+        assert!(manager.report_peer_at(&peer_id, PeerAction::LowToleranceError, now, 0));
+        assert!(manager.is_banned(&peer_id));
+
+        // Further reports do not ban the peer again.
+        assert!(!manager.report_peer_at(&peer_id, PeerAction::Fatal, now, 0));
+    }
+
+    #[test]
+    fn fatal_bans_immediately() {
+        let dir = TempDir::new().unwrap();
+        let mut manager = manager(&dir);
+        let peer_id = PeerId::random();
+
+        assert!(manager.report_peer(&peer_id, PeerAction::Fatal));
+        assert!(manager.is_banned(&peer_id));
+        assert!(!manager.is_banned(&PeerId::random()));
+    }
+
+    #[test]
+    fn score_decays() {
+        let dir = TempDir::new().unwrap();
+        let mut manager = manager(&dir);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        manager.report_peer_at(&peer_id, PeerAction::LowToleranceError, now, 0);
+
+        let info = &manager.peers[&peer_id];
+        let halflife = Duration::from_secs(SCORE_HALFLIFE_SECS as u64);
+        assert_eq!(info.score_at(now), -10.0);
+        assert_eq!(info.score_at(now + halflife), -5.0);
+
+        // Errors that are far apart never ban the peer.
+        for i in 1..10 {
+            let later = now + halflife * 20 * i;
+            assert!(!manager.report_peer_at(&peer_id, PeerAction::LowToleranceError, later, 0));
+        }
+
+        // The score is forgotten once it has decayed.
+        manager.heartbeat_at(now + halflife * 200, 0);
+        assert!(manager.peers.is_empty());
+    }
+
+    #[test]
+    fn rpc_spam() {
+        let dir = TempDir::new().unwrap();
+        let mut manager = manager(&dir);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        for _ in 0..MAX_RPC_REQUESTS_PER_WINDOW {
+            manager.on_rpc_request_at(&peer_id, now, 0);
+        }
+        assert_eq!(manager.peers[&peer_id].score, DEFAULT_SCORE);
+
+        manager.on_rpc_request_at(&peer_id, now, 0);
+        assert_eq!(manager.peers[&peer_id].score, -1.0);
+
+        // Requests in a new window are not penalized.
+        manager.on_rpc_request_at(&peer_id, now + RPC_REQUEST_WINDOW, 0);
+        assert_eq!(manager.peers[&peer_id].rpc_requests, 1);
+    }
+
+    #[test]
+    fn bans_expire_and_persist() {
+        let dir = TempDir::new().unwrap();
+        let banned = PeerId::random();
+        let expired = PeerId::random();
+
+        {
+            let mut manager = manager(&dir);
+            manager.report_peer_at(&banned, PeerAction::Fatal, Instant::now(), 1_000);
+            manager.report_peer_at(&expired, PeerAction::Fatal, Instant::now(), 0);
+        }
+
+        let unban_time = 1_000 + BAN_DURATION.as_secs();
+        let path = dir.path().join(BANNED_PEERS_FILENAME);
+        let log = Logger::root(Discard, o!());
+
+        let loaded = load_banned_peers(&path, BAN_DURATION.as_secs(), &log);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.get(&banned), Some(&unban_time));
+
+        let mut manager = manager(&dir);
+        manager.banned_peers = load_banned_peers(&path, 0, &log);
+        assert_eq!(
+            manager.heartbeat_at(Instant::now(), unban_time - 1).len(),
+            1
+        );
+        assert!(manager.is_banned(&banned));
+        assert_eq!(
+            manager.heartbeat_at(Instant::now(), unban_time),
+            vec![banned.clone()]
+        );
+        assert!(!manager.is_banned(&banned));
+
+        assert!(load_banned_peers(&path, 0, &log).is_empty());
+    }
+}
@@ -62,10 +62,14 @@ where
     type Error = <TCodec as Encoder>::Error;
 
     fn encode(&mut self, item: Self::Item, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        dst.clear();
-        dst.reserve(1);
-        dst.put_u8(item.as_u8());
-        self.inner.encode(item, dst)
+        // A stream termination is not encoded, the substream is closed instead.
+        if let Some(response_code) = item.as_u8() {
+            dst.reserve(1);
+            dst.put_u8(response_code);
+            self.inner.encode(item, dst)
+        } else {
+            Ok(())
+        }
     }
 }
 
@@ -105,8 +109,10 @@ where
             if let Some(resp_code) = self.response_code {
                 resp_code
             } else {
-                // buffer should not be empty
-                debug_assert!(!src.is_empty());
+                // The response code of the next chunk has not been received yet.
+                if src.is_empty() {
+                    return Ok(None);
+                }
 
                 let resp_byte = src.split_to(1);
                 let mut resp_code_byte = [0; 1];
@@ -118,7 +124,7 @@ where
             }
         };
 
-        if RPCErrorResponse::is_response(response_code) {
+        let result = if RPCErrorResponse::is_response(response_code) {
             // decode an actual response
             self.inner
                 .decode(src)
@@ -128,6 +134,67 @@ where
             self.inner
                 .decode_error(src)
                 .map(|r| r.map(|resp| RPCErrorResponse::from_error(response_code, resp)))
+        };
+
+        // Responses may be sent in several chunks, each with its own response code. Once a chunk
+        // has been decoded, the next byte is the response code of the following chunk.
+        if let Ok(Some(_)) = result {
+            self.response_code = None;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::codec::ssz::{SSZInboundCodec, SSZOutboundCodec};
+    use crate::rpc::protocol::ProtocolId;
+
+    const MAX_PACKET_SIZE: usize = 1_024;
+
+    #[test]
+    fn decode_response_chunks() {
+        let protocol = ProtocolId::new("beacon_blocks_by_range", "1.0.0", "ssz");
+        let mut inbound =
+            BaseInboundCodec::new(SSZInboundCodec::new(protocol.clone(), MAX_PACKET_SIZE));
+        let mut outbound = BaseOutboundCodec::new(SSZOutboundCodec::new(protocol, MAX_PACKET_SIZE));
+
+        let mut bytes = BytesMut::new();
+        for chunk in vec![vec![1; 8], vec![2; 16]] {
+            inbound
+                .encode(
+                    RPCErrorResponse::Success(RPCResponse::BlocksByRange(chunk)),
+                    &mut bytes,
+                )
+                .unwrap();
+        }
+        inbound
+            .encode(
+                RPCErrorResponse::ServerError(ErrorMessage::new("no more blocks")),
+                &mut bytes,
+            )
+            .unwrap();
+
+        match outbound.decode(&mut bytes) {
+            Ok(Some(RPCErrorResponse::Success(RPCResponse::BlocksByRange(chunk)))) => {
+                assert_eq!(chunk, vec![1; 8])
+            }
+            other => panic!("unexpected decoding: {:?}", other),
+        }
+        match outbound.decode(&mut bytes) {
+            Ok(Some(RPCErrorResponse::Success(RPCResponse::BlocksByRange(chunk)))) => {
+                assert_eq!(chunk, vec![2; 16])
+            }
+            other => panic!("unexpected decoding: {:?}", other),
+        }
+        match outbound.decode(&mut bytes) {
+            Ok(Some(RPCErrorResponse::ServerError(error))) => {
+                assert_eq!(error.as_string(), "no more blocks")
+            }
+            other => panic!("unexpected decoding: {:?}", other),
         }
+        assert!(outbound.decode(&mut bytes).unwrap().is_none());
     }
 }
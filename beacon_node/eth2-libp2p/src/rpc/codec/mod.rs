@@ -1,8 +1,10 @@
 pub(crate) mod base;
 pub(crate) mod ssz;
+pub(crate) mod ssz_snappy;
 
 use self::base::{BaseInboundCodec, BaseOutboundCodec};
 use self::ssz::{SSZInboundCodec, SSZOutboundCodec};
+use self::ssz_snappy::{SSZSnappyInboundCodec, SSZSnappyOutboundCodec};
 use crate::rpc::protocol::RPCError;
 use crate::rpc::{RPCErrorResponse, RPCRequest};
 use bytes::BytesMut;
@@ -11,10 +13,12 @@ use tokio::codec::{Decoder, Encoder};
 // Known types of codecs
 pub enum InboundCodec {
     SSZ(BaseInboundCodec<SSZInboundCodec>),
+    SSZSnappy(BaseInboundCodec<SSZSnappyInboundCodec>),
 }
 
 pub enum OutboundCodec {
     SSZ(BaseOutboundCodec<SSZOutboundCodec>),
+    SSZSnappy(BaseOutboundCodec<SSZSnappyOutboundCodec>),
 }
 
 impl Encoder for InboundCodec {
@@ -24,6 +28,7 @@ impl Encoder for InboundCodec {
     fn encode(&mut self, item: Self::Item, dst: &mut BytesMut) -> Result<(), Self::Error> {
         match self {
             InboundCodec::SSZ(codec) => codec.encode(item, dst),
+            InboundCodec::SSZSnappy(codec) => codec.encode(item, dst),
         }
     }
 }
@@ -35,6 +40,7 @@ impl Decoder for InboundCodec {
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         match self {
             InboundCodec::SSZ(codec) => codec.decode(src),
+            InboundCodec::SSZSnappy(codec) => codec.decode(src),
         }
     }
 }
@@ -46,6 +52,7 @@ impl Encoder for OutboundCodec {
     fn encode(&mut self, item: Self::Item, dst: &mut BytesMut) -> Result<(), Self::Error> {
         match self {
             OutboundCodec::SSZ(codec) => codec.encode(item, dst),
+            OutboundCodec::SSZSnappy(codec) => codec.encode(item, dst),
         }
     }
 }
@@ -57,6 +64,7 @@ impl Decoder for OutboundCodec {
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         match self {
             OutboundCodec::SSZ(codec) => codec.decode(src),
+            OutboundCodec::SSZSnappy(codec) => codec.decode(src),
         }
     }
 }
@@ -37,20 +37,7 @@ impl Encoder for SSZInboundCodec {
     type Error = RPCError;
 
     fn encode(&mut self, item: Self::Item, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        let bytes = match item {
-            RPCErrorResponse::Success(resp) => {
-                match resp {
-                    RPCResponse::Hello(res) => res.as_ssz_bytes(),
-                    RPCResponse::BeaconBlockRoots(res) => res.as_ssz_bytes(),
-                    RPCResponse::BeaconBlockHeaders(res) => res.headers, // already raw bytes
-                    RPCResponse::BeaconBlockBodies(res) => res.block_bodies, // already raw bytes
-                    RPCResponse::BeaconChainState(res) => res.as_ssz_bytes(),
-                }
-            }
-            RPCErrorResponse::InvalidRequest(err) => err.as_ssz_bytes(),
-            RPCErrorResponse::ServerError(err) => err.as_ssz_bytes(),
-            RPCErrorResponse::Unknown(err) => err.as_ssz_bytes(),
-        };
+        let bytes = encode_response(item);
 
         if !bytes.is_empty() {
             // length-prefix and return
@@ -70,55 +57,7 @@ impl Decoder for SSZInboundCodec {
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         match self.inner.decode(src).map_err(RPCError::from) {
-            Ok(Some(packet)) => match self.protocol.message_name.as_str() {
-                "hello" => match self.protocol.version.as_str() {
-                    "1.0.0" => Ok(Some(RPCRequest::Hello(HelloMessage::from_ssz_bytes(
-                        &packet,
-                    )?))),
-                    _ => Err(RPCError::InvalidProtocol("Unknown HELLO version")),
-                },
-                "goodbye" => match self.protocol.version.as_str() {
-                    "1.0.0" => Ok(Some(RPCRequest::Goodbye(GoodbyeReason::from_ssz_bytes(
-                        &packet,
-                    )?))),
-                    _ => Err(RPCError::InvalidProtocol(
-                        "Unknown GOODBYE version.as_str()",
-                    )),
-                },
-                "beacon_block_roots" => match self.protocol.version.as_str() {
-                    "1.0.0" => Ok(Some(RPCRequest::BeaconBlockRoots(
-                        BeaconBlockRootsRequest::from_ssz_bytes(&packet)?,
-                    ))),
-                    _ => Err(RPCError::InvalidProtocol(
-                        "Unknown BEACON_BLOCK_ROOTS version.",
-                    )),
-                },
-                "beacon_block_headers" => match self.protocol.version.as_str() {
-                    "1.0.0" => Ok(Some(RPCRequest::BeaconBlockHeaders(
-                        BeaconBlockHeadersRequest::from_ssz_bytes(&packet)?,
-                    ))),
-                    _ => Err(RPCError::InvalidProtocol(
-                        "Unknown BEACON_BLOCK_HEADERS version.",
-                    )),
-                },
-                "beacon_block_bodies" => match self.protocol.version.as_str() {
-                    "1.0.0" => Ok(Some(RPCRequest::BeaconBlockBodies(
-                        BeaconBlockBodiesRequest::from_ssz_bytes(&packet)?,
-                    ))),
-                    _ => Err(RPCError::InvalidProtocol(
-                        "Unknown BEACON_BLOCK_BODIES version.",
-                    )),
-                },
-                "beacon_chain_state" => match self.protocol.version.as_str() {
-                    "1.0.0" => Ok(Some(RPCRequest::BeaconChainState(
-                        BeaconChainStateRequest::from_ssz_bytes(&packet)?,
-                    ))),
-                    _ => Err(RPCError::InvalidProtocol(
-                        "Unknown BEACON_CHAIN_STATE version.",
-                    )),
-                },
-                _ => Err(RPCError::InvalidProtocol("Unknown message name.")),
-            },
+            Ok(Some(packet)) => decode_request(&self.protocol, &packet).map(Some),
             Ok(None) => Ok(None),
             Err(e) => Err(e),
         }
@@ -153,14 +92,7 @@ impl Encoder for SSZOutboundCodec {
     type Error = RPCError;
 
     fn encode(&mut self, item: Self::Item, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        let bytes = match item {
-            RPCRequest::Hello(req) => req.as_ssz_bytes(),
-            RPCRequest::Goodbye(req) => req.as_ssz_bytes(),
-            RPCRequest::BeaconBlockRoots(req) => req.as_ssz_bytes(),
-            RPCRequest::BeaconBlockHeaders(req) => req.as_ssz_bytes(),
-            RPCRequest::BeaconBlockBodies(req) => req.as_ssz_bytes(),
-            RPCRequest::BeaconChainState(req) => req.as_ssz_bytes(),
-        };
+        let bytes = encode_request(item);
         // length-prefix
         self.inner
             .encode(bytes::Bytes::from(bytes), dst)
@@ -175,54 +107,7 @@ impl Decoder for SSZOutboundCodec {
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         match self.inner.decode(src).map_err(RPCError::from) {
-            Ok(Some(packet)) => match self.protocol.message_name.as_str() {
-                "hello" => match self.protocol.version.as_str() {
-                    "1.0.0" => Ok(Some(RPCResponse::Hello(HelloMessage::from_ssz_bytes(
-                        &packet,
-                    )?))),
-                    _ => Err(RPCError::InvalidProtocol("Unknown HELLO version.")),
-                },
-                "goodbye" => Err(RPCError::InvalidProtocol("GOODBYE doesn't have a response")),
-                "beacon_block_roots" => match self.protocol.version.as_str() {
-                    "1.0.0" => Ok(Some(RPCResponse::BeaconBlockRoots(
-                        BeaconBlockRootsResponse::from_ssz_bytes(&packet)?,
-                    ))),
-                    _ => Err(RPCError::InvalidProtocol(
-                        "Unknown BEACON_BLOCK_ROOTS version.",
-                    )),
-                },
-                "beacon_block_headers" => match self.protocol.version.as_str() {
-                    "1.0.0" => Ok(Some(RPCResponse::BeaconBlockHeaders(
-                        BeaconBlockHeadersResponse {
-                            headers: packet.to_vec(),
-                        },
-                    ))),
-                    _ => Err(RPCError::InvalidProtocol(
-                        "Unknown BEACON_BLOCK_HEADERS version.",
-                    )),
-                },
-                "beacon_block_bodies" => match self.protocol.version.as_str() {
-                    "1.0.0" => Ok(Some(RPCResponse::BeaconBlockBodies(
-                        BeaconBlockBodiesResponse {
-                            block_bodies: packet.to_vec(),
-                            // this gets filled in the protocol handler
-                            block_roots: None,
-                        },
-                    ))),
-                    _ => Err(RPCError::InvalidProtocol(
-                        "Unknown BEACON_BLOCK_BODIES version.",
-                    )),
-                },
-                "beacon_chain_state" => match self.protocol.version.as_str() {
-                    "1.0.0" => Ok(Some(RPCResponse::BeaconChainState(
-                        BeaconChainStateResponse::from_ssz_bytes(&packet)?,
-                    ))),
-                    _ => Err(RPCError::InvalidProtocol(
-                        "Unknown BEACON_CHAIN_STATE version.",
-                    )),
-                },
-                _ => Err(RPCError::InvalidProtocol("Unknown method")),
-            },
+            Ok(Some(packet)) => decode_response(&self.protocol, &packet).map(Some),
             Ok(None) => Ok(None),
             Err(e) => Err(e),
         }
@@ -240,3 +125,111 @@ impl OutboundCodec for SSZOutboundCodec {
         }
     }
 }
+
+/* Helpers shared by all SSZ-based encodings */
+
+/// Returns the SSZ bytes of a response. Blocks are already SSZ bytes.
+pub(crate) fn encode_response(item: RPCErrorResponse) -> Vec<u8> {
+    match item {
+        RPCErrorResponse::Success(resp) => {
+            match resp {
+                RPCResponse::Hello(res) => res.as_ssz_bytes(),
+                RPCResponse::BlocksByRange(res) => res, // already raw bytes
+                RPCResponse::BlocksByRoot(res) => res,  // already raw bytes
+                RPCResponse::BeaconChainState(res) => res.as_ssz_bytes(),
+            }
+        }
+        RPCErrorResponse::InvalidRequest(err) => err.as_ssz_bytes(),
+        RPCErrorResponse::ServerError(err) => err.as_ssz_bytes(),
+        RPCErrorResponse::Unknown(err) => err.as_ssz_bytes(),
+        // a stream termination has no encoding
+        RPCErrorResponse::StreamTermination(_) => vec![],
+    }
+}
+
+/// Decodes the SSZ bytes of a request for `protocol`.
+pub(crate) fn decode_request(protocol: &ProtocolId, packet: &[u8]) -> Result<RPCRequest, RPCError> {
+    match protocol.message_name.as_str() {
+        "hello" => match protocol.version.as_str() {
+            "1.0.0" => Ok(RPCRequest::Hello(HelloMessage::from_ssz_bytes(packet)?)),
+            _ => Err(RPCError::InvalidProtocol("Unknown HELLO version")),
+        },
+        "goodbye" => match protocol.version.as_str() {
+            "1.0.0" => Ok(RPCRequest::Goodbye(GoodbyeReason::from_ssz_bytes(packet)?)),
+            _ => Err(RPCError::InvalidProtocol(
+                "Unknown GOODBYE version.as_str()",
+            )),
+        },
+        "beacon_blocks_by_range" => match protocol.version.as_str() {
+            "1.0.0" => Ok(RPCRequest::BlocksByRange(
+                BlocksByRangeRequest::from_ssz_bytes(packet)?,
+            )),
+            _ => Err(RPCError::InvalidProtocol(
+                "Unknown BEACON_BLOCKS_BY_RANGE version.",
+            )),
+        },
+        "beacon_blocks_by_root" => match protocol.version.as_str() {
+            "1.0.0" => Ok(RPCRequest::BlocksByRoot(
+                BlocksByRootRequest::from_ssz_bytes(packet)?,
+            )),
+            _ => Err(RPCError::InvalidProtocol(
+                "Unknown BEACON_BLOCKS_BY_ROOT version.",
+            )),
+        },
+        "beacon_chain_state" => match protocol.version.as_str() {
+            "1.0.0" => Ok(RPCRequest::BeaconChainState(
+                BeaconChainStateRequest::from_ssz_bytes(packet)?,
+            )),
+            _ => Err(RPCError::InvalidProtocol(
+                "Unknown BEACON_CHAIN_STATE version.",
+            )),
+        },
+        _ => Err(RPCError::InvalidProtocol("Unknown message name.")),
+    }
+}
+
+/// Returns the SSZ bytes of a request.
+pub(crate) fn encode_request(item: RPCRequest) -> Vec<u8> {
+    match item {
+        RPCRequest::Hello(req) => req.as_ssz_bytes(),
+        RPCRequest::Goodbye(req) => req.as_ssz_bytes(),
+        RPCRequest::BlocksByRange(req) => req.as_ssz_bytes(),
+        RPCRequest::BlocksByRoot(req) => req.as_ssz_bytes(),
+        RPCRequest::BeaconChainState(req) => req.as_ssz_bytes(),
+    }
+}
+
+/// Decodes the SSZ bytes of a response for `protocol`.
+pub(crate) fn decode_response(
+    protocol: &ProtocolId,
+    packet: &[u8],
+) -> Result<RPCResponse, RPCError> {
+    match protocol.message_name.as_str() {
+        "hello" => match protocol.version.as_str() {
+            "1.0.0" => Ok(RPCResponse::Hello(HelloMessage::from_ssz_bytes(packet)?)),
+            _ => Err(RPCError::InvalidProtocol("Unknown HELLO version.")),
+        },
+        "goodbye" => Err(RPCError::InvalidProtocol("GOODBYE doesn't have a response")),
+        "beacon_blocks_by_range" => match protocol.version.as_str() {
+            "1.0.0" => Ok(RPCResponse::BlocksByRange(packet.to_vec())),
+            _ => Err(RPCError::InvalidProtocol(
+                "Unknown BEACON_BLOCKS_BY_RANGE version.",
+            )),
+        },
+        "beacon_blocks_by_root" => match protocol.version.as_str() {
+            "1.0.0" => Ok(RPCResponse::BlocksByRoot(packet.to_vec())),
+            _ => Err(RPCError::InvalidProtocol(
+                "Unknown BEACON_BLOCKS_BY_ROOT version.",
+            )),
+        },
+        "beacon_chain_state" => match protocol.version.as_str() {
+            "1.0.0" => Ok(RPCResponse::BeaconChainState(
+                BeaconChainStateResponse::from_ssz_bytes(packet)?,
+            )),
+            _ => Err(RPCError::InvalidProtocol(
+                "Unknown BEACON_CHAIN_STATE version.",
+            )),
+        },
+        _ => Err(RPCError::InvalidProtocol("Unknown method")),
+    }
+}
@@ -0,0 +1,240 @@
+//! The `ssz_snappy` encoding.
+//!
+//! Identical to the `ssz` encoding, except that the SSZ bytes of each message are compressed
+//! using the raw snappy format before being length-prefixed. The length-prefix is the length of
+//! the compressed bytes.
+use crate::rpc::codec::{
+    base::OutboundCodec,
+    ssz::{decode_request, decode_response, encode_request, encode_response},
+};
+use crate::rpc::protocol::{ProtocolId, RPCError};
+use crate::rpc::{ErrorMessage, RPCErrorResponse, RPCRequest, RPCResponse};
+use bytes::{Bytes, BytesMut};
+use snap::raw::{decompress_len, Decoder as SnappyDecoder, Encoder as SnappyEncoder};
+use ssz::Decode;
+use tokio::codec::{Decoder, Encoder};
+use unsigned_varint::codec::UviBytes;
+
+/* Inbound Codec */
+
+pub struct SSZSnappyInboundCodec {
+    inner: UviBytes,
+    protocol: ProtocolId,
+    max_packet_size: usize,
+}
+
+impl SSZSnappyInboundCodec {
+    pub fn new(protocol: ProtocolId, max_packet_size: usize) -> Self {
+        let mut uvi_codec = UviBytes::default();
+        uvi_codec.set_max_len(max_packet_size);
+
+        // this encoding only applies to ssz_snappy.
+        debug_assert!(protocol.encoding.as_str() == "ssz_snappy");
+
+        SSZSnappyInboundCodec {
+            inner: uvi_codec,
+            protocol,
+            max_packet_size,
+        }
+    }
+}
+
+// Encoder for inbound
+impl Encoder for SSZSnappyInboundCodec {
+    type Item = RPCErrorResponse;
+    type Error = RPCError;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let bytes = encode_response(item);
+
+        if !bytes.is_empty() {
+            // compress, length-prefix and return
+            return self
+                .inner
+                .encode(Bytes::from(compress(&bytes)?), dst)
+                .map_err(RPCError::from);
+        }
+        Ok(())
+    }
+}
+
+// Decoder for inbound
+impl Decoder for SSZSnappyInboundCodec {
+    type Item = RPCRequest;
+    type Error = RPCError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.inner.decode(src).map_err(RPCError::from) {
+            Ok(Some(packet)) => {
+                let packet = decompress(&packet, self.max_packet_size)?;
+                decode_request(&self.protocol, &packet).map(Some)
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/* Outbound Codec */
+
+pub struct SSZSnappyOutboundCodec {
+    inner: UviBytes,
+    protocol: ProtocolId,
+    max_packet_size: usize,
+}
+
+impl SSZSnappyOutboundCodec {
+    pub fn new(protocol: ProtocolId, max_packet_size: usize) -> Self {
+        let mut uvi_codec = UviBytes::default();
+        uvi_codec.set_max_len(max_packet_size);
+
+        // this encoding only applies to ssz_snappy.
+        debug_assert!(protocol.encoding.as_str() == "ssz_snappy");
+
+        SSZSnappyOutboundCodec {
+            inner: uvi_codec,
+            protocol,
+            max_packet_size,
+        }
+    }
+}
+
+// Encoder for outbound
+impl Encoder for SSZSnappyOutboundCodec {
+    type Item = RPCRequest;
+    type Error = RPCError;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let bytes = encode_request(item);
+        // compress and length-prefix
+        self.inner
+            .encode(Bytes::from(compress(&bytes)?), dst)
+            .map_err(RPCError::from)
+    }
+}
+
+// Decoder for outbound
+impl Decoder for SSZSnappyOutboundCodec {
+    type Item = RPCResponse;
+    type Error = RPCError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match self.inner.decode(src).map_err(RPCError::from) {
+            Ok(Some(packet)) => {
+                let packet = decompress(&packet, self.max_packet_size)?;
+                decode_response(&self.protocol, &packet).map(Some)
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl OutboundCodec for SSZSnappyOutboundCodec {
+    type ErrorType = ErrorMessage;
+
+    fn decode_error(&mut self, src: &mut BytesMut) -> Result<Option<Self::ErrorType>, RPCError> {
+        match self.inner.decode(src).map_err(RPCError::from) {
+            Ok(Some(packet)) => {
+                let packet = decompress(&packet, self.max_packet_size)?;
+                Ok(Some(ErrorMessage::from_ssz_bytes(&packet)?))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Compresses `bytes` using the raw snappy format.
+fn compress(bytes: &[u8]) -> Result<Vec<u8>, RPCError> {
+    SnappyEncoder::new()
+        .compress_vec(bytes)
+        .map_err(RPCError::from)
+}
+
+/// Decompresses `bytes`, returning an error if the decompressed bytes would be longer than
+/// `max_len`.
+///
+/// The length is checked before decompressing, so a peer cannot force us to allocate more than
+/// `max_len` bytes.
+fn decompress(bytes: &[u8], max_len: usize) -> Result<Vec<u8>, RPCError> {
+    if decompress_len(bytes)? > max_len {
+        return Err(RPCError::Custom(
+            "Decompressed RPC message is too long".into(),
+        ));
+    }
+
+    SnappyDecoder::new()
+        .decompress_vec(bytes)
+        .map_err(RPCError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::codec::ssz::SSZInboundCodec;
+    use crate::rpc::methods::BlocksByRangeRequest;
+    use types::Slot;
+
+    const MAX_PACKET_SIZE: usize = 1_024;
+
+    fn protocol(encoding: &str) -> ProtocolId {
+        ProtocolId::new("beacon_blocks_by_range", "1.0.0", encoding)
+    }
+
+    #[test]
+    fn request_round_trip() {
+        let request = BlocksByRangeRequest {
+            start_slot: Slot::new(42),
+            count: 10,
+            step: 1,
+        };
+
+        let mut bytes = BytesMut::new();
+        SSZSnappyOutboundCodec::new(protocol("ssz_snappy"), MAX_PACKET_SIZE)
+            .encode(RPCRequest::BlocksByRange(request.clone()), &mut bytes)
+            .unwrap();
+
+        match SSZSnappyInboundCodec::new(protocol("ssz_snappy"), MAX_PACKET_SIZE).decode(&mut bytes)
+        {
+            Ok(Some(RPCRequest::BlocksByRange(decoded))) => assert_eq!(decoded, request),
+            other => panic!("unexpected decoding: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn response_is_compressed() {
+        let block_bytes = vec![0; 512];
+        let item = || RPCErrorResponse::Success(RPCResponse::BlocksByRange(block_bytes.clone()));
+
+        let mut ssz_bytes = BytesMut::new();
+        SSZInboundCodec::new(protocol("ssz"), MAX_PACKET_SIZE)
+            .encode(item(), &mut ssz_bytes)
+            .unwrap();
+
+        let mut snappy_bytes = BytesMut::new();
+        SSZSnappyInboundCodec::new(protocol("ssz_snappy"), MAX_PACKET_SIZE)
+            .encode(item(), &mut snappy_bytes)
+            .unwrap();
+
+        assert!(snappy_bytes.len() < ssz_bytes.len());
+
+        match SSZSnappyOutboundCodec::new(protocol("ssz_snappy"), MAX_PACKET_SIZE)
+            .decode(&mut snappy_bytes)
+        {
+            Ok(Some(RPCResponse::BlocksByRange(decoded))) => assert_eq!(decoded, block_bytes),
+            other => panic!("unexpected decoding: {:?}", other),
+        }
+    }
+    #[test]
+    fn decompressed_length_is_limited() {
+        let compressed = compress(&[0; MAX_PACKET_SIZE]).unwrap();
+
+        assert_eq!(
+            decompress(&compressed, MAX_PACKET_SIZE).unwrap(),
+            vec![0; MAX_PACKET_SIZE]
+        );
+        assert!(decompress(&compressed, MAX_PACKET_SIZE - 1).is_err());
+        assert!(decompress(&[0xff; 8], MAX_PACKET_SIZE).is_err());
+    }
+}
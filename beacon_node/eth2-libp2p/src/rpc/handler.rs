@@ -1,4 +1,4 @@
-use super::methods::{RPCErrorResponse, RPCResponse, RequestId};
+use super::methods::{RPCErrorResponse, RequestId};
 use super::protocol::{RPCError, RPCProtocol, RPCRequest};
 use super::RPCEvent;
 use crate::rpc::protocol::{InboundFramed, OutboundFramed};
@@ -10,6 +10,7 @@ use libp2p::swarm::protocols_handler::{
     KeepAlive, ProtocolsHandler, ProtocolsHandlerEvent, ProtocolsHandlerUpgrErr, SubstreamProtocol,
 };
 use smallvec::SmallVec;
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 use tokio_io::{AsyncRead, AsyncWrite};
 
@@ -36,8 +37,8 @@ where
     /// Current number of concurrent outbound substreams being opened.
     dial_negotiated: u32,
 
-    /// Map of current substreams awaiting a response to an RPC request.
-    waiting_substreams: FnvHashMap<RequestId, WaitingResponse<TSubstream>>,
+    /// Map of current inbound substreams, which are responding to an RPC request.
+    inbound_substreams: FnvHashMap<RequestId, InboundSubstream<TSubstream>>,
 
     /// List of outbound substreams that need to be driven to completion.
     substreams: Vec<SubstreamState<TSubstream>>,
@@ -58,25 +59,46 @@ where
     _phantom: PhantomData<TSubstream>,
 }
 
-/// An outbound substream is waiting a response from the user.
-struct WaitingResponse<TSubstream> {
-    /// The framed negotiated substream.
-    substream: InboundFramed<TSubstream>,
-    /// The time when the substream is closed.
+/// An inbound substream, which sends the responses from the user to an RPC request.
+struct InboundSubstream<TSubstream>
+where
+    TSubstream: AsyncRead + AsyncWrite,
+{
+    /// The current state of the substream.
+    state: InboundSubstreamState<TSubstream>,
+    /// Responses from the user which are waiting to be sent, in order.
+    queued_responses: VecDeque<RPCErrorResponse>,
+    /// The time when the substream is closed. This is extended each time the user responds.
     timeout: Instant,
 }
 
-/// State of an outbound substream. Either waiting for a response, or in the process of sending.
-pub enum SubstreamState<TSubstream>
+/// State of an inbound substream.
+enum InboundSubstreamState<TSubstream>
 where
     TSubstream: AsyncRead + AsyncWrite,
 {
-    /// A response has been sent, pending writing and flush.
+    /// Waiting for a response from the user.
+    ResponseIdle(InboundFramed<TSubstream>),
+    /// A response has been sent, pending writing and flush. If `closing`, the response is the last
+    /// and the substream is closed once it has been sent.
     ResponsePendingSend {
         substream: futures::sink::Send<InboundFramed<TSubstream>>,
+        closing: bool,
     },
+    /// All responses have been sent and the substream is closing.
+    Closing(InboundFramed<TSubstream>),
+}
+
+/// State of an outbound substream.
+pub enum SubstreamState<TSubstream>
+where
+    TSubstream: AsyncRead + AsyncWrite,
+{
     /// A request has been sent, and we are awaiting a response. This future is driven in the
     /// handler because GOODBYE requests can be handled and responses dropped instantly.
+    ///
+    /// Requests with a stream of responses remain in this state until the remote closes the
+    /// substream.
     RequestPendingResponse {
         /// The framed negotiated substream.
         substream: OutboundFramed<TSubstream>,
@@ -102,7 +124,7 @@ where
             events_out: SmallVec::new(),
             dial_queue: SmallVec::new(),
             dial_negotiated: 0,
-            waiting_substreams: FnvHashMap::default(),
+            inbound_substreams: FnvHashMap::default(),
             substreams: Vec::new(),
             current_substream_id: 1,
             max_dial_negotiated: 8,
@@ -181,12 +203,13 @@ where
         }
 
         // New inbound request. Store the stream and tag the output.
-        let awaiting_stream = WaitingResponse {
-            substream,
+        let inbound_substream = InboundSubstream {
+            state: InboundSubstreamState::ResponseIdle(substream),
+            queued_responses: VecDeque::new(),
             timeout: Instant::now() + Duration::from_secs(RESPONSE_TIMEOUT),
         };
-        self.waiting_substreams
-            .insert(self.current_substream_id, awaiting_stream);
+        self.inbound_substreams
+            .insert(self.current_substream_id, inbound_substream);
 
         self.events_out
             .push(RPCEvent::Request(self.current_substream_id, req));
@@ -203,7 +226,7 @@ where
 
         if self.dial_negotiated == 0
             && self.dial_queue.is_empty()
-            && self.waiting_substreams.is_empty()
+            && self.inbound_substreams.is_empty()
         {
             self.keep_alive = KeepAlive::Until(Instant::now() + self.inactive_timeout);
         } else {
@@ -231,12 +254,12 @@ where
         match rpc_event {
             RPCEvent::Request(_, _) => self.send_request(rpc_event),
             RPCEvent::Response(rpc_id, res) => {
-                // check if the stream matching the response still exists
-                if let Some(waiting_stream) = self.waiting_substreams.remove(&rpc_id) {
-                    // only send one response per stream. This must be in the waiting state.
-                    self.substreams.push(SubstreamState::ResponsePendingSend {
-                        substream: waiting_stream.substream.send(res),
-                    });
+                // check if the stream matching the response still exists. The response is sent
+                // once all previous responses on the stream have been sent.
+                if let Some(inbound_substream) = self.inbound_substreams.get_mut(&rpc_id) {
+                    inbound_substream.queued_responses.push_back(res);
+                    inbound_substream.timeout =
+                        Instant::now() + Duration::from_secs(RESPONSE_TIMEOUT);
                 }
             }
             RPCEvent::Error(_, _) => {}
@@ -285,46 +308,74 @@ where
         }
 
         // remove any streams that have expired
-        self.waiting_substreams
-            .retain(|_k, waiting_stream| Instant::now() <= waiting_stream.timeout);
+        self.inbound_substreams
+            .retain(|_k, inbound_substream| Instant::now() <= inbound_substream.timeout);
+
+        // drive inbound streams that are sending responses
+        let inbound_ids: Vec<RequestId> = self.inbound_substreams.keys().cloned().collect();
+        for id in inbound_ids {
+            if let Some(inbound_substream) = self.inbound_substreams.remove(&id) {
+                match drive_inbound_substream(inbound_substream) {
+                    Ok(Some(inbound_substream)) => {
+                        self.inbound_substreams.insert(id, inbound_substream);
+                    }
+                    Ok(None) => {} // all responses sent and the stream closed
+                    Err(e) => {
+                        return Ok(Async::Ready(ProtocolsHandlerEvent::Custom(
+                            RPCEvent::Error(0, e),
+                        )))
+                    }
+                }
+            }
+        }
 
-        // drive streams that need to be processed
+        // drive outbound streams that need to be processed
         for n in (0..self.substreams.len()).rev() {
             let stream = self.substreams.swap_remove(n);
             match stream {
-                SubstreamState::ResponsePendingSend { mut substream } => {
-                    match substream.poll() {
-                        Ok(Async::Ready(_substream)) => {} // sent and flushed
-                        Ok(Async::NotReady) => {
-                            self.substreams
-                                .push(SubstreamState::ResponsePendingSend { substream });
-                        }
-                        Err(e) => {
-                            return Ok(Async::Ready(ProtocolsHandlerEvent::Custom(
-                                RPCEvent::Error(0, e),
-                            )))
-                        }
-                    }
-                }
                 SubstreamState::RequestPendingResponse {
                     mut substream,
                     rpc_event,
                     timeout,
                 } => match substream.poll() {
-                    Ok(Async::Ready(response)) => {
-                        if let Some(response) = response {
-                            return Ok(Async::Ready(ProtocolsHandlerEvent::Custom(
-                                build_response(rpc_event, response),
-                            )));
+                    Ok(Async::Ready(Some(response))) => {
+                        let id = rpc_event.id();
+
+                        // await the remaining responses of a stream
+                        if response.multiple_responses() {
+                            self.substreams
+                                .push(SubstreamState::RequestPendingResponse {
+                                    substream,
+                                    rpc_event,
+                                    timeout: Instant::now() + Duration::from_secs(RESPONSE_TIMEOUT),
+                                });
+                        }
+
+                        return Ok(Async::Ready(ProtocolsHandlerEvent::Custom(
+                            RPCEvent::Response(id, response),
+                        )));
+                    }
+                    Ok(Async::Ready(None)) => {
+                        let termination = match &rpc_event {
+                            RPCEvent::Request(_, request) => request.stream_termination(),
+                            _ => None,
+                        };
+
+                        let event = if let Some(termination) = termination {
+                            // the remote has sent all of the responses in the stream
+                            RPCEvent::Response(
+                                rpc_event.id(),
+                                RPCErrorResponse::StreamTermination(termination),
+                            )
                         } else {
                             // stream closed early
-                            return Ok(Async::Ready(ProtocolsHandlerEvent::Custom(
-                                RPCEvent::Error(
-                                    rpc_event.id(),
-                                    RPCError::Custom("Stream Closed Early".into()),
-                                ),
-                            )));
-                        }
+                            RPCEvent::Error(
+                                rpc_event.id(),
+                                RPCError::Custom("Stream Closed Early".into()),
+                            )
+                        };
+
+                        return Ok(Async::Ready(ProtocolsHandlerEvent::Custom(event)));
                     }
                     Ok(Async::NotReady) => {
                         if Instant::now() < timeout {
@@ -366,30 +417,69 @@ where
     }
 }
 
-/// Given a response back from a peer and the request that sent it, construct a response to send
-/// back to the user. This allows for some data manipulation of responses given requests.
-fn build_response(rpc_event: RPCEvent, rpc_response: RPCErrorResponse) -> RPCEvent {
-    let id = rpc_event.id();
-
-    // handle the types of responses
-    match rpc_response {
-        RPCErrorResponse::Success(response) => {
-            match response {
-                // if the response is block roots, tag on the extra request data
-                RPCResponse::BeaconBlockBodies(mut resp) => {
-                    if let RPCEvent::Request(_id, RPCRequest::BeaconBlockBodies(bodies_req)) =
-                        rpc_event
-                    {
-                        resp.block_roots = Some(bodies_req.block_roots);
+/// Drives an inbound substream, sending the queued responses in order and closing the substream
+/// after the last response.
+///
+/// Returns `None` once the substream has been closed, otherwise returns the substream so it can be
+/// driven again.
+fn drive_inbound_substream<TSubstream>(
+    mut inbound_substream: InboundSubstream<TSubstream>,
+) -> Result<Option<InboundSubstream<TSubstream>>, RPCError>
+where
+    TSubstream: AsyncRead + AsyncWrite,
+{
+    loop {
+        inbound_substream.state = match inbound_substream.state {
+            InboundSubstreamState::ResponseIdle(substream) => {
+                match inbound_substream.queued_responses.pop_front() {
+                    // nothing is sent for a stream termination, the substream is closed instead
+                    Some(RPCErrorResponse::StreamTermination(_)) => {
+                        InboundSubstreamState::Closing(substream)
+                    }
+                    Some(response) => {
+                        // the substream is closed after the last response of the stream
+                        let closing = !response.multiple_responses();
+                        InboundSubstreamState::ResponsePendingSend {
+                            substream: substream.send(response),
+                            closing,
+                        }
+                    }
+                    None => {
+                        return Ok(Some(InboundSubstream {
+                            state: InboundSubstreamState::ResponseIdle(substream),
+                            ..inbound_substream
+                        }))
                     }
-                    RPCEvent::Response(
-                        id,
-                        RPCErrorResponse::Success(RPCResponse::BeaconBlockBodies(resp)),
-                    )
                 }
-                _ => RPCEvent::Response(id, RPCErrorResponse::Success(response)),
             }
+            InboundSubstreamState::ResponsePendingSend {
+                mut substream,
+                closing,
+            } => match substream.poll()? {
+                // sent and flushed
+                Async::Ready(substream) => {
+                    if closing {
+                        InboundSubstreamState::Closing(substream)
+                    } else {
+                        InboundSubstreamState::ResponseIdle(substream)
+                    }
+                }
+                Async::NotReady => {
+                    return Ok(Some(InboundSubstream {
+                        state: InboundSubstreamState::ResponsePendingSend { substream, closing },
+                        ..inbound_substream
+                    }))
+                }
+            },
+            InboundSubstreamState::Closing(mut substream) => match substream.close()? {
+                Async::Ready(()) => return Ok(None),
+                Async::NotReady => {
+                    return Ok(Some(InboundSubstream {
+                        state: InboundSubstreamState::Closing(substream),
+                        ..inbound_substream
+                    }))
+                }
+            },
         }
-        _ => RPCEvent::Response(id, rpc_response),
     }
 }
@@ -2,7 +2,7 @@
 
 use ssz::{impl_decode_via_from, impl_encode_via_from};
 use ssz_derive::{Decode, Encode};
-use types::{BeaconBlockBody, Epoch, EthSpec, Hash256, Slot};
+use types::{Epoch, Hash256, Slot};
 
 /* Request/Response data structures for RPC methods */
 
@@ -72,95 +72,54 @@ impl Into<u64> for GoodbyeReason {
 impl_encode_via_from!(GoodbyeReason, u64);
 impl_decode_via_from!(GoodbyeReason, u64);
 
-/// Request a number of beacon block roots from a peer.
+/// The maximum number of blocks that may be requested in a single `BlocksByRange` or
+/// `BlocksByRoot` request.
+pub const MAX_REQUEST_BLOCKS: u64 = 1024;
+
+/// Request a number of beacon blocks from a peer.
 #[derive(Encode, Decode, Clone, Debug, PartialEq)]
-pub struct BeaconBlockRootsRequest {
+pub struct BlocksByRangeRequest {
     /// The starting slot of the requested blocks.
     pub start_slot: Slot,
 
-    /// The number of blocks from the start slot.
-    pub count: u64, // this must be less than 32768. //TODO: Enforce this in the lower layers
-}
+    /// The number of blocks requested, at the slots `start_slot + i * step` for `i < count`.
+    /// Fewer blocks are returned if any of those slots were skipped.
+    pub count: u64,
 
-/// Response containing a number of beacon block roots from a peer.
-#[derive(Encode, Decode, Clone, Debug, PartialEq)]
-pub struct BeaconBlockRootsResponse {
-    /// List of requested blocks and associated slots.
-    pub roots: Vec<BlockRootSlot>,
+    /// The step increment between requested slots. A step of 1 requests every block in the range.
+    pub step: u64,
 }
 
-/// Contains a block root and associated slot.
-#[derive(Encode, Decode, Clone, Debug, PartialEq)]
-pub struct BlockRootSlot {
-    /// The block root.
-    pub block_root: Hash256,
-
-    /// The block slot.
-    pub slot: Slot,
-}
-
-/// The response of a beacon block roots request.
-impl BeaconBlockRootsResponse {
-    /// Returns `true` if each `self.roots.slot[i]` is higher than the preceding `i`.
-    pub fn slots_are_ascending(&self) -> bool {
-        for window in self.roots.windows(2) {
-            if window[0].slot >= window[1].slot {
-                return false;
-            }
-        }
-
-        true
+impl BlocksByRangeRequest {
+    /// Returns the slot following the last requested slot, i.e., `start_slot + count * step`.
+    ///
+    /// Saturates instead of overflowing.
+    pub fn end_slot(&self) -> Slot {
+        Slot::new(
+            self.start_slot
+                .as_u64()
+                .saturating_add(self.count.saturating_mul(self.step)),
+        )
     }
-}
-
-/// Request a number of beacon block headers from a peer.
-#[derive(Encode, Decode, Clone, Debug, PartialEq)]
-pub struct BeaconBlockHeadersRequest {
-    /// The starting header hash of the requested headers.
-    pub start_root: Hash256,
 
-    /// The starting slot of the requested headers.
-    pub start_slot: Slot,
-
-    /// The maximum number of headers than can be returned.
-    pub max_headers: u64,
-
-    /// The maximum number of slots to skip between blocks.
-    pub skip_slots: u64,
-}
-
-/// Response containing requested block headers.
-#[derive(Clone, Debug, PartialEq)]
-pub struct BeaconBlockHeadersResponse {
-    /// The list of ssz-encoded requested beacon block headers.
-    pub headers: Vec<u8>,
+    /// Returns `true` if a block at `slot` is one of the blocks requested.
+    ///
+    /// The step is counted from `start_slot`, even if there is no block at `start_slot`.
+    pub fn includes_slot(&self, slot: Slot) -> bool {
+        slot >= self.start_slot
+            && slot < self.end_slot()
+            && self.step != 0
+            && (slot - self.start_slot).as_u64() % self.step == 0
+    }
 }
 
-/// Request a number of beacon block bodies from a peer.
+/// Request a number of beacon blocks from a peer, by their block roots.
 #[derive(Encode, Decode, Clone, Debug, PartialEq)]
-pub struct BeaconBlockBodiesRequest {
-    /// The list of beacon block bodies being requested.
+pub struct BlocksByRootRequest {
+    /// The list of beacon block roots being requested.
     pub block_roots: Vec<Hash256>,
 }
 
-/// Response containing the list of requested beacon block bodies.
-#[derive(Clone, Debug, PartialEq)]
-pub struct BeaconBlockBodiesResponse {
-    /// The list of hashes that were sent in the request and match these roots response. None when
-    /// sending outbound.
-    pub block_roots: Option<Vec<Hash256>>,
-    /// The list of ssz-encoded beacon block bodies being requested.
-    pub block_bodies: Vec<u8>,
-}
-
-/// The decoded version of `BeaconBlockBodiesResponse` which is expected in `SimpleSync`.
-pub struct DecodedBeaconBlockBodiesResponse<E: EthSpec> {
-    /// The list of hashes sent in the request to get this response.
-    pub block_roots: Vec<Hash256>,
-    /// The valid decoded block bodies.
-    pub block_bodies: Vec<BeaconBlockBody<E>>,
-}
-
 /// Request values for tree hashes which yield a blocks `state_root`.
 #[derive(Encode, Decode, Clone, Debug, PartialEq)]
 pub struct BeaconChainStateRequest {
@@ -183,12 +142,12 @@ pub struct BeaconChainStateResponse {
 pub enum RPCResponse {
     /// A HELLO message.
     Hello(HelloMessage),
-    /// A response to a get BEACON_BLOCK_ROOTS request.
-    BeaconBlockRoots(BeaconBlockRootsResponse),
-    /// A response to a get BEACON_BLOCK_HEADERS request.
-    BeaconBlockHeaders(BeaconBlockHeadersResponse),
-    /// A response to a get BEACON_BLOCK_BODIES request.
-    BeaconBlockBodies(BeaconBlockBodiesResponse),
+    /// A single ssz-encoded block in response to a BLOCKS_BY_RANGE request. The response is a
+    /// stream of these chunks.
+    BlocksByRange(Vec<u8>),
+    /// A single ssz-encoded block in response to a BLOCKS_BY_ROOT request. The response is a
+    /// stream of these chunks.
+    BlocksByRoot(Vec<u8>),
     /// A response to a get BEACON_CHAIN_STATE request.
     BeaconChainState(BeaconChainStateResponse),
 }
@@ -199,16 +158,42 @@ pub enum RPCErrorResponse {
     InvalidRequest(ErrorMessage),
     ServerError(ErrorMessage),
     Unknown(ErrorMessage),
+    /// The end of a stream of responses. This is never sent across the network, the responder
+    /// closes the substream instead.
+    StreamTermination(ResponseTermination),
+}
+
+/// Identifies the type of a stream of responses that has been terminated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResponseTermination {
+    /// The end of a stream of BLOCKS_BY_RANGE responses.
+    BlocksByRange,
+    /// The end of a stream of BLOCKS_BY_ROOT responses.
+    BlocksByRoot,
 }
 
 impl RPCErrorResponse {
-    /// Used to encode the response.
-    pub fn as_u8(&self) -> u8 {
+    /// Used to encode the response. Returns `None` for a `StreamTermination`, which has no
+    /// encoding.
+    pub fn as_u8(&self) -> Option<u8> {
         match self {
-            RPCErrorResponse::Success(_) => 0,
-            RPCErrorResponse::InvalidRequest(_) => 2,
-            RPCErrorResponse::ServerError(_) => 3,
-            RPCErrorResponse::Unknown(_) => 255,
+            RPCErrorResponse::Success(_) => Some(0),
+            RPCErrorResponse::InvalidRequest(_) => Some(2),
+            RPCErrorResponse::ServerError(_) => Some(3),
+            RPCErrorResponse::Unknown(_) => Some(255),
+            RPCErrorResponse::StreamTermination(_) => None,
+        }
+    }
+
+    /// Returns `true` if further responses may follow this one on the same substream.
+    ///
+    /// Only successful responses to requests that return a stream of responses are followed by
+    /// more responses. An error response always ends the stream.
+    pub fn multiple_responses(&self) -> bool {
+        match self {
+            RPCErrorResponse::Success(RPCResponse::BlocksByRange(_))
+            | RPCErrorResponse::Success(RPCResponse::BlocksByRoot(_)) => true,
+            _ => false,
         }
     }
 
@@ -237,7 +222,61 @@ pub struct ErrorMessage {
 }
 
 impl ErrorMessage {
+    pub fn new(error_message: &str) -> Self {
+        ErrorMessage {
+            error_message: error_message.as_bytes().to_vec(),
+        }
+    }
+
     pub fn as_string(&self) -> String {
         String::from_utf8(self.error_message.clone()).unwrap_or_else(|_| "".into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the slots of the blocks in `block_slots` that are selected by `req`.
+    fn selected_slots(req: &BlocksByRangeRequest, block_slots: &[u64]) -> Vec<u64> {
+        block_slots
+            .iter()
+            .cloned()
+            .filter(|slot| req.includes_slot(Slot::new(*slot)))
+            .collect()
+    }
+
+    #[test]
+    fn blocks_by_range_selection() {
+        // Slots 0, 1, 2, 4, 7, 10 and 11 are skipped.
+        let block_slots = vec![3, 5, 6, 8, 9, 12];
+
+        let req = |start_slot, count, step| BlocksByRangeRequest {
+            start_slot: Slot::new(start_slot),
+            count,
+            step,
+        };
+
+        // The block at slot 3 fills the skipped start slot, but it is not requested.
+        assert_eq!(selected_slots(&req(4, 3, 1), &block_slots), vec![5, 6]);
+        // The step is counted from the skipped start slot.
+        assert_eq!(selected_slots(&req(4, 2, 2), &block_slots), vec![6]);
+        assert_eq!(selected_slots(&req(3, 3, 3), &block_slots), vec![3, 6, 9]);
+        // The count is a number of blocks, not a number of slots.
+        assert_eq!(selected_slots(&req(4, 8, 2), &block_slots), vec![6, 8, 12]);
+        assert_eq!(
+            selected_slots(&req(3, 4, 3), &block_slots),
+            vec![3, 6, 9, 12]
+        );
+        // The end of the range is exclusive.
+        assert_eq!(selected_slots(&req(8, 4, 1), &block_slots), vec![8, 9]);
+        assert_eq!(selected_slots(&req(8, 5, 1), &block_slots), vec![8, 9, 12]);
+        // The end of the range does not overflow.
+        assert_eq!(
+            selected_slots(&req(3, u64::max_value(), 2), &block_slots),
+            vec![3, 5, 9]
+        );
+        // A zero step selects nothing.
+        assert!(selected_slots(&req(3, 10, 0), &block_slots).is_empty());
+    }
+}
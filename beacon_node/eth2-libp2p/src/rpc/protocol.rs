@@ -2,6 +2,7 @@ use super::methods::*;
 use crate::rpc::codec::{
     base::{BaseInboundCodec, BaseOutboundCodec},
     ssz::{SSZInboundCodec, SSZOutboundCodec},
+    ssz_snappy::{SSZSnappyInboundCodec, SSZSnappyOutboundCodec},
     InboundCodec, OutboundCodec,
 };
 use futures::{
@@ -23,6 +24,8 @@ const MAX_RPC_SIZE: usize = 4_194_304; // 4M
 const PROTOCOL_PREFIX: &str = "/eth2/beacon_node/rpc";
 /// The number of seconds to wait for a request once a protocol has been established before the stream is terminated.
 const REQUEST_TIMEOUT: u64 = 3;
+/// The supported encodings, in order of preference.
+const ENCODINGS: [&str; 2] = ["ssz_snappy", "ssz"];
 
 #[derive(Debug, Clone)]
 pub struct RPCProtocol;
@@ -32,13 +35,15 @@ impl UpgradeInfo for RPCProtocol {
     type InfoIter = Vec<Self::Info>;
 
     fn protocol_info(&self) -> Self::InfoIter {
-        vec![
-            ProtocolId::new("hello", "1.0.0", "ssz"),
-            ProtocolId::new("goodbye", "1.0.0", "ssz"),
-            ProtocolId::new("beacon_block_roots", "1.0.0", "ssz"),
-            ProtocolId::new("beacon_block_headers", "1.0.0", "ssz"),
-            ProtocolId::new("beacon_block_bodies", "1.0.0", "ssz"),
+        [
+            "hello",
+            "goodbye",
+            "beacon_blocks_by_range",
+            "beacon_blocks_by_root",
         ]
+        .iter()
+        .flat_map(|message_name| protocol_ids(message_name, "1.0.0"))
+        .collect()
     }
 }
 
@@ -75,6 +80,15 @@ impl ProtocolId {
     }
 }
 
+/// Returns a `ProtocolId` for each supported encoding of the given message, in order of
+/// preference.
+fn protocol_ids(message_name: &str, version: &str) -> Vec<ProtocolId> {
+    ENCODINGS
+        .iter()
+        .map(|encoding| ProtocolId::new(message_name, version, encoding))
+        .collect()
+}
+
 impl ProtocolName for ProtocolId {
     fn protocol_name(&self) -> &[u8] {
         self.protocol_id.as_bytes()
@@ -114,24 +128,28 @@ where
         socket: upgrade::Negotiated<TSocket>,
         protocol: ProtocolId,
     ) -> Self::Future {
-        match protocol.encoding.as_str() {
-            "ssz" | _ => {
-                let ssz_codec = BaseInboundCodec::new(SSZInboundCodec::new(protocol, MAX_RPC_SIZE));
-                let codec = InboundCodec::SSZ(ssz_codec);
-                Framed::new(socket, codec)
-                    .into_future()
-                    .timeout(Duration::from_secs(REQUEST_TIMEOUT))
-                    .map_err(RPCError::from as FnMapErr<TSocket>)
-                    .and_then({
-                        |(req, stream)| match req {
-                            Some(req) => futures::future::ok((req, stream)),
-                            None => futures::future::err(RPCError::Custom(
-                                "Stream terminated early".into(),
-                            )),
-                        }
-                    } as FnAndThen<TSocket>)
-            }
-        }
+        let codec = match protocol.encoding.as_str() {
+            "ssz_snappy" => InboundCodec::SSZSnappy(BaseInboundCodec::new(
+                SSZSnappyInboundCodec::new(protocol, MAX_RPC_SIZE),
+            )),
+            "ssz" | _ => InboundCodec::SSZ(BaseInboundCodec::new(SSZInboundCodec::new(
+                protocol,
+                MAX_RPC_SIZE,
+            ))),
+        };
+
+        Framed::new(socket, codec)
+            .into_future()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT))
+            .map_err(RPCError::from as FnMapErr<TSocket>)
+            .and_then({
+                |(req, stream)| match req {
+                    Some(req) => futures::future::ok((req, stream)),
+                    None => {
+                        futures::future::err(RPCError::Custom("Stream terminated early".into()))
+                    }
+                }
+            } as FnAndThen<TSocket>)
     }
 }
 
@@ -144,9 +162,8 @@ where
 pub enum RPCRequest {
     Hello(HelloMessage),
     Goodbye(GoodbyeReason),
-    BeaconBlockRoots(BeaconBlockRootsRequest),
-    BeaconBlockHeaders(BeaconBlockHeadersRequest),
-    BeaconBlockBodies(BeaconBlockBodiesRequest),
+    BlocksByRange(BlocksByRangeRequest),
+    BlocksByRoot(BlocksByRootRequest),
     BeaconChainState(BeaconChainStateRequest),
 }
 
@@ -163,25 +180,19 @@ impl UpgradeInfo for RPCRequest {
 /// Implements the encoding per supported protocol for RPCRequest.
 impl RPCRequest {
     pub fn supported_protocols(&self) -> Vec<ProtocolId> {
+        // Each encoding is listed in order of preference, so that peers which support
+        // `ssz_snappy` negotiate it, whilst older peers fall back to `ssz`.
         match self {
             // add more protocols when versions/encodings are supported
-            RPCRequest::Hello(_) => vec![
-                ProtocolId::new("hello", "1.0.0", "ssz"),
-                ProtocolId::new("goodbye", "1.0.0", "ssz"),
-            ],
-            RPCRequest::Goodbye(_) => vec![ProtocolId::new("goodbye", "1.0.0", "ssz")],
-            RPCRequest::BeaconBlockRoots(_) => {
-                vec![ProtocolId::new("beacon_block_roots", "1.0.0", "ssz")]
-            }
-            RPCRequest::BeaconBlockHeaders(_) => {
-                vec![ProtocolId::new("beacon_block_headers", "1.0.0", "ssz")]
-            }
-            RPCRequest::BeaconBlockBodies(_) => {
-                vec![ProtocolId::new("beacon_block_bodies", "1.0.0", "ssz")]
-            }
-            RPCRequest::BeaconChainState(_) => {
-                vec![ProtocolId::new("beacon_block_state", "1.0.0", "ssz")]
+            RPCRequest::Hello(_) => {
+                let mut protocols = protocol_ids("hello", "1.0.0");
+                protocols.append(&mut protocol_ids("goodbye", "1.0.0"));
+                protocols
             }
+            RPCRequest::Goodbye(_) => protocol_ids("goodbye", "1.0.0"),
+            RPCRequest::BlocksByRange(_) => protocol_ids("beacon_blocks_by_range", "1.0.0"),
+            RPCRequest::BlocksByRoot(_) => protocol_ids("beacon_blocks_by_root", "1.0.0"),
+            RPCRequest::BeaconChainState(_) => protocol_ids("beacon_block_state", "1.0.0"),
         }
     }
 
@@ -193,6 +204,17 @@ impl RPCRequest {
             _ => true,
         }
     }
+
+    /// Returns the type of stream termination if the response to this request is a stream of
+    /// responses, which ends when the responder closes the substream. Returns `None` if there is
+    /// a single response.
+    pub fn stream_termination(&self) -> Option<ResponseTermination> {
+        match self {
+            RPCRequest::BlocksByRange(_) => Some(ResponseTermination::BlocksByRange),
+            RPCRequest::BlocksByRoot(_) => Some(ResponseTermination::BlocksByRoot),
+            _ => None,
+        }
+    }
 }
 
 /* RPC Response type - used for outbound upgrades */
@@ -213,13 +235,16 @@ where
         socket: upgrade::Negotiated<TSocket>,
         protocol: Self::Info,
     ) -> Self::Future {
-        match protocol.encoding.as_str() {
-            "ssz" | _ => {
-                let ssz_codec = BaseOutboundCodec::new(SSZOutboundCodec::new(protocol, 4096));
-                let codec = OutboundCodec::SSZ(ssz_codec);
-                Framed::new(socket, codec).send(self)
-            }
-        }
+        let codec = match protocol.encoding.as_str() {
+            "ssz_snappy" => OutboundCodec::SSZSnappy(BaseOutboundCodec::new(
+                SSZSnappyOutboundCodec::new(protocol, MAX_RPC_SIZE),
+            )),
+            "ssz" | _ => OutboundCodec::SSZ(BaseOutboundCodec::new(SSZOutboundCodec::new(
+                protocol,
+                MAX_RPC_SIZE,
+            ))),
+        };
+        Framed::new(socket, codec).send(self)
     }
 }
 
@@ -230,6 +255,8 @@ pub enum RPCError {
     ReadError(upgrade::ReadOneError),
     /// Error when decoding the raw buffer from ssz.
     SSZDecodeError(ssz::DecodeError),
+    /// Error when compressing or decompressing with snappy.
+    SnappyError(snap::Error),
     /// Invalid Protocol ID.
     InvalidProtocol(&'static str),
     /// IO Error.
@@ -253,6 +280,14 @@ impl From<ssz::DecodeError> for RPCError {
         RPCError::SSZDecodeError(err)
     }
 }
+
+impl From<snap::Error> for RPCError {
+    #[inline]
+    fn from(err: snap::Error) -> Self {
+        RPCError::SnappyError(err)
+    }
+}
+
 impl<T> From<tokio::timer::timeout::Error<T>> for RPCError {
     fn from(err: tokio::timer::timeout::Error<T>) -> Self {
         if err.is_elapsed() {
@@ -275,6 +310,7 @@ impl std::fmt::Display for RPCError {
         match *self {
             RPCError::ReadError(ref err) => write!(f, "Error while reading from socket: {}", err),
             RPCError::SSZDecodeError(ref err) => write!(f, "Error while decoding ssz: {:?}", err),
+            RPCError::SnappyError(ref err) => write!(f, "Error while using snappy: {}", err),
             RPCError::InvalidProtocol(ref err) => write!(f, "Invalid Protocol: {}", err),
             RPCError::IoError(ref err) => write!(f, "IO Error: {}", err),
             RPCError::StreamTimeout => write!(f, "Stream Timeout"),
@@ -288,6 +324,7 @@ impl std::error::Error for RPCError {
         match *self {
             RPCError::ReadError(ref err) => Some(err),
             RPCError::SSZDecodeError(_) => None,
+            RPCError::SnappyError(ref err) => Some(err),
             RPCError::InvalidProtocol(_) => None,
             RPCError::IoError(ref err) => Some(err),
             RPCError::StreamTimeout => None,
@@ -3,8 +3,8 @@ use crate::error;
 use crate::multiaddr::Protocol;
 use crate::rpc::RPCEvent;
 use crate::NetworkConfig;
-use crate::{Topic, TopicHash};
-use crate::{BEACON_ATTESTATION_TOPIC, BEACON_BLOCK_TOPIC};
+use crate::{fork_topic, BEACON_ATTESTATION_TOPIC, BEACON_BLOCK_TOPIC};
+use crate::{MessageId, Topic, TopicHash};
 use futures::prelude::*;
 use futures::Stream;
 use libp2p::core::{
@@ -56,6 +56,12 @@ impl Service {
             Swarm::new(transport, behaviour, local_peer_id.clone())
         };
 
+        // re-apply the bans that were persisted before the last shutdown
+        for peer_id in swarm.banned_peers() {
+            debug!(log, "Banning peer"; "peer" => format!("{:?}", peer_id));
+            Swarm::ban_peer_id(&mut swarm, peer_id);
+        }
+
         // listen on the specified address
         let listen_multiaddr = {
             let mut m = Multiaddr::from(config.listen_address);
@@ -95,8 +101,13 @@ impl Service {
         let mut topics = vec![];
         //TODO: Handle multiple shard attestations. For now we simply use a separate topic for
         // attestations
-        topics.push(Topic::new(BEACON_ATTESTATION_TOPIC.into()));
-        topics.push(Topic::new(BEACON_BLOCK_TOPIC.into()));
+        for fork_version in &config.fork_versions {
+            topics.push(Topic::new(fork_topic(
+                BEACON_ATTESTATION_TOPIC,
+                *fork_version,
+            )));
+            topics.push(Topic::new(fork_topic(BEACON_BLOCK_TOPIC, *fork_version)));
+        }
         topics.append(
             &mut config
                 .topics
@@ -136,12 +147,14 @@ impl Stream for Service {
                 Ok(Async::Ready(Some(event))) => match event {
                     // TODO: Stub here for debugging
                     BehaviourEvent::GossipMessage {
+                        id,
                         source,
                         topics,
                         message,
                     } => {
                         trace!(self.log, "Gossipsub message received"; "Message" => format!("{:?}", message));
                         return Ok(Async::Ready(Some(Libp2pEvent::PubsubMessage {
+                            id,
                             source,
                             topics,
                             message,
@@ -156,6 +169,12 @@ impl Stream for Service {
                     BehaviourEvent::PeerDisconnected(peer_id) => {
                         return Ok(Async::Ready(Some(Libp2pEvent::PeerDisconnected(peer_id))));
                     }
+                    BehaviourEvent::PeerBanned(peer_id) => {
+                        Swarm::ban_peer_id(&mut self.swarm, peer_id);
+                    }
+                    BehaviourEvent::PeerUnbanned(peer_id) => {
+                        Swarm::unban_peer_id(&mut self.swarm, peer_id);
+                    }
                 },
                 Ok(Async::Ready(None)) => unreachable!("Swarm stream shouldn't end"),
                 Ok(Async::NotReady) => break,
@@ -207,8 +226,10 @@ pub enum Libp2pEvent {
     PeerDialed(PeerId),
     /// A peer has disconnected.
     PeerDisconnected(PeerId),
-    /// Received pubsub message.
+    /// Received pubsub message. It is not propagated until its validation result is reported via
+    /// `Behaviour::validate_gossip`.
     PubsubMessage {
+        id: MessageId,
         source: PeerId,
         topics: Vec<TopicHash>,
         message: PubsubMessage,
@@ -4,9 +4,9 @@ use crate::sync::SimpleSync;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::rpc::methods::*;
 use eth2_libp2p::{
-    behaviour::PubsubMessage,
+    behaviour::{GossipValidationResult, PubsubMessage},
     rpc::{RPCError, RPCErrorResponse, RPCRequest, RPCResponse, RequestId},
-    PeerId, RPCEvent,
+    MessageId, PeerAction, PeerId, RPCEvent,
 };
 use futures::future::Future;
 use futures::stream::Stream;
@@ -14,7 +14,7 @@ use slog::{debug, warn};
 use ssz::{Decode, DecodeError};
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use types::{Attestation, BeaconBlock, BeaconBlockHeader};
+use types::{Attestation, BeaconBlock};
 
 /// Handles messages received from the network and client and organises syncing.
 pub struct MessageHandler<T: BeaconChainTypes> {
@@ -37,8 +37,8 @@ pub enum HandlerMessage {
     PeerDisconnected(PeerId),
     /// An RPC response/request has been received.
     RPC(PeerId, RPCEvent),
-    /// A gossip message has been received.
-    PubsubMessage(PeerId, PubsubMessage),
+    /// A gossip message has been received. It is not propagated until it has been validated.
+    PubsubMessage(MessageId, PeerId, PubsubMessage),
 }
 
 impl<T: BeaconChainTypes + 'static> MessageHandler<T> {
@@ -92,9 +92,10 @@ impl<T: BeaconChainTypes + 'static> MessageHandler<T> {
             HandlerMessage::RPC(peer_id, rpc_event) => {
                 self.handle_rpc_message(peer_id, rpc_event);
             }
-            // we have received an RPC message request/response
-            HandlerMessage::PubsubMessage(peer_id, gossip) => {
-                self.handle_gossip(peer_id, gossip);
+            // we have received a gossip message
+            HandlerMessage::PubsubMessage(id, peer_id, gossip) => {
+                let result = self.handle_gossip(peer_id.clone(), gossip);
+                self.network_context.validate_gossip(id, peer_id, result);
             }
         }
     }
@@ -120,19 +121,13 @@ impl<T: BeaconChainTypes + 'static> MessageHandler<T> {
                 &mut self.network_context,
             ),
             RPCRequest::Goodbye(goodbye_reason) => self.sync.on_goodbye(peer_id, goodbye_reason),
-            RPCRequest::BeaconBlockRoots(request) => self.sync.on_beacon_block_roots_request(
+            RPCRequest::BlocksByRange(request) => self.sync.on_blocks_by_range_request(
                 peer_id,
                 request_id,
                 request,
                 &mut self.network_context,
             ),
-            RPCRequest::BeaconBlockHeaders(request) => self.sync.on_beacon_block_headers_request(
-                peer_id,
-                request_id,
-                request,
-                &mut self.network_context,
-            ),
-            RPCRequest::BeaconBlockBodies(request) => self.sync.on_beacon_block_bodies_request(
+            RPCRequest::BlocksByRoot(request) => self.sync.on_blocks_by_root_request(
                 peer_id,
                 request_id,
                 request,
@@ -161,6 +156,10 @@ impl<T: BeaconChainTypes + 'static> MessageHandler<T> {
             RPCErrorResponse::Unknown(error) => {
                 warn!(self.log, "";"peer" => format!("{:?}", peer_id), "Unknown Error" => error.as_string())
             }
+            RPCErrorResponse::StreamTermination(termination) => {
+                self.sync
+                    .on_stream_termination(peer_id, termination, &mut self.network_context);
+            }
             RPCErrorResponse::Success(response) => {
                 match response {
                     RPCResponse::Hello(hello_message) => {
@@ -170,41 +169,34 @@ impl<T: BeaconChainTypes + 'static> MessageHandler<T> {
                             &mut self.network_context,
                         );
                     }
-                    RPCResponse::BeaconBlockRoots(response) => {
-                        self.sync.on_beacon_block_roots_response(
-                            peer_id,
-                            response,
-                            &mut self.network_context,
-                        );
-                    }
-                    RPCResponse::BeaconBlockHeaders(response) => {
-                        match self.decode_block_headers(response) {
-                            Ok(decoded_block_headers) => {
-                                self.sync.on_beacon_block_headers_response(
-                                    peer_id,
-                                    decoded_block_headers,
-                                    &mut self.network_context,
-                                );
-                            }
-                            Err(_e) => {
-                                warn!(self.log, "Peer sent invalid block headers";"peer" => format!("{:?}", peer_id))
-                            }
+                    RPCResponse::BlocksByRange(response) => match self.decode_rpc_block(response) {
+                        Ok(block) => {
+                            self.sync.on_blocks_by_range_response(
+                                peer_id,
+                                block,
+                                &mut self.network_context,
+                            );
                         }
-                    }
-                    RPCResponse::BeaconBlockBodies(response) => {
-                        match self.decode_block_bodies(response) {
-                            Ok(decoded_block_bodies) => {
-                                self.sync.on_beacon_block_bodies_response(
-                                    peer_id,
-                                    decoded_block_bodies,
-                                    &mut self.network_context,
-                                );
-                            }
-                            Err(_e) => {
-                                warn!(self.log, "Peer sent invalid block bodies";"peer" => format!("{:?}", peer_id))
-                            }
+                        Err(_e) => {
+                            warn!(self.log, "Peer sent invalid block";"peer" => format!("{:?}", peer_id));
+                            self.network_context
+                                .report_peer(peer_id, PeerAction::LowToleranceError);
                         }
-                    }
+                    },
+                    RPCResponse::BlocksByRoot(response) => match self.decode_rpc_block(response) {
+                        Ok(block) => {
+                            self.sync.on_blocks_by_root_response(
+                                peer_id,
+                                block,
+                                &mut self.network_context,
+                            );
+                        }
+                        Err(_e) => {
+                            warn!(self.log, "Peer sent invalid block";"peer" => format!("{:?}", peer_id));
+                            self.network_context
+                                .report_peer(peer_id, PeerAction::LowToleranceError);
+                        }
+                    },
                     RPCResponse::BeaconChainState(_) => {
                         // We do not implement this endpoint, it is not required and will only likely be
                         // useful for light-client support in later phases.
@@ -224,22 +216,28 @@ impl<T: BeaconChainTypes + 'static> MessageHandler<T> {
         warn!(self.log, "RPC Error"; "Peer" => format!("{:?}", peer_id), "Request Id" => format!("{}", request_id), "Error" => format!("{:?}", error));
     }
 
-    /// Handle RPC messages
-    fn handle_gossip(&mut self, peer_id: PeerId, gossip_message: PubsubMessage) {
+    /// Handle gossip messages, returning the result of validating the message.
+    ///
+    /// Messages that cannot be decoded are rejected.
+    fn handle_gossip(
+        &mut self,
+        peer_id: PeerId,
+        gossip_message: PubsubMessage,
+    ) -> GossipValidationResult {
         match gossip_message {
             PubsubMessage::Block(message) => match self.decode_gossip_block(message) {
                 Err(e) => {
                     debug!(self.log, "Invalid Gossiped Beacon Block"; "Peer" => format!("{}", peer_id), "Error" => format!("{:?}", e));
+                    GossipValidationResult::Reject
                 }
-                Ok(block) => {
-                    let _should_forward_on =
-                        self.sync
-                            .on_block_gossip(peer_id, block, &mut self.network_context);
-                }
+                Ok(block) => self
+                    .sync
+                    .on_block_gossip(peer_id, block, &mut self.network_context),
             },
             PubsubMessage::Attestation(message) => match self.decode_gossip_attestation(message) {
                 Err(e) => {
                     debug!(self.log, "Invalid Gossiped Attestation"; "Peer" => format!("{}", peer_id), "Error" => format!("{:?}", e));
+                    GossipValidationResult::Reject
                 }
                 Ok(attestation) => {
                     self.sync
@@ -249,6 +247,7 @@ impl<T: BeaconChainTypes + 'static> MessageHandler<T> {
             PubsubMessage::Unknown(message) => {
                 // Received a message from an unknown topic. Ignore for now
                 debug!(self.log, "Unknown Gossip Message"; "Peer" => format!("{}", peer_id), "Message" => format!("{:?}", message));
+                GossipValidationResult::Ignore
             }
         }
     }
@@ -274,28 +273,13 @@ impl<T: BeaconChainTypes + 'static> MessageHandler<T> {
         Attestation::from_ssz_bytes(&beacon_block)
     }
 
-    /// Verifies and decodes the ssz-encoded block bodies received from peers.
-    fn decode_block_bodies(
+    /// Verifies and decodes an ssz-encoded block received from a peer via RPC.
+    fn decode_rpc_block(
         &self,
-        bodies_response: BeaconBlockBodiesResponse,
-    ) -> Result<DecodedBeaconBlockBodiesResponse<T::EthSpec>, DecodeError> {
+        beacon_block: Vec<u8>,
+    ) -> Result<BeaconBlock<T::EthSpec>, DecodeError> {
         //TODO: Implement faster block verification before decoding entirely
-        let block_bodies = Vec::from_ssz_bytes(&bodies_response.block_bodies)?;
-        Ok(DecodedBeaconBlockBodiesResponse {
-            block_roots: bodies_response
-                .block_roots
-                .expect("Responses must have associated roots"),
-            block_bodies,
-        })
-    }
-
-    /// Verifies and decodes the ssz-encoded block headers received from peers.
-    fn decode_block_headers(
-        &self,
-        headers_response: BeaconBlockHeadersResponse,
-    ) -> Result<Vec<BeaconBlockHeader>, DecodeError> {
-        //TODO: Implement faster header verification before decoding entirely
-        Vec::from_ssz_bytes(&headers_response.headers)
+        BeaconBlock::from_ssz_bytes(&beacon_block)
     }
 }
 
@@ -323,7 +307,6 @@ impl NetworkContext {
         self.send_rpc_event(peer_id, RPCEvent::Request(0, rpc_request));
     }
 
-    //TODO: Handle Error responses
     pub fn send_rpc_response(
         &mut self,
         peer_id: PeerId,
@@ -336,6 +319,52 @@ impl NetworkContext {
         );
     }
 
+    /// Sends a response which is not a success, such as an error or the end of a stream of
+    /// responses.
+    pub fn send_rpc_error_response(
+        &mut self,
+        peer_id: PeerId,
+        request_id: RequestId,
+        rpc_error_response: RPCErrorResponse,
+    ) {
+        self.send_rpc_event(peer_id, RPCEvent::Response(request_id, rpc_error_response));
+    }
+
+    /// Reports the result of validating the gossip message with `message_id` to the network
+    /// service. The message is only propagated if it was accepted.
+    pub fn validate_gossip(
+        &mut self,
+        message_id: MessageId,
+        propagation_source: PeerId,
+        result: GossipValidationResult,
+    ) {
+        self.network_send
+            .try_send(NetworkMessage::Validate {
+                message_id,
+                propagation_source,
+                result,
+            })
+            .unwrap_or_else(|_| {
+                warn!(
+                    self.log,
+                    "Could not send gossip validation result to the network service"
+                )
+            });
+    }
+
+    /// Updates the score of `peer_id` due to `action`. The peer is disconnected and banned if its
+    /// score becomes too low.
+    pub fn report_peer(&mut self, peer_id: PeerId, action: PeerAction) {
+        self.network_send
+            .try_send(NetworkMessage::ReportPeer { peer_id, action })
+            .unwrap_or_else(|_| {
+                warn!(
+                    self.log,
+                    "Could not send peer report to the network service"
+                )
+            });
+    }
+
     fn send_rpc_event(&mut self, peer_id: PeerId, rpc_event: RPCEvent) {
         self.send(peer_id, OutgoingMessage::RPC(rpc_event))
     }
@@ -6,7 +6,7 @@ use core::marker::PhantomData;
 use eth2_libp2p::Service as LibP2PService;
 use eth2_libp2p::Topic;
 use eth2_libp2p::{Enr, Libp2pEvent, Multiaddr, PeerId, Swarm};
-use eth2_libp2p::{PubsubMessage, RPCEvent};
+use eth2_libp2p::{GossipValidationResult, MessageId, PeerAction, PubsubMessage, RPCEvent};
 use futures::prelude::*;
 use futures::Stream;
 use parking_lot::Mutex;
@@ -111,6 +111,18 @@ impl<T: BeaconChainTypes + 'static> Service<T> {
             .collect()
     }
 
+    /// Returns the `PeerId` and score of each peer that is connected via libp2p.
+    pub fn connected_peer_scores(&self) -> Vec<(PeerId, f64)> {
+        let libp2p_service = self.libp2p_service.lock();
+        libp2p_service
+            .swarm
+            .discovery()
+            .connected_peer_set()
+            .iter()
+            .map(|peer_id| (peer_id.clone(), libp2p_service.swarm.peer_score(peer_id)))
+            .collect()
+    }
+
     /// Provides a reference to the underlying libp2p service.
     pub fn libp2p_service(&self) -> Arc<Mutex<LibP2PService>> {
         self.libp2p_service.clone()
@@ -168,6 +180,21 @@ fn network_service(
                         debug!(log, "Sending pubsub message"; "topics" => format!("{:?}",topics));
                         libp2p_service.lock().swarm.publish(topics, message);
                     }
+                    NetworkMessage::Validate {
+                        message_id,
+                        propagation_source,
+                        result,
+                    } => {
+                        trace!(log, "Gossip message validated"; "result" => format!("{:?}", result));
+                        libp2p_service.lock().swarm.validate_gossip(
+                            &message_id,
+                            &propagation_source,
+                            result,
+                        );
+                    }
+                    NetworkMessage::ReportPeer { peer_id, action } => {
+                        libp2p_service.lock().swarm.report_peer(&peer_id, action);
+                    }
                 },
                 Ok(Async::NotReady) => break,
                 Ok(Async::Ready(None)) => {
@@ -202,12 +229,15 @@ fn network_service(
                             .map_err(|_| "Failed to send PeerDisconnected to handler")?;
                     }
                     Libp2pEvent::PubsubMessage {
-                        source, message, ..
+                        id,
+                        source,
+                        message,
+                        ..
                     } => {
                         //TODO: Decide if we need to propagate the topic upwards. (Potentially for
                         //attestations)
                         message_handler_send
-                            .try_send(HandlerMessage::PubsubMessage(source, message))
+                            .try_send(HandlerMessage::PubsubMessage(id, source, message))
                             .map_err(|_| " failed to send pubsub message to handler")?;
                     }
                 },
@@ -232,6 +262,15 @@ pub enum NetworkMessage {
         topics: Vec<Topic>,
         message: PubsubMessage,
     },
+    /// Report the result of validating a received gossip message. The message is only propagated
+    /// to our peers if it was accepted.
+    Validate {
+        message_id: MessageId,
+        propagation_source: PeerId,
+        result: GossipValidationResult,
+    },
+    /// Update the score of a peer due to its behaviour.
+    ReportPeer { peer_id: PeerId, action: PeerAction },
 }
 
 /// Type of outgoing messages that can be sent through the network service.
@@ -1,32 +1,31 @@
-use super::import_queue::{ImportQueue, PartialBeaconBlockCompletion};
+use super::import_queue::ImportQueue;
 use crate::message_handler::NetworkContext;
-use beacon_chain::{BeaconChain, BeaconChainTypes, BlockProcessingOutcome};
+use beacon_chain::{
+    AttestationProcessingOutcome, BeaconChain, BeaconChainTypes, BlockProcessingOutcome,
+};
 use eth2_libp2p::rpc::methods::*;
-use eth2_libp2p::rpc::{RPCRequest, RPCResponse, RequestId};
-use eth2_libp2p::PeerId;
+use eth2_libp2p::rpc::{
+    ErrorMessage, RPCErrorResponse, RPCRequest, RPCResponse, RequestId, ResponseTermination,
+};
+use eth2_libp2p::{GossipValidationResult, PeerAction, PeerId};
 use slog::{debug, error, info, o, trace, warn};
 use ssz::Encode;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use store::Store;
-use types::{
-    Attestation, BeaconBlock, BeaconBlockBody, BeaconBlockHeader, Epoch, EthSpec, Hash256, Slot,
-};
+use types::{Attestation, BeaconBlock, Epoch, EthSpec, Hash256, Slot};
 
 /// The number of slots that we can import blocks ahead of us, before going into full Sync mode.
 const SLOT_IMPORT_TOLERANCE: u64 = 100;
 
-/// The amount of seconds a block (or partial block) may exist in the import queue.
+/// The amount of seconds a block may exist in the import queue.
 const QUEUE_STALE_SECS: u64 = 100;
 
 /// If a block is more than `FUTURE_SLOT_TOLERANCE` slots ahead of our slot clock, we drop it.
 /// Otherwise we queue it.
 const FUTURE_SLOT_TOLERANCE: u64 = 1;
 
-const SHOULD_FORWARD_GOSSIP_BLOCK: bool = true;
-const SHOULD_NOT_FORWARD_GOSSIP_BLOCK: bool = false;
-
 /// Keeps track of syncing information for known connected peers.
 #[derive(Clone, Copy, Debug)]
 pub struct PeerSyncInfo {
@@ -57,6 +56,32 @@ impl<T: BeaconChainTypes> From<&Arc<BeaconChain<T>>> for PeerSyncInfo {
     }
 }
 
+/// A range of blocks that is downloaded from a peer in batches of at most `MAX_REQUEST_BLOCKS`.
+struct RangeSync {
+    /// The batch that is currently being downloaded.
+    batch: BlocksByRangeRequest,
+    /// The slot following the last slot of the range.
+    end_slot: Slot,
+}
+
+impl RangeSync {
+    /// Returns the request for the batch following the current one, if the range has not been
+    /// completely requested.
+    fn next_batch(&self) -> Option<BlocksByRangeRequest> {
+        let start_slot = self.batch.end_slot();
+
+        if start_slot < self.end_slot {
+            Some(BlocksByRangeRequest {
+                start_slot,
+                count: (self.end_slot - start_slot).as_u64() / self.batch.step,
+                step: self.batch.step,
+            })
+        } else {
+            None
+        }
+    }
+}
+
 /// The current syncing state.
 #[derive(PartialEq)]
 pub enum SyncState {
@@ -73,6 +98,8 @@ pub struct SimpleSync<T: BeaconChainTypes> {
     known_peers: HashMap<PeerId, PeerSyncInfo>,
     /// A queue to allow importing of blocks
     import_queue: ImportQueue<T>,
+    /// The ranges of blocks that are being downloaded from each peer.
+    range_syncs: HashMap<PeerId, RangeSync>,
     /// The current state of the syncing protocol.
     state: SyncState,
     log: slog::Logger,
@@ -85,12 +112,12 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
 
         let queue_item_stale_time = Duration::from_secs(QUEUE_STALE_SECS);
 
-        let import_queue =
-            ImportQueue::new(beacon_chain.clone(), queue_item_stale_time, log.clone());
+        let import_queue = ImportQueue::new(beacon_chain.clone(), queue_item_stale_time);
         SimpleSync {
             chain: beacon_chain.clone(),
             known_peers: HashMap::new(),
             import_queue,
+            range_syncs: HashMap::new(),
             state: SyncState::Idle,
             log: sync_logger,
         }
@@ -107,6 +134,7 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
         );
 
         self.known_peers.remove(&peer_id);
+        self.remove_range_sync(&peer_id);
     }
 
     /// Handle a peer disconnect.
@@ -118,6 +146,7 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
             "peer" => format!("{:?}", peer_id),
         );
         self.known_peers.remove(&peer_id);
+        self.remove_range_sync(&peer_id);
     }
 
     /// Handle the connection of a new peer.
@@ -251,13 +280,15 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
             let start_slot = local
                 .latest_finalized_epoch
                 .start_slot(T::EthSpec::slots_per_epoch());
-            let required_slots = remote.best_slot - start_slot;
+            // Request every block up to and including the remote head.
+            let required_slots = remote.best_slot + 1 - start_slot;
 
-            self.request_block_roots(
+            self.request_blocks_by_range(
                 peer_id,
-                BeaconBlockRootsRequest {
+                BlocksByRangeRequest {
                     start_slot,
                     count: required_slots.as_u64(),
+                    step: 1,
                 },
                 network,
             );
@@ -271,297 +302,284 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
             .map(|(root, _slot)| root)
     }
 
-    /// Handle a `BeaconBlockRoots` request from the peer.
-    pub fn on_beacon_block_roots_request(
+    /// Handle a `BlocksByRange` request from the peer.
+    ///
+    /// Responds with a stream of the blocks in the canonical chain at the requested slots.
+    pub fn on_blocks_by_range_request(
         &mut self,
         peer_id: PeerId,
         request_id: RequestId,
-        req: BeaconBlockRootsRequest,
+        req: BlocksByRangeRequest,
         network: &mut NetworkContext,
     ) {
         debug!(
             self.log,
-            "BlockRootsRequest";
+            "BlocksByRangeRequest";
             "peer" => format!("{:?}", peer_id),
             "count" => req.count,
             "start_slot" => req.start_slot,
+            "step" => req.step,
         );
 
-        let mut roots: Vec<BlockRootSlot> = self
+        if req.step == 0 {
+            network.send_rpc_error_response(
+                peer_id,
+                request_id,
+                RPCErrorResponse::InvalidRequest(ErrorMessage::new("Step must not be zero")),
+            );
+            return;
+        }
+
+        if req.count > MAX_REQUEST_BLOCKS {
+            network.send_rpc_error_response(
+                peer_id,
+                request_id,
+                RPCErrorResponse::InvalidRequest(ErrorMessage::new("Count exceeds the maximum")),
+            );
+            return;
+        }
+
+        // Collect the block roots. The root of a skipped slot is that of the previous block.
+        let mut roots: Vec<Hash256> = self
             .chain
             .rev_iter_block_roots()
             .take_while(|(_root, slot)| req.start_slot <= *slot)
-            .filter(|(_root, slot)| *slot < req.start_slot + req.count)
-            .map(|(block_root, slot)| BlockRootSlot { slot, block_root })
+            .filter(|(_root, slot)| *slot < req.end_slot())
+            .map(|(root, _slot)| root)
             .collect();
 
-        if roots.len() as u64 != req.count {
-            debug!(
-                self.log,
-                "BlockRootsRequest";
-                "peer" => format!("{:?}", peer_id),
-                "msg" => "Failed to return all requested hashes",
-                "start_slot" => req.start_slot,
-                "current_slot" => self.chain.present_slot(),
-                "requested" => req.count,
-                "returned" => roots.len(),
-            );
+        roots.reverse();
+        roots.dedup();
+
+        let mut returned = 0;
+        for root in roots {
+            if let Ok(Some(block)) = self.chain.store.get::<BeaconBlock<T::EthSpec>>(&root) {
+                // If the start slot was skipped, the first block is prior to the start slot.
+                if req.includes_slot(block.slot) {
+                    network.send_rpc_response(
+                        peer_id.clone(),
+                        request_id,
+                        RPCResponse::BlocksByRange(block.as_ssz_bytes()),
+                    );
+                    returned += 1;
+                }
+            } else {
+                error!(
+                    self.log,
+                    "Block in the canonical chain is not in the store";
+                    "request_root" => format!("{:}", root),
+                );
+            }
         }
 
-        roots.reverse();
-        roots.dedup_by_key(|brs| brs.block_root);
+        debug!(
+            self.log,
+            "BlocksByRangeRequest";
+            "peer" => format!("{:?}", peer_id),
+            "start_slot" => req.start_slot,
+            "current_slot" => self.chain.present_slot(),
+            "requested" => req.count,
+            "returned" => returned,
+        );
 
-        network.send_rpc_response(
+        network.send_rpc_error_response(
             peer_id,
             request_id,
-            RPCResponse::BeaconBlockRoots(BeaconBlockRootsResponse { roots }),
-        )
+            RPCErrorResponse::StreamTermination(ResponseTermination::BlocksByRange),
+        );
     }
 
-    /// Handle a `BeaconBlockRoots` response from the peer.
-    pub fn on_beacon_block_roots_response(
+    /// Handle a `BlocksByRoot` request from the peer.
+    ///
+    /// Responds with a stream of the requested blocks that are known to us.
+    pub fn on_blocks_by_root_request(
         &mut self,
         peer_id: PeerId,
-        res: BeaconBlockRootsResponse,
+        request_id: RequestId,
+        req: BlocksByRootRequest,
         network: &mut NetworkContext,
     ) {
-        debug!(
-            self.log,
-            "BlockRootsResponse";
-            "peer" => format!("{:?}", peer_id),
-            "count" => res.roots.len(),
-        );
-
-        if res.roots.is_empty() {
-            warn!(
-                self.log,
-                "Peer returned empty block roots response";
-                "peer_id" => format!("{:?}", peer_id)
-            );
-            return;
-        }
-
-        // The wire protocol specifies that slots must be in ascending order.
-        if !res.slots_are_ascending() {
-            warn!(
-                self.log,
-                "Peer returned block roots response with bad slot ordering";
-                "peer_id" => format!("{:?}", peer_id)
+        if req.block_roots.len() as u64 > MAX_REQUEST_BLOCKS {
+            network.send_rpc_error_response(
+                peer_id,
+                request_id,
+                RPCErrorResponse::InvalidRequest(ErrorMessage::new(
+                    "Number of block roots exceeds the maximum",
+                )),
             );
             return;
         }
 
-        let new_roots = self
-            .import_queue
-            .enqueue_block_roots(&res.roots, peer_id.clone());
-
-        // No new roots means nothing to do.
-        //
-        // This check protects against future panics.
-        if new_roots.is_empty() {
-            return;
+        let mut returned = 0;
+        for root in req.block_roots.iter() {
+            if let Ok(Some(block)) = self.chain.store.get::<BeaconBlock<T::EthSpec>>(root) {
+                network.send_rpc_response(
+                    peer_id.clone(),
+                    request_id,
+                    RPCResponse::BlocksByRoot(block.as_ssz_bytes()),
+                );
+                returned += 1;
+            } else {
+                debug!(
+                    self.log,
+                    "Peer requested unknown block";
+                    "peer" => format!("{:?}", peer_id),
+                    "request_root" => format!("{:}", root),
+                );
+            }
         }
 
-        // Determine the first (earliest) and last (latest) `BlockRootSlot` items.
-        //
-        // This logic relies upon slots to be in ascending order, which is enforced earlier.
-        let first = new_roots.first().expect("Non-empty list must have first");
-        let last = new_roots.last().expect("Non-empty list must have last");
+        debug!(
+            self.log,
+            "BlocksByRootRequest";
+            "peer" => format!("{:?}", peer_id),
+            "requested" => req.block_roots.len(),
+            "returned" => returned,
+        );
 
-        // Request all headers between the earliest and latest new `BlockRootSlot` items.
-        self.request_block_headers(
+        network.send_rpc_error_response(
             peer_id,
-            BeaconBlockHeadersRequest {
-                start_root: first.block_root,
-                start_slot: first.slot,
-                max_headers: (last.slot - first.slot + 1).as_u64(),
-                skip_slots: 0,
-            },
-            network,
-        )
+            request_id,
+            RPCErrorResponse::StreamTermination(ResponseTermination::BlocksByRoot),
+        );
     }
 
-    /// Handle a `BeaconBlockHeaders` request from the peer.
-    pub fn on_beacon_block_headers_request(
+    /// Handle a `BlocksByRange` response from the peer, which is a single block of the stream.
+    pub fn on_blocks_by_range_response(
         &mut self,
         peer_id: PeerId,
-        request_id: RequestId,
-        req: BeaconBlockHeadersRequest,
+        block: BeaconBlock<T::EthSpec>,
         network: &mut NetworkContext,
     ) {
-        debug!(
+        trace!(
             self.log,
-            "BlockHeadersRequest";
+            "BlocksByRangeResponse";
             "peer" => format!("{:?}", peer_id),
-            "count" => req.max_headers,
+            "slot" => block.slot,
         );
 
-        let count = req.max_headers;
-
-        // Collect the block roots.
-        let mut roots: Vec<Hash256> = self
-            .chain
-            .rev_iter_block_roots()
-            .take_while(|(_root, slot)| req.start_slot <= *slot)
-            .filter(|(_root, slot)| *slot < req.start_slot + count)
-            .map(|(root, _slot)| root)
-            .collect();
-
-        roots.reverse();
-        roots.dedup();
-
-        let headers: Vec<BeaconBlockHeader> = roots
-            .into_iter()
-            .step_by(req.skip_slots as usize + 1)
-            .filter_map(|root| {
-                let block = self
-                    .chain
-                    .store
-                    .get::<BeaconBlock<T::EthSpec>>(&root)
-                    .ok()?;
-                Some(block?.block_header())
-            })
-            .collect();
-
-        // ssz-encode the headers
-        let headers = headers.as_ssz_bytes();
-
-        network.send_rpc_response(
-            peer_id,
-            request_id,
-            RPCResponse::BeaconBlockHeaders(BeaconBlockHeadersResponse { headers }),
-        )
+        self.process_rpc_block(peer_id, block, network);
     }
 
-    /// Handle a `BeaconBlockHeaders` response from the peer.
-    pub fn on_beacon_block_headers_response(
+    /// Handle a `BlocksByRoot` response from the peer, which is a single block of the stream.
+    pub fn on_blocks_by_root_response(
         &mut self,
         peer_id: PeerId,
-        headers: Vec<BeaconBlockHeader>,
+        block: BeaconBlock<T::EthSpec>,
         network: &mut NetworkContext,
     ) {
-        debug!(
+        trace!(
             self.log,
-            "BlockHeadersResponse";
+            "BlocksByRootResponse";
             "peer" => format!("{:?}", peer_id),
-            "count" => headers.len(),
+            "slot" => block.slot,
         );
 
-        if headers.is_empty() {
-            warn!(
-                self.log,
-                "Peer returned empty block headers response. PeerId: {:?}", peer_id
-            );
-            return;
-        }
-
-        // Enqueue the headers, obtaining a list of the roots of the headers which were newly added
-        // to the queue.
-        let block_roots = self.import_queue.enqueue_headers(headers, peer_id.clone());
-
-        if !block_roots.is_empty() {
-            self.request_block_bodies(peer_id, BeaconBlockBodiesRequest { block_roots }, network);
-        }
+        self.process_rpc_block(peer_id, block, network);
     }
 
-    /// Handle a `BeaconBlockBodies` request from the peer.
-    pub fn on_beacon_block_bodies_request(
+    /// Handle the end of a stream of responses from the peer.
+    ///
+    /// If the stream was a batch of a larger range of blocks, the next batch is requested.
+    pub fn on_stream_termination(
         &mut self,
         peer_id: PeerId,
-        request_id: RequestId,
-        req: BeaconBlockBodiesRequest,
+        termination: ResponseTermination,
         network: &mut NetworkContext,
     ) {
-        let block_bodies: Vec<BeaconBlockBody<_>> = req
-            .block_roots
-            .iter()
-            .filter_map(|root| {
-                if let Ok(Some(block)) = self.chain.store.get::<BeaconBlock<T::EthSpec>>(root) {
-                    Some(block.body)
-                } else {
-                    debug!(
-                        self.log,
-                        "Peer requested unknown block";
-                        "peer" => format!("{:?}", peer_id),
-                        "request_root" => format!("{:}", root),
-                    );
-
-                    None
-                }
-            })
-            .collect();
-
         debug!(
             self.log,
-            "BlockBodiesRequest";
+            "RPCStreamTermination";
             "peer" => format!("{:?}", peer_id),
-            "requested" => req.block_roots.len(),
-            "returned" => block_bodies.len(),
+            "response" => format!("{:?}", termination),
         );
 
-        let bytes = block_bodies.as_ssz_bytes();
+        if termination == ResponseTermination::BlocksByRange {
+            let next_batch = self
+                .range_syncs
+                .get(&peer_id)
+                .and_then(RangeSync::next_batch);
 
-        network.send_rpc_response(
-            peer_id,
-            request_id,
-            RPCResponse::BeaconBlockBodies(BeaconBlockBodiesResponse {
-                block_bodies: bytes,
-                block_roots: None,
-            }),
-        )
+            if let Some(req) = next_batch {
+                self.request_blocks_by_range(peer_id, req, network);
+            } else {
+                self.remove_range_sync(&peer_id);
+            }
+        }
+
+        // Clear out old entries
+        self.import_queue.remove_stale();
     }
 
-    /// Handle a `BeaconBlockBodies` response from the peer.
-    pub fn on_beacon_block_bodies_response(
+    /// Stops downloading blocks from `peer_id`, entering the idle state if blocks are no longer
+    /// being downloaded from any peer.
+    fn remove_range_sync(&mut self, peer_id: &PeerId) {
+        self.range_syncs.remove(peer_id);
+
+        if self.range_syncs.is_empty() && self.state == SyncState::Downloading {
+            debug!(self.log, "Entering idle sync state.");
+            self.state = SyncState::Idle;
+        }
+    }
+
+    /// Processes a block received via RPC, followed by any queued blocks which descend from it.
+    ///
+    /// If the parent of a block is unknown, the block is queued and its parent is requested from
+    /// the peer.
+    fn process_rpc_block(
         &mut self,
         peer_id: PeerId,
-        res: DecodedBeaconBlockBodiesResponse<T::EthSpec>,
+        block: BeaconBlock<T::EthSpec>,
         network: &mut NetworkContext,
     ) {
-        debug!(
-            self.log,
-            "BlockBodiesResponse";
-            "peer" => format!("{:?}", peer_id),
-            "count" => res.block_bodies.len(),
-        );
+        let mut blocks = vec![block];
+
+        while let Some(block) = blocks.pop() {
+            match self.process_block(peer_id.clone(), block.clone(), network, &"rpc") {
+                Some(BlockProcessingOutcome::Processed { block_root }) => {
+                    self.import_queue.remove(block_root);
 
-        if !res.block_bodies.is_empty() {
-            // Import all blocks to queue
-            let last_root = self
-                .import_queue
-                .enqueue_bodies(res.block_bodies, peer_id.clone());
-
-            // Attempt to process all received bodies by recursively processing the latest block
-            if let Some(root) = last_root {
-                if let Some(BlockProcessingOutcome::Processed { .. }) =
-                    self.attempt_process_partial_block(peer_id, root, network, &"rpc")
-                {
-                    // If processing is successful remove from `import_queue`
-                    self.import_queue.remove(root);
+                    // Any queued children of the block may now be processed.
+                    blocks.append(&mut self.import_queue.remove_children(block_root));
                 }
+                Some(BlockProcessingOutcome::ParentUnknown { parent }) => {
+                    // A queued parent is already awaiting its own parent.
+                    if !self.import_queue.contains_block_root(parent) {
+                        self.request_blocks_by_root(
+                            peer_id.clone(),
+                            BlocksByRootRequest {
+                                block_roots: vec![parent],
+                            },
+                            network,
+                        );
+                    }
+
+                    self.import_queue
+                        .enqueue_full_blocks(vec![block], peer_id.clone());
+                }
+                _ => {}
             }
         }
-
-        // Clear out old entries
-        self.import_queue.remove_stale();
     }
 
     /// Process a gossip message declaring a new block.
     ///
     /// Attempts to apply to block to the beacon chain. May queue the block for later processing.
     ///
-    /// Returns a `bool` which, if `true`, indicates we should forward the block to our peers.
+    /// Returns the result of validating the block, which determines whether it is forwarded to
+    /// our peers. Only blocks that were imported are accepted; blocks that could not yet be
+    /// validated (e.g., their parent is unknown) or are already known are ignored.
     pub fn on_block_gossip(
         &mut self,
         peer_id: PeerId,
         block: BeaconBlock<T::EthSpec>,
         network: &mut NetworkContext,
-    ) -> bool {
+    ) -> GossipValidationResult {
         if let Some(outcome) =
             self.process_block(peer_id.clone(), block.clone(), network, &"gossip")
         {
             match outcome {
-                BlockProcessingOutcome::Processed { .. } => SHOULD_FORWARD_GOSSIP_BLOCK,
+                BlockProcessingOutcome::Processed { .. } => GossipValidationResult::Accept,
                 BlockProcessingOutcome::ParentUnknown { parent } => {
                     // Add this block to the queue
                     self.import_queue
@@ -573,7 +591,7 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
                         "peer" => format!("{:?}", peer_id),
                     );
 
-                    // Request roots between parent and start of finality from peer.
+                    // Request blocks between parent and start of finality from peer.
                     let start_slot = self
                         .chain
                         .head()
@@ -581,12 +599,13 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
                         .finalized_checkpoint
                         .epoch
                         .start_slot(T::EthSpec::slots_per_epoch());
-                    self.request_block_roots(
+                    self.request_blocks_by_range(
                         peer_id,
-                        BeaconBlockRootsRequest {
+                        BlocksByRangeRequest {
                             // Request blocks between `latest_finalized_slot` and the `block`
                             start_slot,
                             count: block.slot.as_u64() - start_slot.as_u64(),
+                            step: 1,
                         },
                         network,
                     );
@@ -594,7 +613,7 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
                     // Clean the stale entries from the queue.
                     self.import_queue.remove_stale();
 
-                    SHOULD_FORWARD_GOSSIP_BLOCK
+                    GossipValidationResult::Ignore
                 }
 
                 BlockProcessingOutcome::FutureSlot {
@@ -604,47 +623,69 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
                     self.import_queue
                         .enqueue_full_blocks(vec![block], peer_id.clone());
 
-                    SHOULD_FORWARD_GOSSIP_BLOCK
+                    GossipValidationResult::Ignore
+                }
+                // Note: known blocks are not forwarded on the gossip network, they were either
+                // forwarded when they were first received or were received via RPC.
+                BlockProcessingOutcome::BlockIsAlreadyKnown
+                | BlockProcessingOutcome::GenesisBlock
+                | BlockProcessingOutcome::FinalizedSlot => GossipValidationResult::Ignore,
+                BlockProcessingOutcome::FutureSlot { .. }
+                | BlockProcessingOutcome::StateRootMismatch
+                | BlockProcessingOutcome::PerBlockProcessingError(_) => {
+                    GossipValidationResult::Reject
                 }
-                // Note: known blocks are forwarded on the gossip network.
-                //
-                // We rely upon the lower layers (libp2p) to stop loops occurring from re-gossiped
-                // blocks.
-                BlockProcessingOutcome::BlockIsAlreadyKnown => SHOULD_FORWARD_GOSSIP_BLOCK,
-                _ => SHOULD_NOT_FORWARD_GOSSIP_BLOCK,
             }
         } else {
-            SHOULD_NOT_FORWARD_GOSSIP_BLOCK
+            // The block could not be processed due to an internal error.
+            GossipValidationResult::Ignore
         }
     }
 
     /// Process a gossip message declaring a new attestation.
     ///
-    /// Not currently implemented.
+    /// Returns the result of validating the attestation, which determines whether it is
+    /// forwarded to our peers.
     pub fn on_attestation_gossip(
         &mut self,
         _peer_id: PeerId,
         msg: Attestation<T::EthSpec>,
         _network: &mut NetworkContext,
-    ) {
+    ) -> GossipValidationResult {
         match self.chain.process_attestation(msg) {
-            Ok(outcome) => info!(
-                self.log,
-                "Processed attestation";
-                "source" => "gossip",
-                "outcome" => format!("{:?}", outcome)
-            ),
+            Ok(outcome) => {
+                info!(
+                    self.log,
+                    "Processed attestation";
+                    "source" => "gossip",
+                    "outcome" => format!("{:?}", outcome)
+                );
+
+                match outcome {
+                    AttestationProcessingOutcome::Processed => GossipValidationResult::Accept,
+                    AttestationProcessingOutcome::UnknownHeadBlock { .. }
+                    | AttestationProcessingOutcome::FinalizedSlot { .. } => {
+                        GossipValidationResult::Ignore
+                    }
+                    AttestationProcessingOutcome::AttestsToFutureState { .. }
+                    | AttestationProcessingOutcome::Invalid(_) => GossipValidationResult::Reject,
+                }
+            }
             Err(e) => {
-                warn!(self.log, "InvalidAttestation"; "source" => "gossip", "error" => format!("{:?}", e))
+                warn!(self.log, "InvalidAttestation"; "source" => "gossip", "error" => format!("{:?}", e));
+                GossipValidationResult::Ignore
             }
         }
     }
 
-    /// Request some `BeaconBlockRoots` from the remote peer.
-    fn request_block_roots(
+    /// Request a `BlocksByRange` stream from the remote peer.
+    ///
+    /// Peers reject requests for more than `MAX_REQUEST_BLOCKS`, so larger ranges are requested in
+    /// batches. The next batch is requested when the stream of the previous batch terminates.
+    fn request_blocks_by_range(
         &mut self,
         peer_id: PeerId,
-        req: BeaconBlockRootsRequest,
+        mut req: BlocksByRangeRequest,
         network: &mut NetworkContext,
     ) {
         // Potentially set state to sync.
@@ -653,49 +694,44 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
             self.state = SyncState::Downloading;
         }
 
-        debug!(
-            self.log,
-            "RPCRequest(BeaconBlockRoots)";
-            "count" => req.count,
-            "peer" => format!("{:?}", peer_id)
-        );
+        let end_slot = req.end_slot();
+        req.count = std::cmp::min(req.count, MAX_REQUEST_BLOCKS);
 
-        // TODO: handle count > max count.
-        network.send_rpc_request(peer_id.clone(), RPCRequest::BeaconBlockRoots(req));
-    }
+        self.range_syncs.insert(
+            peer_id.clone(),
+            RangeSync {
+                batch: req.clone(),
+                end_slot,
+            },
+        );
 
-    /// Request some `BeaconBlockHeaders` from the remote peer.
-    fn request_block_headers(
-        &mut self,
-        peer_id: PeerId,
-        req: BeaconBlockHeadersRequest,
-        network: &mut NetworkContext,
-    ) {
         debug!(
             self.log,
-            "RPCRequest(BeaconBlockHeaders)";
-            "max_headers" => req.max_headers,
+            "RPCRequest(BlocksByRange)";
+            "start_slot" => req.start_slot,
+            "count" => req.count,
+            "step" => req.step,
             "peer" => format!("{:?}", peer_id)
         );
 
-        network.send_rpc_request(peer_id.clone(), RPCRequest::BeaconBlockHeaders(req));
+        network.send_rpc_request(peer_id.clone(), RPCRequest::BlocksByRange(req));
     }
 
-    /// Request some `BeaconBlockBodies` from the remote peer.
-    fn request_block_bodies(
+    /// Request a `BlocksByRoot` stream from the remote peer.
+    fn request_blocks_by_root(
         &mut self,
         peer_id: PeerId,
-        req: BeaconBlockBodiesRequest,
+        req: BlocksByRootRequest,
         network: &mut NetworkContext,
     ) {
         debug!(
             self.log,
-            "RPCRequest(BeaconBlockBodies)";
+            "RPCRequest(BlocksByRoot)";
             "count" => req.block_roots.len(),
             "peer" => format!("{:?}", peer_id)
         );
 
-        network.send_rpc_request(peer_id.clone(), RPCRequest::BeaconBlockBodies(req));
+        network.send_rpc_request(peer_id.clone(), RPCRequest::BlocksByRoot(req));
     }
 
     /// Returns `true` if `self.chain` has not yet processed this block.
@@ -714,86 +750,37 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
         hello_message(&self.chain)
     }
 
-    /// Helper function to attempt to process a partial block.
+    /// Helper function to attempt to process a block from the import queue.
     ///
-    /// If the block can be completed recursively call `process_block`
-    /// else request missing parts.
-    fn attempt_process_partial_block(
+    /// If the block is in the queue, recursively call `process_block`.
+    fn attempt_process_queued_block(
         &mut self,
         peer_id: PeerId,
         block_root: Hash256,
         network: &mut NetworkContext,
         source: &str,
     ) -> Option<BlockProcessingOutcome> {
-        match self.import_queue.attempt_complete_block(block_root) {
-            PartialBeaconBlockCompletion::MissingBody => {
-                // Unable to complete the block because the block body is missing.
-                debug!(
-                    self.log, "RequestParentBody";
-                    "source" => source,
-                    "block_root" => format!("{}", block_root),
-                    "peer" => format!("{:?}", peer_id),
-                );
-
-                // Request the block body from the peer.
-                self.request_block_bodies(
-                    peer_id,
-                    BeaconBlockBodiesRequest {
-                        block_roots: vec![block_root],
-                    },
-                    network,
-                );
-
-                None
-            }
-            PartialBeaconBlockCompletion::MissingHeader(slot) => {
-                // Unable to complete the block because the block header is missing.
-                debug!(
-                    self.log, "RequestParentHeader";
-                    "source" => source,
-                    "block_root" => format!("{}", block_root),
-                    "peer" => format!("{:?}", peer_id),
-                );
-
-                // Request the block header from the peer.
-                self.request_block_headers(
-                    peer_id,
-                    BeaconBlockHeadersRequest {
-                        start_root: block_root,
-                        start_slot: slot,
-                        max_headers: 1,
-                        skip_slots: 0,
-                    },
-                    network,
-                );
-
-                None
-            }
-            PartialBeaconBlockCompletion::MissingRoot => {
-                // The `block_root` is not known to the queue.
-                debug!(
-                    self.log, "MissingParentRoot";
-                    "source" => source,
-                    "block_root" => format!("{}", block_root),
-                    "peer" => format!("{:?}", peer_id),
-                );
-
-                // Do nothing.
+        if let Some(block) = self.import_queue.get(block_root) {
+            // The block exists in the queue, attempt to process it
+            trace!(
+                self.log, "AttemptProcessParent";
+                "source" => source,
+                "block_root" => format!("{}", block_root),
+                "parent_slot" => block.slot,
+                "peer" => format!("{:?}", peer_id),
+            );
 
-                None
-            }
-            PartialBeaconBlockCompletion::Complete(block) => {
-                // The block exists in the queue, attempt to process it
-                trace!(
-                    self.log, "AttemptProcessParent";
-                    "source" => source,
-                    "block_root" => format!("{}", block_root),
-                    "parent_slot" => block.slot,
-                    "peer" => format!("{:?}", peer_id),
-                );
+            self.process_block(peer_id.clone(), block, network, source)
+        } else {
+            // The `block_root` is not known to the queue.
+            debug!(
+                self.log, "MissingParentRoot";
+                "source" => source,
+                "block_root" => format!("{}", block_root),
+                "peer" => format!("{:?}", peer_id),
+            );
 
-                self.process_block(peer_id.clone(), block, network, source)
-            }
+            None
         }
     }
 
@@ -845,7 +832,7 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
                     // If the parent is in the `import_queue` attempt to complete it then process it.
                     // All other cases leave `parent` in `import_queue` and return original outcome.
                     if let Some(BlockProcessingOutcome::Processed { .. }) =
-                        self.attempt_process_partial_block(peer_id, parent, network, source)
+                        self.attempt_process_queued_block(peer_id, parent, network, source)
                     {
                         // If processing parent is successful, re-process block and remove parent from queue
                         self.import_queue.remove(parent);
@@ -886,6 +873,22 @@ impl<T: BeaconChainTypes> SimpleSync<T> {
                         );
                     }
                 }
+                BlockProcessingOutcome::StateRootMismatch
+                | BlockProcessingOutcome::PerBlockProcessingError(_) => {
+                    debug!(
+                        self.log, "InvalidBlock";
+                        "source" => source,
+                        "msg" => "peer sent invalid block",
+                        "outcome" => format!("{:?}", outcome),
+                        "peer" => format!("{:?}", peer_id),
+                    );
+
+                    // Invalid gossip blocks are rejected by `on_block_gossip`, which penalizes the
+                    // peer.
+                    if source != "gossip" {
+                        network.report_peer(peer_id, PeerAction::LowToleranceError);
+                    }
+                }
                 _ => {
                     debug!(
                         self.log, "InvalidBlock";
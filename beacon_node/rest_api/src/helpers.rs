@@ -5,6 +5,7 @@ use hex;
 use hyper::{Body, Request, StatusCode};
 use serde::de::value::StringDeserializer;
 use serde_json::Deserializer;
+use state_processing::SkipSlotProcessor;
 use store::{iter::AncestorIter, Store};
 use types::{BeaconState, EthSpec, Hash256, RelativeEpoch, Slot};
 
@@ -147,21 +148,21 @@ pub fn state_root_at_slot<T: BeaconChainTypes>(
     } else {
         // 4. The request slot is later than the head slot.
         //
-        // Use `per_slot_processing` to advance the head state to the present slot,
+        // Use per-slot processing to advance the head state to the present slot,
         // assuming that all slots do not contain a block (i.e., they are skipped slots).
-        let mut state = beacon_chain.head().beacon_state.clone();
+        let head = beacon_chain.head();
+        let mut state = head.beacon_state.clone();
         let spec = &T::EthSpec::default_spec();
 
+        let mut processor = SkipSlotProcessor::new(Some(head.beacon_state_root));
         for _ in state.slot.as_u64()..slot.as_u64() {
             // Ensure the next epoch state caches are built in case of an epoch transition.
             state.build_committee_cache(RelativeEpoch::Next, spec)?;
 
-            state_processing::per_slot_processing(&mut state, spec)?;
+            processor.per_slot_processing(&mut state, spec)?;
         }
 
-        // Note: this is an expensive operation. Once the tree hash cache is implement it may be
-        // used here.
-        Ok(state.canonical_root())
+        Ok(processor.state_root(&state))
     }
 }
 
@@ -8,14 +8,16 @@ mod helpers;
 mod metrics;
 mod network;
 mod node;
+mod pool;
 mod spec;
 mod url_query;
 mod validator;
 
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use client_network::Service as NetworkService;
+use futures::future::IntoFuture;
 use hyper::rt::Future;
-use hyper::service::service_fn_ok;
+use hyper::service::service_fn;
 use hyper::{Body, Method, Response, Server, StatusCode};
 use slog::{info, o, warn};
 use std::ops::Deref;
@@ -34,11 +36,16 @@ pub enum ApiError {
     NotImplemented(String),
     InvalidQueryParams(String),
     NotFound(String),
+    InvalidBody(String),
     ImATeapot(String), // Just in case.
 }
 
 pub type ApiResult = Result<Response<Body>, ApiError>;
 
+/// The response to a request which must be handled asynchronously (e.g., one which reads the
+/// request body).
+pub type BoxFut = Box<dyn Future<Item = Response<Body>, Error = ApiError> + Send>;
+
 impl Into<Response<Body>> for ApiError {
     fn into(self) -> Response<Body> {
         let status_code: (StatusCode, String) = match self {
@@ -47,6 +54,7 @@ impl Into<Response<Body>> for ApiError {
             ApiError::NotImplemented(desc) => (StatusCode::NOT_IMPLEMENTED, desc),
             ApiError::InvalidQueryParams(desc) => (StatusCode::BAD_REQUEST, desc),
             ApiError::NotFound(desc) => (StatusCode::NOT_FOUND, desc),
+            ApiError::InvalidBody(desc) => (StatusCode::BAD_REQUEST, desc),
             ApiError::ImATeapot(desc) => (StatusCode::IM_A_TEAPOT, desc),
         };
         Response::builder()
@@ -109,7 +117,7 @@ pub fn start_server<T: BeaconChainTypes>(
         let network_service = network_service.clone();
 
         // Create a simple handler for the router, inject our stateful objects into the request.
-        service_fn_ok(move |mut req| {
+        service_fn(move |mut req| {
             metrics::inc_counter(&metrics::REQUEST_COUNT);
             let timer = metrics::start_timer(&metrics::REQUEST_RESPONSE_TIME);
 
@@ -123,91 +131,152 @@ pub fn start_server<T: BeaconChainTypes>(
             let path = req.uri().path().to_string();
 
             // Route the request to the correct handler.
-            let result = match (req.method(), path.as_ref()) {
+            let result: BoxFut = match (req.method(), path.as_ref()) {
                 // Methods for Beacon Node
                 //TODO: Remove?
                 //(&Method::GET, "/beacon/best_slot") => beacon::get_best_slot::<T>(req),
-                (&Method::GET, "/beacon/head") => beacon::get_head::<T>(req),
-                (&Method::GET, "/beacon/block") => beacon::get_block::<T>(req),
-                (&Method::GET, "/beacon/blocks") => helpers::implementation_pending_response(req),
+                (&Method::GET, "/beacon/head") => into_boxfut(beacon::get_head::<T>(req)),
+                (&Method::GET, "/beacon/block") => into_boxfut(beacon::get_block::<T>(req)),
+                (&Method::GET, "/beacon/blocks") => {
+                    into_boxfut(helpers::implementation_pending_response(req))
+                }
                 //TODO Is the below replaced by finalized_checkpoint?
                 (&Method::GET, "/beacon/chainhead") => {
-                    helpers::implementation_pending_response(req)
+                    into_boxfut(helpers::implementation_pending_response(req))
+                }
+                (&Method::GET, "/beacon/block_root") => {
+                    into_boxfut(beacon::get_block_root::<T>(req))
                 }
-                (&Method::GET, "/beacon/block_root") => beacon::get_block_root::<T>(req),
                 (&Method::GET, "/beacon/latest_finalized_checkpoint") => {
-                    beacon::get_latest_finalized_checkpoint::<T>(req)
+                    into_boxfut(beacon::get_latest_finalized_checkpoint::<T>(req))
+                }
+                (&Method::GET, "/beacon/state") => into_boxfut(beacon::get_state::<T>(req)),
+                (&Method::GET, "/beacon/state_root") => {
+                    into_boxfut(beacon::get_state_root::<T>(req))
+                }
+
+                // Methods for the operation pool
+                (&Method::GET, "/beacon/pool/attestations") => {
+                    into_boxfut(pool::get_attestations::<T>(req))
+                }
+                (&Method::GET, "/beacon/pool/attester_slashings") => {
+                    into_boxfut(pool::get_attester_slashings::<T>(req))
+                }
+                (&Method::POST, "/beacon/pool/attester_slashings") => {
+                    pool::post_attester_slashing::<T>(req)
+                }
+                (&Method::GET, "/beacon/pool/proposer_slashings") => {
+                    into_boxfut(pool::get_proposer_slashings::<T>(req))
+                }
+                (&Method::POST, "/beacon/pool/proposer_slashings") => {
+                    pool::post_proposer_slashing::<T>(req)
+                }
+                (&Method::GET, "/beacon/pool/voluntary_exits") => {
+                    into_boxfut(pool::get_voluntary_exits::<T>(req))
+                }
+                (&Method::POST, "/beacon/pool/voluntary_exits") => {
+                    pool::post_voluntary_exit::<T>(req)
+                }
+                (&Method::GET, "/beacon/pool/transfers") => {
+                    into_boxfut(pool::get_transfers::<T>(req))
+                }
+                (&Method::GET, "/beacon/pool/deposits") => {
+                    into_boxfut(pool::get_deposits::<T>(req))
                 }
-                (&Method::GET, "/beacon/state") => beacon::get_state::<T>(req),
-                (&Method::GET, "/beacon/state_root") => beacon::get_state_root::<T>(req),
 
                 //TODO: Add aggreggate/filtered state lookups here, e.g. /beacon/validators/balances
 
                 // Methods for Client
-                (&Method::GET, "/metrics") => metrics::get_prometheus::<T>(req),
-                (&Method::GET, "/network/enr") => network::get_enr::<T>(req),
-                (&Method::GET, "/network/peer_count") => network::get_peer_count::<T>(req),
-                (&Method::GET, "/network/peer_id") => network::get_peer_id::<T>(req),
-                (&Method::GET, "/network/peers") => network::get_peer_list::<T>(req),
-                (&Method::GET, "/network/listen_port") => network::get_listen_port::<T>(req),
+                (&Method::GET, "/metrics") => into_boxfut(metrics::get_prometheus::<T>(req)),
+                (&Method::GET, "/network/enr") => into_boxfut(network::get_enr::<T>(req)),
+                (&Method::GET, "/network/peer_count") => {
+                    into_boxfut(network::get_peer_count::<T>(req))
+                }
+                (&Method::GET, "/network/peer_id") => into_boxfut(network::get_peer_id::<T>(req)),
+                (&Method::GET, "/network/peers") => into_boxfut(network::get_peer_list::<T>(req)),
+                (&Method::GET, "/network/listen_port") => {
+                    into_boxfut(network::get_listen_port::<T>(req))
+                }
                 (&Method::GET, "/network/listen_addresses") => {
-                    network::get_listen_addresses::<T>(req)
+                    into_boxfut(network::get_listen_addresses::<T>(req))
+                }
+                (&Method::GET, "/node/version") => into_boxfut(node::get_version(req)),
+                (&Method::GET, "/node/genesis_time") => {
+                    into_boxfut(node::get_genesis_time::<T>(req))
                 }
-                (&Method::GET, "/node/version") => node::get_version(req),
-                (&Method::GET, "/node/genesis_time") => node::get_genesis_time::<T>(req),
                 (&Method::GET, "/node/deposit_contract") => {
-                    helpers::implementation_pending_response(req)
+                    into_boxfut(helpers::implementation_pending_response(req))
+                }
+                (&Method::GET, "/node/syncing") => {
+                    into_boxfut(helpers::implementation_pending_response(req))
+                }
+                (&Method::GET, "/node/fork") => {
+                    into_boxfut(helpers::implementation_pending_response(req))
                 }
-                (&Method::GET, "/node/syncing") => helpers::implementation_pending_response(req),
-                (&Method::GET, "/node/fork") => helpers::implementation_pending_response(req),
 
                 // Methods for Network
-                (&Method::GET, "/network/enr") => network::get_enr::<T>(req),
-                (&Method::GET, "/network/peer_count") => network::get_peer_count::<T>(req),
-                (&Method::GET, "/network/peer_id") => network::get_peer_id::<T>(req),
-                (&Method::GET, "/network/peers") => network::get_peer_list::<T>(req),
+                (&Method::GET, "/network/enr") => into_boxfut(network::get_enr::<T>(req)),
+                (&Method::GET, "/network/peer_count") => {
+                    into_boxfut(network::get_peer_count::<T>(req))
+                }
+                (&Method::GET, "/network/peer_id") => into_boxfut(network::get_peer_id::<T>(req)),
+                (&Method::GET, "/network/peers") => into_boxfut(network::get_peer_list::<T>(req)),
                 (&Method::GET, "/network/listen_addresses") => {
-                    network::get_listen_addresses::<T>(req)
+                    into_boxfut(network::get_listen_addresses::<T>(req))
                 }
 
                 // Methods for Validator
-                (&Method::GET, "/validator/duties") => validator::get_validator_duties::<T>(req),
-                (&Method::GET, "/validator/block") => helpers::implementation_pending_response(req),
+                (&Method::GET, "/validator/duties") => {
+                    into_boxfut(validator::get_validator_duties::<T>(req))
+                }
+                (&Method::GET, "/validator/rewards") => {
+                    into_boxfut(validator::get_validator_rewards::<T>(req))
+                }
+                (&Method::GET, "/validator/block") => {
+                    into_boxfut(helpers::implementation_pending_response(req))
+                }
                 (&Method::POST, "/validator/block") => {
-                    helpers::implementation_pending_response(req)
+                    into_boxfut(helpers::implementation_pending_response(req))
                 }
                 (&Method::GET, "/validator/attestation") => {
-                    helpers::implementation_pending_response(req)
+                    into_boxfut(helpers::implementation_pending_response(req))
                 }
                 (&Method::POST, "/validator/attestation") => {
-                    helpers::implementation_pending_response(req)
+                    into_boxfut(helpers::implementation_pending_response(req))
                 }
 
-                (&Method::GET, "/spec") => spec::get_spec::<T>(req),
-                (&Method::GET, "/spec/slots_per_epoch") => spec::get_slots_per_epoch::<T>(req),
+                (&Method::GET, "/spec") => into_boxfut(spec::get_spec::<T>(req)),
+                (&Method::GET, "/spec/slots_per_epoch") => {
+                    into_boxfut(spec::get_slots_per_epoch::<T>(req))
+                }
 
-                _ => Err(ApiError::NotFound(
+                _ => Box::new(futures::future::err(ApiError::NotFound(
                     "Request path and/or method not found.".to_owned(),
-                )),
+                ))),
             };
 
-            let response = match result {
-                // Return the `hyper::Response`.
-                Ok(response) => {
-                    metrics::inc_counter(&metrics::SUCCESS_COUNT);
-                    slog::debug!(log, "Request successful: {:?}", path);
-                    response
-                }
-                // Map the `ApiError` into `hyper::Response`.
-                Err(e) => {
-                    slog::debug!(log, "Request failure: {:?}", path);
-                    e.into()
-                }
-            };
-
-            metrics::stop_timer(timer);
-
-            response
+            let log = log.clone();
+            let response = result.then(move |result| {
+                let response = match result {
+                    // Return the `hyper::Response`.
+                    Ok(response) => {
+                        metrics::inc_counter(&metrics::SUCCESS_COUNT);
+                        slog::debug!(log, "Request successful: {:?}", path);
+                        response
+                    }
+                    // Map the `ApiError` into `hyper::Response`.
+                    Err(e) => {
+                        slog::debug!(log, "Request failure: {:?}", path);
+                        e.into()
+                    }
+                };
+
+                metrics::stop_timer(timer);
+
+                Ok::<_, hyper::Error>(response)
+            });
+
+            Box::new(response)
         })
     };
 
@@ -234,6 +303,15 @@ pub fn start_server<T: BeaconChainTypes>(
     Ok(exit_signal)
 }
 
+/// Wraps the result of a synchronous handler so it can be returned alongside asynchronous ones.
+fn into_boxfut<F>(item: F) -> BoxFut
+where
+    F: IntoFuture<Item = Response<Body>, Error = ApiError>,
+    F::Future: Send + 'static,
+{
+    Box::new(item.into_future())
+}
+
 fn success_response(body: Body) -> Response<Body> {
     Response::builder()
         .status(StatusCode::OK)
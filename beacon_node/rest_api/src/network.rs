@@ -2,8 +2,17 @@ use crate::{success_response, ApiError, ApiResult, NetworkService};
 use beacon_chain::BeaconChainTypes;
 use eth2_libp2p::{Enr, Multiaddr, PeerId};
 use hyper::{Body, Request};
+use serde::Serialize;
 use std::sync::Arc;
 
+#[derive(Serialize)]
+pub struct PeerResponse {
+    /// The `PeerId::to_string()` of the peer.
+    pub peer_id: String,
+    /// The reputation score of the peer. Peers are banned if their score becomes too low.
+    pub score: f64,
+}
+
 /// HTTP handle to return the list of libp2p multiaddr the client is listening on.
 ///
 /// Returns a list of `Multiaddr`, serialized according to their `serde` impl.
@@ -87,22 +96,26 @@ pub fn get_peer_count<T: BeaconChainTypes>(req: Request<Body>) -> ApiResult {
 
 /// HTTP handle to return the list of peers connected to the client's libp2p service.
 ///
-/// Peers are presented as a list of `PeerId::to_string()`.
+/// Peers are presented as a list of `PeerResponse`, containing their `PeerId::to_string()` and
+/// score.
 pub fn get_peer_list<T: BeaconChainTypes>(req: Request<Body>) -> ApiResult {
     let network = req
         .extensions()
         .get::<Arc<NetworkService<T>>>()
         .ok_or_else(|| ApiError::ServerError("NetworkService extension missing".to_string()))?;
 
-    let connected_peers: Vec<String> = network
-        .connected_peer_set()
-        .iter()
-        .map(PeerId::to_string)
+    let connected_peers: Vec<PeerResponse> = network
+        .connected_peer_scores()
+        .into_iter()
+        .map(|(peer_id, score)| PeerResponse {
+            peer_id: peer_id.to_string(),
+            score,
+        })
         .collect();
 
     Ok(success_response(Body::from(
         serde_json::to_string(&connected_peers).map_err(|e| {
-            ApiError::ServerError(format!("Unable to serialize Vec<PeerId>: {:?}", e))
+            ApiError::ServerError(format!("Unable to serialize Vec<PeerResponse>: {:?}", e))
         })?,
     )))
 }
@@ -0,0 +1,222 @@
+use crate::helpers::parse_slot;
+use crate::{success_response, ApiError, ApiResult, BoxFut, UrlQuery};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use futures::{Future, Stream};
+use hyper::{Body, Request};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Arc;
+use types::{AttesterSlashing, Deposit, ProposerSlashing, Shard, VoluntaryExit};
+
+/// A deposit in the operation pool, along with its index in the deposit contract.
+#[derive(Serialize)]
+pub struct DepositResponse {
+    pub deposit_index: u64,
+    pub deposit: Deposit,
+}
+
+/// HTTP handler to return the attestations in the operation pool.
+///
+/// Accepts the optional `slot` and `shard` query parameters, which only return attestations
+/// for that slot and/or shard. Attestations whose slot cannot be determined from the current
+/// state (e.g., because they are too old) are never returned when filtering by `slot`.
+pub fn get_attestations<T: BeaconChainTypes + 'static>(req: Request<Body>) -> ApiResult {
+    let beacon_chain = req
+        .extensions()
+        .get::<Arc<BeaconChain<T>>>()
+        .ok_or_else(|| ApiError::ServerError("Beacon chain extension missing".to_string()))?;
+
+    let (slot, shard) = match req.uri().query() {
+        Some(_) => {
+            let query = UrlQuery::from_request(&req)?;
+            let slot = match query.first_of(&["slot"]) {
+                Ok((_, value)) => Some(parse_slot(&value)?),
+                Err(_) => None,
+            };
+            let shard = match query.first_of(&["shard"]) {
+                Ok((_, value)) => Some(value.parse::<Shard>().map_err(|e| {
+                    ApiError::InvalidQueryParams(format!("Unable to parse shard: {:?}", e))
+                })?),
+                Err(_) => None,
+            };
+            (slot, shard)
+        }
+        None => (None, None),
+    };
+
+    let mut attestations = beacon_chain.op_pool.get_all_attestations();
+
+    if let Some(shard) = shard {
+        attestations.retain(|attestation| attestation.data.crosslink.shard == shard);
+    }
+
+    if let Some(slot) = slot {
+        beacon_chain
+            .ensure_state_caches_are_built()
+            .map_err(|e| ApiError::ServerError(format!("Unable to build state caches: {:?}", e)))?;
+        let state = beacon_chain
+            .speculative_state()
+            .map_err(|e| ApiError::ServerError(format!("Unable to read current state: {:?}", e)))?;
+
+        attestations.retain(|attestation| {
+            state
+                .get_attestation_data_slot(&attestation.data)
+                .ok()
+                .map_or(false, |attestation_slot| attestation_slot == slot)
+        });
+    }
+
+    let json: String = serde_json::to_string(&attestations)
+        .map_err(|e| ApiError::ServerError(format!("Unable to serialize attestations: {:?}", e)))?;
+
+    Ok(success_response(Body::from(json)))
+}
+
+/// HTTP handler to return the attester slashings in the operation pool.
+pub fn get_attester_slashings<T: BeaconChainTypes + 'static>(req: Request<Body>) -> ApiResult {
+    let beacon_chain = req
+        .extensions()
+        .get::<Arc<BeaconChain<T>>>()
+        .ok_or_else(|| ApiError::ServerError("Beacon chain extension missing".to_string()))?;
+
+    let json: String = serde_json::to_string(&beacon_chain.op_pool.get_all_attester_slashings())
+        .map_err(|e| {
+            ApiError::ServerError(format!("Unable to serialize attester slashings: {:?}", e))
+        })?;
+
+    Ok(success_response(Body::from(json)))
+}
+
+/// HTTP handler to return the proposer slashings in the operation pool.
+pub fn get_proposer_slashings<T: BeaconChainTypes + 'static>(req: Request<Body>) -> ApiResult {
+    let beacon_chain = req
+        .extensions()
+        .get::<Arc<BeaconChain<T>>>()
+        .ok_or_else(|| ApiError::ServerError("Beacon chain extension missing".to_string()))?;
+
+    let json: String = serde_json::to_string(&beacon_chain.op_pool.get_all_proposer_slashings())
+        .map_err(|e| {
+            ApiError::ServerError(format!("Unable to serialize proposer slashings: {:?}", e))
+        })?;
+
+    Ok(success_response(Body::from(json)))
+}
+
+/// HTTP handler to return the voluntary exits in the operation pool.
+pub fn get_voluntary_exits<T: BeaconChainTypes + 'static>(req: Request<Body>) -> ApiResult {
+    let beacon_chain = req
+        .extensions()
+        .get::<Arc<BeaconChain<T>>>()
+        .ok_or_else(|| ApiError::ServerError("Beacon chain extension missing".to_string()))?;
+
+    let json: String = serde_json::to_string(&beacon_chain.op_pool.get_all_voluntary_exits())
+        .map_err(|e| {
+            ApiError::ServerError(format!("Unable to serialize voluntary exits: {:?}", e))
+        })?;
+
+    Ok(success_response(Body::from(json)))
+}
+
+/// HTTP handler to return the transfers in the operation pool.
+pub fn get_transfers<T: BeaconChainTypes + 'static>(req: Request<Body>) -> ApiResult {
+    let beacon_chain = req
+        .extensions()
+        .get::<Arc<BeaconChain<T>>>()
+        .ok_or_else(|| ApiError::ServerError("Beacon chain extension missing".to_string()))?;
+
+    let json: String = serde_json::to_string(&beacon_chain.op_pool.get_all_transfers())
+        .map_err(|e| ApiError::ServerError(format!("Unable to serialize transfers: {:?}", e)))?;
+
+    Ok(success_response(Body::from(json)))
+}
+
+/// HTTP handler to return the deposits in the operation pool, ordered by deposit index.
+pub fn get_deposits<T: BeaconChainTypes + 'static>(req: Request<Body>) -> ApiResult {
+    let beacon_chain = req
+        .extensions()
+        .get::<Arc<BeaconChain<T>>>()
+        .ok_or_else(|| ApiError::ServerError("Beacon chain extension missing".to_string()))?;
+
+    let deposits: Vec<DepositResponse> = beacon_chain
+        .op_pool
+        .get_all_deposits()
+        .into_iter()
+        .map(|(deposit_index, deposit)| DepositResponse {
+            deposit_index,
+            deposit,
+        })
+        .collect();
+
+    let json: String = serde_json::to_string(&deposits)
+        .map_err(|e| ApiError::ServerError(format!("Unable to serialize deposits: {:?}", e)))?;
+
+    Ok(success_response(Body::from(json)))
+}
+
+/// HTTP handler to submit an `AttesterSlashing` (as JSON in the request body) to the operation
+/// pool.
+///
+/// The slashing is verified against the current state before it is inserted.
+pub fn post_attester_slashing<T: BeaconChainTypes + 'static>(req: Request<Body>) -> BoxFut {
+    post_operation::<T, AttesterSlashing<T::EthSpec>, _, _>(req, |beacon_chain, slashing| {
+        beacon_chain.process_attester_slashing(slashing)
+    })
+}
+
+/// HTTP handler to submit a `ProposerSlashing` (as JSON in the request body) to the operation
+/// pool.
+///
+/// The slashing is verified against the current state before it is inserted.
+pub fn post_proposer_slashing<T: BeaconChainTypes + 'static>(req: Request<Body>) -> BoxFut {
+    post_operation::<T, ProposerSlashing, _, _>(req, |beacon_chain, slashing| {
+        beacon_chain.process_proposer_slashing(slashing)
+    })
+}
+
+/// HTTP handler to submit a `VoluntaryExit` (as JSON in the request body) to the operation pool.
+///
+/// The exit is verified against the current state before it is inserted. Exits which are
+/// otherwise valid but cannot be included in a block yet (e.g., because their epoch has not
+/// arrived) are accepted.
+pub fn post_voluntary_exit<T: BeaconChainTypes + 'static>(req: Request<Body>) -> BoxFut {
+    post_operation::<T, VoluntaryExit, _, _>(req, |beacon_chain, exit| {
+        beacon_chain.process_voluntary_exit(exit)
+    })
+}
+
+/// Reads the entire body of `req`, decodes it as JSON into an `O` and passes it to `process`.
+///
+/// A decoding error or an error from `process` is returned as `ApiError::InvalidBody`.
+fn post_operation<T, O, F, E>(req: Request<Body>, process: F) -> BoxFut
+where
+    T: BeaconChainTypes + 'static,
+    O: DeserializeOwned + 'static,
+    F: FnOnce(&BeaconChain<T>, O) -> Result<(), E> + Send + 'static,
+    E: std::fmt::Debug + 'static,
+{
+    let beacon_chain = match req.extensions().get::<Arc<BeaconChain<T>>>() {
+        Some(beacon_chain) => beacon_chain.clone(),
+        None => {
+            return Box::new(futures::future::err(ApiError::ServerError(
+                "Beacon chain extension missing".to_string(),
+            )))
+        }
+    };
+
+    let future = req
+        .into_body()
+        .concat2()
+        .map_err(|e| ApiError::ServerError(format!("Unable to read request body: {:?}", e)))
+        .and_then(move |chunk| -> ApiResult {
+            let operation: O = serde_json::from_slice(&chunk).map_err(|e| {
+                ApiError::InvalidBody(format!("Unable to parse JSON body: {:?}", e))
+            })?;
+
+            process(&beacon_chain, operation)
+                .map_err(|e| ApiError::InvalidBody(format!("Invalid operation: {:?}", e)))?;
+
+            Ok(success_response(Body::empty()))
+        });
+
+    Box::new(future)
+}
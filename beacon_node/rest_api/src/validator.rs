@@ -147,3 +147,56 @@ pub fn get_validator_duties<T: BeaconChainTypes + 'static>(req: Request<Body>) -
     );
     Ok(success_response(body))
 }
+
+/// HTTP Handler to retrieve the report of the rewards and penalties for the attestations made
+/// during some `epoch`.
+///
+/// Reports are only available for the validators given to the beacon node with
+/// `--reward-report-validators`. The optional `validator_index` query parameter (which may be
+/// repeated) restricts the response to the given validators.
+pub fn get_validator_rewards<T: BeaconChainTypes + 'static>(req: Request<Body>) -> ApiResult {
+    let beacon_chain = req
+        .extensions()
+        .get::<Arc<BeaconChain<T>>>()
+        .ok_or_else(|| ApiError::ServerError("Beacon chain extension missing".to_string()))?;
+
+    let query = UrlQuery::from_request(&req)?;
+
+    let epoch = query.first_of(&["epoch"]).and_then(|(_, value)| {
+        value.parse::<u64>().map(Epoch::new).map_err(|e| {
+            ApiError::InvalidQueryParams(format!("Invalid epoch parameter, must be a u64. {:?}", e))
+        })
+    })?;
+
+    let validator_indices = query
+        .all_of("validator_index")?
+        .iter()
+        .map(|index| {
+            index.parse::<u64>().map_err(|e| {
+                ApiError::InvalidQueryParams(format!(
+                    "Invalid validator_index parameter, must be a u64. {:?}",
+                    e
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut report = beacon_chain
+        .get_reward_report(epoch)
+        .map_err(|e| ApiError::ServerError(format!("Unable to read reward report: {:?}", e)))?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("No reward report is available for epoch {}", epoch))
+        })?;
+
+    if !validator_indices.is_empty() {
+        report
+            .validators
+            .retain(|validator| validator_indices.contains(&validator.validator_index));
+    }
+
+    let body = Body::from(
+        serde_json::to_string(&report)
+            .map_err(|e| ApiError::ServerError(format!("Unable to serialize report: {:?}", e)))?,
+    );
+    Ok(success_response(body))
+}
@@ -1,7 +1,7 @@
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
 use eth2_libp2p::PubsubMessage;
 use eth2_libp2p::Topic;
-use eth2_libp2p::BEACON_ATTESTATION_TOPIC;
+use eth2_libp2p::{fork_topic, BEACON_ATTESTATION_TOPIC};
 use futures::Future;
 use grpcio::{RpcContext, RpcStatus, RpcStatusCode, UnarySink};
 use network::NetworkMessage;
@@ -144,7 +144,11 @@ impl<T: BeaconChainTypes> AttestationService for AttestationServiceInstance<T> {
                 );
 
                 // valid attestation, propagate to the network
-                let topic = Topic::new(BEACON_ATTESTATION_TOPIC.into());
+                let fork_version = self
+                    .chain
+                    .spec
+                    .fork_version_at_epoch(attestation.data.target.epoch);
+                let topic = Topic::new(fork_topic(BEACON_ATTESTATION_TOPIC, fork_version));
                 let message = PubsubMessage::Attestation(attestation.as_ssz_bytes());
 
                 self.network_chan
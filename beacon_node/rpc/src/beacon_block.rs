@@ -1,5 +1,5 @@
 use beacon_chain::{BeaconChain, BeaconChainTypes, BlockProcessingOutcome};
-use eth2_libp2p::BEACON_BLOCK_TOPIC;
+use eth2_libp2p::{fork_topic, BEACON_BLOCK_TOPIC};
 use eth2_libp2p::{PubsubMessage, Topic};
 use futures::Future;
 use grpcio::{RpcContext, RpcStatus, RpcStatusCode, UnarySink};
@@ -14,7 +14,7 @@ use slog::{error, info, trace, warn};
 use ssz::{ssz_encode, Decode, Encode};
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use types::{BeaconBlock, Signature, Slot};
+use types::{BeaconBlock, EthSpec, Signature, Slot};
 
 #[derive(Clone)]
 pub struct BeaconBlockServiceInstance<T: BeaconChainTypes> {
@@ -106,7 +106,10 @@ impl<T: BeaconChainTypes> BeaconBlockService for BeaconBlockServiceInstance<T> {
                             );
 
                             // get the network topic to send on
-                            let topic = Topic::new(BEACON_BLOCK_TOPIC.into());
+                            let fork_version = self.chain.spec.fork_version_at_epoch(
+                                block.slot.epoch(T::EthSpec::slots_per_epoch()),
+                            );
+                            let topic = Topic::new(fork_topic(BEACON_BLOCK_TOPIC, fork_version));
                             let message = PubsubMessage::Block(block.as_ssz_bytes());
 
                             // Publish the block to the p2p network via gossipsub.
@@ -150,7 +150,37 @@ fn main() {
                 .help("Set the listen TCP port for the RESTful HTTP API server.")
                 .takes_value(true),
         )
-
+        /*
+         * Slasher parameters.
+         */
+        .arg(
+            Arg::with_name("slasher")
+                .long("slasher")
+                .help("Detect slashable attestations and blocks, adding the slashings to the operation pool.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("slasher-history-length")
+                .long("slasher-history-length")
+                .value_name("EPOCHS")
+                .help("The number of epochs of history the slasher uses to detect surround votes.")
+                .requires("slasher")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("reward-report-validators")
+                .long("reward-report-validators")
+                .value_name("INDICES")
+                .help("Comma-separated validator indices for which a report of the rewards and penalties at each epoch transition is stored.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("monitor-validators")
+                .long("monitor-validators")
+                .value_name("PUBKEYS")
+                .help("Comma-separated, 0x-prefixed validator public keys for which attestations, proposals and balances are tracked in the logs and metrics.")
+                .takes_value(true),
+        )
         /*
          * Database parameters.
          */
@@ -175,6 +205,13 @@ fn main() {
                 .takes_value(true)
                 .possible_values(&["mainnet", "minimal", "interop"])
         )
+        .arg(
+            Arg::with_name("testnet-dir")
+                .long("testnet-dir")
+                .value_name("DIR")
+                .help("Path to a directory describing a testnet. Must contain the spec constants in config.yaml and may contain the genesis state in genesis.ssz boot node ENRs in boot_enr.yaml and a fork schedule in fork_schedule.yaml. Overrides --default-spec.")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("recent-genesis")
                 .long("recent-genesis")
@@ -216,10 +216,10 @@ mod test {
 
         let mut hashes = (0..).into_iter().map(|i| Hash256::from_low_u64_be(i));
 
-        for root in &mut state_a.block_roots[..] {
+        for root in state_a.block_roots.iter_mut() {
             *root = hashes.next().unwrap()
         }
-        for root in &mut state_b.block_roots[..] {
+        for root in state_b.block_roots.iter_mut() {
             *root = hashes.next().unwrap()
         }
 
@@ -82,6 +82,22 @@ pub enum DBColumn {
     BeaconBlock,
     BeaconState,
     BeaconChain,
+    /// Block headers seen by the slasher, keyed by proposer index and slot.
+    SlasherProposal,
+    /// `IndexedAttestation`s seen by the slasher, keyed by their tree hash root.
+    SlasherAttestation,
+    /// The attestation each validator made for each target epoch, as seen by the slasher.
+    SlasherTarget,
+    /// Chunks of the slasher's per-validator min-span index.
+    SlasherMinSpan,
+    /// Chunks of the slasher's per-validator max-span index.
+    SlasherMaxSpan,
+    /// The keys of the slasher records belonging to each epoch, used to prune old records.
+    SlasherEpochIndex,
+    /// Per-validator reports of the rewards and penalties applied during an epoch transition.
+    RewardReport,
+    /// Uncompressed validator public keys, keyed by validator index.
+    PubkeyCache,
 }
 
 impl<'a> Into<&'a str> for DBColumn {
@@ -91,6 +107,14 @@ impl<'a> Into<&'a str> for DBColumn {
             DBColumn::BeaconBlock => &"blk",
             DBColumn::BeaconState => &"ste",
             DBColumn::BeaconChain => &"bch",
+            DBColumn::SlasherProposal => &"spr",
+            DBColumn::SlasherAttestation => &"sat",
+            DBColumn::SlasherTarget => &"stg",
+            DBColumn::SlasherMinSpan => &"smn",
+            DBColumn::SlasherMaxSpan => &"smx",
+            DBColumn::SlasherEpochIndex => &"sei",
+            DBColumn::RewardReport => &"rwd",
+            DBColumn::PubkeyCache => &"pkc",
         }
     }
 }
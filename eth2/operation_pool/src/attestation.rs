@@ -1,59 +1,129 @@
 use crate::max_cover::MaxCover;
-use types::{Attestation, BeaconState, BitList, EthSpec};
+use state_processing::common::get_attesting_indices;
+use state_processing::per_epoch_processing::apply_rewards::get_base_reward;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use types::{Attestation, BeaconState, BitList, ChainSpec, EthSpec};
 
 pub struct AttMaxCover<'a, T: EthSpec> {
     /// Underlying attestation.
     att: &'a Attestation<T>,
-    /// Bitfield of validators that are covered by this attestation.
-    fresh_validators: BitList<T::MaxValidatorsPerCommittee>,
+    /// Mapping from the indices of validators covered by this attestation to the reward that
+    /// including their vote would produce.
+    fresh_validators_rewards: HashMap<u64, u64>,
 }
 
 impl<'a, T: EthSpec> AttMaxCover<'a, T> {
+    /// Returns `None` if the rewards for `att` cannot be computed against `state` (e.g., it is
+    /// not from the current or previous epoch).
     pub fn new(
         att: &'a Attestation<T>,
-        fresh_validators: BitList<T::MaxValidatorsPerCommittee>,
-    ) -> Self {
-        Self {
+        state: &BeaconState<T>,
+        total_active_balance: u64,
+        spec: &ChainSpec,
+    ) -> Option<Self> {
+        let fresh_validators = earliest_attestation_validators(att, state);
+        let fresh_indices = get_attesting_indices(state, &att.data, &fresh_validators).ok()?;
+
+        let attestation_slot = state.get_attestation_data_slot(&att.data).ok()?;
+        let inclusion_delay = state.slot.as_u64().checked_sub(attestation_slot.as_u64())?;
+
+        let target_slot = att.data.target.epoch.start_slot(T::slots_per_epoch());
+        let matches_target = state
+            .get_block_root(target_slot)
+            .map_or(false, |root| *root == att.data.target.root);
+        let matches_head = state
+            .get_block_root(attestation_slot)
+            .map_or(false, |root| *root == att.data.beacon_block_root);
+
+        let fresh_validators_rewards = fresh_indices
+            .into_iter()
+            .map(|index| {
+                let base_reward = get_base_reward(state, index, total_active_balance, spec).ok()?;
+                let reward = attestation_reward::<T>(
+                    base_reward,
+                    inclusion_delay,
+                    matches_target,
+                    matches_head,
+                    spec,
+                );
+                Some((index as u64, reward))
+            })
+            .collect::<Option<HashMap<_, _>>>()?;
+
+        Some(Self {
             att,
-            fresh_validators,
-        }
+            fresh_validators_rewards,
+        })
     }
 }
 
 impl<'a, T: EthSpec> MaxCover for AttMaxCover<'a, T> {
     type Object = Attestation<T>;
-    type Set = BitList<T::MaxValidatorsPerCommittee>;
+    type Set = HashMap<u64, u64>;
 
     fn object(&self) -> Attestation<T> {
         self.att.clone()
     }
 
-    fn covering_set(&self) -> &BitList<T::MaxValidatorsPerCommittee> {
-        &self.fresh_validators
+    fn covering_set(&self) -> &HashMap<u64, u64> {
+        &self.fresh_validators_rewards
     }
 
-    /// Sneaky: we keep all the attestations together in one bucket, even though
-    /// their aggregation bitfields refer to different committees. In order to avoid
-    /// confusing committees when updating covering sets, we update only those attestations
-    /// whose shard and epoch match the attestation being included in the solution, by the logic
-    /// that a shard and epoch uniquely identify a committee.
+    /// Covering sets are keyed by validator index, so they can be compared across committees.
+    /// A validator is rewarded for one attestation per epoch, so only the covering sets of
+    /// attestations with the same target epoch as `best_att` are updated.
     fn update_covering_set(
         &mut self,
         best_att: &Attestation<T>,
-        covered_validators: &BitList<T::MaxValidatorsPerCommittee>,
+        covered_validators: &HashMap<u64, u64>,
     ) {
-        if self.att.data.crosslink.shard == best_att.data.crosslink.shard
-            && self.att.data.target.epoch == best_att.data.target.epoch
-        {
-            self.fresh_validators.difference_inplace(covered_validators);
+        if self.att.data.target.epoch == best_att.data.target.epoch {
+            for validator_index in covered_validators.keys() {
+                self.fresh_validators_rewards.remove(validator_index);
+            }
         }
     }
 
     fn score(&self) -> usize {
-        self.fresh_validators.num_set_bits()
+        let reward = self.fresh_validators_rewards.values().sum::<u64>();
+        usize::try_from(reward).unwrap_or(usize::max_value())
     }
 }
 
+/// Returns the reward produced by including the vote of a single validator (whose vote has not
+/// already been included) in a block with the given `inclusion_delay`.
+///
+/// The reward is the sum of:
+///
+/// - The proposer reward for including the attestation.
+/// - The attester's inclusion delay reward.
+/// - The attester's FFG source reward, and target/head rewards if the votes match `state`.
+///
+/// The source, target and head rewards are scaled by participation at the end of the epoch, which
+/// is unknown during block production. They are estimated as if participation were full.
+///
+/// Crosslink rewards are not considered, the winning crosslink is also unknown until the end of the
+/// epoch.
+fn attestation_reward<T: EthSpec>(
+    base_reward: u64,
+    inclusion_delay: u64,
+    matches_target: bool,
+    matches_head: bool,
+    spec: &ChainSpec,
+) -> u64 {
+    let proposer_reward = base_reward / spec.proposer_reward_quotient;
+    let max_attester_reward = base_reward - proposer_reward;
+    let inclusion_reward = max_attester_reward
+        * (T::slots_per_epoch() + spec.min_attestation_inclusion_delay)
+            .saturating_sub(inclusion_delay)
+        / T::slots_per_epoch();
+
+    let matching_votes = 1 + matches_target as u64 + matches_head as u64;
+
+    proposer_reward + inclusion_reward + matching_votes * base_reward
+}
+
 /// Extract the validators for which `attestation` would be their earliest in the epoch.
 ///
 /// The reward paid to a proposer for including an attestation is proportional to the number
@@ -93,3 +163,31 @@ pub fn earliest_attestation_validators<T: EthSpec>(
 
     new_validators
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    #[test]
+    fn attestation_reward_prefers_earlier_inclusion_and_matching_votes() {
+        let spec = E::default_spec();
+        let base_reward = 1_000_000;
+
+        let reward =
+            |delay, target, head| attestation_reward::<E>(base_reward, delay, target, head, &spec);
+
+        assert!(reward(1, true, true) > reward(2, true, true));
+        assert!(reward(1, true, true) > reward(1, true, false));
+        assert!(reward(1, true, false) > reward(1, false, false));
+
+        // The inclusion reward is exhausted after an epoch, but the other rewards remain.
+        let late = E::slots_per_epoch() + spec.min_attestation_inclusion_delay;
+        assert_eq!(
+            reward(late, false, false),
+            base_reward / spec.proposer_reward_quotient + base_reward
+        );
+    }
+}
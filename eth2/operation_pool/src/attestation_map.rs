@@ -0,0 +1,119 @@
+use crate::attestation_id::AttestationId;
+use crate::ordered_map::OrderedMap;
+use std::iter::FromIterator;
+use types::{Attestation, Epoch, EthSpec};
+
+/// The attestations of an `OperationPool`, grouped by `AttestationId` and ordered by target epoch
+/// for eviction.
+#[derive(Debug, PartialEq)]
+pub struct AttestationMap<T: EthSpec> {
+    attestations: OrderedMap<AttestationId, Epoch, Vec<Attestation<T>>>,
+    /// The total number of attestations, including those with the same `AttestationId`.
+    num_attestations: usize,
+}
+
+impl<T: EthSpec> Default for AttestationMap<T> {
+    fn default() -> Self {
+        Self {
+            attestations: OrderedMap::default(),
+            num_attestations: 0,
+        }
+    }
+}
+
+impl<T: EthSpec> AttestationMap<T> {
+    /// Insert `attestation`, aggregating it with the existing attestations for `id` if possible.
+    pub fn insert(&mut self, id: AttestationId, attestation: Attestation<T>) {
+        if let Some(existing_attestations) = self.attestations.get_mut(&id) {
+            let mut aggregated = false;
+            for existing_attestation in existing_attestations.iter_mut() {
+                if existing_attestation.signers_disjoint_from(&attestation) {
+                    existing_attestation.aggregate(&attestation);
+                    aggregated = true;
+                } else if *existing_attestation == attestation {
+                    aggregated = true;
+                }
+            }
+
+            if !aggregated {
+                existing_attestations.push(attestation);
+                self.num_attestations += 1;
+            }
+        } else {
+            let target_epoch = attestation.data.target.epoch;
+            self.attestations
+                .insert(id, target_epoch, vec![attestation]);
+            self.num_attestations += 1;
+        }
+    }
+
+    /// Remove attestations until there are at most `limit`.
+    ///
+    /// All attestations with the same `AttestationId` are evicted together, in order of their
+    /// target epoch (oldest first) and then `AttestationId`.
+    ///
+    /// Returns the number of attestations evicted.
+    pub fn evict_to_limit(&mut self, limit: usize) -> usize {
+        let mut evicted = 0;
+        while self.num_attestations > limit {
+            match self.attestations.pop_lowest() {
+                Some((_, attestations)) => {
+                    self.num_attestations -= attestations.len();
+                    evicted += attestations.len();
+                }
+                None => break,
+            }
+        }
+        evicted
+    }
+
+    /// Retains only the attestations for which `f` returns `true`, given their `AttestationId`
+    /// and all of the attestations with that ID.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&AttestationId, &Vec<Attestation<T>>) -> bool,
+    {
+        self.attestations.retain(f);
+        self.num_attestations = self.attestations.values().map(Vec::len).sum();
+    }
+
+    /// The total number of attestations, including those with the same `AttestationId`.
+    pub fn num_attestations(&self) -> usize {
+        self.num_attestations
+    }
+
+    /// The number of distinct `AttestationId`s.
+    pub fn len(&self) -> usize {
+        self.attestations.len()
+    }
+
+    /// Iterates over each `AttestationId` and its attestations, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&AttestationId, &Vec<Attestation<T>>)> {
+        self.attestations.iter()
+    }
+
+    /// Iterates over the attestations for each `AttestationId`, in arbitrary order.
+    pub fn values(&self) -> impl Iterator<Item = &Vec<Attestation<T>>> {
+        self.attestations.values()
+    }
+}
+
+impl<T: EthSpec> FromIterator<(AttestationId, Vec<Attestation<T>>)> for AttestationMap<T> {
+    /// Collects groups of attestations as they are, without re-aggregating them. Empty groups
+    /// are skipped.
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (AttestationId, Vec<Attestation<T>>)>,
+    {
+        let mut map = Self::default();
+        for (id, attestations) in iter {
+            if let Some(target_epoch) = attestations.first().map(|att| att.data.target.epoch) {
+                map.num_attestations += attestations.len();
+                if let Some(replaced) = map.attestations.insert(id, target_epoch, attestations) {
+                    map.num_attestations -= replaced.len();
+                }
+            }
+        }
+        map
+    }
+}
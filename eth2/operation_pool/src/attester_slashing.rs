@@ -0,0 +1,167 @@
+use crate::max_cover::MaxCover;
+use state_processing::per_block_processing::get_slashable_indices_modular;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use types::{AttesterSlashing, BeaconState, ChainSpec, EthSpec};
+
+pub struct AttesterSlashingMaxCover<'a, T: EthSpec> {
+    /// Underlying attester slashing.
+    slashing: &'a AttesterSlashing<T>,
+    /// Mapping from the indices of validators slashed by this slashing to the reward the
+    /// proposer receives for slashing them.
+    slashed_validators_rewards: HashMap<u64, u64>,
+}
+
+impl<'a, T: EthSpec> AttesterSlashingMaxCover<'a, T> {
+    /// Returns `None` if `slashing` would not slash any validators, ignoring those validators in
+    /// `to_be_slashed`.
+    pub fn new(
+        slashing: &'a AttesterSlashing<T>,
+        to_be_slashed: &HashSet<u64>,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) -> Option<Self> {
+        let current_epoch = state.current_epoch();
+
+        let slashed_validators_rewards =
+            get_slashable_indices_modular(state, slashing, |index, validator| {
+                validator.is_slashable_at(current_epoch) && !to_be_slashed.contains(&index)
+            })
+            .ok()?
+            .into_iter()
+            .map(|index| {
+                let effective_balance = state.get_effective_balance(index as usize, spec).ok()?;
+                Some((index, slashing_reward(effective_balance, spec)))
+            })
+            .collect::<Option<HashMap<_, _>>>()?;
+
+        Some(Self {
+            slashing,
+            slashed_validators_rewards,
+        })
+    }
+}
+
+impl<'a, T: EthSpec> MaxCover for AttesterSlashingMaxCover<'a, T> {
+    type Object = AttesterSlashing<T>;
+    type Set = HashMap<u64, u64>;
+
+    fn object(&self) -> AttesterSlashing<T> {
+        self.slashing.clone()
+    }
+
+    fn covering_set(&self) -> &HashMap<u64, u64> {
+        &self.slashed_validators_rewards
+    }
+
+    /// A validator can only be slashed once, regardless of which slashing slashes it.
+    fn update_covering_set(
+        &mut self,
+        _best_slashing: &AttesterSlashing<T>,
+        covered_validators: &HashMap<u64, u64>,
+    ) {
+        for validator_index in covered_validators.keys() {
+            self.slashed_validators_rewards.remove(validator_index);
+        }
+    }
+
+    fn score(&self) -> usize {
+        let reward = self.slashed_validators_rewards.values().sum::<u64>();
+        usize::try_from(reward).unwrap_or(usize::max_value())
+    }
+}
+
+/// Returns the reward paid to a block proposer for slashing a validator with the given
+/// `effective_balance`.
+///
+/// When there is no whistleblower the proposer receives the entire whistleblower reward.
+///
+/// Spec v0.8.0
+pub fn slashing_reward(effective_balance: u64, spec: &ChainSpec) -> u64 {
+    effective_balance / spec.whistleblower_reward_quotient
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::max_cover::maximum_cover;
+    use types::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use types::*;
+
+    type E = MainnetEthSpec;
+
+    /// A state whose validators are all slashable, with the given effective balances.
+    fn state_with_balances(rng: &mut XorShiftRng, balances: &[u64]) -> BeaconState<E> {
+        let mut state = BeaconState::random_for_test(rng);
+        state.validators = balances
+            .iter()
+            .map(|&effective_balance| Validator {
+                effective_balance,
+                slashed: false,
+                activation_epoch: Epoch::new(0),
+                withdrawable_epoch: Epoch::new(u64::max_value()),
+                ..Validator::random_for_test(rng)
+            })
+            .collect::<Vec<_>>()
+            .into();
+        state
+    }
+
+    /// A slashing of the validators in `indices`.
+    fn slashing(rng: &mut XorShiftRng, indices: &[u64]) -> AttesterSlashing<E> {
+        let mut slashing = AttesterSlashing::random_for_test(rng);
+        for attestation in &mut [&mut slashing.attestation_1, &mut slashing.attestation_2] {
+            attestation.custody_bit_0_indices = VariableList::new(indices.to_vec()).unwrap();
+            attestation.custody_bit_1_indices = VariableList::empty();
+        }
+        slashing
+    }
+
+    #[test]
+    fn max_cover_maximizes_reward() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let spec = E::default_spec();
+        let high = spec.max_effective_balance;
+        let low = spec.effective_balance_increment;
+        let state = state_with_balances(rng, &[high, high, high, low, low, low]);
+
+        let pair = slashing(rng, &[0, 1]);
+        let low_balance_triple = slashing(rng, &[3, 4, 5]);
+        let overlapping_pair = slashing(rng, &[1, 2]);
+        let subset = slashing(rng, &[0]);
+        let slashings = vec![&pair, &low_balance_triple, &overlapping_pair, &subset];
+
+        let covers = |to_be_slashed: &HashSet<u64>| {
+            slashings
+                .iter()
+                .filter_map(|slashing| {
+                    AttesterSlashingMaxCover::new(slashing, to_be_slashed, &state, &spec)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Slashing 3 low balance validators pays less than slashing 2 high balance validators,
+        // and `overlapping_pair` only pays for validator 2 once `pair` is included.
+        assert_eq!(
+            maximum_cover(covers(&HashSet::new()), 2),
+            vec![pair.clone(), overlapping_pair.clone()]
+        );
+        // `subset` never pays anything once `pair` is included.
+        assert_eq!(
+            maximum_cover(covers(&HashSet::new()), 4),
+            vec![
+                pair.clone(),
+                overlapping_pair.clone(),
+                low_balance_triple.clone()
+            ]
+        );
+
+        // Validators that are already to be slashed are not counted.
+        let to_be_slashed = vec![0, 1].into_iter().collect();
+        assert_eq!(covers(&to_be_slashed).len(), 2);
+        assert_eq!(
+            maximum_cover(covers(&to_be_slashed), 2),
+            vec![overlapping_pair.clone(), low_balance_triple.clone()]
+        );
+    }
+}
@@ -1,15 +1,31 @@
+#[macro_use]
+extern crate lazy_static;
+
 mod attestation;
 mod attestation_id;
+mod attestation_map;
+mod attester_slashing;
 mod max_cover;
+mod metrics;
+mod naive_aggregation_pool;
+mod ordered_map;
 mod persistence;
 
+pub use naive_aggregation_pool::{
+    Error as NaiveAggregationError, InsertOutcome as NaiveAggregationInsertOutcome,
+    NaiveAggregationPool,
+};
 pub use persistence::PersistedOperationPool;
 
-use attestation::{earliest_attestation_validators, AttMaxCover};
+use attestation::AttMaxCover;
 use attestation_id::AttestationId;
+use attestation_map::AttestationMap;
+use attester_slashing::{slashing_reward, AttesterSlashingMaxCover};
 use itertools::Itertools;
 use max_cover::maximum_cover;
+use ordered_map::OrderedMap;
 use parking_lot::RwLock;
+use serde_derive::{Deserialize, Serialize};
 use state_processing::per_block_processing::errors::{
     AttestationValidationError, AttesterSlashingValidationError, DepositValidationError,
     ExitValidationError, ProposerSlashingValidationError, TransferValidationError,
@@ -20,34 +36,74 @@ use state_processing::per_block_processing::{
     verify_proposer_slashing, verify_transfer, verify_transfer_time_independent_only,
     VerifySignatures,
 };
-use std::collections::{btree_map::Entry, hash_map, BTreeMap, HashMap, HashSet};
+use std::collections::{btree_map::Entry, BTreeMap, HashSet};
 use std::marker::PhantomData;
 use types::{
-    typenum::Unsigned, Attestation, AttesterSlashing, BeaconState, ChainSpec, Deposit, EthSpec,
-    ProposerSlashing, Transfer, Validator, VoluntaryExit,
+    typenum::Unsigned, Attestation, AttestationData, AttesterSlashing, BeaconState,
+    BeaconStateError, ChainSpec, Deposit, Domain, Epoch, EthSpec, ProposerSlashing, RelativeEpoch,
+    Slot, Transfer, Validator, VoluntaryExit,
 };
 
 #[derive(Default, Debug)]
 pub struct OperationPool<T: EthSpec + Default> {
     /// Map from attestation ID (see below) to vectors of attestations.
-    attestations: RwLock<HashMap<AttestationId, Vec<Attestation<T>>>>,
+    attestations: RwLock<AttestationMap<T>>,
+    /// Eagerly aggregated attestations that were each signed by a single validator.
+    ///
+    /// Not persisted to disk, the contents can be re-learned from the network.
+    naive_aggregation_pool: NaiveAggregationPool<T>,
     /// Map from deposit index to deposit data.
     // NOTE: We assume that there is only one deposit per index
     // because the Eth1 data is updated (at most) once per epoch,
     // and the spec doesn't seem to accommodate for re-orgs on a time-frame
     // longer than an epoch
     deposits: RwLock<BTreeMap<u64, Deposit>>,
-    /// Map from two attestation IDs to a slashing for those IDs.
-    attester_slashings: RwLock<HashMap<(AttestationId, AttestationId), AttesterSlashing<T>>>,
-    /// Map from proposer index to slashing.
-    proposer_slashings: RwLock<HashMap<u64, ProposerSlashing>>,
-    /// Map from exiting validator to their exit data.
-    voluntary_exits: RwLock<HashMap<u64, VoluntaryExit>>,
+    /// Map from two attestation IDs to a slashing for those IDs, ordered by the later target
+    /// epoch of the two attestations.
+    attester_slashings:
+        RwLock<OrderedMap<(AttestationId, AttestationId), Epoch, AttesterSlashing<T>>>,
+    /// Map from proposer index to slashing, ordered by the slot of the block headers.
+    proposer_slashings: RwLock<OrderedMap<u64, Slot, ProposerSlashing>>,
+    /// Map from exiting validator to their exit data, ordered by the exit epoch.
+    voluntary_exits: RwLock<OrderedMap<u64, Epoch, VoluntaryExit>>,
     /// Set of transfers.
     transfers: RwLock<HashSet<Transfer>>,
+    /// The maximum number of each type of operation to store.
+    limits: OperationPoolLimits,
     _phantom: PhantomData<T>,
 }
 
+/// The maximum number of each type of operation stored in an `OperationPool`.
+///
+/// Once a limit is exceeded, operations of that type are evicted deterministically, oldest first
+/// (see the `insert_*` functions on `OperationPool` for the ordering used for each type).
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct OperationPoolLimits {
+    /// Total number of attestations, including those with the same data.
+    pub attestations: usize,
+    /// Number of distinct `AttestationData` in the naive aggregation pool.
+    pub naive_aggregates: usize,
+    pub deposits: usize,
+    pub attester_slashings: usize,
+    pub proposer_slashings: usize,
+    pub voluntary_exits: usize,
+    pub transfers: usize,
+}
+
+impl Default for OperationPoolLimits {
+    fn default() -> Self {
+        Self {
+            attestations: 16_384,
+            naive_aggregates: 4_096,
+            deposits: 4_096,
+            attester_slashings: 1_024,
+            proposer_slashings: 1_024,
+            voluntary_exits: 16_384,
+            transfers: 1_024,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum DepositInsertStatus {
     /// The deposit was not already in the pool.
@@ -64,8 +120,21 @@ impl<T: EthSpec> OperationPool<T> {
         Self::default()
     }
 
+    /// Create a new operation pool which stores at most `limits` operations of each type.
+    pub fn with_limits(limits: OperationPoolLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
     /// Insert an attestation into the pool, aggregating it with existing attestations if possible.
     ///
+    /// Attestations signed by a single validator should also be provided to
+    /// `insert_unaggregated_attestation`.
+    ///
+    /// If the pool is full, the attestations with the oldest target epoch are evicted first.
+    ///
     /// ## Note
     ///
     /// This function assumes the given `attestation` is valid.
@@ -80,48 +149,115 @@ impl<T: EthSpec> OperationPool<T> {
         // Take a write lock on the attestations map.
         let mut attestations = self.attestations.write();
 
-        let existing_attestations = match attestations.entry(id) {
-            hash_map::Entry::Vacant(entry) => {
-                entry.insert(vec![attestation]);
-                return Ok(());
-            }
-            hash_map::Entry::Occupied(entry) => entry.into_mut(),
-        };
-
-        let mut aggregated = false;
-        for existing_attestation in existing_attestations.iter_mut() {
-            if existing_attestation.signers_disjoint_from(&attestation) {
-                existing_attestation.aggregate(&attestation);
-                aggregated = true;
-            } else if *existing_attestation == attestation {
-                aggregated = true;
-            }
-        }
+        attestations.insert(id, attestation);
 
-        if !aggregated {
-            existing_attestations.push(attestation);
-        }
+        let evicted = attestations.evict_to_limit(self.limits.attestations);
+        metrics::inc_counter_by(&metrics::ATTESTATION_EVICTIONS, evicted as i64);
+        metrics::set_gauge(
+            &metrics::ATTESTATIONS,
+            attestations.num_attestations() as i64,
+        );
 
         Ok(())
     }
 
+    /// Insert an attestation signed by a single validator into the `NaiveAggregationPool`,
+    /// aggregating it with all other attestations seen with the same `AttestationData` and
+    /// signature domain.
+    ///
+    /// The domain is computed from the fork of `state`, in the same way as for `AttestationId`.
+    ///
+    /// If the pool is full, the aggregates with the oldest target epoch are evicted first.
+    ///
+    /// ## Note
+    ///
+    /// This function assumes the given `attestation` is valid.
+    pub fn insert_unaggregated_attestation(
+        &self,
+        attestation: &Attestation<T>,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) -> Result<NaiveAggregationInsertOutcome, NaiveAggregationError> {
+        let domain = Self::attestation_domain(&attestation.data, state, spec);
+        let outcome = self.naive_aggregation_pool.insert(attestation, domain)?;
+
+        let evicted = self
+            .naive_aggregation_pool
+            .evict_to_limit(self.limits.naive_aggregates);
+        metrics::inc_counter_by(&metrics::NAIVE_AGGREGATE_EVICTIONS, evicted as i64);
+        metrics::set_gauge(
+            &metrics::NAIVE_AGGREGATES,
+            self.naive_aggregation_pool.num_aggregates() as i64,
+        );
+
+        Ok(outcome)
+    }
+
     /// Total number of attestations in the pool, including attestations for the same data.
+    ///
+    /// Does not include the aggregates in the `NaiveAggregationPool`.
     pub fn num_attestations(&self) -> usize {
-        self.attestations.read().values().map(Vec::len).sum()
+        self.attestations.read().num_attestations()
+    }
+
+    /// Returns the aggregate of all unaggregated attestations seen with the given `data`, signed
+    /// on the fork of `state`, if any.
+    ///
+    /// Useful for producing an aggregate as an aggregator.
+    pub fn get_naive_aggregate(
+        &self,
+        data: &AttestationData,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) -> Option<Attestation<T>> {
+        self.naive_aggregation_pool
+            .get(data, Self::attestation_domain(data, state, spec))
+    }
+
+    /// The domain that attestations with the given `data` are signed with on the fork of
+    /// `state`.
+    fn attestation_domain(data: &AttestationData, state: &BeaconState<T>, spec: &ChainSpec) -> u64 {
+        spec.get_domain(data.target.epoch, Domain::Attestation, &state.fork)
+    }
+
+    /// Returns a copy of every attestation in the pool, including the aggregates in the
+    /// `NaiveAggregationPool`.
+    ///
+    /// Attestations are not checked for validity or for inclusion in the chain.
+    pub fn get_all_attestations(&self) -> Vec<Attestation<T>> {
+        self.attestations
+            .read()
+            .values()
+            .flat_map(|attestations| attestations.iter().cloned())
+            .chain(self.naive_aggregation_pool.get_all())
+            .collect()
     }
 
     /// Get a list of attestations for inclusion in a block.
+    ///
+    /// Attestations are chosen to maximize the total proposer and attester rewards that their
+    /// inclusion would produce against `state`, which should be the pre-state of the block.
+    ///
+    /// Requires the committee cache for the current epoch of `state` to be built.
     pub fn get_attestations(
         &self,
         state: &BeaconState<T>,
         spec: &ChainSpec,
-    ) -> Vec<Attestation<T>> {
+    ) -> Result<Vec<Attestation<T>>, BeaconStateError> {
         // Attestations for the current fork, which may be from the current or previous epoch.
         let prev_epoch = state.previous_epoch();
         let current_epoch = state.current_epoch();
+        let total_active_balance = state.get_total_balance(
+            state.get_cached_active_validator_indices(RelativeEpoch::Current)?,
+            spec,
+        )?;
         let prev_domain_bytes = AttestationId::compute_domain_bytes(prev_epoch, state, spec);
         let curr_domain_bytes = AttestationId::compute_domain_bytes(current_epoch, state, spec);
         let reader = self.attestations.read();
+        let naive_aggregates = self.naive_aggregation_pool.get_with_domains(&[
+            spec.get_domain(prev_epoch, Domain::Attestation, &state.fork),
+            spec.get_domain(current_epoch, Domain::Attestation, &state.fork),
+        ]);
         let valid_attestations = reader
             .iter()
             .filter(|(key, _)| {
@@ -129,6 +265,11 @@ impl<T: EthSpec> OperationPool<T> {
                     || key.domain_bytes_match(&curr_domain_bytes)
             })
             .flat_map(|(_, attestations)| attestations)
+            // Include the naive aggregate for each `AttestationData` signed on the current fork.
+            .chain(naive_aggregates.iter().filter(|attestation| {
+                attestation.data.target.epoch == prev_epoch
+                    || attestation.data.target.epoch == current_epoch
+            }))
             // That are valid...
             .filter(|attestation| {
                 verify_attestation_for_block_inclusion(
@@ -139,9 +280,12 @@ impl<T: EthSpec> OperationPool<T> {
                 )
                 .is_ok()
             })
-            .map(|att| AttMaxCover::new(att, earliest_attestation_validators(att, state)));
+            .filter_map(|att| AttMaxCover::new(att, state, total_active_balance, spec));
 
-        maximum_cover(valid_attestations, T::MaxAttestations::to_usize())
+        Ok(maximum_cover(
+            valid_attestations,
+            T::MaxAttestations::to_usize(),
+        ))
     }
 
     /// Remove attestations which are too old to be included in a block.
@@ -157,11 +301,16 @@ impl<T: EthSpec> OperationPool<T> {
                 finalized_state.current_epoch() <= att.data.target.epoch + 1
             })
         });
+        self.naive_aggregation_pool
+            .prune(finalized_state.current_epoch());
     }
 
     /// Add a deposit to the pool.
     ///
     /// No two distinct deposits should be added with the same index.
+    ///
+    /// If the pool is full, the deposits with the highest index are evicted first, as they are
+    /// the furthest from inclusion.
     // TODO: we need to rethink this entirely
     pub fn insert_deposit(
         &self,
@@ -170,19 +319,27 @@ impl<T: EthSpec> OperationPool<T> {
     ) -> Result<DepositInsertStatus, DepositValidationError> {
         use DepositInsertStatus::*;
 
-        match self.deposits.write().entry(index) {
+        let mut deposits = self.deposits.write();
+
+        let status = match deposits.entry(index) {
             Entry::Vacant(entry) => {
                 entry.insert(deposit);
-                Ok(Fresh)
+                Fresh
             }
             Entry::Occupied(mut entry) => {
                 if entry.get() == &deposit {
-                    Ok(Duplicate)
+                    Duplicate
                 } else {
-                    Ok(Replaced(Box::new(entry.insert(deposit))))
+                    Replaced(Box::new(entry.insert(deposit)))
                 }
             }
-        }
+        };
+
+        let evicted = evict_deposits(&mut deposits, self.limits.deposits);
+        metrics::inc_counter_by(&metrics::DEPOSIT_EVICTIONS, evicted as i64);
+        metrics::set_gauge(&metrics::DEPOSITS, deposits.len() as i64);
+
+        Ok(status)
     }
 
     /// Get an ordered list of deposits for inclusion in a block.
@@ -211,7 +368,18 @@ impl<T: EthSpec> OperationPool<T> {
         self.deposits.read().len()
     }
 
+    /// Returns a copy of every deposit in the pool, ordered by deposit index.
+    pub fn get_all_deposits(&self) -> Vec<(u64, Deposit)> {
+        self.deposits
+            .read()
+            .iter()
+            .map(|(index, deposit)| (*index, deposit.clone()))
+            .collect()
+    }
+
     /// Insert a proposer slashing into the pool.
+    ///
+    /// If the pool is full, the slashings for the oldest block headers are evicted first.
     pub fn insert_proposer_slashing(
         &self,
         slashing: ProposerSlashing,
@@ -220,10 +388,18 @@ impl<T: EthSpec> OperationPool<T> {
     ) -> Result<(), ProposerSlashingValidationError> {
         // TODO: should maybe insert anyway if the proposer is unknown in the validator index,
         // because they could *become* known later
-        verify_proposer_slashing(&slashing, state, spec)?;
-        self.proposer_slashings
-            .write()
-            .insert(slashing.proposer_index, slashing);
+        verify_proposer_slashing(&slashing, state, VerifySignatures::True, spec)?;
+
+        let mut proposer_slashings = self.proposer_slashings.write();
+        proposer_slashings.insert(slashing.proposer_index, slashing.header_1.slot, slashing);
+
+        let evicted = proposer_slashings.evict_to_limit(self.limits.proposer_slashings);
+        metrics::inc_counter_by(&metrics::PROPOSER_SLASHING_EVICTIONS, evicted as i64);
+        metrics::set_gauge(
+            &metrics::PROPOSER_SLASHINGS,
+            proposer_slashings.len() as i64,
+        );
+
         Ok(())
     }
 
@@ -242,6 +418,8 @@ impl<T: EthSpec> OperationPool<T> {
     }
 
     /// Insert an attester slashing into the pool.
+    ///
+    /// If the pool is full, the slashings with the oldest target epochs are evicted first.
     pub fn insert_attester_slashing(
         &self,
         slashing: AttesterSlashing<T>,
@@ -250,7 +428,17 @@ impl<T: EthSpec> OperationPool<T> {
     ) -> Result<(), AttesterSlashingValidationError> {
         verify_attester_slashing(state, &slashing, true, spec)?;
         let id = Self::attester_slashing_id(&slashing, state, spec);
-        self.attester_slashings.write().insert(id, slashing);
+
+        let mut attester_slashings = self.attester_slashings.write();
+        attester_slashings.insert(id, attester_slashing_target_epoch(&slashing), slashing);
+
+        let evicted = attester_slashings.evict_to_limit(self.limits.attester_slashings);
+        metrics::inc_counter_by(&metrics::ATTESTER_SLASHING_EVICTIONS, evicted as i64);
+        metrics::set_gauge(
+            &metrics::ATTESTER_SLASHINGS,
+            attester_slashings.len() as i64,
+        );
+
         Ok(())
     }
 
@@ -259,21 +447,75 @@ impl<T: EthSpec> OperationPool<T> {
     /// This function computes both types of slashings together, because
     /// attester slashings may be invalidated by proposer slashings included
     /// earlier in the block.
+    ///
+    /// Slashings are chosen to maximize the whistleblower reward paid to the proposer.
     pub fn get_slashings(
         &self,
         state: &BeaconState<T>,
         spec: &ChainSpec,
     ) -> (Vec<ProposerSlashing>, Vec<AttesterSlashing<T>>) {
-        let proposer_slashings = filter_limit_operations(
-            self.proposer_slashings.read().values(),
-            |slashing| {
+        let (proposer_slashings, attester_slashings, _) =
+            self.get_slashings_and_slashed_indices(state, spec);
+        (proposer_slashings, attester_slashings)
+    }
+
+    /// Get slashings and voluntary exits for inclusion in a block.
+    ///
+    /// Voluntary exits are invalid for validators that are slashed earlier in the same block, so
+    /// exits for those validators are excluded.
+    pub fn get_slashings_and_exits(
+        &self,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) -> (
+        Vec<ProposerSlashing>,
+        Vec<AttesterSlashing<T>>,
+        Vec<VoluntaryExit>,
+    ) {
+        let (proposer_slashings, attester_slashings, to_be_slashed) =
+            self.get_slashings_and_slashed_indices(state, spec);
+
+        let voluntary_exits = filter_limit_operations(
+            self.voluntary_exits.read().values(),
+            |exit| {
+                !to_be_slashed.contains(&exit.validator_index)
+                    && verify_exit(state, exit, VerifySignatures::True, spec).is_ok()
+            },
+            T::MaxVoluntaryExits::to_usize(),
+        );
+
+        (proposer_slashings, attester_slashings, voluntary_exits)
+    }
+
+    /// Compute the slashings for inclusion in a block, returning the set of indices of all
+    /// validators that they slash.
+    fn get_slashings_and_slashed_indices(
+        &self,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) -> (
+        Vec<ProposerSlashing>,
+        Vec<AttesterSlashing<T>>,
+        HashSet<u64>,
+    ) {
+        let current_epoch = state.current_epoch();
+
+        let proposer_slashings = self
+            .proposer_slashings
+            .read()
+            .values()
+            .filter_map(|slashing| {
                 state
                     .validators
                     .get(slashing.proposer_index as usize)
-                    .map_or(false, |validator| !validator.slashed)
-            },
-            T::MaxProposerSlashings::to_usize(),
-        );
+                    .filter(|validator| validator.is_slashable_at(current_epoch))
+                    .map(|validator| (slashing, slashing_reward(validator.effective_balance, spec)))
+            })
+            // Highest reward first.
+            .sorted_by_key(|(_, reward)| std::cmp::Reverse(*reward))
+            .take(T::MaxProposerSlashings::to_usize())
+            .map(|(slashing, _)| slashing.clone())
+            .collect::<Vec<_>>();
 
         // Set of validators to be slashed, so we don't attempt to construct invalid attester
         // slashings.
@@ -282,40 +524,51 @@ impl<T: EthSpec> OperationPool<T> {
             .map(|s| s.proposer_index)
             .collect::<HashSet<_>>();
 
-        let attester_slashings = self
-            .attester_slashings
-            .read()
+        let reader = self.attester_slashings.read();
+        let relevant_attester_slashings = reader
             .iter()
             .filter(|(id, slashing)| {
                 // Check the fork.
                 Self::attester_slashing_id(slashing, state, spec) == **id
             })
-            .filter(|(_, slashing)| {
-                // Take all slashings that will slash 1 or more validators.
-                let slashed_validators =
-                    get_slashable_indices_modular(state, slashing, |index, validator| {
-                        validator.slashed || to_be_slashed.contains(&index)
-                    });
-
-                // Extend the `to_be_slashed` set so subsequent iterations don't try to include
-                // useless slashings.
-                if let Ok(validators) = slashed_validators {
-                    to_be_slashed.extend(validators);
-                    true
-                } else {
-                    false
-                }
-            })
-            .take(T::MaxAttesterSlashings::to_usize())
-            .map(|(_, slashing)| slashing.clone())
-            .collect();
+            .filter_map(|(_, slashing)| {
+                AttesterSlashingMaxCover::new(slashing, &to_be_slashed, state, spec)
+            });
 
-        (proposer_slashings, attester_slashings)
+        let attester_slashings = maximum_cover(
+            relevant_attester_slashings,
+            T::MaxAttesterSlashings::to_usize(),
+        );
+
+        for slashing in &attester_slashings {
+            if let Ok(indices) = get_slashable_indices_modular(state, slashing, |_, validator| {
+                validator.is_slashable_at(current_epoch)
+            }) {
+                to_be_slashed.extend(indices);
+            }
+        }
+
+        (proposer_slashings, attester_slashings, to_be_slashed)
+    }
+
+    /// Returns a copy of every proposer slashing in the pool, ordered by proposer index.
+    pub fn get_all_proposer_slashings(&self) -> Vec<ProposerSlashing> {
+        self.proposer_slashings
+            .read()
+            .values()
+            .sorted_by_key(|slashing| slashing.proposer_index)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns a copy of every attester slashing in the pool.
+    pub fn get_all_attester_slashings(&self) -> Vec<AttesterSlashing<T>> {
+        self.attester_slashings.read().values().cloned().collect()
     }
 
     /// Prune proposer slashings for all slashed or withdrawn validators.
     pub fn prune_proposer_slashings(&self, finalized_state: &BeaconState<T>) {
-        prune_validator_hash_map(
+        prune_validator_map(
             &mut self.proposer_slashings.write(),
             |validator| {
                 validator.slashed || validator.is_withdrawable_at(finalized_state.current_epoch())
@@ -340,16 +593,23 @@ impl<T: EthSpec> OperationPool<T> {
     }
 
     /// Insert a voluntary exit, validating it almost-entirely (future exits are permitted).
+    ///
+    /// If the pool is full, the exits with the earliest epochs are evicted first.
     pub fn insert_voluntary_exit(
         &self,
         exit: VoluntaryExit,
         state: &BeaconState<T>,
         spec: &ChainSpec,
     ) -> Result<(), ExitValidationError> {
-        verify_exit_time_independent_only(state, &exit, spec)?;
-        self.voluntary_exits
-            .write()
-            .insert(exit.validator_index, exit);
+        verify_exit_time_independent_only(state, &exit, VerifySignatures::True, spec)?;
+
+        let mut voluntary_exits = self.voluntary_exits.write();
+        voluntary_exits.insert(exit.validator_index, exit.epoch, exit);
+
+        let evicted = voluntary_exits.evict_to_limit(self.limits.voluntary_exits);
+        metrics::inc_counter_by(&metrics::VOLUNTARY_EXIT_EVICTIONS, evicted as i64);
+        metrics::set_gauge(&metrics::VOLUNTARY_EXITS, voluntary_exits.len() as i64);
+
         Ok(())
     }
 
@@ -361,14 +621,24 @@ impl<T: EthSpec> OperationPool<T> {
     ) -> Vec<VoluntaryExit> {
         filter_limit_operations(
             self.voluntary_exits.read().values(),
-            |exit| verify_exit(state, exit, spec).is_ok(),
+            |exit| verify_exit(state, exit, VerifySignatures::True, spec).is_ok(),
             T::MaxVoluntaryExits::to_usize(),
         )
     }
 
+    /// Returns a copy of every voluntary exit in the pool, ordered by validator index.
+    pub fn get_all_voluntary_exits(&self) -> Vec<VoluntaryExit> {
+        self.voluntary_exits
+            .read()
+            .values()
+            .sorted_by_key(|exit| exit.validator_index)
+            .cloned()
+            .collect()
+    }
+
     /// Prune if validator has already exited at the last finalized state.
     pub fn prune_voluntary_exits(&self, finalized_state: &BeaconState<T>) {
-        prune_validator_hash_map(
+        prune_validator_map(
             &mut self.voluntary_exits.write(),
             |validator| validator.is_exited_at(finalized_state.current_epoch()),
             finalized_state,
@@ -376,6 +646,9 @@ impl<T: EthSpec> OperationPool<T> {
     }
 
     /// Insert a transfer into the pool, checking it for validity in the process.
+    ///
+    /// If the pool is full, the transfers with the earliest slots are evicted first, with ties
+    /// broken by evicting the lowest fee.
     pub fn insert_transfer(
         &self,
         transfer: Transfer,
@@ -386,7 +659,14 @@ impl<T: EthSpec> OperationPool<T> {
         // it before we insert into the HashSet, we can't end up with duplicate
         // transactions.
         verify_transfer_time_independent_only(state, &transfer, spec)?;
-        self.transfers.write().insert(transfer);
+
+        let mut transfers = self.transfers.write();
+        transfers.insert(transfer);
+
+        let evicted = evict_transfers(&mut transfers, self.limits.transfers);
+        metrics::inc_counter_by(&metrics::TRANSFER_EVICTIONS, evicted as i64);
+        metrics::set_gauge(&metrics::TRANSFERS, transfers.len() as i64);
+
         Ok(())
     }
 
@@ -404,6 +684,16 @@ impl<T: EthSpec> OperationPool<T> {
             .collect()
     }
 
+    /// Returns a copy of every transfer in the pool, ordered by slot.
+    pub fn get_all_transfers(&self) -> Vec<Transfer> {
+        self.transfers
+            .read()
+            .iter()
+            .sorted_by_key(|t| (t.slot, t.sender, t.recipient, t.amount, t.fee))
+            .cloned()
+            .collect()
+    }
+
     /// Prune the set of transfers by removing all those whose slot has already passed.
     pub fn prune_transfers(&self, finalized_state: &BeaconState<T>) {
         self.transfers
@@ -419,6 +709,31 @@ impl<T: EthSpec> OperationPool<T> {
         self.prune_attester_slashings(finalized_state, spec);
         self.prune_voluntary_exits(finalized_state);
         self.prune_transfers(finalized_state);
+
+        self.update_size_metrics();
+    }
+
+    /// Update the Prometheus gauges for the number of each type of operation in the pool.
+    fn update_size_metrics(&self) {
+        metrics::set_gauge(&metrics::ATTESTATIONS, self.num_attestations() as i64);
+        metrics::set_gauge(
+            &metrics::NAIVE_AGGREGATES,
+            self.naive_aggregation_pool.num_aggregates() as i64,
+        );
+        metrics::set_gauge(&metrics::DEPOSITS, self.num_deposits() as i64);
+        metrics::set_gauge(
+            &metrics::ATTESTER_SLASHINGS,
+            self.attester_slashings.read().len() as i64,
+        );
+        metrics::set_gauge(
+            &metrics::PROPOSER_SLASHINGS,
+            self.proposer_slashings.read().len() as i64,
+        );
+        metrics::set_gauge(
+            &metrics::VOLUNTARY_EXITS,
+            self.voluntary_exits.read().len() as i64,
+        );
+        metrics::set_gauge(&metrics::TRANSFERS, self.transfers.read().len() as i64);
     }
 }
 
@@ -437,16 +752,61 @@ where
         .collect()
 }
 
-/// Remove all entries from the given hash map for which `prune_if` returns true.
+/// Returns the later of the target epochs of the two attestations of `slashing`.
+fn attester_slashing_target_epoch<T: EthSpec>(slashing: &AttesterSlashing<T>) -> Epoch {
+    std::cmp::max(
+        slashing.attestation_1.data.target.epoch,
+        slashing.attestation_2.data.target.epoch,
+    )
+}
+
+/// Remove the deposits with the highest indices until there are at most `limit`.
+///
+/// Returns the number of deposits evicted.
+fn evict_deposits(deposits: &mut BTreeMap<u64, Deposit>, limit: usize) -> usize {
+    let mut evicted = 0;
+    while deposits.len() > limit {
+        if let Some(highest_index) = deposits.keys().next_back().cloned() {
+            deposits.remove(&highest_index);
+            evicted += 1;
+        }
+    }
+    evicted
+}
+
+/// Remove the transfers with the earliest slots until there are at most `limit`, with ties
+/// broken by evicting the lowest fee.
+///
+/// Returns the number of transfers evicted.
+fn evict_transfers(transfers: &mut HashSet<Transfer>, limit: usize) -> usize {
+    if transfers.len() <= limit {
+        return 0;
+    }
+
+    let to_evict = transfers
+        .iter()
+        .sorted_by_key(|t| (t.slot, t.fee, t.sender, t.recipient, t.amount))
+        .take(transfers.len() - limit)
+        .cloned()
+        .collect::<Vec<_>>();
+    for transfer in &to_evict {
+        transfers.remove(transfer);
+    }
+
+    to_evict.len()
+}
+
+/// Remove all entries from the given map for which `prune_if` returns true.
 ///
 /// The keys in the map should be validator indices, which will be looked up
 /// in the state's validator registry and then passed to `prune_if`.
 /// Entries for unknown validators will be kept.
-fn prune_validator_hash_map<T, F, E: EthSpec>(
-    map: &mut HashMap<u64, T>,
+fn prune_validator_map<T, O, F, E: EthSpec>(
+    map: &mut OrderedMap<u64, O, T>,
     prune_if: F,
     finalized_state: &BeaconState<E>,
 ) where
+    O: Clone + Ord,
     F: Fn(&Validator) -> bool,
 {
     map.retain(|&validator_index, _| {
@@ -574,6 +934,64 @@ mod tests {
         assert_eq!(op_pool.num_deposits(), 0);
     }
 
+    #[test]
+    fn insert_deposit_evicts_highest_index() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let limit = 4;
+        let op_pool = OperationPool::<MinimalEthSpec>::with_limits(OperationPoolLimits {
+            deposits: limit,
+            ..OperationPoolLimits::default()
+        });
+
+        let deposits = dummy_deposits(rng, 0, 2 * limit as u64);
+
+        // Insert in reverse order so the highest indices are evicted as the lower ones arrive.
+        for (i, deposit) in deposits.iter().rev() {
+            assert_eq!(op_pool.insert_deposit(*i, deposit.clone()), Ok(Fresh));
+            assert!(op_pool.num_deposits() <= limit);
+        }
+
+        let mut state = BeaconState::random_for_test(rng);
+        state.eth1_deposit_index = 0;
+        let expected = deposits[..limit]
+            .iter()
+            .map(|(_, d)| d.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(op_pool.get_deposits(&state), expected);
+    }
+
+    #[test]
+    fn restore_with_limits() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let (spec, mut state) = test_state(rng);
+        let limit = 4;
+
+        let op_pool = OperationPool::new();
+        let deposits = dummy_deposits(rng, 0, 2 * limit as u64);
+        for (i, deposit) in &deposits {
+            assert_eq!(op_pool.insert_deposit(*i, deposit.clone()), Ok(Fresh));
+        }
+
+        let limits = OperationPoolLimits {
+            deposits: limit,
+            ..OperationPoolLimits::default()
+        };
+        let restored = PersistedOperationPool::from_operation_pool(&op_pool).into_operation_pool(
+            limits.clone(),
+            &state,
+            &spec,
+        );
+        assert_eq!(restored.limits, limits);
+        assert_eq!(restored.num_deposits(), limit);
+
+        state.eth1_deposit_index = 0;
+        let expected = deposits[..limit]
+            .iter()
+            .map(|(_, d)| d.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(restored.get_deposits(&state), expected);
+    }
+
     // Create a random deposit
     fn make_deposit(rng: &mut XorShiftRng) -> Deposit {
         Deposit::random_for_test(rng)
@@ -604,6 +1022,8 @@ mod tests {
     #[cfg(not(debug_assertions))]
     mod release_tests {
         use super::*;
+        use crate::attestation::earliest_attestation_validators;
+        use crate::max_cover::MaxCover;
 
         /// Create a signed attestation for use in tests.
         /// Signed by all validators in `committee[signing_range]` and `committee[extra_signer]`.
@@ -753,12 +1173,12 @@ mod tests {
 
             // Before the min attestation inclusion delay, get_attestations shouldn't return anything.
             state.slot -= 1;
-            assert_eq!(op_pool.get_attestations(state, spec).len(), 0);
+            assert_eq!(op_pool.get_attestations(state, spec).unwrap().len(), 0);
 
             // Then once the delay has elapsed, we should get a single aggregated attestation.
             state.slot += spec.min_attestation_inclusion_delay;
 
-            let block_attestations = op_pool.get_attestations(state, spec);
+            let block_attestations = op_pool.get_attestations(state, spec).unwrap();
             assert_eq!(block_attestations.len(), committees.len());
 
             let agg_att = &block_attestations[0];
@@ -814,6 +1234,81 @@ mod tests {
             assert_eq!(op_pool.num_attestations(), committees.len());
         }
 
+        /// Attestations signed by a single validator should be aggregated in the naive
+        /// aggregation pool, and the aggregate should be available for block inclusion.
+        #[test]
+        fn attestation_naive_aggregation() {
+            let (ref mut state, ref keypairs, ref spec) =
+                attestation_test_state::<MainnetEthSpec>(1);
+
+            let op_pool = OperationPool::new();
+
+            let slot = state.slot - 1;
+            let committees = state
+                .get_crosslink_committees_at_slot(slot)
+                .unwrap()
+                .into_iter()
+                .map(CrosslinkCommittee::into_owned)
+                .collect::<Vec<_>>();
+
+            let mut data = None;
+            for cc in &committees {
+                for i in 0..cc.committee.len() {
+                    let att = signed_attestation(
+                        &cc.committee,
+                        cc.shard,
+                        keypairs,
+                        i..=i,
+                        slot,
+                        state,
+                        spec,
+                        None,
+                    );
+                    data = Some(att.data.clone());
+                    op_pool
+                        .insert_unaggregated_attestation(&att, state, spec)
+                        .unwrap();
+                    // Duplicates should not be double-counted.
+                    assert_eq!(
+                        op_pool.insert_unaggregated_attestation(&att, state, spec),
+                        Ok(NaiveAggregationInsertOutcome::SignatureAlreadyKnown {
+                            committee_index: i
+                        })
+                    );
+                    op_pool.insert_attestation(att, state, spec).unwrap();
+                }
+            }
+
+            assert_eq!(
+                op_pool.naive_aggregation_pool.num_aggregates(),
+                committees.len()
+            );
+
+            let aggregate = op_pool
+                .get_naive_aggregate(
+                    &data.expect("should have produced an attestation"),
+                    state,
+                    spec,
+                )
+                .expect("should have an aggregate");
+            assert_eq!(
+                aggregate.aggregation_bits.num_set_bits(),
+                spec.target_committee_size as usize
+            );
+
+            state.slot += spec.min_attestation_inclusion_delay;
+            let block_attestations = op_pool.get_attestations(state, spec).unwrap();
+            assert_eq!(block_attestations.len(), committees.len());
+            assert_eq!(
+                block_attestations[0].aggregation_bits.num_set_bits(),
+                spec.target_committee_size as usize
+            );
+
+            state.slot += 2 * MainnetEthSpec::slots_per_epoch();
+            op_pool.prune_attestations(state);
+            assert_eq!(op_pool.naive_aggregation_pool.num_aggregates(), 0);
+        }
+
         /// Adding lots of attestations that only intersect pairwise should lead to two aggregate
         /// attestations.
         #[test]
@@ -917,7 +1412,7 @@ mod tests {
             assert!(op_pool.num_attestations() > max_attestations);
 
             state.slot += spec.min_attestation_inclusion_delay;
-            let best_attestations = op_pool.get_attestations(state, spec);
+            let best_attestations = op_pool.get_attestations(state, spec).unwrap();
             assert_eq!(best_attestations.len(), max_attestations);
 
             // All the best attestations should be signed by at least `big_step_size` (4) validators.
@@ -925,6 +1420,118 @@ mod tests {
                 assert!(att.aggregation_bits.num_set_bits() >= big_step_size);
             }
         }
+
+        /// Check that attestations are packed by the rewards they produce rather than by the
+        /// number of validators they include, and that validators covered by one attestation
+        /// are not counted again for the others.
+        #[test]
+        fn attestation_max_cover_maximizes_reward() {
+            let (ref mut state, ref keypairs, ref spec) =
+                attestation_test_state::<MainnetEthSpec>(1);
+
+            let slot = state.slot - 1;
+            let committee = state
+                .get_crosslink_committees_at_slot(slot)
+                .unwrap()
+                .into_iter()
+                .map(CrosslinkCommittee::into_owned)
+                .next()
+                .unwrap();
+
+            let attestation = |signing_range: std::ops::Range<usize>| {
+                signed_attestation(
+                    &committee.committee,
+                    committee.shard,
+                    keypairs,
+                    signing_range,
+                    slot,
+                    state,
+                    spec,
+                    None,
+                )
+            };
+
+            let four_matching = attestation(0..4);
+            let three_matching = attestation(0..3);
+            // Votes for the wrong target and head earn roughly half of the reward.
+            let mut five_wrong = attestation(3..8);
+            five_wrong.data.target.root = Hash256::from_low_u64_be(42);
+            five_wrong.data.beacon_block_root = Hash256::from_low_u64_be(42);
+
+            state.slot += spec.min_attestation_inclusion_delay;
+            let total_active_balance = state
+                .get_total_balance(
+                    state
+                        .get_cached_active_validator_indices(RelativeEpoch::Current)
+                        .unwrap(),
+                    spec,
+                )
+                .unwrap();
+            let covers = vec![&five_wrong, &three_matching, &four_matching]
+                .into_iter()
+                .map(|att| AttMaxCover::new(att, state, total_active_balance, spec).unwrap())
+                .collect::<Vec<_>>();
+
+            let scores = covers.iter().map(MaxCover::score).collect::<Vec<_>>();
+            assert!(scores[0] < scores[1] && scores[1] < scores[2]);
+
+            // Once `four_matching` is included, `three_matching` is worth nothing and
+            // `five_wrong` is only worth its four validators that are not in `four_matching`.
+            assert_eq!(maximum_cover(covers, 3), vec![four_matching, five_wrong]);
+        }
+
+        /// Check that voluntary exits are excluded for validators that are slashed by the
+        /// slashings returned alongside them.
+        #[test]
+        fn get_slashings_and_exits_excludes_slashed_validators() {
+            let spec = MinimalEthSpec::default_spec();
+            let mut state_builder =
+                TestingBeaconStateBuilder::<MinimalEthSpec>::from_deterministic_keypairs(16, &spec);
+            let epoch = Epoch::new(spec.persistent_committee_period + 1);
+            state_builder.teleport_to_slot(epoch.start_slot(MinimalEthSpec::slots_per_epoch()));
+            state_builder.build_caches(&spec).unwrap();
+            let (state, keypairs) = state_builder.build();
+
+            let signer = |validator_index: u64, message: &[u8], epoch: Epoch, domain: Domain| {
+                let domain = spec.get_domain(epoch, domain, &state.fork);
+                Signature::new(message, domain, &keypairs[validator_index as usize].sk)
+            };
+
+            let op_pool = OperationPool::new();
+
+            let proposer_slashing =
+                TestingProposerSlashingBuilder::double_vote::<MinimalEthSpec, _>(0, signer);
+            op_pool
+                .insert_proposer_slashing(proposer_slashing.clone(), &state, &spec)
+                .unwrap();
+            let attester_slashing = TestingAttesterSlashingBuilder::double_vote(&[1], signer);
+            op_pool
+                .insert_attester_slashing(attester_slashing.clone(), &state, &spec)
+                .unwrap();
+
+            for validator_index in 0..3 {
+                let mut builder = TestingVoluntaryExitBuilder::new(epoch, validator_index);
+                builder.sign(&keypairs[validator_index as usize].sk, &state.fork, &spec);
+                op_pool
+                    .insert_voluntary_exit(builder.build(), &state, &spec)
+                    .unwrap();
+            }
+
+            // Each exit is valid on its own.
+            assert_eq!(op_pool.get_voluntary_exits(&state, &spec).len(), 3);
+
+            let (proposer_slashings, attester_slashings, exits) =
+                op_pool.get_slashings_and_exits(&state, &spec);
+            assert_eq!(proposer_slashings, vec![proposer_slashing]);
+            assert_eq!(attester_slashings, vec![attester_slashing]);
+            assert_eq!(
+                exits
+                    .iter()
+                    .map(|exit| exit.validator_index)
+                    .collect::<Vec<_>>(),
+                vec![2]
+            );
+        }
     }
 
     // TODO: more tests
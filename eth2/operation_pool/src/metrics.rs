@@ -0,0 +1,63 @@
+pub use lighthouse_metrics::*;
+
+lazy_static! {
+    /*
+     * Pool sizes
+     */
+    pub static ref ATTESTATIONS: Result<IntGauge> = try_create_int_gauge(
+        "op_pool_attestations",
+        "Count of attestations in the operation pool, including those with the same data"
+    );
+    pub static ref NAIVE_AGGREGATES: Result<IntGauge> = try_create_int_gauge(
+        "op_pool_naive_aggregates",
+        "Count of distinct attestation data in the naive aggregation pool"
+    );
+    pub static ref DEPOSITS: Result<IntGauge> =
+        try_create_int_gauge("op_pool_deposits", "Count of deposits in the operation pool");
+    pub static ref ATTESTER_SLASHINGS: Result<IntGauge> = try_create_int_gauge(
+        "op_pool_attester_slashings",
+        "Count of attester slashings in the operation pool"
+    );
+    pub static ref PROPOSER_SLASHINGS: Result<IntGauge> = try_create_int_gauge(
+        "op_pool_proposer_slashings",
+        "Count of proposer slashings in the operation pool"
+    );
+    pub static ref VOLUNTARY_EXITS: Result<IntGauge> = try_create_int_gauge(
+        "op_pool_voluntary_exits",
+        "Count of voluntary exits in the operation pool"
+    );
+    pub static ref TRANSFERS: Result<IntGauge> =
+        try_create_int_gauge("op_pool_transfers", "Count of transfers in the operation pool");
+
+    /*
+     * Evictions
+     */
+    pub static ref ATTESTATION_EVICTIONS: Result<IntCounter> = try_create_int_counter(
+        "op_pool_attestation_evictions_total",
+        "Count of attestations evicted from a full operation pool"
+    );
+    pub static ref NAIVE_AGGREGATE_EVICTIONS: Result<IntCounter> = try_create_int_counter(
+        "op_pool_naive_aggregate_evictions_total",
+        "Count of aggregates evicted from a full naive aggregation pool"
+    );
+    pub static ref DEPOSIT_EVICTIONS: Result<IntCounter> = try_create_int_counter(
+        "op_pool_deposit_evictions_total",
+        "Count of deposits evicted from a full operation pool"
+    );
+    pub static ref ATTESTER_SLASHING_EVICTIONS: Result<IntCounter> = try_create_int_counter(
+        "op_pool_attester_slashing_evictions_total",
+        "Count of attester slashings evicted from a full operation pool"
+    );
+    pub static ref PROPOSER_SLASHING_EVICTIONS: Result<IntCounter> = try_create_int_counter(
+        "op_pool_proposer_slashing_evictions_total",
+        "Count of proposer slashings evicted from a full operation pool"
+    );
+    pub static ref VOLUNTARY_EXIT_EVICTIONS: Result<IntCounter> = try_create_int_counter(
+        "op_pool_voluntary_exit_evictions_total",
+        "Count of voluntary exits evicted from a full operation pool"
+    );
+    pub static ref TRANSFER_EVICTIONS: Result<IntCounter> = try_create_int_counter(
+        "op_pool_transfer_evictions_total",
+        "Count of transfers evicted from a full operation pool"
+    );
+}
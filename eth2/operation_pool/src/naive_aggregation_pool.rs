@@ -0,0 +1,349 @@
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use tree_hash::TreeHash;
+use types::{Attestation, AttestationData, Epoch, EthSpec, Hash256};
+
+/// The outcome of inserting an unaggregated attestation into a `NaiveAggregationPool`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum InsertOutcome {
+    /// The `AttestationData` had not been seen before, the attestation was stored as-is.
+    NewAttestationData { committee_index: usize },
+    /// The signer of the attestation was already included in the aggregate, no change was made.
+    SignatureAlreadyKnown { committee_index: usize },
+    /// The attestation was aggregated into the existing aggregate for its `AttestationData`.
+    SignatureAggregated { committee_index: usize },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Error {
+    /// The attestation did not have any aggregation bits set.
+    NoAggregationBitsSet,
+    /// The attestation had more than one aggregation bit set, it is not unaggregated.
+    ///
+    /// (number of bits set)
+    MoreThanOneAggregationBitSet(usize),
+    /// The attestation had an aggregation bitfield of a different length to the existing
+    /// aggregate for the same `AttestationData`.
+    InconsistentBitfieldLengths,
+}
+
+/// A pool of unaggregated attestations (i.e., attestations signed by a single validator).
+///
+/// Each attestation is eagerly aggregated with all other attestations that share the same
+/// `AttestationData`, so there is only ever a single aggregate for each distinct
+/// `AttestationData`. This is naive in the sense that it cannot handle attestations that are
+/// already aggregated, but it means that the aggregate for some `AttestationData` always
+/// includes every signer we have seen.
+///
+/// ## Note
+///
+/// The aggregates are keyed by the tree hash root of their `AttestationData` and the signature
+/// domain of the attestations (which includes the fork version), so that attestations signed on
+/// different forks are never aggregated together.
+#[derive(Default, Debug)]
+pub struct NaiveAggregationPool<T: EthSpec> {
+    /// Map from `AttestationData` tree hash root and signature domain to the aggregate of all
+    /// attestations seen with that data and domain.
+    aggregates: RwLock<HashMap<(Hash256, u64), Attestation<T>>>,
+}
+
+impl<T: EthSpec> NaiveAggregationPool<T> {
+    /// Insert an unaggregated attestation, signed with `domain`, into the pool, aggregating it
+    /// with the existing aggregate for the same `AttestationData` and `domain` (if any).
+    ///
+    /// ## Note
+    ///
+    /// This function assumes the given `attestation` is valid.
+    pub fn insert(
+        &self,
+        attestation: &Attestation<T>,
+        domain: u64,
+    ) -> Result<InsertOutcome, Error> {
+        let committee_index = get_committee_index(attestation)?;
+        let key = (
+            Hash256::from_slice(&attestation.data.tree_hash_root()),
+            domain,
+        );
+
+        let mut aggregates = self.aggregates.write();
+
+        if let Some(existing_attestation) = aggregates.get_mut(&key) {
+            if existing_attestation.aggregation_bits.len() != attestation.aggregation_bits.len() {
+                return Err(Error::InconsistentBitfieldLengths);
+            }
+
+            if existing_attestation
+                .aggregation_bits
+                .get(committee_index)
+                .unwrap_or(false)
+            {
+                Ok(InsertOutcome::SignatureAlreadyKnown { committee_index })
+            } else {
+                existing_attestation.aggregate(attestation);
+                Ok(InsertOutcome::SignatureAggregated { committee_index })
+            }
+        } else {
+            aggregates.insert(key, attestation.clone());
+            Ok(InsertOutcome::NewAttestationData { committee_index })
+        }
+    }
+
+    /// Returns the aggregate of all attestations seen for the given `data` and `domain`, if any.
+    pub fn get(&self, data: &AttestationData, domain: u64) -> Option<Attestation<T>> {
+        let key = (Hash256::from_slice(&data.tree_hash_root()), domain);
+        self.aggregates.read().get(&key).cloned()
+    }
+
+    /// Returns a copy of the aggregate for every distinct `AttestationData` and domain in the
+    /// pool.
+    pub fn get_all(&self) -> Vec<Attestation<T>> {
+        self.aggregates.read().values().cloned().collect()
+    }
+
+    /// Returns a copy of the aggregates that were signed with any of the given `domains`.
+    pub fn get_with_domains(&self, domains: &[u64]) -> Vec<Attestation<T>> {
+        self.aggregates
+            .read()
+            .iter()
+            .filter(|((_, domain), _)| domains.contains(domain))
+            .map(|(_, attestation)| attestation.clone())
+            .collect()
+    }
+
+    /// The number of distinct `AttestationData` and domains in the pool.
+    pub fn num_aggregates(&self) -> usize {
+        self.aggregates.read().len()
+    }
+
+    /// Remove aggregates until there are at most `limit` in the pool.
+    ///
+    /// Aggregates are evicted in order of their target epoch (oldest first) and then their
+    /// `AttestationData` root and domain.
+    ///
+    /// Returns the number of aggregates evicted.
+    pub fn evict_to_limit(&self, limit: usize) -> usize {
+        let mut aggregates = self.aggregates.write();
+
+        if aggregates.len() <= limit {
+            return 0;
+        }
+
+        let mut ordered = aggregates
+            .iter()
+            .map(|(key, attestation)| (attestation.data.target.epoch, *key))
+            .collect::<Vec<_>>();
+        ordered.sort();
+
+        let num_to_evict = aggregates.len() - limit;
+        for (_, key) in ordered.into_iter().take(num_to_evict) {
+            aggregates.remove(&key);
+        }
+
+        num_to_evict
+    }
+
+    /// Remove all aggregates which are too old to be included in a block at `current_epoch`.
+    pub fn prune(&self, current_epoch: Epoch) {
+        self.aggregates
+            .write()
+            .retain(|_, attestation| current_epoch <= attestation.data.target.epoch + 1);
+    }
+}
+
+/// Returns the index of the single signer of `attestation` within its committee.
+///
+/// Returns an error if `attestation` is not signed by exactly one validator.
+fn get_committee_index<T: EthSpec>(attestation: &Attestation<T>) -> Result<usize, Error> {
+    let mut set_bits = attestation
+        .aggregation_bits
+        .iter()
+        .enumerate()
+        .filter(|(_, bit)| *bit)
+        .map(|(i, _)| i);
+
+    let committee_index = set_bits.next().ok_or_else(|| Error::NoAggregationBitsSet)?;
+
+    if set_bits.next().is_some() {
+        Err(Error::MoreThanOneAggregationBitSet(
+            attestation.aggregation_bits.num_set_bits(),
+        ))
+    } else {
+        Ok(committee_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use types::{AggregateSignature, BitList, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    const COMMITTEE_SIZE: usize = 8;
+    const DOMAIN: u64 = 42;
+
+    fn unaggregated_attestation(data: &AttestationData, committee_index: usize) -> Attestation<E> {
+        let mut aggregation_bits = BitList::with_capacity(COMMITTEE_SIZE).unwrap();
+        aggregation_bits.set(committee_index, true).unwrap();
+
+        Attestation {
+            aggregation_bits,
+            data: data.clone(),
+            custody_bits: BitList::with_capacity(COMMITTEE_SIZE).unwrap(),
+            signature: AggregateSignature::new(),
+        }
+    }
+
+    fn random_data(rng: &mut XorShiftRng) -> AttestationData {
+        AttestationData::random_for_test(rng)
+    }
+
+    #[test]
+    fn rejects_aggregated_and_empty_attestations() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let pool = NaiveAggregationPool::<E>::default();
+        let data = random_data(rng);
+
+        let mut attestation = unaggregated_attestation(&data, 0);
+        attestation.aggregation_bits.set(1, true).unwrap();
+        assert_eq!(
+            pool.insert(&attestation, DOMAIN),
+            Err(Error::MoreThanOneAggregationBitSet(2))
+        );
+
+        attestation.aggregation_bits = BitList::with_capacity(COMMITTEE_SIZE).unwrap();
+        assert_eq!(
+            pool.insert(&attestation, DOMAIN),
+            Err(Error::NoAggregationBitsSet)
+        );
+
+        assert_eq!(pool.num_aggregates(), 0);
+    }
+
+    #[test]
+    fn aggregates_attestations_with_the_same_data() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let pool = NaiveAggregationPool::<E>::default();
+        let data = random_data(rng);
+
+        assert_eq!(
+            pool.insert(&unaggregated_attestation(&data, 0), DOMAIN),
+            Ok(InsertOutcome::NewAttestationData { committee_index: 0 })
+        );
+        for i in 1..COMMITTEE_SIZE {
+            assert_eq!(
+                pool.insert(&unaggregated_attestation(&data, i), DOMAIN),
+                Ok(InsertOutcome::SignatureAggregated { committee_index: i })
+            );
+        }
+        assert_eq!(
+            pool.insert(&unaggregated_attestation(&data, 3), DOMAIN),
+            Ok(InsertOutcome::SignatureAlreadyKnown { committee_index: 3 })
+        );
+
+        assert_eq!(pool.num_aggregates(), 1);
+        let aggregate = pool.get(&data, DOMAIN).expect("aggregate should exist");
+        assert_eq!(aggregate.aggregation_bits.num_set_bits(), COMMITTEE_SIZE);
+
+        // Attestations with different data are not aggregated together.
+        let other_data = random_data(rng);
+        assert_eq!(
+            pool.insert(&unaggregated_attestation(&other_data, 0), DOMAIN),
+            Ok(InsertOutcome::NewAttestationData { committee_index: 0 })
+        );
+        assert_eq!(pool.num_aggregates(), 2);
+        assert_eq!(
+            pool.get(&other_data, DOMAIN)
+                .expect("aggregate should exist")
+                .aggregation_bits
+                .num_set_bits(),
+            1
+        );
+    }
+
+    #[test]
+    fn separates_attestations_by_domain() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let pool = NaiveAggregationPool::<E>::default();
+        let data = random_data(rng);
+        let other_domain = DOMAIN + 1;
+
+        assert_eq!(
+            pool.insert(&unaggregated_attestation(&data, 0), DOMAIN),
+            Ok(InsertOutcome::NewAttestationData { committee_index: 0 })
+        );
+        // The same data signed on another fork must not be merged into the existing aggregate.
+        assert_eq!(
+            pool.insert(&unaggregated_attestation(&data, 1), other_domain),
+            Ok(InsertOutcome::NewAttestationData { committee_index: 1 })
+        );
+        assert_eq!(
+            pool.insert(&unaggregated_attestation(&data, 0), other_domain),
+            Ok(InsertOutcome::SignatureAggregated { committee_index: 0 })
+        );
+        assert_eq!(pool.num_aggregates(), 2);
+
+        let aggregate = pool.get(&data, DOMAIN).expect("aggregate should exist");
+        assert_eq!(aggregate.aggregation_bits.num_set_bits(), 1);
+        assert_eq!(aggregate.aggregation_bits.get(0), Ok(true));
+
+        let other_aggregate = pool
+            .get(&data, other_domain)
+            .expect("aggregate should exist");
+        assert_eq!(other_aggregate.aggregation_bits.num_set_bits(), 2);
+
+        assert!(pool.get(&data, DOMAIN + 2).is_none());
+        assert_eq!(pool.get_with_domains(&[DOMAIN]), vec![aggregate]);
+        assert_eq!(
+            pool.get_with_domains(&[other_domain]),
+            vec![other_aggregate]
+        );
+        assert_eq!(pool.get_with_domains(&[DOMAIN, other_domain]).len(), 2);
+    }
+
+    #[test]
+    fn prunes_old_aggregates() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let pool = NaiveAggregationPool::<E>::default();
+        let mut data = random_data(rng);
+        data.target.epoch = Epoch::new(10);
+
+        pool.insert(&unaggregated_attestation(&data, 0), DOMAIN)
+            .unwrap();
+
+        pool.prune(Epoch::new(11));
+        assert_eq!(pool.num_aggregates(), 1);
+
+        pool.prune(Epoch::new(12));
+        assert_eq!(pool.num_aggregates(), 0);
+    }
+
+    #[test]
+    fn evicts_oldest_aggregates() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let pool = NaiveAggregationPool::<E>::default();
+
+        let datas = (0..4)
+            .map(|epoch| {
+                let mut data = random_data(rng);
+                data.target.epoch = Epoch::new(epoch);
+                data
+            })
+            .collect::<Vec<_>>();
+
+        for data in datas.iter().rev() {
+            pool.insert(&unaggregated_attestation(data, 0), DOMAIN)
+                .unwrap();
+        }
+
+        assert_eq!(pool.evict_to_limit(4), 0);
+        assert_eq!(pool.evict_to_limit(2), 2);
+        assert_eq!(pool.num_aggregates(), 2);
+
+        assert!(pool.get(&datas[0], DOMAIN).is_none());
+        assert!(pool.get(&datas[1], DOMAIN).is_none());
+        assert!(pool.get(&datas[2], DOMAIN).is_some());
+        assert!(pool.get(&datas[3], DOMAIN).is_some());
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+
+/// A `HashMap` which also indexes its entries by an order, so that the lowest-ordered entries
+/// can be found and evicted in `O(log n)` time.
+///
+/// The order of an entry is given when it is inserted. Ties are broken by the key, so eviction
+/// is deterministic.
+#[derive(Debug, Clone)]
+pub struct OrderedMap<K, O, V> {
+    map: HashMap<K, (O, V)>,
+    index: BTreeSet<(O, K)>,
+}
+
+impl<K, O, V> Default for OrderedMap<K, O, V>
+where
+    K: Clone + Eq + Hash + Ord,
+    O: Clone + Ord,
+{
+    fn default() -> Self {
+        Self {
+            map: HashMap::new(),
+            index: BTreeSet::new(),
+        }
+    }
+}
+
+impl<K, O, V> OrderedMap<K, O, V>
+where
+    K: Clone + Eq + Hash + Ord,
+    O: Clone + Ord,
+{
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns a mutable reference to the value for `key`, if any.
+    ///
+    /// The order of the entry is not changed.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.map.get_mut(key).map(|(_, value)| value)
+    }
+
+    /// Inserts `value` at `key` with the given `order`, returning the value it replaced (if any).
+    pub fn insert(&mut self, key: K, order: O, value: V) -> Option<V> {
+        self.index.insert((order.clone(), key.clone()));
+
+        let (old_order, old_value) = self.map.insert(key.clone(), (order.clone(), value))?;
+        if old_order != order {
+            self.index.remove(&(old_order, key));
+        }
+
+        Some(old_value)
+    }
+
+    /// Removes the entry for `key`, returning its value (if any).
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (order, value) = self.map.remove(key)?;
+        self.index.remove(&(order, key.clone()));
+        Some(value)
+    }
+
+    /// Removes and returns the entry with the lowest order, if any.
+    pub fn pop_lowest(&mut self) -> Option<(K, V)> {
+        let (_, key) = self.index.iter().next().cloned()?;
+        let value = self.remove(&key)?;
+        Some((key, value))
+    }
+
+    /// Removes the lowest-ordered entries until there are at most `limit`, returning the number
+    /// of entries removed.
+    pub fn evict_to_limit(&mut self, limit: usize) -> usize {
+        let mut evicted = 0;
+        while self.len() > limit && self.pop_lowest().is_some() {
+            evicted += 1;
+        }
+        evicted
+    }
+
+    /// Retains only the entries for which `f` returns `true`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        let to_remove = self
+            .map
+            .iter()
+            .filter(|(key, (_, value))| !f(key, value))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        for key in &to_remove {
+            self.remove(key);
+        }
+    }
+
+    /// Iterates over the keys and values of the map, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.map.iter().map(|(key, (_, value))| (key, value))
+    }
+
+    /// Iterates over the values of the map, in arbitrary order.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.map.values().map(|(_, value)| value)
+    }
+}
+
+impl<K, O, V> PartialEq for OrderedMap<K, O, V>
+where
+    K: Eq + Hash,
+    O: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evict_to_limit_is_deterministic() {
+        let mut map = OrderedMap::default();
+        for key in 0..10u64 {
+            map.insert(key, 100 - key % 3, ());
+        }
+
+        assert_eq!(map.evict_to_limit(10), 0);
+        assert_eq!(map.len(), 10);
+
+        // Orders are 100, 99 and 98. The four lowest (order, key) pairs are evicted.
+        assert_eq!(map.evict_to_limit(6), 4);
+        let mut remaining = map.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+        remaining.sort();
+        assert_eq!(remaining, vec![0, 3, 4, 6, 7, 9]);
+    }
+
+    #[test]
+    fn insert_replaces_order() {
+        let mut map = OrderedMap::default();
+        map.insert(1u64, 1u64, "a");
+        map.insert(2, 2, "b");
+
+        // Re-inserting key 1 with a later order moves it after key 2.
+        assert_eq!(map.insert(1, 3, "c"), Some("a"));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.pop_lowest(), Some((2, "b")));
+        assert_eq!(map.pop_lowest(), Some((1, "c")));
+        assert_eq!(map.pop_lowest(), None);
+    }
+
+    #[test]
+    fn retain_updates_index() {
+        let mut map = OrderedMap::default();
+        for key in 0..4u64 {
+            map.insert(key, key, key);
+        }
+
+        map.retain(|key, _| key % 2 == 1);
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.pop_lowest(), Some((1, 1)));
+        assert_eq!(map.pop_lowest(), Some((3, 3)));
+        assert_eq!(map.len(), 0);
+    }
+}
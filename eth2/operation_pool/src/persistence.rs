@@ -1,5 +1,10 @@
 use crate::attestation_id::AttestationId;
-use crate::OperationPool;
+use crate::attestation_map::AttestationMap;
+use crate::ordered_map::OrderedMap;
+use crate::{
+    attester_slashing_target_epoch, evict_deposits, evict_transfers, OperationPool,
+    OperationPoolLimits,
+};
 use parking_lot::RwLock;
 use ssz_derive::{Decode, Encode};
 use types::*;
@@ -75,43 +80,59 @@ impl<T: EthSpec> PersistedOperationPool<T> {
         }
     }
 
-    /// Reconstruct an `OperationPool`.
-    pub fn into_operation_pool(self, state: &BeaconState<T>, spec: &ChainSpec) -> OperationPool<T> {
-        let attestations = RwLock::new(self.attestations.into_iter().collect());
-        let deposits = RwLock::new(self.deposits.into_iter().collect());
-        let attester_slashings = RwLock::new(
-            self.attester_slashings
-                .into_iter()
-                .map(|slashing| {
-                    (
-                        OperationPool::attester_slashing_id(&slashing, state, spec),
-                        slashing,
-                    )
-                })
-                .collect(),
-        );
-        let proposer_slashings = RwLock::new(
-            self.proposer_slashings
-                .into_iter()
-                .map(|slashing| (slashing.proposer_index, slashing))
-                .collect(),
-        );
-        let voluntary_exits = RwLock::new(
-            self.voluntary_exits
-                .into_iter()
-                .map(|exit| (exit.validator_index, exit))
-                .collect(),
-        );
-        let transfers = RwLock::new(self.transfers.into_iter().collect());
+    /// Reconstruct an `OperationPool` which stores at most `limits` operations of each type.
+    ///
+    /// If the persisted pool holds more operations than `limits` allows, the excess operations
+    /// are evicted in the same order as they would be on insertion.
+    pub fn into_operation_pool(
+        self,
+        limits: OperationPoolLimits,
+        state: &BeaconState<T>,
+        spec: &ChainSpec,
+    ) -> OperationPool<T> {
+        let mut attestations: AttestationMap<T> = self.attestations.into_iter().collect();
+        attestations.evict_to_limit(limits.attestations);
 
-        OperationPool {
-            attestations,
-            deposits,
-            attester_slashings,
-            proposer_slashings,
-            voluntary_exits,
-            transfers,
-            _phantom: Default::default(),
+        let mut deposits = self.deposits.into_iter().collect();
+        evict_deposits(&mut deposits, limits.deposits);
+
+        let mut attester_slashings = OrderedMap::default();
+        for slashing in self.attester_slashings {
+            attester_slashings.insert(
+                OperationPool::attester_slashing_id(&slashing, state, spec),
+                attester_slashing_target_epoch(&slashing),
+                slashing,
+            );
         }
+        attester_slashings.evict_to_limit(limits.attester_slashings);
+
+        let mut proposer_slashings = OrderedMap::default();
+        for slashing in self.proposer_slashings {
+            proposer_slashings.insert(slashing.proposer_index, slashing.header_1.slot, slashing);
+        }
+        proposer_slashings.evict_to_limit(limits.proposer_slashings);
+
+        let mut voluntary_exits = OrderedMap::default();
+        for exit in self.voluntary_exits {
+            voluntary_exits.insert(exit.validator_index, exit.epoch, exit);
+        }
+        voluntary_exits.evict_to_limit(limits.voluntary_exits);
+
+        let mut transfers = self.transfers.into_iter().collect();
+        evict_transfers(&mut transfers, limits.transfers);
+
+        let op_pool = OperationPool {
+            attestations: RwLock::new(attestations),
+            naive_aggregation_pool: Default::default(),
+            deposits: RwLock::new(deposits),
+            attester_slashings: RwLock::new(attester_slashings),
+            proposer_slashings: RwLock::new(proposer_slashings),
+            voluntary_exits: RwLock::new(voluntary_exits),
+            transfers: RwLock::new(transfers),
+            limits,
+            _phantom: Default::default(),
+        };
+        op_pool.update_size_metrics();
+        op_pool
     }
 }
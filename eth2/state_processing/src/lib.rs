@@ -6,11 +6,20 @@ pub mod genesis;
 pub mod per_block_processing;
 pub mod per_epoch_processing;
 pub mod per_slot_processing;
+pub mod upgrade_state;
 
 pub use genesis::{initialize_beacon_state_from_eth1, is_valid_genesis_state};
 pub use per_block_processing::{
     errors::{BlockInvalid, BlockProcessingError},
-    per_block_processing, per_block_processing_without_verifying_block_signature,
+    per_block_processing, per_block_processing_with_pubkeys,
+    per_block_processing_without_verifying_block_signature,
 };
-pub use per_epoch_processing::{errors::EpochProcessingError, per_epoch_processing};
-pub use per_slot_processing::{per_slot_processing, Error as SlotProcessingError};
+pub use per_epoch_processing::{
+    errors::EpochProcessingError, per_epoch_processing, per_epoch_processing_with_reward_report,
+    EpochRewardReport,
+};
+pub use per_slot_processing::{
+    fast_forward_state, per_slot_processing, per_slot_processing_with_reward_report,
+    Error as SlotProcessingError, SkipSlotProcessor,
+};
+pub use upgrade_state::upgrade_state;
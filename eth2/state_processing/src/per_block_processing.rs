@@ -1,12 +1,16 @@
 use crate::common::{initiate_validator_exit, slash_validator};
 use errors::{BlockInvalid as Invalid, BlockProcessingError as Error, IntoWithIndex};
 use rayon::prelude::*;
+use std::borrow::Cow;
 use std::collections::HashSet;
 use std::convert::TryInto;
 use std::iter::FromIterator;
 use tree_hash::{SignedRoot, TreeHash};
 use types::*;
 
+pub use self::block_signature_verifier::{
+    BlockSignatureVerifier, Error as BlockSignatureVerifierError,
+};
 pub use self::verify_attester_slashing::{
     get_slashable_indices, get_slashable_indices_modular, verify_attester_slashing,
 };
@@ -26,8 +30,10 @@ pub use verify_transfer::{
 };
 
 pub mod block_processing_builder;
+mod block_signature_verifier;
 pub mod errors;
 mod is_valid_indexed_attestation;
+pub mod signature_sets;
 pub mod tests;
 mod verify_attestation;
 mod verify_attester_slashing;
@@ -36,7 +42,7 @@ mod verify_exit;
 mod verify_proposer_slashing;
 mod verify_transfer;
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 pub enum VerifySignatures {
     True,
     False,
@@ -53,7 +59,7 @@ pub fn per_block_processing<T: EthSpec>(
     block: &BeaconBlock<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    per_block_processing_signature_optional(state, block, true, spec)
+    per_block_processing_signature_optional(state, block, true, VerifySignatures::True, spec)
 }
 
 /// Updates the state for a new block, whilst validating that the block is valid, without actually
@@ -68,11 +74,45 @@ pub fn per_block_processing_without_verifying_block_signature<T: EthSpec>(
     block: &BeaconBlock<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    per_block_processing_signature_optional(state, block, false, spec)
+    per_block_processing_signature_optional(state, block, false, VerifySignatures::True, spec)
+}
+
+/// Updates the state for a new block, whilst validating that the block is valid.
+///
+/// The signatures of all validators in the registry (i.e., all except those of deposits and
+/// transfers) are verified in a single batch, with the public keys read from `get_pubkey`
+/// instead of from `state.validators`. This allows the public keys to be provided by a cache
+/// shared between states.
+///
+/// Returns `Ok(())` if the block is valid and the state was successfully updated. Otherwise
+/// returns an error describing why the block was invalid or how the function failed to execute.
+///
+/// Spec v0.8.0
+pub fn per_block_processing_with_pubkeys<'a, T, F>(
+    state: &mut BeaconState<T>,
+    block: &BeaconBlock<T>,
+    get_pubkey: F,
+    spec: &ChainSpec,
+) -> Result<(), Error>
+where
+    T: EthSpec,
+    F: Fn(usize) -> Option<Cow<'a, PublicKey>>,
+{
+    // Ensure the current and previous epoch caches are built, they are required to obtain the
+    // attesting indices of attestations.
+    state.build_committee_cache(RelativeEpoch::Previous, spec)?;
+    state.build_committee_cache(RelativeEpoch::Current, spec)?;
+
+    BlockSignatureVerifier::verify_entire_block(state, |i| get_pubkey(i), block, spec)?;
+
+    per_block_processing_signature_optional(state, block, false, VerifySignatures::False, spec)
 }
 
 /// Updates the state for a new block, whilst validating that the block is valid, optionally
-/// checking the block proposer signature.
+/// checking the block proposer signature and the signatures of the block's operations.
+///
+/// Deposit and transfer signatures are always verified, since their public keys are not
+/// necessarily in the validator registry.
 ///
 /// Returns `Ok(())` if the block is valid and the state was successfully updated. Otherwise
 /// returns an error describing why the block was invalid or how the function failed to execute.
@@ -82,6 +122,7 @@ fn per_block_processing_signature_optional<T: EthSpec>(
     mut state: &mut BeaconState<T>,
     block: &BeaconBlock<T>,
     should_verify_block_signature: bool,
+    verify_signatures: VerifySignatures,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     process_block_header(state, block, spec, should_verify_block_signature)?;
@@ -90,13 +131,33 @@ fn per_block_processing_signature_optional<T: EthSpec>(
     state.build_committee_cache(RelativeEpoch::Previous, spec)?;
     state.build_committee_cache(RelativeEpoch::Current, spec)?;
 
-    process_randao(&mut state, &block, &spec)?;
+    process_randao(&mut state, &block, verify_signatures, &spec)?;
     process_eth1_data(&mut state, &block.body.eth1_data)?;
-    process_proposer_slashings(&mut state, &block.body.proposer_slashings, spec)?;
-    process_attester_slashings(&mut state, &block.body.attester_slashings, spec)?;
-    process_attestations(&mut state, &block.body.attestations, spec)?;
+    process_proposer_slashings(
+        &mut state,
+        &block.body.proposer_slashings,
+        verify_signatures,
+        spec,
+    )?;
+    process_attester_slashings(
+        &mut state,
+        &block.body.attester_slashings,
+        verify_signatures,
+        spec,
+    )?;
+    process_attestations(
+        &mut state,
+        &block.body.attestations,
+        verify_signatures,
+        spec,
+    )?;
     process_deposits(&mut state, &block.body.deposits, spec)?;
-    process_exits(&mut state, &block.body.voluntary_exits, spec)?;
+    process_exits(
+        &mut state,
+        &block.body.voluntary_exits,
+        verify_signatures,
+        spec,
+    )?;
     process_transfers(&mut state, &block.body.transfers, spec)?;
 
     Ok(())
@@ -164,31 +225,34 @@ pub fn verify_block_signature<T: EthSpec>(
     Ok(())
 }
 
-/// Verifies the `randao_reveal` against the block's proposer pubkey and updates
-/// `state.latest_randao_mixes`.
+/// Verifies the `randao_reveal` against the block's proposer pubkey (if `verify_signatures` is
+/// `True`) and updates `state.latest_randao_mixes`.
 ///
 /// Spec v0.8.0
 pub fn process_randao<T: EthSpec>(
     state: &mut BeaconState<T>,
     block: &BeaconBlock<T>,
+    verify_signatures: VerifySignatures,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    let block_proposer = &state.validators
-        [state.get_beacon_proposer_index(block.slot, RelativeEpoch::Current, spec)?];
-
-    // Verify RANDAO reveal.
-    verify!(
-        block.body.randao_reveal.verify(
-            &state.current_epoch().tree_hash_root()[..],
-            spec.get_domain(
-                block.slot.epoch(T::slots_per_epoch()),
-                Domain::Randao,
-                &state.fork
+    if verify_signatures == VerifySignatures::True {
+        let block_proposer = &state.validators
+            [state.get_beacon_proposer_index(block.slot, RelativeEpoch::Current, spec)?];
+
+        // Verify RANDAO reveal.
+        verify!(
+            block.body.randao_reveal.verify(
+                &state.current_epoch().tree_hash_root()[..],
+                spec.get_domain(
+                    block.slot.epoch(T::slots_per_epoch()),
+                    Domain::Randao,
+                    &state.fork
+                ),
+                &block_proposer.pubkey
             ),
-            &block_proposer.pubkey
-        ),
-        Invalid::BadRandaoSignature
-    );
+            Invalid::BadRandaoSignature
+        );
+    }
 
     // Update the current epoch RANDAO mix.
     state.update_randao_mix(state.current_epoch(), &block.body.randao_reveal)?;
@@ -227,6 +291,7 @@ pub fn process_eth1_data<T: EthSpec>(
 pub fn process_proposer_slashings<T: EthSpec>(
     state: &mut BeaconState<T>,
     proposer_slashings: &[ProposerSlashing],
+    verify_signatures: VerifySignatures,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     // Verify proposer slashings in parallel.
@@ -234,7 +299,7 @@ pub fn process_proposer_slashings<T: EthSpec>(
         .par_iter()
         .enumerate()
         .try_for_each(|(i, proposer_slashing)| {
-            verify_proposer_slashing(proposer_slashing, &state, spec)
+            verify_proposer_slashing(proposer_slashing, &state, verify_signatures, spec)
                 .map_err(|e| e.into_with_index(i))
         })?;
 
@@ -255,6 +320,7 @@ pub fn process_proposer_slashings<T: EthSpec>(
 pub fn process_attester_slashings<T: EthSpec>(
     state: &mut BeaconState<T>,
     attester_slashings: &[AttesterSlashing<T>],
+    verify_signatures: VerifySignatures,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     // Verify the `IndexedAttestation`s in parallel (these are the resource-consuming objects, not
@@ -270,8 +336,12 @@ pub fn process_attester_slashings<T: EthSpec>(
         .par_iter()
         .enumerate()
         .try_for_each(|(i, indexed_attestation)| {
-            is_valid_indexed_attestation(&state, indexed_attestation, spec)
-                .map_err(|e| e.into_with_index(i))
+            if verify_signatures == VerifySignatures::True {
+                is_valid_indexed_attestation(&state, indexed_attestation, spec)
+            } else {
+                is_valid_indexed_attestation_without_signature(&state, indexed_attestation, spec)
+            }
+            .map_err(|e| e.into_with_index(i))
         })?;
     let all_indexed_attestations_have_been_checked = true;
 
@@ -307,6 +377,7 @@ pub fn process_attester_slashings<T: EthSpec>(
 pub fn process_attestations<T: EthSpec>(
     state: &mut BeaconState<T>,
     attestations: &[Attestation<T>],
+    verify_signatures: VerifySignatures,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     // Ensure the previous epoch cache exists.
@@ -317,7 +388,7 @@ pub fn process_attestations<T: EthSpec>(
         .par_iter()
         .enumerate()
         .try_for_each(|(i, attestation)| {
-            verify_attestation_for_block_inclusion(state, attestation, spec, VerifySignatures::True)
+            verify_attestation_for_block_inclusion(state, attestation, spec, verify_signatures)
                 .map_err(|e| e.into_with_index(i))
         })?;
 
@@ -454,6 +525,7 @@ pub fn process_deposit<T: EthSpec>(
 pub fn process_exits<T: EthSpec>(
     state: &mut BeaconState<T>,
     voluntary_exits: &[VoluntaryExit],
+    verify_signatures: VerifySignatures,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     // Verify exits in parallel.
@@ -461,7 +533,7 @@ pub fn process_exits<T: EthSpec>(
         .par_iter()
         .enumerate()
         .try_for_each(|(i, exit)| {
-            verify_exit(&state, exit, spec).map_err(|e| e.into_with_index(i))
+            verify_exit(&state, exit, verify_signatures, spec).map_err(|e| e.into_with_index(i))
         })?;
 
     // Update the state in series.
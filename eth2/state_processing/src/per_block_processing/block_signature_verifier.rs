@@ -0,0 +1,212 @@
+use super::errors::{AttestationValidationError, BlockInvalid, BlockProcessingError};
+use super::signature_sets::{
+    block_proposal_signature_set, exit_signature_set, indexed_attestation_signature_set,
+    proposer_slashing_signature_set, randao_signature_set, Error as SignatureSetError,
+};
+use crate::common::get_indexed_attestation;
+use bls::{verify_signature_sets, SignatureSet};
+use std::borrow::Cow;
+use types::{BeaconBlock, BeaconState, BeaconStateError, ChainSpec, EthSpec, PublicKey};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// All public keys were found but signature verification failed. The block is invalid.
+    SignatureInvalid,
+    /// An attestation in the block was invalid. The block is invalid.
+    AttestationValidationError(AttestationValidationError),
+    /// There was an error attempting to read from a `BeaconState`. Block validity was not
+    /// determined.
+    BeaconStateError(BeaconStateError),
+    /// Failed to load a signature set. The block may be invalid or we failed to process it.
+    SignatureSetError(SignatureSetError),
+}
+
+impl From<BeaconStateError> for Error {
+    fn from(e: BeaconStateError) -> Error {
+        Error::BeaconStateError(e)
+    }
+}
+
+impl From<AttestationValidationError> for Error {
+    fn from(e: AttestationValidationError) -> Error {
+        match e {
+            AttestationValidationError::BeaconStateError(e) => Error::BeaconStateError(e),
+            e => Error::AttestationValidationError(e),
+        }
+    }
+}
+
+impl From<SignatureSetError> for Error {
+    fn from(e: SignatureSetError) -> Error {
+        match e {
+            SignatureSetError::BeaconStateError(e) => Error::BeaconStateError(e),
+            e => Error::SignatureSetError(e),
+        }
+    }
+}
+
+impl From<Error> for BlockProcessingError {
+    fn from(e: Error) -> BlockProcessingError {
+        match e {
+            Error::BeaconStateError(e) => BlockProcessingError::BeaconStateError(e),
+            e => BlockProcessingError::Invalid(BlockInvalid::BadSignatures(e)),
+        }
+    }
+}
+
+/// Reads the signatures from a `BeaconBlock` and verifies them in a single batch.
+///
+/// This allows for optimizations related to batch BLS operations (see the
+/// `bls::verify_signature_sets` function).
+///
+/// Only the signatures from validators in the registry are verified; deposit and transfer
+/// signatures are checked during block processing.
+pub struct BlockSignatureVerifier<'a, T: EthSpec, F> {
+    get_pubkey: F,
+    state: &'a BeaconState<T>,
+    spec: &'a ChainSpec,
+    sets: Vec<SignatureSet<'a>>,
+}
+
+impl<'a, T, F> BlockSignatureVerifier<'a, T, F>
+where
+    T: EthSpec,
+    F: Fn(usize) -> Option<Cow<'a, PublicKey>>,
+{
+    /// Create a new verifier without any included signatures. See the `include...` functions to
+    /// add signatures, and the `verify` function to verify them.
+    ///
+    /// The committee caches of `state` must be built for the previous and current epochs.
+    pub fn new(state: &'a BeaconState<T>, get_pubkey: F, spec: &'a ChainSpec) -> Self {
+        Self {
+            get_pubkey,
+            state,
+            spec,
+            sets: vec![],
+        }
+    }
+
+    /// Verify all* the signatures in the given `BeaconBlock`, returning `Ok(())` if the signatures
+    /// are valid.
+    ///
+    /// * : _Does not verify any signatures in `block.body.deposits` or `block.body.transfers`._
+    pub fn verify_entire_block(
+        state: &'a BeaconState<T>,
+        get_pubkey: F,
+        block: &'a BeaconBlock<T>,
+        spec: &'a ChainSpec,
+    ) -> Result<()> {
+        let mut verifier = Self::new(state, get_pubkey, spec);
+
+        verifier.include_all_signatures(block)?;
+
+        verifier.verify()
+    }
+
+    /// Includes all signatures on the block (except the deposit and transfer signatures) for
+    /// verification.
+    pub fn include_all_signatures(&mut self, block: &'a BeaconBlock<T>) -> Result<()> {
+        self.include_block_proposal(block)?;
+        self.include_randao_reveal(block)?;
+        self.include_proposer_slashings(block)?;
+        self.include_attester_slashings(block)?;
+        self.include_attestations(block)?;
+        self.include_exits(block)?;
+
+        Ok(())
+    }
+
+    /// Includes the signature of `block` for verification.
+    pub fn include_block_proposal(&mut self, block: &'a BeaconBlock<T>) -> Result<()> {
+        let set = block_proposal_signature_set(self.state, &self.get_pubkey, block, self.spec)?;
+        self.sets.push(set);
+        Ok(())
+    }
+
+    /// Includes the randao reveal of `block` for verification.
+    pub fn include_randao_reveal(&mut self, block: &'a BeaconBlock<T>) -> Result<()> {
+        let set = randao_signature_set(self.state, &self.get_pubkey, block, self.spec)?;
+        self.sets.push(set);
+        Ok(())
+    }
+
+    /// Includes all signatures in `block.body.proposer_slashings` for verification.
+    pub fn include_proposer_slashings(&mut self, block: &'a BeaconBlock<T>) -> Result<()> {
+        for proposer_slashing in block.body.proposer_slashings.iter() {
+            let (set_1, set_2) = proposer_slashing_signature_set(
+                self.state,
+                &self.get_pubkey,
+                proposer_slashing,
+                self.spec,
+            )?;
+            self.sets.push(set_1);
+            self.sets.push(set_2);
+        }
+
+        Ok(())
+    }
+
+    /// Includes all signatures in `block.body.attester_slashings` for verification.
+    pub fn include_attester_slashings(&mut self, block: &'a BeaconBlock<T>) -> Result<()> {
+        for attester_slashing in block.body.attester_slashings.iter() {
+            for indexed_attestation in [
+                &attester_slashing.attestation_1,
+                &attester_slashing.attestation_2,
+            ]
+            .iter()
+            .copied()
+            {
+                let set = indexed_attestation_signature_set(
+                    self.state,
+                    &self.get_pubkey,
+                    &indexed_attestation.signature,
+                    indexed_attestation,
+                    self.spec,
+                )?;
+                self.sets.push(set);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Includes all signatures in `block.body.attestations` for verification.
+    pub fn include_attestations(&mut self, block: &'a BeaconBlock<T>) -> Result<()> {
+        for attestation in block.body.attestations.iter() {
+            let indexed_attestation = get_indexed_attestation(self.state, attestation)?;
+
+            let set = indexed_attestation_signature_set(
+                self.state,
+                &self.get_pubkey,
+                &attestation.signature,
+                &indexed_attestation,
+                self.spec,
+            )?;
+            self.sets.push(set);
+        }
+
+        Ok(())
+    }
+
+    /// Includes all signatures in `block.body.voluntary_exits` for verification.
+    pub fn include_exits(&mut self, block: &'a BeaconBlock<T>) -> Result<()> {
+        for exit in block.body.voluntary_exits.iter() {
+            let set = exit_signature_set(self.state, &self.get_pubkey, exit, self.spec)?;
+            self.sets.push(set);
+        }
+
+        Ok(())
+    }
+
+    /// Verify all the signatures that have been included in `self`, returning `Ok(())` if the
+    /// signatures are all valid.
+    pub fn verify(self) -> Result<()> {
+        if verify_signature_sets(&self.sets) {
+            Ok(())
+        } else {
+            Err(Error::SignatureInvalid)
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use super::block_signature_verifier::Error as BlockSignatureVerifierError;
 use types::*;
 
 macro_rules! impl_from_beacon_state_error {
@@ -76,6 +77,8 @@ pub enum BlockInvalid {
     ProposerSlashed(usize),
     BadSignature,
     BadRandaoSignature,
+    /// The signatures of the block, verified as a single batch, were invalid.
+    BadSignatures(BlockSignatureVerifierError),
     MaxAttestationsExceeded,
     MaxAttesterSlashingsExceed,
     MaxProposerSlashingsExceeded,
@@ -0,0 +1,206 @@
+//! Provides functions for obtaining the `SignatureSet`s for the objects in a `BeaconBlock`, so
+//! that their signatures may be verified in a single batch.
+//!
+//! Public keys are obtained through a `get_pubkey` function, rather than from
+//! `state.validators`. This allows callers to provide public keys from a cache that is shared
+//! between states.
+use bls::{SignatureSet, SignedMessage};
+use std::borrow::Cow;
+use tree_hash::{SignedRoot, TreeHash};
+use types::{
+    AggregateSignature, AttestationDataAndCustodyBit, BeaconBlock, BeaconBlockHeader, BeaconState,
+    BeaconStateError, ChainSpec, Domain, EthSpec, IndexedAttestation, ProposerSlashing, PublicKey,
+    RelativeEpoch, VoluntaryExit,
+};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// Encountered a `BeaconStateError` whilst attempting to obtain a signature set.
+    BeaconStateError(BeaconStateError),
+    /// The public key of the validator with the given index could not be found.
+    ValidatorUnknown(u64),
+}
+
+impl From<BeaconStateError> for Error {
+    fn from(e: BeaconStateError) -> Error {
+        Error::BeaconStateError(e)
+    }
+}
+
+/// Returns a function which reads public keys from the validator registry of `state`.
+pub fn get_pubkey_from_state<'a, T: EthSpec>(
+    state: &'a BeaconState<T>,
+) -> impl Fn(usize) -> Option<Cow<'a, PublicKey>> + Clone {
+    move |validator_index| {
+        state
+            .validators
+            .get(validator_index)
+            .map(|validator| Cow::Borrowed(&validator.pubkey))
+    }
+}
+
+/// A signature set that is valid if a block was signed by the expected block producer.
+pub fn block_proposal_signature_set<'a, T, F>(
+    state: &BeaconState<T>,
+    get_pubkey: F,
+    block: &BeaconBlock<T>,
+    spec: &ChainSpec,
+) -> Result<SignatureSet<'a>>
+where
+    T: EthSpec,
+    F: Fn(usize) -> Option<Cow<'a, PublicKey>>,
+{
+    let proposer_index =
+        state.get_beacon_proposer_index(block.slot, RelativeEpoch::Current, spec)?;
+
+    let domain = spec.get_domain(
+        block.slot.epoch(T::slots_per_epoch()),
+        Domain::BeaconProposer,
+        &state.fork,
+    );
+
+    Ok(SignatureSet::single(
+        &block.signature,
+        get_pubkey_or_err(&get_pubkey, proposer_index)?,
+        block.signed_root(),
+        domain,
+    ))
+}
+
+/// A signature set that is valid if the block proposer's randao reveal signature is correct.
+pub fn randao_signature_set<'a, T, F>(
+    state: &BeaconState<T>,
+    get_pubkey: F,
+    block: &BeaconBlock<T>,
+    spec: &ChainSpec,
+) -> Result<SignatureSet<'a>>
+where
+    T: EthSpec,
+    F: Fn(usize) -> Option<Cow<'a, PublicKey>>,
+{
+    let proposer_index =
+        state.get_beacon_proposer_index(block.slot, RelativeEpoch::Current, spec)?;
+    let epoch = block.slot.epoch(T::slots_per_epoch());
+
+    let domain = spec.get_domain(epoch, Domain::Randao, &state.fork);
+
+    Ok(SignatureSet::single(
+        &block.body.randao_reveal,
+        get_pubkey_or_err(&get_pubkey, proposer_index)?,
+        epoch.tree_hash_root(),
+        domain,
+    ))
+}
+
+/// Returns two signature sets, one for each `BlockHeader` included in the `ProposerSlashing`.
+pub fn proposer_slashing_signature_set<'a, T, F>(
+    state: &BeaconState<T>,
+    get_pubkey: F,
+    proposer_slashing: &ProposerSlashing,
+    spec: &ChainSpec,
+) -> Result<(SignatureSet<'a>, SignatureSet<'a>)>
+where
+    T: EthSpec,
+    F: Fn(usize) -> Option<Cow<'a, PublicKey>>,
+{
+    let proposer_index = proposer_slashing.proposer_index as usize;
+
+    let header_signature_set = |header: &BeaconBlockHeader| -> Result<SignatureSet<'a>> {
+        let domain = spec.get_domain(
+            header.slot.epoch(T::slots_per_epoch()),
+            Domain::BeaconProposer,
+            &state.fork,
+        );
+
+        Ok(SignatureSet::single(
+            &header.signature,
+            get_pubkey_or_err(&get_pubkey, proposer_index)?,
+            header.signed_root(),
+            domain,
+        ))
+    };
+
+    Ok((
+        header_signature_set(&proposer_slashing.header_1)?,
+        header_signature_set(&proposer_slashing.header_2)?,
+    ))
+}
+
+/// Returns the signature set for the given `indexed_attestation`, which is signed by `signature`.
+pub fn indexed_attestation_signature_set<'a, T, F>(
+    state: &BeaconState<T>,
+    get_pubkey: F,
+    signature: &'a AggregateSignature,
+    indexed_attestation: &IndexedAttestation<T>,
+    spec: &ChainSpec,
+) -> Result<SignatureSet<'a>>
+where
+    T: EthSpec,
+    F: Fn(usize) -> Option<Cow<'a, PublicKey>>,
+{
+    let custody_bits = [
+        (&indexed_attestation.custody_bit_0_indices, false),
+        (&indexed_attestation.custody_bit_1_indices, true),
+    ];
+
+    let mut signed_messages = Vec::with_capacity(custody_bits.len());
+    for (indices, custody_bit) in custody_bits.iter() {
+        // A message without any signers contributes nothing to the aggregate signature, so it is
+        // omitted. This is always the case for custody bit `1` in phase 0.
+        if indices.is_empty() {
+            continue;
+        }
+
+        let signing_keys = indices
+            .iter()
+            .map(|&validator_index| get_pubkey_or_err(&get_pubkey, validator_index as usize))
+            .collect::<Result<_>>()?;
+
+        let message = AttestationDataAndCustodyBit {
+            data: indexed_attestation.data.clone(),
+            custody_bit: *custody_bit,
+        }
+        .tree_hash_root();
+
+        signed_messages.push(SignedMessage::new(signing_keys, message));
+    }
+
+    let domain = spec.get_domain(
+        indexed_attestation.data.target.epoch,
+        Domain::Attestation,
+        &state.fork,
+    );
+
+    Ok(SignatureSet::new(signature, signed_messages, domain))
+}
+
+/// Returns the signature set for the given `exit`.
+pub fn exit_signature_set<'a, T, F>(
+    state: &BeaconState<T>,
+    get_pubkey: F,
+    exit: &VoluntaryExit,
+    spec: &ChainSpec,
+) -> Result<SignatureSet<'a>>
+where
+    T: EthSpec,
+    F: Fn(usize) -> Option<Cow<'a, PublicKey>>,
+{
+    let domain = spec.get_domain(exit.epoch, Domain::VoluntaryExit, &state.fork);
+
+    Ok(SignatureSet::single(
+        &exit.signature,
+        get_pubkey_or_err(&get_pubkey, exit.validator_index as usize)?,
+        exit.signed_root(),
+        domain,
+    ))
+}
+
+/// Calls `get_pubkey`, returning an error if the public key is unknown.
+fn get_pubkey_or_err<'a, F>(get_pubkey: &F, validator_index: usize) -> Result<Cow<'a, PublicKey>>
+where
+    F: Fn(usize) -> Option<Cow<'a, PublicKey>>,
+{
+    get_pubkey(validator_index).ok_or_else(|| Error::ValidatorUnknown(validator_index as u64))
+}
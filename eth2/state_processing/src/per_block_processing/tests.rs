@@ -1,7 +1,10 @@
 #![cfg(all(test, not(feature = "fake_crypto")))]
 use super::block_processing_builder::BlockProcessingBuilder;
 use super::errors::*;
-use crate::per_block_processing;
+use super::signature_sets::{get_pubkey_from_state, Error as SignatureSetError};
+use super::BlockSignatureVerifierError;
+use crate::{per_block_processing, per_block_processing_with_pubkeys};
+use std::borrow::Cow;
 use tree_hash::SignedRoot;
 use types::*;
 
@@ -100,6 +103,70 @@ fn invalid_randao_reveal_signature() {
     );
 }
 
+#[test]
+fn valid_block_ok_with_pubkeys() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec);
+    let (block, mut state) = builder.build(None, None, &spec);
+
+    let pubkeys: Vec<PublicKey> = state.validators.iter().map(|v| v.pubkey.clone()).collect();
+    let get_pubkey = |i: usize| pubkeys.get(i).map(Cow::Borrowed);
+
+    let result = per_block_processing_with_pubkeys(&mut state, &block, get_pubkey, &spec);
+
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn invalid_block_signature_with_pubkeys() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec);
+    let (mut block, state) = builder.build(None, None, &spec);
+
+    // sign the block with a keypair that is not the expected proposer
+    let keypair = Keypair::random();
+    let message = block.signed_root();
+    let epoch = block.slot.epoch(MainnetEthSpec::slots_per_epoch());
+    let domain = spec.get_domain(epoch, Domain::BeaconProposer, &state.fork);
+    block.signature = Signature::new(&message, domain, &keypair.sk);
+
+    let result = per_block_processing_with_pubkeys(
+        &mut state.clone(),
+        &block,
+        get_pubkey_from_state(&state),
+        &spec,
+    );
+
+    assert_eq!(
+        result,
+        Err(BlockProcessingError::Invalid(BlockInvalid::BadSignatures(
+            BlockSignatureVerifierError::SignatureInvalid
+        )))
+    );
+}
+
+#[test]
+fn unknown_pubkey_with_pubkeys() {
+    let spec = MainnetEthSpec::default_spec();
+    let builder = get_builder(&spec);
+    let (block, mut state) = builder.build(None, None, &spec);
+
+    let proposer_index = state
+        .get_beacon_proposer_index(block.slot, RelativeEpoch::Current, &spec)
+        .unwrap();
+
+    let result = per_block_processing_with_pubkeys(&mut state, &block, |_| None, &spec);
+
+    assert_eq!(
+        result,
+        Err(BlockProcessingError::Invalid(BlockInvalid::BadSignatures(
+            BlockSignatureVerifierError::SignatureSetError(SignatureSetError::ValidatorUnknown(
+                proposer_index as u64
+            ))
+        )))
+    );
+}
+
 fn get_builder(spec: &ChainSpec) -> (BlockProcessingBuilder<MainnetEthSpec>) {
     let mut builder = BlockProcessingBuilder::new(VALIDATOR_COUNT, &spec);
 
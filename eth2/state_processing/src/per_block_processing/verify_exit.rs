@@ -1,4 +1,5 @@
 use super::errors::{ExitInvalid as Invalid, ExitValidationError as Error};
+use super::VerifySignatures;
 use tree_hash::SignedRoot;
 use types::*;
 
@@ -7,13 +8,16 @@ use types::*;
 ///
 /// Returns `Ok(())` if the `Exit` is valid, otherwise indicates the reason for invalidity.
 ///
+/// The signature of the `Exit` is only checked if `verify_signatures` is `True`.
+///
 /// Spec v0.8.0
 pub fn verify_exit<T: EthSpec>(
     state: &BeaconState<T>,
     exit: &VoluntaryExit,
+    verify_signatures: VerifySignatures,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    verify_exit_parametric(state, exit, spec, false)
+    verify_exit_parametric(state, exit, verify_signatures, spec, false)
 }
 
 /// Like `verify_exit` but doesn't run checks which may become true in future states.
@@ -22,9 +26,10 @@ pub fn verify_exit<T: EthSpec>(
 pub fn verify_exit_time_independent_only<T: EthSpec>(
     state: &BeaconState<T>,
     exit: &VoluntaryExit,
+    verify_signatures: VerifySignatures,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    verify_exit_parametric(state, exit, spec, true)
+    verify_exit_parametric(state, exit, verify_signatures, spec, true)
 }
 
 /// Parametric version of `verify_exit` that skips some checks if `time_independent_only` is true.
@@ -33,6 +38,7 @@ pub fn verify_exit_time_independent_only<T: EthSpec>(
 fn verify_exit_parametric<T: EthSpec>(
     state: &BeaconState<T>,
     exit: &VoluntaryExit,
+    verify_signatures: VerifySignatures,
     spec: &ChainSpec,
     time_independent_only: bool,
 ) -> Result<(), Error> {
@@ -72,13 +78,15 @@ fn verify_exit_parametric<T: EthSpec>(
     );
 
     // Verify signature.
-    let message = exit.signed_root();
-    let domain = spec.get_domain(exit.epoch, Domain::VoluntaryExit, &state.fork);
-    verify!(
-        exit.signature
-            .verify(&message[..], domain, &validator.pubkey),
-        Invalid::BadSignature
-    );
+    if verify_signatures == VerifySignatures::True {
+        let message = exit.signed_root();
+        let domain = spec.get_domain(exit.epoch, Domain::VoluntaryExit, &state.fork);
+        verify!(
+            exit.signature
+                .verify(&message[..], domain, &validator.pubkey),
+            Invalid::BadSignature
+        );
+    }
 
     Ok(())
 }
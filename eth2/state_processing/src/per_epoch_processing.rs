@@ -1,8 +1,9 @@
 use crate::common::get_compact_committees_root;
-use apply_rewards::process_rewards_and_penalties;
+use apply_rewards::process_rewards_and_penalties_with_report;
 use errors::EpochProcessingError as Error;
 use process_slashings::process_slashings;
 use registry_updates::process_registry_updates;
+use reward_report::{ValidatorRewardReport, ValidatorRewardReports};
 use std::collections::HashMap;
 use tree_hash::TreeHash;
 use types::*;
@@ -13,6 +14,7 @@ pub mod apply_rewards;
 pub mod errors;
 pub mod process_slashings;
 pub mod registry_updates;
+pub mod reward_report;
 pub mod tests;
 pub mod validator_statuses;
 pub mod winning_root;
@@ -22,6 +24,8 @@ pub mod winning_root;
 /// It is generated during crosslink processing and later used to reward/penalize validators.
 pub type WinningRootHashSet = HashMap<u64, WinningRoot>;
 
+pub use reward_report::EpochRewardReport;
+
 /// Performs per-epoch processing on some BeaconState.
 ///
 /// Mutates the given `BeaconState`, returning early if an error is encountered. If an error is
@@ -32,6 +36,29 @@ pub fn per_epoch_processing<T: EthSpec>(
     state: &mut BeaconState<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
+    per_epoch_processing_with_reward_report(state, &[], spec).map(|_| ())
+}
+
+/// Performs per-epoch processing on some BeaconState, returning a report of each change made to
+/// the balances of the validators in `validator_indices`.
+///
+/// Unknown validator indices are ignored.
+///
+/// Spec v0.8.0
+pub fn per_epoch_processing_with_reward_report<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    validator_indices: &[usize],
+    spec: &ChainSpec,
+) -> Result<EpochRewardReport, Error> {
+    let report_epoch = state.previous_epoch();
+    let mut reports = validator_indices
+        .iter()
+        .filter_map(|&index| {
+            let balance = *state.balances.get(index)?;
+            Some((index, ValidatorRewardReport::new(index, balance)))
+        })
+        .collect::<ValidatorRewardReports>();
+
     // Ensure the committee caches are built.
     state.build_committee_cache(RelativeEpoch::Previous, spec)?;
     state.build_committee_cache(RelativeEpoch::Current, spec)?;
@@ -50,10 +77,11 @@ pub fn per_epoch_processing<T: EthSpec>(
     let winning_root_for_shards = process_crosslinks(state, spec)?;
 
     // Rewards and Penalties.
-    process_rewards_and_penalties(
+    process_rewards_and_penalties_with_report(
         state,
         &mut validator_statuses,
         &winning_root_for_shards,
+        &mut reports,
         spec,
     )?;
 
@@ -61,15 +89,33 @@ pub fn per_epoch_processing<T: EthSpec>(
     process_registry_updates(state, spec)?;
 
     // Slashings.
+    //
+    // Registry updates do not modify balances, so any change to the balance of a reported
+    // validator from this point is a slashing penalty.
+    for (&index, report) in reports.iter_mut() {
+        report.slashing_penalty = state.balances[index];
+    }
+
     process_slashings(state, validator_statuses.total_balances.current_epoch, spec)?;
 
+    for (&index, report) in reports.iter_mut() {
+        report.slashing_penalty -= state.balances[index];
+    }
+
     // Final updates.
     process_final_updates(state, spec)?;
 
     // Rotate the epoch caches to suit the epoch transition.
     state.advance_caches();
 
-    Ok(())
+    for (&index, report) in reports.iter_mut() {
+        report.balance_after = state.balances[index];
+    }
+
+    Ok(EpochRewardReport {
+        epoch: report_epoch,
+        validators: reports.into_iter().map(|(_, report)| report).collect(),
+    })
 }
 
 /// Update the following fields on the `BeaconState`:
@@ -205,17 +251,31 @@ pub fn process_final_updates<T: EthSpec>(
     }
 
     // Update effective balances with hysteresis (lag).
-    for (index, validator) in state.validators.iter_mut().enumerate() {
-        let balance = state.balances[index];
-        let half_increment = spec.effective_balance_increment / 2;
-        if balance < validator.effective_balance
-            || validator.effective_balance + 3 * half_increment < balance
-        {
-            validator.effective_balance = std::cmp::min(
-                balance - balance % spec.effective_balance_increment,
-                spec.max_effective_balance,
-            );
-        }
+    //
+    // Only the validators which change are modified, so that the rest of the registry remains
+    // shared with prior states.
+    let half_increment = spec.effective_balance_increment / 2;
+    let effective_balance_updates: Vec<(usize, u64)> = state
+        .validators
+        .iter()
+        .zip(state.balances.iter())
+        .enumerate()
+        .filter_map(|(i, (validator, &balance))| {
+            if balance < validator.effective_balance
+                || validator.effective_balance + 3 * half_increment < balance
+            {
+                let effective_balance = std::cmp::min(
+                    balance - balance % spec.effective_balance_increment,
+                    spec.max_effective_balance,
+                );
+                Some((i, effective_balance))
+            } else {
+                None
+            }
+        })
+        .collect();
+    for (i, effective_balance) in effective_balance_updates {
+        state.validators[i].effective_balance = effective_balance;
     }
 
     // Update start shard.
@@ -1,13 +1,17 @@
+use super::reward_report::ValidatorRewardReports;
 use super::validator_statuses::{TotalBalances, ValidatorStatus, ValidatorStatuses};
 use super::{Error, WinningRootHashSet};
 use integer_sqrt::IntegerSquareRoot;
+use rayon::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
 use types::*;
 
 /// Use to track the changes to a validators balance.
-#[derive(Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct Delta {
-    rewards: u64,
-    penalties: u64,
+    pub rewards: u64,
+    pub penalties: u64,
 }
 
 impl Delta {
@@ -30,6 +34,29 @@ impl std::ops::AddAssign for Delta {
     }
 }
 
+/// The components of the delta for a single validator, sans proposer and crosslink rewards.
+#[derive(Default, Clone)]
+struct AttestationDelta {
+    source: Delta,
+    inclusion_delay: Delta,
+    target: Delta,
+    head: Delta,
+    inactivity: Delta,
+}
+
+impl AttestationDelta {
+    /// The sum of all the components of `self`.
+    fn total(&self) -> Delta {
+        let mut total = Delta::default();
+        total += self.source.clone();
+        total += self.inclusion_delay.clone();
+        total += self.target.clone();
+        total += self.head.clone();
+        total += self.inactivity.clone();
+        total
+    }
+}
+
 /// Apply attester and proposer rewards.
 ///
 /// Spec v0.8.0
@@ -38,6 +65,26 @@ pub fn process_rewards_and_penalties<T: EthSpec>(
     validator_statuses: &mut ValidatorStatuses,
     winning_root_for_shards: &WinningRootHashSet,
     spec: &ChainSpec,
+) -> Result<(), Error> {
+    process_rewards_and_penalties_with_report(
+        state,
+        validator_statuses,
+        winning_root_for_shards,
+        &mut ValidatorRewardReports::new(),
+        spec,
+    )
+}
+
+/// Apply attester and proposer rewards, recording each component of the rewards and penalties
+/// of the validators in `reports`.
+///
+/// Spec v0.8.0
+pub fn process_rewards_and_penalties_with_report<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    validator_statuses: &mut ValidatorStatuses,
+    winning_root_for_shards: &WinningRootHashSet,
+    reports: &mut ValidatorRewardReports,
+    spec: &ChainSpec,
 ) -> Result<(), Error> {
     if state.current_epoch() == T::genesis_epoch() {
         return Ok(());
@@ -52,22 +99,27 @@ pub fn process_rewards_and_penalties<T: EthSpec>(
 
     let mut deltas = vec![Delta::default(); state.balances.len()];
 
-    get_attestation_deltas(&mut deltas, state, &validator_statuses, spec)?;
-    get_crosslink_deltas(&mut deltas, state, &validator_statuses, spec)?;
+    get_attestation_deltas(&mut deltas, state, &validator_statuses, reports, spec)?;
+    get_crosslink_deltas(&mut deltas, state, &validator_statuses, reports, spec)?;
 
     get_proposer_deltas(
         &mut deltas,
         state,
         validator_statuses,
         winning_root_for_shards,
+        reports,
         spec,
     )?;
 
     // Apply the deltas, over-flowing but not under-flowing (saturating at 0 instead).
-    for (i, delta) in deltas.iter().enumerate() {
-        state.balances[i] += delta.rewards;
-        state.balances[i] = state.balances[i].saturating_sub(delta.penalties);
-    }
+    state
+        .balances
+        .iter_mut()
+        .zip(deltas.iter())
+        .for_each(|(balance, delta)| {
+            *balance += delta.rewards;
+            *balance = balance.saturating_sub(delta.penalties);
+        });
 
     Ok(())
 }
@@ -80,13 +132,20 @@ fn get_proposer_deltas<T: EthSpec>(
     state: &BeaconState<T>,
     validator_statuses: &mut ValidatorStatuses,
     winning_root_for_shards: &WinningRootHashSet,
+    reports: &mut ValidatorRewardReports,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     // Update statuses with the information from winning roots.
     validator_statuses.process_winning_roots(state, winning_root_for_shards, spec)?;
 
-    for (index, validator) in validator_statuses.statuses.iter().enumerate() {
-        if validator.is_previous_epoch_attester {
+    // Compute the reward for including each attestation in parallel, applying them in series
+    // since many attesters share the same proposer.
+    let proposer_rewards = validator_statuses
+        .statuses
+        .par_iter()
+        .enumerate()
+        .filter(|(_, validator)| validator.is_previous_epoch_attester)
+        .map(|(index, validator)| -> Result<_, Error> {
             let inclusion = validator
                 .inclusion_info
                 .expect("It is a logic error for an attester not to have an inclusion distance.");
@@ -98,11 +157,22 @@ fn get_proposer_deltas<T: EthSpec>(
                 spec,
             )?;
 
-            if inclusion.proposer_index >= deltas.len() {
-                return Err(Error::ValidatorStatusesInconsistent);
-            }
+            Ok((
+                inclusion.proposer_index,
+                base_reward / spec.proposer_reward_quotient,
+            ))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (proposer_index, proposer_reward) in proposer_rewards {
+        if proposer_index >= deltas.len() {
+            return Err(Error::ValidatorStatusesInconsistent);
+        }
+
+        deltas[proposer_index].reward(proposer_reward);
 
-            deltas[inclusion.proposer_index].reward(base_reward / spec.proposer_reward_quotient);
+        if let Some(report) = reports.get_mut(&proposer_index) {
+            report.proposer_reward += proposer_reward;
         }
     }
 
@@ -116,33 +186,55 @@ fn get_attestation_deltas<T: EthSpec>(
     deltas: &mut Vec<Delta>,
     state: &BeaconState<T>,
     validator_statuses: &ValidatorStatuses,
+    reports: &mut ValidatorRewardReports,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
     let finality_delay = (state.previous_epoch() - state.finalized_checkpoint.epoch).as_u64();
 
-    for (index, validator) in validator_statuses.statuses.iter().enumerate() {
-        let base_reward = get_base_reward(
-            state,
-            index,
-            validator_statuses.total_balances.current_epoch,
-            spec,
-        )?;
-
-        let delta = get_attestation_delta::<T>(
-            &validator,
-            &validator_statuses.total_balances,
-            base_reward,
-            finality_delay,
-            spec,
-        );
+    let attestation_deltas = validator_statuses
+        .statuses
+        .par_iter()
+        .enumerate()
+        .map(|(index, validator)| -> Result<_, Error> {
+            let base_reward = get_base_reward(
+                state,
+                index,
+                validator_statuses.total_balances.current_epoch,
+                spec,
+            )?;
 
-        deltas[index] += delta;
+            Ok(get_attestation_delta::<T>(
+                &validator,
+                &validator_statuses.total_balances,
+                base_reward,
+                finality_delay,
+                spec,
+            ))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (index, delta) in attestation_deltas.into_iter().enumerate() {
+        if let Some(report) = reports.get_mut(&index) {
+            let validator = &validator_statuses.statuses[index];
+
+            report.source = delta.source.clone();
+            report.target = delta.target.clone();
+            report.head = delta.head.clone();
+            report.inclusion_delay = validator
+                .inclusion_info
+                .filter(|_| validator.is_previous_epoch_attester)
+                .map(|inclusion| inclusion.distance);
+            report.inclusion_delay_reward = delta.inclusion_delay.rewards;
+            report.inactivity_penalty = delta.inactivity.penalties;
+        }
+
+        deltas[index] += delta.total();
     }
 
     Ok(())
 }
 
-/// Determine the delta for a single validator, sans proposer rewards.
+/// Determine the delta for a single validator, sans proposer and crosslink rewards.
 ///
 /// Spec v0.8.0
 fn get_attestation_delta<T: EthSpec>(
@@ -151,8 +243,8 @@ fn get_attestation_delta<T: EthSpec>(
     base_reward: u64,
     finality_delay: u64,
     spec: &ChainSpec,
-) -> Delta {
-    let mut delta = Delta::default();
+) -> AttestationDelta {
+    let mut delta = AttestationDelta::default();
 
     // Is this validator eligible to be rewarded or penalized?
     // Spec: validator index in `eligible_validator_indices`
@@ -172,49 +264,57 @@ fn get_attestation_delta<T: EthSpec>(
     // Spec:
     // - validator index in `get_unslashed_attesting_indices(state, matching_source_attestations)`
     if validator.is_previous_epoch_attester && !validator.is_slashed {
-        delta.reward(base_reward * total_attesting_balance / total_balance);
+        delta
+            .source
+            .reward(base_reward * total_attesting_balance / total_balance);
         // Inclusion speed bonus
         let proposer_reward = base_reward / spec.proposer_reward_quotient;
         let max_attester_reward = base_reward - proposer_reward;
         let inclusion = validator
             .inclusion_info
             .expect("It is a logic error for an attester not to have an inclusion distance.");
-        delta.reward(
+        delta.inclusion_delay.reward(
             max_attester_reward
                 * (T::SlotsPerEpoch::to_u64() + spec.min_attestation_inclusion_delay
                     - inclusion.distance)
                 / T::SlotsPerEpoch::to_u64(),
         );
     } else {
-        delta.penalize(base_reward);
+        delta.source.penalize(base_reward);
     }
 
     // Expected FFG target.
     // Spec:
     // - validator index in `get_unslashed_attesting_indices(state, matching_target_attestations)`
     if validator.is_previous_epoch_target_attester && !validator.is_slashed {
-        delta.reward(base_reward * matching_target_balance / total_balance);
+        delta
+            .target
+            .reward(base_reward * matching_target_balance / total_balance);
     } else {
-        delta.penalize(base_reward);
+        delta.target.penalize(base_reward);
     }
 
     // Expected head.
     // Spec:
     // - validator index in `get_unslashed_attesting_indices(state, matching_head_attestations)`
     if validator.is_previous_epoch_head_attester && !validator.is_slashed {
-        delta.reward(base_reward * matching_head_balance / total_balance);
+        delta
+            .head
+            .reward(base_reward * matching_head_balance / total_balance);
     } else {
-        delta.penalize(base_reward);
+        delta.head.penalize(base_reward);
     }
 
     // Inactivity penalty
     if finality_delay > spec.min_epochs_to_inactivity_penalty {
         // All eligible validators are penalized
-        delta.penalize(spec.base_rewards_per_epoch * base_reward);
+        delta
+            .inactivity
+            .penalize(spec.base_rewards_per_epoch * base_reward);
 
         // Additionally, all validators whose FFG target didn't match are penalized extra
         if !validator.is_previous_epoch_target_attester {
-            delta.penalize(
+            delta.inactivity.penalize(
                 validator.current_epoch_effective_balance * finality_delay
                     / spec.inactivity_penalty_quotient,
             );
@@ -236,25 +336,39 @@ fn get_crosslink_deltas<T: EthSpec>(
     deltas: &mut Vec<Delta>,
     state: &BeaconState<T>,
     validator_statuses: &ValidatorStatuses,
+    reports: &mut ValidatorRewardReports,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    for (index, validator) in validator_statuses.statuses.iter().enumerate() {
-        let mut delta = Delta::default();
-
-        let base_reward = get_base_reward(
-            state,
-            index,
-            validator_statuses.total_balances.current_epoch,
-            spec,
-        )?;
-
-        if let Some(ref winning_root) = validator.winning_root_info {
-            delta.reward(
-                base_reward * winning_root.total_attesting_balance
-                    / winning_root.total_committee_balance,
-            );
-        } else {
-            delta.penalize(base_reward);
+    let crosslink_deltas = validator_statuses
+        .statuses
+        .par_iter()
+        .enumerate()
+        .map(|(index, validator)| -> Result<_, Error> {
+            let mut delta = Delta::default();
+
+            let base_reward = get_base_reward(
+                state,
+                index,
+                validator_statuses.total_balances.current_epoch,
+                spec,
+            )?;
+
+            if let Some(ref winning_root) = validator.winning_root_info {
+                delta.reward(
+                    base_reward * winning_root.total_attesting_balance
+                        / winning_root.total_committee_balance,
+                );
+            } else {
+                delta.penalize(base_reward);
+            }
+
+            Ok(delta)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (index, delta) in crosslink_deltas.into_iter().enumerate() {
+        if let Some(report) = reports.get_mut(&index) {
+            report.crosslink = delta.clone();
         }
 
         deltas[index] += delta;
@@ -266,7 +380,7 @@ fn get_crosslink_deltas<T: EthSpec>(
 /// Returns the base reward for some validator.
 ///
 /// Spec v0.8.0
-fn get_base_reward<T: EthSpec>(
+pub fn get_base_reward<T: EthSpec>(
     state: &BeaconState<T>,
     index: usize,
     // Should be == get_total_active_balance(state, spec)
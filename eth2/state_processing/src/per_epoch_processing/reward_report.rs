@@ -0,0 +1,56 @@
+use super::apply_rewards::Delta;
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use std::collections::BTreeMap;
+use types::Epoch;
+
+/// In-progress reports for a set of validators, keyed by validator index.
+pub type ValidatorRewardReports = BTreeMap<usize, ValidatorRewardReport>;
+
+/// Describes each change made to a validator's balance during a single epoch transition.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct ValidatorRewardReport {
+    pub validator_index: u64,
+    /// The balance of the validator prior to the epoch transition.
+    pub balance_before: u64,
+    /// The balance of the validator after the epoch transition.
+    pub balance_after: u64,
+    /// Attesting to the correct FFG source.
+    pub source: Delta,
+    /// Attesting to the correct FFG target.
+    pub target: Delta,
+    /// Attesting to the correct head block.
+    pub head: Delta,
+    /// The distance between the slot of the validator's attestation and the slot it was first
+    /// included in a block, if it was included.
+    pub inclusion_delay: Option<u64>,
+    /// The reward for the speed at which the validator's attestation was included.
+    pub inclusion_delay_reward: u64,
+    /// Attesting to the winning crosslink for the validator's shard.
+    pub crosslink: Delta,
+    /// The reward for including the attestations of other validators in blocks.
+    pub proposer_reward: u64,
+    /// The penalty applied to all eligible validators when the chain is not finalizing.
+    pub inactivity_penalty: u64,
+    /// The penalty applied to a slashed validator half-way through its withdrawability delay.
+    pub slashing_penalty: u64,
+}
+
+impl ValidatorRewardReport {
+    pub fn new(validator_index: usize, balance_before: u64) -> Self {
+        Self {
+            validator_index: validator_index as u64,
+            balance_before,
+            ..Self::default()
+        }
+    }
+}
+
+/// The reports for a set of validators for a single epoch transition.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+pub struct EpochRewardReport {
+    /// The epoch in which the rewarded attestations were made (i.e., the previous epoch at the
+    /// time of the transition).
+    pub epoch: Epoch,
+    pub validators: Vec<ValidatorRewardReport>,
+}
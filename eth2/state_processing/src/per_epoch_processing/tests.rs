@@ -1,5 +1,8 @@
 #![cfg(test)]
-use crate::per_epoch_processing::per_epoch_processing;
+use crate::per_epoch_processing::{
+    per_epoch_processing, per_epoch_processing_with_reward_report,
+    reward_report::ValidatorRewardReport,
+};
 use env_logger::{Builder, Env};
 use types::test_utils::TestingBeaconStateBuilder;
 use types::*;
@@ -21,3 +24,96 @@ fn runs_without_error() {
 
     per_epoch_processing(&mut state, &spec).unwrap();
 }
+
+#[test]
+fn reward_report_matches_balance_changes() {
+    let spec = MinimalEthSpec::default_spec();
+
+    let mut builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+
+    let target_slot =
+        (MinimalEthSpec::genesis_epoch() + 4).end_slot(MinimalEthSpec::slots_per_epoch());
+    builder.teleport_to_slot(target_slot);
+
+    let (mut state, _keypairs) = builder.build();
+    let mut reported_state = state.clone();
+
+    per_epoch_processing(&mut state, &spec).unwrap();
+    let report =
+        per_epoch_processing_with_reward_report(&mut reported_state, &[0, 3, 100], &spec).unwrap();
+
+    // Producing a report must not change the outcome of the transition.
+    assert_eq!(state, reported_state);
+
+    assert_eq!(report.epoch, MinimalEthSpec::genesis_epoch() + 3);
+    assert_eq!(
+        report
+            .validators
+            .iter()
+            .map(|validator| validator.validator_index)
+            .collect::<Vec<_>>(),
+        vec![0, 3]
+    );
+
+    for validator in &report.validators {
+        assert_eq!(
+            validator.balance_after,
+            state.balances[validator.validator_index as usize]
+        );
+        assert_eq!(expected_balance_after(validator), validator.balance_after);
+    }
+}
+
+#[test]
+fn reward_report_saturates_penalties_exceeding_balance() {
+    let spec = MinimalEthSpec::default_spec();
+
+    let mut builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+        TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+
+    let target_slot =
+        (MinimalEthSpec::genesis_epoch() + 4).end_slot(MinimalEthSpec::slots_per_epoch());
+    builder.teleport_to_slot(target_slot);
+
+    let (mut state, _keypairs) = builder.build();
+
+    // The penalties are computed from the effective balance, so a validator with a tiny actual
+    // balance is penalized by more than it holds.
+    state.balances[1] = 1;
+
+    let report = per_epoch_processing_with_reward_report(&mut state, &[1], &spec).unwrap();
+    let validator = &report.validators[0];
+
+    assert!(total_penalties(validator) > validator.balance_before + total_rewards(validator));
+    assert_eq!(validator.balance_after, 0);
+    assert_eq!(state.balances[1], 0);
+    assert_eq!(expected_balance_after(validator), validator.balance_after);
+}
+
+fn total_rewards(validator: &ValidatorRewardReport) -> u64 {
+    validator.source.rewards
+        + validator.inclusion_delay_reward
+        + validator.target.rewards
+        + validator.head.rewards
+        + validator.crosslink.rewards
+        + validator.proposer_reward
+}
+
+/// The penalties applied alongside the rewards, excluding the slashing penalty.
+fn total_penalties(validator: &ValidatorRewardReport) -> u64 {
+    validator.source.penalties
+        + validator.target.penalties
+        + validator.head.penalties
+        + validator.crosslink.penalties
+        + validator.inactivity_penalty
+}
+
+/// Derives the balance after the transition from the report, in the same way the balances are
+/// updated during the transition: rewards are added before penalties are subtracted and each
+/// subtraction saturates at zero.
+fn expected_balance_after(validator: &ValidatorRewardReport) -> u64 {
+    (validator.balance_before + total_rewards(validator))
+        .saturating_sub(total_penalties(validator))
+        .saturating_sub(validator.slashing_penalty)
+}
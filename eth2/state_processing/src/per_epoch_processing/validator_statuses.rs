@@ -1,5 +1,6 @@
 use super::WinningRootHashSet;
 use crate::common::get_attesting_indices;
+use rayon::prelude::*;
 use types::*;
 
 /// Sets the boolean `var` on `self` to be true if it is true on `other`. Otherwise leaves `self`
@@ -146,6 +147,18 @@ pub struct TotalBalances {
     pub previous_epoch_head_attesters: u64,
 }
 
+impl std::ops::AddAssign for TotalBalances {
+    fn add_assign(&mut self, other: TotalBalances) {
+        self.current_epoch += other.current_epoch;
+        self.previous_epoch += other.previous_epoch;
+        self.current_epoch_attesters += other.current_epoch_attesters;
+        self.current_epoch_target_attesters += other.current_epoch_target_attesters;
+        self.previous_epoch_attesters += other.previous_epoch_attesters;
+        self.previous_epoch_target_attesters += other.previous_epoch_target_attesters;
+        self.previous_epoch_head_attesters += other.previous_epoch_head_attesters;
+    }
+}
+
 /// Summarised information about validator participation in the _previous and _current_ epochs of
 /// some `BeaconState`.
 #[derive(Clone)]
@@ -167,31 +180,50 @@ impl ValidatorStatuses {
         state: &BeaconState<T>,
         spec: &ChainSpec,
     ) -> Result<Self, BeaconStateError> {
-        let mut statuses = Vec::with_capacity(state.validators.len());
-        let mut total_balances = TotalBalances::default();
-
-        for (i, validator) in state.validators.iter().enumerate() {
-            let effective_balance = state.get_effective_balance(i, spec)?;
-            let mut status = ValidatorStatus {
-                is_slashed: validator.slashed,
-                is_withdrawable_in_current_epoch: validator
-                    .is_withdrawable_at(state.current_epoch()),
-                current_epoch_effective_balance: effective_balance,
-                ..ValidatorStatus::default()
-            };
-
-            if validator.is_active_at(state.current_epoch()) {
-                status.is_active_in_current_epoch = true;
-                total_balances.current_epoch += effective_balance;
-            }
-
-            if validator.is_active_at(state.previous_epoch()) {
-                status.is_active_in_previous_epoch = true;
-                total_balances.previous_epoch += effective_balance;
-            }
+        let current_epoch = state.current_epoch();
+        let previous_epoch = state.previous_epoch();
 
-            statuses.push(status);
-        }
+        // Each status is independent, so they are built in parallel.
+        let statuses = state
+            .validators
+            .iter()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .enumerate()
+            .map(|(i, validator)| -> Result<_, BeaconStateError> {
+                Ok(ValidatorStatus {
+                    is_slashed: validator.slashed,
+                    is_withdrawable_in_current_epoch: validator.is_withdrawable_at(current_epoch),
+                    is_active_in_current_epoch: validator.is_active_at(current_epoch),
+                    is_active_in_previous_epoch: validator.is_active_at(previous_epoch),
+                    current_epoch_effective_balance: state.get_effective_balance(i, spec)?,
+                    ..ValidatorStatus::default()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let total_balances = statuses
+            .par_iter()
+            .map(|status| {
+                let effective_balance = status.current_epoch_effective_balance;
+                TotalBalances {
+                    current_epoch: if status.is_active_in_current_epoch {
+                        effective_balance
+                    } else {
+                        0
+                    },
+                    previous_epoch: if status.is_active_in_previous_epoch {
+                        effective_balance
+                    } else {
+                        0
+                    },
+                    ..TotalBalances::default()
+                }
+            })
+            .reduce(TotalBalances::default, |mut a, b| {
+                a += b;
+                a
+            });
 
         Ok(Self {
             statuses,
@@ -208,79 +240,65 @@ impl ValidatorStatuses {
         state: &BeaconState<T>,
         spec: &ChainSpec,
     ) -> Result<(), BeaconStateError> {
-        for a in state
+        // Profile each attestation in parallel, generating the participants and a
+        // `ValidatorStatus` that applies to all of them.
+        let attestation_statuses = state
             .previous_epoch_attestations
-            .iter()
-            .chain(state.current_epoch_attestations.iter())
-        {
-            let attesting_indices = get_attesting_indices(state, &a.data, &a.aggregation_bits)?;
-
-            let mut status = ValidatorStatus::default();
-
-            // Profile this attestation, updating the total balances and generating an
-            // `ValidatorStatus` object that applies to all participants in the attestation.
-            if a.data.target.epoch == state.current_epoch() {
-                status.is_current_epoch_attester = true;
-
-                if target_matches_epoch_start_block(a, state, state.current_epoch())? {
-                    status.is_current_epoch_target_attester = true;
-                }
-            } else if a.data.target.epoch == state.previous_epoch() {
-                status.is_previous_epoch_attester = true;
-
-                // The inclusion slot and distance are only required for previous epoch attesters.
-                let attestation_slot = state.get_attestation_data_slot(&a.data)?;
-                let inclusion_slot = attestation_slot + a.inclusion_delay;
-                let relative_epoch =
-                    RelativeEpoch::from_slot(state.slot, inclusion_slot, T::slots_per_epoch())?;
-                status.inclusion_info = Some(InclusionInfo {
-                    slot: inclusion_slot,
-                    distance: a.inclusion_delay,
-                    proposer_index: state.get_beacon_proposer_index(
-                        inclusion_slot,
-                        relative_epoch,
-                        spec,
-                    )?,
-                });
-
-                if target_matches_epoch_start_block(a, state, state.previous_epoch())? {
-                    status.is_previous_epoch_target_attester = true;
-                }
-
-                if has_common_beacon_block_root(a, state)? {
-                    status.is_previous_epoch_head_attester = true;
-                }
-            }
-
-            // Loop through the participating validator indices and update the status vec.
+            .par_iter()
+            .chain(state.current_epoch_attestations.par_iter())
+            .map(|a| -> Result<_, BeaconStateError> {
+                let attesting_indices = get_attesting_indices(state, &a.data, &a.aggregation_bits)?;
+                let status = get_attestation_status(a, state, spec)?;
+                Ok((attesting_indices, status))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Update the statuses in the same order as the attestations appear in the state, since
+        // `ValidatorStatus::update` keeps the first of any equal inclusion slots.
+        for (attesting_indices, status) in attestation_statuses {
             for validator_index in attesting_indices {
                 self.statuses[validator_index].update(&status);
             }
         }
 
         // Compute the total balances
-        for (index, v) in self.statuses.iter().enumerate() {
-            // According to the spec, we only count unslashed validators towards the totals.
-            if !v.is_slashed {
-                let validator_balance = state.get_effective_balance(index, spec)?;
+        let total_balances = self
+            .statuses
+            .par_iter()
+            .enumerate()
+            .try_fold(
+                TotalBalances::default,
+                |mut totals, (index, v)| -> Result<_, BeaconStateError> {
+                    // According to the spec, we only count unslashed validators towards the totals.
+                    if !v.is_slashed {
+                        let validator_balance = state.get_effective_balance(index, spec)?;
+
+                        if v.is_current_epoch_attester {
+                            totals.current_epoch_attesters += validator_balance;
+                        }
+                        if v.is_current_epoch_target_attester {
+                            totals.current_epoch_target_attesters += validator_balance;
+                        }
+                        if v.is_previous_epoch_attester {
+                            totals.previous_epoch_attesters += validator_balance;
+                        }
+                        if v.is_previous_epoch_target_attester {
+                            totals.previous_epoch_target_attesters += validator_balance;
+                        }
+                        if v.is_previous_epoch_head_attester {
+                            totals.previous_epoch_head_attesters += validator_balance;
+                        }
+                    }
 
-                if v.is_current_epoch_attester {
-                    self.total_balances.current_epoch_attesters += validator_balance;
-                }
-                if v.is_current_epoch_target_attester {
-                    self.total_balances.current_epoch_target_attesters += validator_balance;
-                }
-                if v.is_previous_epoch_attester {
-                    self.total_balances.previous_epoch_attesters += validator_balance;
-                }
-                if v.is_previous_epoch_target_attester {
-                    self.total_balances.previous_epoch_target_attesters += validator_balance;
-                }
-                if v.is_previous_epoch_head_attester {
-                    self.total_balances.previous_epoch_head_attesters += validator_balance;
-                }
-            }
-        }
+                    Ok(totals)
+                },
+            )
+            .try_reduce(TotalBalances::default, |mut a, b| {
+                a += b;
+                Ok(a)
+            })?;
+
+        self.total_balances += total_balances;
 
         Ok(())
     }
@@ -320,6 +338,52 @@ impl ValidatorStatuses {
     }
 }
 
+/// Returns the `ValidatorStatus` that applies to every validator that participated in `a`.
+///
+/// Spec v0.8.1
+fn get_attestation_status<T: EthSpec>(
+    a: &PendingAttestation<T>,
+    state: &BeaconState<T>,
+    spec: &ChainSpec,
+) -> Result<ValidatorStatus, BeaconStateError> {
+    let mut status = ValidatorStatus::default();
+
+    if a.data.target.epoch == state.current_epoch() {
+        status.is_current_epoch_attester = true;
+
+        if target_matches_epoch_start_block(a, state, state.current_epoch())? {
+            status.is_current_epoch_target_attester = true;
+        }
+    } else if a.data.target.epoch == state.previous_epoch() {
+        status.is_previous_epoch_attester = true;
+
+        // The inclusion slot and distance are only required for previous epoch attesters.
+        let attestation_slot = state.get_attestation_data_slot(&a.data)?;
+        let inclusion_slot = attestation_slot + a.inclusion_delay;
+        let relative_epoch =
+            RelativeEpoch::from_slot(state.slot, inclusion_slot, T::slots_per_epoch())?;
+        status.inclusion_info = Some(InclusionInfo {
+            slot: inclusion_slot,
+            distance: a.inclusion_delay,
+            proposer_index: state.get_beacon_proposer_index(
+                inclusion_slot,
+                relative_epoch,
+                spec,
+            )?,
+        });
+
+        if target_matches_epoch_start_block(a, state, state.previous_epoch())? {
+            status.is_previous_epoch_target_attester = true;
+        }
+
+        if has_common_beacon_block_root(a, state)? {
+            status.is_previous_epoch_head_attester = true;
+        }
+    }
+
+    Ok(status)
+}
+
 /// Returns `true` if the attestation's FFG target is equal to the hash of the `state`'s first
 /// beacon block in the given `epoch`.
 ///
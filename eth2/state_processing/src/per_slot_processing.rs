@@ -9,25 +9,172 @@ pub enum Error {
 
 /// Advances a state forward by one slot, performing per-epoch processing if required.
 ///
+/// Upgrades the state if `spec.fork_schedule` contains a fork at the start of the new epoch.
+///
 /// Spec v0.8.0
 pub fn per_slot_processing<T: EthSpec>(
     state: &mut BeaconState<T>,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    cache_state(state)?;
+    per_slot_processing_with_reward_report(state, &[], spec).map(|_| ())
+}
 
-    if state.slot > spec.genesis_slot && (state.slot + 1) % T::slots_per_epoch() == 0 {
-        per_epoch_processing(state, spec)?;
-    }
+/// Advances a state forward by one slot, performing per-epoch processing if required.
+///
+/// If per-epoch processing was performed, returns a report of the changes made to the balances
+/// of the validators in `validator_indices`.
+///
+/// Spec v0.8.0
+pub fn per_slot_processing_with_reward_report<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    validator_indices: &[usize],
+    spec: &ChainSpec,
+) -> Result<Option<EpochRewardReport>, Error> {
+    let state_root = state.update_tree_hash_cache()?;
 
-    state.slot += 1;
+    process_slot(state, state_root, validator_indices, spec)
+}
+
+/// Advances `state` forward until it reaches `target_slot`, assuming that there are no blocks in
+/// any of the slots in between.
+///
+/// If `state_root` is supplied it must be the tree hash root of `state`.
+///
+/// This produces the same result as repeated calls to `per_slot_processing`, but avoids a full
+/// tree hash of the state at each slot. See `SkipSlotProcessor`.
+pub fn fast_forward_state<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    target_slot: Slot,
+    state_root: Option<Hash256>,
+    spec: &ChainSpec,
+) -> Result<(), Error> {
+    let mut processor = SkipSlotProcessor::new(state_root);
+
+    while state.slot < target_slot {
+        processor.per_slot_processing(state, spec)?;
+    }
 
     Ok(())
 }
 
-fn cache_state<T: EthSpec>(state: &mut BeaconState<T>) -> Result<(), Error> {
-    let previous_state_root = state.update_tree_hash_cache()?;
+/// Performs per-slot processing across consecutive slots without blocks.
+///
+/// A slot without a block only modifies a few small fields of the state, unless it includes an
+/// epoch transition. Instead of hashing the entire state at each slot, the roots of the
+/// unmodified fields are re-used and the whole state is only re-hashed after per-epoch
+/// processing.
+///
+/// ## Note
+///
+/// The same state must be given to each call of `self` and it must not be modified in between
+/// (e.g., by applying a block to it).
+#[derive(Debug, Default, Clone)]
+pub struct SkipSlotProcessor {
+    /// The known root of the state, to be used instead of hashing it.
+    state_root: Option<Hash256>,
+    /// The roots of each field of the state, if they are still valid.
+    roots: Option<SkipSlotRoots>,
+}
+
+impl SkipSlotProcessor {
+    /// Instantiate a new processor, optionally with the known root of the state that will be
+    /// given to the first call of `self` (e.g., the `state_root` of the block which produced
+    /// it).
+    pub fn new(state_root: Option<Hash256>) -> Self {
+        Self {
+            state_root,
+            roots: None,
+        }
+    }
+
+    /// Returns the tree hash root of `state`, only re-hashing the fields that may have changed
+    /// since the previous call.
+    pub fn state_root<T: EthSpec>(&mut self, state: &BeaconState<T>) -> Hash256 {
+        if let Some(state_root) = self.state_root {
+            state_root
+        } else if let Some(roots) = self.roots.as_mut() {
+            roots.update(state)
+        } else {
+            let roots = SkipSlotRoots::new(state);
+            let state_root = roots.root();
+            self.roots = Some(roots);
+            state_root
+        }
+    }
+
+    /// Advances a state forward by one slot, performing per-epoch processing if required.
+    ///
+    /// Equivalent to `per_slot_processing`.
+    pub fn per_slot_processing<T: EthSpec>(
+        &mut self,
+        state: &mut BeaconState<T>,
+        spec: &ChainSpec,
+    ) -> Result<(), Error> {
+        self.per_slot_processing_with_reward_report(state, &[], spec)
+            .map(|_| ())
+    }
+
+    /// Advances a state forward by one slot, performing per-epoch processing if required.
+    ///
+    /// Equivalent to `per_slot_processing_with_reward_report`.
+    pub fn per_slot_processing_with_reward_report<T: EthSpec>(
+        &mut self,
+        state: &mut BeaconState<T>,
+        validator_indices: &[usize],
+        spec: &ChainSpec,
+    ) -> Result<Option<EpochRewardReport>, Error> {
+        let state_root = self.state_root(state);
+
+        // The known root no longer applies once the state is modified.
+        self.state_root = None;
+
+        let report = process_slot(state, state_root, validator_indices, spec)?;
+
+        // Per-epoch processing, and any fork upgrade that follows it, may modify any field of the
+        // state.
+        if report.is_some() {
+            self.roots = None;
+        }
+
+        Ok(report)
+    }
+}
 
+/// Advances a state forward by one slot, given the tree hash root of the state prior to the
+/// slot.
+fn process_slot<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    state_root: Hash256,
+    validator_indices: &[usize],
+    spec: &ChainSpec,
+) -> Result<Option<EpochRewardReport>, Error> {
+    cache_state(state, state_root)?;
+
+    let report = if state.slot > spec.genesis_slot && (state.slot + 1) % T::slots_per_epoch() == 0 {
+        Some(per_epoch_processing_with_reward_report(
+            state,
+            validator_indices,
+            spec,
+        )?)
+    } else {
+        None
+    };
+
+    state.slot += 1;
+
+    if state.slot % T::slots_per_epoch() == 0 {
+        if let Some(scheduled_fork) = spec.scheduled_fork(state.current_epoch()) {
+            upgrade_state(state, scheduled_fork, spec)?;
+        }
+    }
+
+    Ok(report)
+}
+
+fn cache_state<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    previous_state_root: Hash256,
+) -> Result<(), Error> {
     // Note: increment the state slot here to allow use of our `state_root` and `block_root`
     // getter/setter functions.
     //
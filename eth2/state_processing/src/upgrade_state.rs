@@ -0,0 +1,98 @@
+use types::*;
+
+/// Upgrades `state` to the `scheduled_fork`, which must begin at the current epoch of `state`.
+///
+/// Sets `state.fork` so that signatures from the new epoch use the new fork version, then applies
+/// any changes to the state which are specific to the new version.
+pub fn upgrade_state<T: EthSpec>(
+    state: &mut BeaconState<T>,
+    scheduled_fork: &ScheduledFork,
+    spec: &ChainSpec,
+) -> Result<(), BeaconStateError> {
+    if scheduled_fork.epoch != state.current_epoch() {
+        return Err(BeaconStateError::EpochOutOfBounds);
+    }
+
+    state.fork = Fork {
+        previous_version: state.fork.current_version,
+        current_version: scheduled_fork.version,
+        epoch: scheduled_fork.epoch,
+    };
+
+    apply_version_transformations(state, scheduled_fork.version, spec)
+}
+
+/// Applies the changes to `state` which are required when upgrading to the fork `version`,
+/// beyond updating `state.fork`.
+///
+/// A fork which modifies the contents of the state (e.g., resetting a field or migrating the
+/// validator registry) should add its transformation here, matching on its `version`. No
+/// presently scheduled version requires a transformation.
+fn apply_version_transformations<T: EthSpec>(
+    _state: &mut BeaconState<T>,
+    _version: [u8; 4],
+    _spec: &ChainSpec,
+) -> Result<(), BeaconStateError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::per_slot_processing;
+    use types::test_utils::TestingBeaconStateBuilder;
+
+    #[test]
+    fn per_slot_processing_upgrades_at_scheduled_epoch() {
+        let mut spec = MinimalEthSpec::default_spec();
+        let fork_epoch = MinimalEthSpec::genesis_epoch() + 2;
+        spec.fork_schedule = vec![ScheduledFork {
+            epoch: fork_epoch,
+            version: [1, 0, 0, 0],
+        }];
+
+        let builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+        let (mut state, _keypairs) = builder.build();
+        let genesis_fork = state.fork.clone();
+
+        while state.current_epoch() < fork_epoch {
+            assert_eq!(state.fork, genesis_fork);
+            per_slot_processing(&mut state, &spec).unwrap();
+        }
+
+        assert_eq!(
+            state.slot,
+            fork_epoch.start_slot(MinimalEthSpec::slots_per_epoch())
+        );
+        assert_eq!(
+            state.fork,
+            spec.fork_at_epoch(fork_epoch, genesis_fork.epoch)
+        );
+        assert_eq!(state.fork.previous_version, genesis_fork.current_version);
+        assert_eq!(state.fork.current_version, [1, 0, 0, 0]);
+
+        // Messages from before the fork are still signed with the previous version.
+        assert_ne!(
+            spec.get_domain(fork_epoch - 1, Domain::Randao, &state.fork),
+            spec.get_domain(fork_epoch, Domain::Randao, &state.fork)
+        );
+    }
+
+    #[test]
+    fn rejects_fork_at_other_epoch() {
+        let spec = MinimalEthSpec::default_spec();
+        let builder: TestingBeaconStateBuilder<MinimalEthSpec> =
+            TestingBeaconStateBuilder::from_deterministic_keypairs(8, &spec);
+        let (mut state, _keypairs) = builder.build();
+        let fork = ScheduledFork {
+            epoch: state.current_epoch() + 1,
+            version: [1, 0, 0, 0],
+        };
+
+        assert_eq!(
+            upgrade_state(&mut state, &fork, &spec),
+            Err(BeaconStateError::EpochOutOfBounds)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use self::committee_cache::get_active_validator_indices;
 use self::exit_cache::ExitCache;
 use crate::test_utils::TestRandom;
+use crate::utils::{quoted_u64, quoted_u64_fixed_vec, quoted_u64_persistent_list};
 use crate::*;
 use compare_fields_derive::CompareFields;
 use eth2_hashing::hash;
@@ -8,13 +9,15 @@ use int_to_bytes::{int_to_bytes32, int_to_bytes8};
 use pubkey_cache::PubkeyCache;
 use serde_derive::{Deserialize, Serialize};
 use ssz::ssz_encode;
-use ssz_derive::{Decode, Encode};
-use ssz_types::{typenum::Unsigned, BitVector, FixedVector};
+use ssz_derive::{Decode, Encode, SszView};
+use ssz_types::{typenum::Unsigned, BitVector, FixedVector, PersistentList, PersistentVector};
 use test_random_derive::TestRandom;
 use tree_hash::TreeHash;
-use tree_hash_derive::TreeHash;
+use tree_hash_derive::{TreeHash, TreeHashFields, TreeHashProof};
 
 pub use self::committee_cache::CommitteeCache;
+pub use self::skip_slot_roots::SkipSlotRoots;
+pub use self::tree_hash_cache::BeaconTreeHashCache;
 pub use beacon_state_types::*;
 
 #[macro_use]
@@ -22,7 +25,9 @@ mod beacon_state_types;
 mod committee_cache;
 mod exit_cache;
 mod pubkey_cache;
+mod skip_slot_roots;
 mod tests;
+mod tree_hash_cache;
 
 pub const CACHED_EPOCHS: usize = 3;
 const MAX_RANDOM_BYTE: u64 = (1 << 8) - 1;
@@ -57,6 +62,7 @@ pub enum Error {
     CurrentCommitteeCacheUninitialized,
     RelativeEpochError(RelativeEpochError),
     CommitteeCacheUninitialized(RelativeEpoch),
+    TreeHashCacheUninitialized,
     SszTypesError(ssz_types::Error),
 }
 
@@ -72,7 +78,10 @@ pub enum Error {
     TestRandom,
     Encode,
     Decode,
+    SszView,
     TreeHash,
+    TreeHashProof,
+    TreeHashFields,
     CompareFields,
 )]
 #[serde(bound = "T: EthSpec")]
@@ -81,6 +90,7 @@ where
     T: EthSpec,
 {
     // Versioning
+    #[serde(with = "quoted_u64")]
     pub genesis_time: u64,
     pub slot: Slot,
     pub fork: Fork,
@@ -88,31 +98,35 @@ where
     // History
     pub latest_block_header: BeaconBlockHeader,
     #[compare_fields(as_slice)]
-    pub block_roots: FixedVector<Hash256, T::SlotsPerHistoricalRoot>,
+    pub block_roots: PersistentVector<Hash256, T::SlotsPerHistoricalRoot>,
     #[compare_fields(as_slice)]
-    pub state_roots: FixedVector<Hash256, T::SlotsPerHistoricalRoot>,
+    pub state_roots: PersistentVector<Hash256, T::SlotsPerHistoricalRoot>,
     pub historical_roots: VariableList<Hash256, T::HistoricalRootsLimit>,
 
     // Ethereum 1.0 chain data
     pub eth1_data: Eth1Data,
     pub eth1_data_votes: VariableList<Eth1Data, T::SlotsPerEth1VotingPeriod>,
+    #[serde(with = "quoted_u64")]
     pub eth1_deposit_index: u64,
 
     // Registry
     #[compare_fields(as_slice)]
-    pub validators: VariableList<Validator, T::ValidatorRegistryLimit>,
+    pub validators: PersistentList<Validator, T::ValidatorRegistryLimit>,
     #[compare_fields(as_slice)]
-    pub balances: VariableList<u64, T::ValidatorRegistryLimit>,
+    #[serde(with = "quoted_u64_persistent_list")]
+    pub balances: PersistentList<u64, T::ValidatorRegistryLimit>,
 
     // Shuffling
+    #[serde(with = "quoted_u64")]
     pub start_shard: u64,
-    pub randao_mixes: FixedVector<Hash256, T::EpochsPerHistoricalVector>,
+    pub randao_mixes: PersistentVector<Hash256, T::EpochsPerHistoricalVector>,
     #[compare_fields(as_slice)]
-    active_index_roots: FixedVector<Hash256, T::EpochsPerHistoricalVector>,
+    active_index_roots: PersistentVector<Hash256, T::EpochsPerHistoricalVector>,
     #[compare_fields(as_slice)]
-    compact_committees_roots: FixedVector<Hash256, T::EpochsPerHistoricalVector>,
+    compact_committees_roots: PersistentVector<Hash256, T::EpochsPerHistoricalVector>,
 
     // Slashings
+    #[serde(with = "quoted_u64_fixed_vec")]
     slashings: FixedVector<u64, T::EpochsPerSlashingsVector>,
 
     // Attestations
@@ -149,6 +163,12 @@ where
     #[tree_hash(skip_hashing)]
     #[test_random(default)]
     pub exit_cache: ExitCache,
+    #[serde(skip_serializing, skip_deserializing)]
+    #[ssz(skip_serializing)]
+    #[ssz(skip_deserializing)]
+    #[tree_hash(skip_hashing)]
+    #[test_random(default)]
+    pub tree_hash_cache: BeaconTreeHashCache,
 }
 
 impl<T: EthSpec> BeaconState<T> {
@@ -166,8 +186,8 @@ impl<T: EthSpec> BeaconState<T> {
 
             // History
             latest_block_header: BeaconBlock::<T>::empty(spec).temporary_block_header(),
-            block_roots: FixedVector::from_elem(Hash256::zero()),
-            state_roots: FixedVector::from_elem(Hash256::zero()),
+            block_roots: PersistentVector::from_elem(Hash256::zero()),
+            state_roots: PersistentVector::from_elem(Hash256::zero()),
             historical_roots: VariableList::empty(),
 
             // Eth1
@@ -176,14 +196,14 @@ impl<T: EthSpec> BeaconState<T> {
             eth1_deposit_index: 0,
 
             // Validator registry
-            validators: VariableList::empty(), // Set later.
-            balances: VariableList::empty(),   // Set later.
+            validators: PersistentList::empty(), // Set later.
+            balances: PersistentList::empty(),   // Set later.
 
             // Shuffling
             start_shard: 0,
-            randao_mixes: FixedVector::from_elem(Hash256::zero()),
-            active_index_roots: FixedVector::from_elem(Hash256::zero()),
-            compact_committees_roots: FixedVector::from_elem(Hash256::zero()),
+            randao_mixes: PersistentVector::from_elem(Hash256::zero()),
+            active_index_roots: PersistentVector::from_elem(Hash256::zero()),
+            compact_committees_roots: PersistentVector::from_elem(Hash256::zero()),
 
             // Slashings
             slashings: FixedVector::from_elem(0),
@@ -210,6 +230,7 @@ impl<T: EthSpec> BeaconState<T> {
             ],
             pubkey_cache: PubkeyCache::default(),
             exit_cache: ExitCache::default(),
+            tree_hash_cache: BeaconTreeHashCache::default(),
         }
     }
 
@@ -222,8 +243,8 @@ impl<T: EthSpec> BeaconState<T> {
 
     pub fn historical_batch(&self) -> HistoricalBatch<T> {
         HistoricalBatch {
-            block_roots: self.block_roots.clone(),
-            state_roots: self.state_roots.clone(),
+            block_roots: self.block_roots.clone().into(),
+            state_roots: self.state_roots.clone().into(),
         }
     }
 
@@ -547,7 +568,7 @@ impl<T: EthSpec> BeaconState<T> {
     ///
     /// Spec v0.8.0
     pub fn fill_active_index_roots_with(&mut self, index_root: Hash256) {
-        self.active_index_roots = FixedVector::from_elem(index_root);
+        self.active_index_roots = PersistentVector::from_elem(index_root);
     }
 
     /// Safely obtains the index for `compact_committees_roots`, given some `epoch`.
@@ -600,7 +621,7 @@ impl<T: EthSpec> BeaconState<T> {
     ///
     /// Spec v0.8.0
     pub fn fill_compact_committees_roots_with(&mut self, committee_root: Hash256) {
-        self.compact_committees_roots = FixedVector::from_elem(committee_root);
+        self.compact_committees_roots = PersistentVector::from_elem(committee_root);
     }
 
     /// Safely obtains the index for latest state roots, given some `slot`.
@@ -919,37 +940,29 @@ impl<T: EthSpec> BeaconState<T> {
     ///
     /// Returns the `tree_hash_root` resulting from the update. This root can be considered the
     /// canonical root of `self`.
-    ///
-    /// ## Note
-    ///
-    /// Cache not currently implemented, just performs a full tree hash.
     pub fn update_tree_hash_cache(&mut self) -> Result<Hash256, Error> {
-        // TODO(#440): re-enable cached tree hash
-        Ok(Hash256::from_slice(&self.tree_hash_root()))
+        let mut cache =
+            std::mem::replace(&mut self.tree_hash_cache, BeaconTreeHashCache::default());
+        let root = cache.recalculate_tree_hash_root(self);
+        self.tree_hash_cache = cache;
+
+        Ok(root)
     }
 
     /// Returns the tree hash root determined by the last execution of `self.update_tree_hash_cache(..)`.
     ///
     /// Note: does _not_ update the cache and may return an outdated root.
     ///
-    /// Returns an error if the cache is not initialized or if an error is encountered during the
-    /// cache update.
-    ///
-    /// ## Note
-    ///
-    /// Cache not currently implemented, just performs a full tree hash.
+    /// Returns an error if the cache is not initialized.
     pub fn cached_tree_hash_root(&self) -> Result<Hash256, Error> {
-        // TODO(#440): re-enable cached tree hash
-        Ok(Hash256::from_slice(&self.tree_hash_root()))
+        self.tree_hash_cache
+            .root()
+            .ok_or(Error::TreeHashCacheUninitialized)
     }
 
     /// Completely drops the tree hash cache, replacing it with a new, empty cache.
-    ///
-    /// ## Note
-    ///
-    /// Cache not currently implemented, is a no-op.
     pub fn drop_tree_hash_cache(&mut self) {
-        // TODO(#440): re-enable cached tree hash
+        self.tree_hash_cache = BeaconTreeHashCache::default()
     }
 }
 
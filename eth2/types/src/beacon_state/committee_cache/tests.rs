@@ -77,7 +77,7 @@ fn shuffles_for_the_right_epoch() {
         .map(|i| Hash256::from_low_u64_be(i as u64))
         .collect();
 
-    state.randao_mixes = FixedVector::from(distinct_hashes);
+    state.randao_mixes = PersistentVector::from(distinct_hashes);
 
     let previous_seed = state.get_seed(state.previous_epoch(), spec).unwrap();
     let current_seed = state.get_seed(state.current_epoch(), spec).unwrap();
@@ -0,0 +1,62 @@
+use super::BeaconState;
+use crate::{EthSpec, Hash256};
+use tree_hash::{TreeHash, TreeHashFields, HASHSIZE};
+
+/// The tree hash roots of each field of a `BeaconState`.
+///
+/// During a slot without a block or an epoch transition, only the `slot`, `latest_block_header`,
+/// `block_roots` and `state_roots` fields of a `BeaconState` are modified. By keeping the roots
+/// of the other fields, the root of the state after such a slot can be found without re-hashing
+/// the (large) fields that did not change.
+///
+/// ## Note
+///
+/// It is the responsibility of the caller to discard `self` if any other field of the state is
+/// modified (e.g., by per-block or per-epoch processing).
+#[derive(Debug, PartialEq, Clone)]
+pub struct SkipSlotRoots {
+    /// The concatenated roots of each field, in the order they are hashed.
+    field_roots: Vec<u8>,
+}
+
+impl SkipSlotRoots {
+    /// Hashes every field of `state`.
+    pub fn new<T: EthSpec>(state: &BeaconState<T>) -> Self {
+        Self {
+            field_roots: state.tree_hash_field_roots().concat(),
+        }
+    }
+
+    /// Returns the tree hash root of the state last given to `Self::new` or `Self::update`.
+    pub fn root(&self) -> Hash256 {
+        Hash256::from_slice(&tree_hash::merkle_root(&self.field_roots, 0))
+    }
+
+    /// Re-hashes only the fields of `state` which change during a slot without a block, returning
+    /// the tree hash root of `state`.
+    pub fn update<T: EthSpec>(&mut self, state: &BeaconState<T>) -> Hash256 {
+        self.set_field_root::<T>("slot", &state.slot.tree_hash_root());
+        self.set_field_root::<T>(
+            "latest_block_header",
+            &state.latest_block_header.tree_hash_root(),
+        );
+        self.set_field_root::<T>("block_roots", &state.block_roots.tree_hash_root());
+        self.set_field_root::<T>("state_roots", &state.state_roots.tree_hash_root());
+
+        self.root()
+    }
+
+    /// Sets the root of the `BeaconState` field called `field`.
+    ///
+    /// ## Panics
+    ///
+    /// If `BeaconState` has no hashed field called `field`.
+    fn set_field_root<T: EthSpec>(&mut self, field: &str, root: &[u8]) {
+        let index = <BeaconState<T> as TreeHashFields>::tree_hash_field_names()
+            .iter()
+            .position(|name| *name == field)
+            .expect("BeaconState has the skip slot fields");
+
+        self.field_roots[index * HASHSIZE..(index + 1) * HASHSIZE].copy_from_slice(root);
+    }
+}
@@ -196,6 +196,178 @@ fn tree_hash_cache() {
 
     let root = state.update_tree_hash_cache().unwrap();
     assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
+    assert_eq!(state.cached_tree_hash_root(), Ok(root));
+
+    // Modify, append and remove elements of the cached fields.
+    state.validators[0].effective_balance += 1;
+    state
+        .validators
+        .push(Validator::random_for_test(&mut rng))
+        .unwrap();
+    state.balances[0] += 1;
+    state.balances.push(42).unwrap();
+    state.randao_mixes[3] = Hash256::from_low_u64_be(42);
+    state.historical_roots = VariableList::from(vec![Hash256::from_low_u64_be(7)]);
+
+    let root = state.update_tree_hash_cache().unwrap();
+    assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
+
+    state.validators.truncate(1);
+    state.balances = PersistentList::from(vec![]);
+
+    let root = state.update_tree_hash_cache().unwrap();
+    assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
+
+    state.drop_tree_hash_cache();
+    assert_eq!(
+        state.cached_tree_hash_root(),
+        Err(Error::TreeHashCacheUninitialized)
+    );
+}
+
+#[test]
+fn tree_hash_cache_clone() {
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use tree_hash::TreeHash;
+
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+
+    let mut state: FoundationBeaconState = BeaconState::random_for_test(&mut rng);
+    let root = state.update_tree_hash_cache().unwrap();
+
+    let mut clone = state.clone();
+    assert_eq!(clone.cached_tree_hash_root(), Ok(root));
+
+    clone.validators[0].effective_balance += 1;
+    clone.balances[0] += 1;
+
+    let clone_root = clone.update_tree_hash_cache().unwrap();
+    assert_eq!(clone_root.as_bytes(), &clone.tree_hash_root()[..]);
+    assert_ne!(clone_root, root);
+
+    // Updating the clone must not modify the cache of the original.
+    assert_eq!(state.cached_tree_hash_root(), Ok(root));
+    assert_eq!(state.update_tree_hash_cache(), Ok(root));
+}
+
+#[test]
+fn skip_slot_roots() {
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use tree_hash::TreeHash;
+
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+
+    let mut state: BeaconState<MinimalEthSpec> = BeaconState::random_for_test(&mut rng);
+
+    let mut roots = SkipSlotRoots::new(&state);
+    assert_eq!(roots.root().as_bytes(), &state.tree_hash_root()[..]);
+
+    // Modify each of the fields that change during a skipped slot.
+    state.slot += 1;
+    state.latest_block_header.state_root = Hash256::from_low_u64_be(1);
+    state.block_roots[3] = Hash256::from_low_u64_be(2);
+    state.state_roots[5] = Hash256::from_low_u64_be(3);
+
+    let root = roots.update(&state);
+    assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
+    assert_eq!(roots.root(), root);
+
+    // Each field must be updated at its own position in the state.
+    let modifications: Vec<fn(&mut BeaconState<MinimalEthSpec>)> = vec![
+        |state| state.slot += 1,
+        |state| state.latest_block_header.slot += 1,
+        |state| state.block_roots[0] = Hash256::from_low_u64_be(4),
+        |state| state.state_roots[0] = Hash256::from_low_u64_be(5),
+    ];
+    for modify in modifications {
+        modify(&mut state);
+        assert_eq!(roots.update(&state).as_bytes(), &state.tree_hash_root()[..]);
+    }
+}
+
+#[test]
+fn merkle_proofs() {
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use tree_hash::{PathElement, TreeHash, TreeHashProof};
+
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+
+    let mut state: BeaconState<MinimalEthSpec> = BeaconState::random_for_test(&mut rng);
+    state.validators = PersistentList::from(vec![Validator::random_for_test(&mut rng); 2]);
+    state.balances = PersistentList::from(vec![7, 8]);
+    let root = Hash256::from_slice(&state.tree_hash_root());
+
+    let finalized_root = [
+        PathElement::Field("finalized_checkpoint"),
+        PathElement::Field("root"),
+    ];
+    assert_eq!(
+        BeaconState::<MinimalEthSpec>::generalized_index(&finalized_root),
+        Ok(113)
+    );
+
+    let proof = state.merkle_proof(&[&finalized_root]).unwrap();
+    assert_eq!(proof.leaves, vec![state.finalized_checkpoint.root]);
+    assert_eq!(proof.proof.len(), 6);
+    assert!(proof.verify(root));
+
+    // A balance is proven by the chunk which contains it.
+    let balance = [PathElement::Field("balances"), PathElement::Index(1)];
+    let validator = [
+        PathElement::Field("validators"),
+        PathElement::Index(1),
+        PathElement::Field("effective_balance"),
+    ];
+    let proof = state.merkle_proof(&[&balance, &validator]).unwrap();
+    assert_eq!(
+        &proof.leaves[0].as_bytes()[8..16],
+        &state.balances[1].to_le_bytes()[..]
+    );
+    assert_eq!(
+        proof.leaves[1].as_bytes(),
+        &state.validators[1].effective_balance.tree_hash_root()[..]
+    );
+    assert!(proof.verify(root));
+
+    let mut invalid = proof.clone();
+    invalid.leaves[1] = Hash256::from_low_u64_be(42);
+    assert!(!invalid.verify(root));
+
+    assert!(state
+        .merkle_proof(&[&[PathElement::Field("tree_hash_cache")]])
+        .is_err());
+}
+
+#[test]
+fn ssz_view() {
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use ssz::{Encode, SszListView};
+
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+
+    let mut state: BeaconState<MinimalEthSpec> = BeaconState::random_for_test(&mut rng);
+    state.validators = PersistentList::from(vec![Validator::random_for_test(&mut rng); 3]);
+    state.balances = PersistentList::from(vec![7, 8, 9]);
+    let bytes = state.as_ssz_bytes();
+
+    let view = BeaconStateView::<MinimalEthSpec>::new(&bytes).unwrap();
+    assert_eq!(view.slot(), Ok(state.slot));
+    assert_eq!(
+        view.finalized_checkpoint(),
+        Ok(state.finalized_checkpoint.clone())
+    );
+
+    let balances: SszListView<u64> = SszListView::new(view.balances_bytes()).unwrap();
+    assert_eq!(balances.len(), 3);
+    assert_eq!(balances.get(2), Some(Ok(9)));
+
+    let validators: SszListView<Validator> = SszListView::new(view.validators_bytes()).unwrap();
+    assert!(validators
+        .iter()
+        .zip(state.validators.iter())
+        .all(|(a, b)| a.as_ref() == Ok(b)));
+
+    assert!(BeaconStateView::<MinimalEthSpec>::new(&bytes[..bytes.len() / 2]).is_err());
 }
 
 /// Tests committee-specific components
@@ -309,7 +481,7 @@ mod committees {
             .into_iter()
             .map(|i| Hash256::from_low_u64_be(i as u64))
             .collect();
-        state.randao_mixes = FixedVector::from(distinct_hashes);
+        state.randao_mixes = PersistentVector::from(distinct_hashes);
 
         state
             .build_committee_cache(RelativeEpoch::Previous, spec)
@@ -0,0 +1,227 @@
+use super::BeaconState;
+use crate::{EthSpec, Hash256, PersistentList, Validator};
+use rayon::prelude::*;
+use ssz_types::typenum::Unsigned;
+use std::sync::Arc;
+use tree_hash::{mix_in_length, TreeHash, TreeHashCache, TreeHashFields, BYTES_PER_CHUNK};
+
+/// The number of `u64` values packed into each leaf of a list or vector of `u64`.
+const U64S_PER_CHUNK: usize = BYTES_PER_CHUNK / 8;
+
+/// Caches the internal nodes of the Merkle trees of the large fields of a `BeaconState`, so
+/// that its tree hash root can be re-calculated by only hashing the leaves which have changed.
+///
+/// The small fields of the state are always re-hashed in full.
+///
+/// The trees are shared between clones of the cache and only copied when a clone is updated, so
+/// cloning a state does not copy its cache.
+///
+/// ## Note
+///
+/// The validator cache assumes the `pubkey` of each validator never changes. Therefore, a cache
+/// must only ever be used with the state it was built from or its descendants.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct BeaconTreeHashCache {
+    /// The root determined by the last call to `Self::recalculate_tree_hash_root`. `None` if
+    /// the cache has not been built.
+    root: Option<Hash256>,
+    trees: Option<Arc<FieldTrees>>,
+}
+
+impl BeaconTreeHashCache {
+    /// Returns `true` if the cache has been built.
+    pub fn is_initialized(&self) -> bool {
+        self.root.is_some()
+    }
+
+    /// Returns the root determined by the last call to `Self::recalculate_tree_hash_root`, if
+    /// any.
+    pub fn root(&self) -> Option<Hash256> {
+        self.root
+    }
+
+    /// Updates the cache to reflect `state` and returns its tree hash root, building the cache
+    /// first if it has not been built.
+    ///
+    /// If the trees are shared with a clone of `self`, they are copied first.
+    pub fn recalculate_tree_hash_root<T: EthSpec>(&mut self, state: &BeaconState<T>) -> Hash256 {
+        let trees = Arc::make_mut(
+            self.trees
+                .get_or_insert_with(|| Arc::new(FieldTrees::new::<T>())),
+        );
+
+        let field_roots = state.tree_hash_field_roots_with(|field| trees.field_root(field, state));
+
+        let root = Hash256::from_slice(&tree_hash::merkle_root(&field_roots.concat(), 0));
+        self.root = Some(root);
+
+        root
+    }
+}
+
+/// The cached trees of the fields of a `BeaconState` which are not re-hashed in full.
+#[derive(Debug, PartialEq, Clone)]
+struct FieldTrees {
+    block_roots: TreeHashCache,
+    state_roots: TreeHashCache,
+    historical_roots: TreeHashCache,
+    validators: ValidatorsTreeHashCache,
+    balances: TreeHashCache,
+    randao_mixes: TreeHashCache,
+    active_index_roots: TreeHashCache,
+    compact_committees_roots: TreeHashCache,
+    slashings: TreeHashCache,
+}
+
+impl FieldTrees {
+    /// Instantiate empty trees, sized for the fields of `BeaconState<T>`.
+    fn new<T: EthSpec>() -> Self {
+        let u64_chunks = |n: usize| (n + U64S_PER_CHUNK - 1) / U64S_PER_CHUNK;
+
+        Self {
+            block_roots: TreeHashCache::new(T::SlotsPerHistoricalRoot::to_usize()),
+            state_roots: TreeHashCache::new(T::SlotsPerHistoricalRoot::to_usize()),
+            historical_roots: TreeHashCache::new(T::HistoricalRootsLimit::to_usize()),
+            validators: ValidatorsTreeHashCache::new(T::ValidatorRegistryLimit::to_usize()),
+            balances: TreeHashCache::new(u64_chunks(T::ValidatorRegistryLimit::to_usize())),
+            randao_mixes: TreeHashCache::new(T::EpochsPerHistoricalVector::to_usize()),
+            active_index_roots: TreeHashCache::new(T::EpochsPerHistoricalVector::to_usize()),
+            compact_committees_roots: TreeHashCache::new(T::EpochsPerHistoricalVector::to_usize()),
+            slashings: TreeHashCache::new(u64_chunks(T::EpochsPerSlashingsVector::to_usize())),
+        }
+    }
+
+    /// Updates the tree of the field of `state` named `field` and returns its root, or returns
+    /// `None` if the field is not cached.
+    fn field_root<T: EthSpec>(&mut self, field: &str, state: &BeaconState<T>) -> Option<Vec<u8>> {
+        let root = match field {
+            "block_roots" => self
+                .block_roots
+                .recalculate_merkle_root(state.block_roots.iter()),
+            "state_roots" => self
+                .state_roots
+                .recalculate_merkle_root(state.state_roots.iter()),
+            "historical_roots" => mix_in_length(
+                &self
+                    .historical_roots
+                    .recalculate_merkle_root(state.historical_roots.iter()),
+                state.historical_roots.len(),
+            ),
+            "validators" => self
+                .validators
+                .recalculate_tree_hash_root(&state.validators),
+            "balances" => mix_in_length(
+                &self
+                    .balances
+                    .recalculate_merkle_root(pack_u64s(state.balances.slices())),
+                state.balances.len(),
+            ),
+            "randao_mixes" => self
+                .randao_mixes
+                .recalculate_merkle_root(state.randao_mixes.iter()),
+            "active_index_roots" => self
+                .active_index_roots
+                .recalculate_merkle_root(state.active_index_roots.iter()),
+            "compact_committees_roots" => self
+                .compact_committees_roots
+                .recalculate_merkle_root(state.compact_committees_roots.iter()),
+            "slashings" => self
+                .slashings
+                .recalculate_merkle_root(pack_u64s(Some(&state.slashings[..]))),
+            _ => return None,
+        };
+
+        Some(root)
+    }
+}
+
+/// Caches the tree of the validator registry, along with the validators that produced each
+/// leaf so that only the roots of modified validators are re-computed.
+#[derive(Debug, PartialEq, Clone, Default)]
+struct ValidatorsTreeHashCache {
+    validators: Vec<Validator>,
+    tree: TreeHashCache,
+}
+
+impl ValidatorsTreeHashCache {
+    fn new(limit: usize) -> Self {
+        Self {
+            validators: vec![],
+            tree: TreeHashCache::new(limit),
+        }
+    }
+
+    /// Returns the tree hash root of `validators`.
+    fn recalculate_tree_hash_root<N: Unsigned>(
+        &mut self,
+        validators: &PersistentList<Validator, N>,
+    ) -> Vec<u8> {
+        let cached_validators = &self.validators;
+        let tree = &self.tree;
+
+        let leaves: Vec<Hash256> = validators
+            .iter()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .enumerate()
+            .map(
+                |(i, validator)| match (cached_validators.get(i), tree.leaf(i)) {
+                    (Some(cached), Some(leaf)) if mutable_fields_eq(cached, validator) => {
+                        Hash256::from_slice(leaf)
+                    }
+                    _ => Hash256::from_slice(&validator.tree_hash_root()),
+                },
+            )
+            .collect();
+
+        self.validators.truncate(validators.len());
+        for (i, validator) in validators.iter().enumerate() {
+            match self.validators.get_mut(i) {
+                Some(cached) => {
+                    if !mutable_fields_eq(cached, validator) {
+                        *cached = validator.clone()
+                    }
+                }
+                None => self.validators.push(validator.clone()),
+            }
+        }
+
+        mix_in_length(
+            &self.tree.recalculate_merkle_root(leaves.iter()),
+            validators.len(),
+        )
+    }
+}
+
+/// Returns `true` if all fields of `a` and `b` are equal, except the `pubkey`.
+///
+/// The `pubkey` of a validator never changes and comparing public keys is expensive.
+fn mutable_fields_eq(a: &Validator, b: &Validator) -> bool {
+    a.withdrawal_credentials == b.withdrawal_credentials
+        && a.effective_balance == b.effective_balance
+        && a.slashed == b.slashed
+        && a.activation_eligibility_epoch == b.activation_eligibility_epoch
+        && a.activation_epoch == b.activation_epoch
+        && a.exit_epoch == b.exit_epoch
+        && a.withdrawable_epoch == b.withdrawable_epoch
+}
+
+/// Packs the values of `slices` into leaves, as per `TreeHashType::Basic`.
+///
+/// The length of each slice except the last must be a multiple of `U64S_PER_CHUNK`.
+fn pack_u64s<'a, I>(slices: I) -> impl Iterator<Item = [u8; BYTES_PER_CHUNK]> + 'a
+where
+    I: IntoIterator<Item = &'a [u64]>,
+    I::IntoIter: 'a,
+{
+    slices
+        .into_iter()
+        .flat_map(|values| values.chunks(U64S_PER_CHUNK))
+        .map(|chunk| {
+            let mut leaf = [0; BYTES_PER_CHUNK];
+            for (i, value) in chunk.iter().enumerate() {
+                leaf[i * 8..(i + 1) * 8].copy_from_slice(&value.to_le_bytes());
+            }
+            leaf
+        })
+}
@@ -1,7 +1,11 @@
 use crate::*;
 use int_to_bytes::int_to_bytes4;
 use serde_derive::{Deserialize, Serialize};
-use utils::{u8_from_hex_str, u8_to_hex_str};
+use ssz_types::typenum::Unsigned;
+use utils::{
+    fork_from_hex_str, fork_to_hex_str, u32_from_hex_str, u32_to_hex_str, u8_from_hex_str,
+    u8_to_hex_str,
+};
 
 /// Each of the BLS signature domains.
 ///
@@ -93,6 +97,27 @@ pub struct ChainSpec {
 
     pub boot_nodes: Vec<String>,
     pub network_id: u8,
+
+    /*
+     * Fork schedule
+     *
+     * Must be the last field, since TOML requires tables (i.e., `ScheduledFork`) to follow all
+     * other values.
+     */
+    pub fork_schedule: Vec<ScheduledFork>,
+}
+
+/// An upgrade of the `BeaconState` to a new fork version at the start of some epoch.
+///
+/// Allows for rehearsing hard forks on testnets.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledFork {
+    pub epoch: Epoch,
+    #[serde(
+        serialize_with = "fork_to_hex_str",
+        deserialize_with = "fork_from_hex_str"
+    )]
+    pub version: [u8; 4],
 }
 
 impl ChainSpec {
@@ -118,6 +143,47 @@ impl ChainSpec {
         u64::from_le_bytes(fork_and_domain)
     }
 
+    /// Returns the fork which is scheduled to begin at the start of `epoch`, if any.
+    pub fn scheduled_fork(&self, epoch: Epoch) -> Option<&ScheduledFork> {
+        self.fork_schedule.iter().find(|fork| fork.epoch == epoch)
+    }
+
+    /// Returns the `Fork` that a state will have at `epoch`, if it was created at `genesis_epoch`
+    /// and upgraded according to `self.fork_schedule`.
+    ///
+    /// Allows for determining the signature domain of a message before the state has been
+    /// upgraded (e.g., for a message at the first epoch of a fork).
+    pub fn fork_at_epoch(&self, epoch: Epoch, genesis_epoch: Epoch) -> Fork {
+        let mut scheduled = self
+            .fork_schedule
+            .iter()
+            .filter(|fork| fork.epoch > genesis_epoch && fork.epoch <= epoch)
+            .collect::<Vec<_>>();
+        scheduled.sort_by_key(|fork| fork.epoch);
+
+        scheduled
+            .into_iter()
+            .fold(Fork::genesis(genesis_epoch), |fork, next| Fork {
+                previous_version: fork.current_version,
+                current_version: next.version,
+                epoch: next.epoch,
+            })
+    }
+
+    /// Returns the fork version that will be in use at `epoch`, according to
+    /// `self.fork_schedule`.
+    pub fn fork_version_at_epoch(&self, epoch: Epoch) -> [u8; 4] {
+        self.fork_at_epoch(epoch, Epoch::new(0)).current_version
+    }
+
+    /// Returns every fork version that a chain may use: the genesis version followed by each
+    /// scheduled version.
+    pub fn fork_versions(&self) -> Vec<[u8; 4]> {
+        std::iter::once(Fork::genesis(Epoch::new(0)).current_version)
+            .chain(self.fork_schedule.iter().map(|fork| fork.version))
+            .collect()
+    }
+
     /// Returns a `ChainSpec` compatible with the Ethereum Foundation specification.
     ///
     /// Spec v0.8.1
@@ -191,6 +257,11 @@ impl ChainSpec {
              */
             boot_nodes: vec![],
             network_id: 1, // mainnet network id
+
+            /*
+             * Fork schedule
+             */
+            fork_schedule: vec![],
         }
     }
 
@@ -237,6 +308,346 @@ impl Default for ChainSpec {
     }
 }
 
+/// The union of the `ChainSpec` and `EthSpec` constants, in the format of the standard
+/// spec-constants YAML files, as defined here:
+///
+/// https://github.com/ethereum/eth2.0-specs/blob/v0.8.1/configs/constant_presets/mainnet.yaml
+///
+/// Allows a `ChainSpec` to be loaded at runtime. The `EthSpec` constants are fixed at compile-time
+/// and are only used to check that the file is compatible with some `EthSpec`.
+///
+/// Spec v0.8.1
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct YamlConfig {
+    /*
+     * Misc
+     */
+    shard_count: u64,
+    target_committee_size: usize,
+    max_validators_per_committee: u64,
+    min_per_epoch_churn_limit: u64,
+    churn_limit_quotient: u64,
+    shuffle_round_count: u8,
+    min_genesis_active_validator_count: u64,
+    min_genesis_time: u64,
+
+    /*
+     *  Gwei values
+     */
+    min_deposit_amount: u64,
+    max_effective_balance: u64,
+    ejection_balance: u64,
+    effective_balance_increment: u64,
+
+    /*
+     * Initial Values
+     */
+    genesis_slot: u64,
+    genesis_epoch: u64,
+    #[serde(deserialize_with = "u8_from_hex_str", serialize_with = "u8_to_hex_str")]
+    bls_withdrawal_prefix: u8,
+
+    /*
+     * Time parameters
+     */
+    seconds_per_slot: u64,
+    min_attestation_inclusion_delay: u64,
+    slots_per_epoch: u64,
+    min_seed_lookahead: u64,
+    activation_exit_delay: u64,
+    slots_per_eth1_voting_period: u64,
+    slots_per_historical_root: u64,
+    min_validator_withdrawability_delay: u64,
+    persistent_committee_period: u64,
+    max_epochs_per_crosslink: u64,
+    min_epochs_to_inactivity_penalty: u64,
+
+    /*
+     * State vector lengths
+     */
+    epochs_per_historical_vector: u64,
+    epochs_per_slashings_vector: u64,
+    historical_roots_limit: u64,
+    validator_registry_limit: u64,
+
+    /*
+     * Reward and penalty quotients
+     */
+    base_reward_factor: u64,
+    whistleblower_reward_quotient: u64,
+    proposer_reward_quotient: u64,
+    inactivity_penalty_quotient: u64,
+    min_slashing_penalty_quotient: u64,
+
+    /*
+     * Max operations per block
+     */
+    max_proposer_slashings: u64,
+    max_attester_slashings: u64,
+    max_attestations: u64,
+    max_deposits: u64,
+    max_voluntary_exits: u64,
+    max_transfers: u64,
+
+    /*
+     * Signature domains
+     */
+    #[serde(
+        deserialize_with = "u32_from_hex_str",
+        serialize_with = "u32_to_hex_str"
+    )]
+    domain_beacon_proposer: u32,
+    #[serde(
+        deserialize_with = "u32_from_hex_str",
+        serialize_with = "u32_to_hex_str"
+    )]
+    domain_randao: u32,
+    #[serde(
+        deserialize_with = "u32_from_hex_str",
+        serialize_with = "u32_to_hex_str"
+    )]
+    domain_attestation: u32,
+    #[serde(
+        deserialize_with = "u32_from_hex_str",
+        serialize_with = "u32_to_hex_str"
+    )]
+    domain_deposit: u32,
+    #[serde(
+        deserialize_with = "u32_from_hex_str",
+        serialize_with = "u32_to_hex_str"
+    )]
+    domain_voluntary_exit: u32,
+    #[serde(
+        deserialize_with = "u32_from_hex_str",
+        serialize_with = "u32_to_hex_str"
+    )]
+    domain_transfer: u32,
+}
+
+impl YamlConfig {
+    /// Returns the config which represents `spec` when used with the `EthSpec` `T`.
+    pub fn from_spec<T: EthSpec>(spec: &ChainSpec) -> Self {
+        Self {
+            shard_count: T::ShardCount::to_u64(),
+            target_committee_size: spec.target_committee_size,
+            max_validators_per_committee: T::MaxValidatorsPerCommittee::to_u64(),
+            min_per_epoch_churn_limit: spec.min_per_epoch_churn_limit,
+            churn_limit_quotient: spec.churn_limit_quotient,
+            shuffle_round_count: spec.shuffle_round_count,
+            min_genesis_active_validator_count: spec.min_genesis_active_validator_count,
+            min_genesis_time: spec.min_genesis_time,
+            min_deposit_amount: spec.min_deposit_amount,
+            max_effective_balance: spec.max_effective_balance,
+            ejection_balance: spec.ejection_balance,
+            effective_balance_increment: spec.effective_balance_increment,
+            genesis_slot: spec.genesis_slot.as_u64(),
+            genesis_epoch: T::GenesisEpoch::to_u64(),
+            bls_withdrawal_prefix: spec.bls_withdrawal_prefix_byte,
+            seconds_per_slot: spec.seconds_per_slot,
+            min_attestation_inclusion_delay: spec.min_attestation_inclusion_delay,
+            slots_per_epoch: T::SlotsPerEpoch::to_u64(),
+            min_seed_lookahead: spec.min_seed_lookahead.as_u64(),
+            activation_exit_delay: spec.activation_exit_delay,
+            slots_per_eth1_voting_period: T::SlotsPerEth1VotingPeriod::to_u64(),
+            slots_per_historical_root: T::SlotsPerHistoricalRoot::to_u64(),
+            min_validator_withdrawability_delay: spec.min_validator_withdrawability_delay.as_u64(),
+            persistent_committee_period: spec.persistent_committee_period,
+            max_epochs_per_crosslink: spec.max_epochs_per_crosslink,
+            min_epochs_to_inactivity_penalty: spec.min_epochs_to_inactivity_penalty,
+            epochs_per_historical_vector: T::EpochsPerHistoricalVector::to_u64(),
+            epochs_per_slashings_vector: T::EpochsPerSlashingsVector::to_u64(),
+            historical_roots_limit: T::HistoricalRootsLimit::to_u64(),
+            validator_registry_limit: T::ValidatorRegistryLimit::to_u64(),
+            base_reward_factor: spec.base_reward_factor,
+            whistleblower_reward_quotient: spec.whistleblower_reward_quotient,
+            proposer_reward_quotient: spec.proposer_reward_quotient,
+            inactivity_penalty_quotient: spec.inactivity_penalty_quotient,
+            min_slashing_penalty_quotient: spec.min_slashing_penalty_quotient,
+            max_proposer_slashings: T::MaxProposerSlashings::to_u64(),
+            max_attester_slashings: T::MaxAttesterSlashings::to_u64(),
+            max_attestations: T::MaxAttestations::to_u64(),
+            max_deposits: T::MaxDeposits::to_u64(),
+            max_voluntary_exits: T::MaxVoluntaryExits::to_u64(),
+            max_transfers: T::MaxTransfers::to_u64(),
+            domain_beacon_proposer: spec.domain_beacon_proposer,
+            domain_randao: spec.domain_randao,
+            domain_attestation: spec.domain_attestation,
+            domain_deposit: spec.domain_deposit,
+            domain_voluntary_exit: spec.domain_voluntary_exit,
+            domain_transfer: spec.domain_transfer,
+        }
+    }
+
+    /// Returns `Ok` if the compile-time constants of the `EthSpec` `T` match those of `self`.
+    ///
+    /// Otherwise, returns an error describing each mismatched constant.
+    pub fn validate_eth_spec<T: EthSpec>(&self) -> Result<(), String> {
+        let mismatches = [
+            ("SHARD_COUNT", self.shard_count, T::ShardCount::to_u64()),
+            (
+                "MAX_VALIDATORS_PER_COMMITTEE",
+                self.max_validators_per_committee,
+                T::MaxValidatorsPerCommittee::to_u64(),
+            ),
+            (
+                "GENESIS_EPOCH",
+                self.genesis_epoch,
+                T::GenesisEpoch::to_u64(),
+            ),
+            (
+                "SLOTS_PER_EPOCH",
+                self.slots_per_epoch,
+                T::SlotsPerEpoch::to_u64(),
+            ),
+            (
+                "SLOTS_PER_ETH1_VOTING_PERIOD",
+                self.slots_per_eth1_voting_period,
+                T::SlotsPerEth1VotingPeriod::to_u64(),
+            ),
+            (
+                "SLOTS_PER_HISTORICAL_ROOT",
+                self.slots_per_historical_root,
+                T::SlotsPerHistoricalRoot::to_u64(),
+            ),
+            (
+                "EPOCHS_PER_HISTORICAL_VECTOR",
+                self.epochs_per_historical_vector,
+                T::EpochsPerHistoricalVector::to_u64(),
+            ),
+            (
+                "EPOCHS_PER_SLASHINGS_VECTOR",
+                self.epochs_per_slashings_vector,
+                T::EpochsPerSlashingsVector::to_u64(),
+            ),
+            (
+                "HISTORICAL_ROOTS_LIMIT",
+                self.historical_roots_limit,
+                T::HistoricalRootsLimit::to_u64(),
+            ),
+            (
+                "VALIDATOR_REGISTRY_LIMIT",
+                self.validator_registry_limit,
+                T::ValidatorRegistryLimit::to_u64(),
+            ),
+            (
+                "MAX_PROPOSER_SLASHINGS",
+                self.max_proposer_slashings,
+                T::MaxProposerSlashings::to_u64(),
+            ),
+            (
+                "MAX_ATTESTER_SLASHINGS",
+                self.max_attester_slashings,
+                T::MaxAttesterSlashings::to_u64(),
+            ),
+            (
+                "MAX_ATTESTATIONS",
+                self.max_attestations,
+                T::MaxAttestations::to_u64(),
+            ),
+            ("MAX_DEPOSITS", self.max_deposits, T::MaxDeposits::to_u64()),
+            (
+                "MAX_VOLUNTARY_EXITS",
+                self.max_voluntary_exits,
+                T::MaxVoluntaryExits::to_u64(),
+            ),
+            (
+                "MAX_TRANSFERS",
+                self.max_transfers,
+                T::MaxTransfers::to_u64(),
+            ),
+        ]
+        .iter()
+        .filter(|(_, config, compiled)| config != compiled)
+        .map(|(name, config, compiled)| format!("{} is {} but must be {}", name, config, compiled))
+        .collect::<Vec<_>>();
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Config is incompatible with {:?}: {}",
+                T::default(),
+                mismatches.join(", ")
+            ))
+        }
+    }
+
+    /// Returns `chain_spec` with all of its runtime constants replaced by those in `self`.
+    ///
+    /// Returns an error if `self` is incompatible with the `EthSpec` `T` or if any of the
+    /// constants are invalid.
+    pub fn apply_to_chain_spec<T: EthSpec>(
+        &self,
+        chain_spec: &ChainSpec,
+    ) -> Result<ChainSpec, String> {
+        self.validate_eth_spec::<T>()?;
+
+        let non_zero = [
+            ("TARGET_COMMITTEE_SIZE", self.target_committee_size as u64),
+            ("CHURN_LIMIT_QUOTIENT", self.churn_limit_quotient),
+            (
+                "EFFECTIVE_BALANCE_INCREMENT",
+                self.effective_balance_increment,
+            ),
+            ("SECONDS_PER_SLOT", self.seconds_per_slot),
+            (
+                "WHISTLEBLOWER_REWARD_QUOTIENT",
+                self.whistleblower_reward_quotient,
+            ),
+            ("PROPOSER_REWARD_QUOTIENT", self.proposer_reward_quotient),
+            (
+                "INACTIVITY_PENALTY_QUOTIENT",
+                self.inactivity_penalty_quotient,
+            ),
+            (
+                "MIN_SLASHING_PENALTY_QUOTIENT",
+                self.min_slashing_penalty_quotient,
+            ),
+        ];
+        if let Some((name, _)) = non_zero.iter().find(|(_, value)| *value == 0) {
+            return Err(format!("{} must not be zero", name));
+        }
+
+        Ok(ChainSpec {
+            target_committee_size: self.target_committee_size,
+            min_per_epoch_churn_limit: self.min_per_epoch_churn_limit,
+            churn_limit_quotient: self.churn_limit_quotient,
+            shuffle_round_count: self.shuffle_round_count,
+            min_genesis_active_validator_count: self.min_genesis_active_validator_count,
+            min_genesis_time: self.min_genesis_time,
+            min_deposit_amount: self.min_deposit_amount,
+            max_effective_balance: self.max_effective_balance,
+            ejection_balance: self.ejection_balance,
+            effective_balance_increment: self.effective_balance_increment,
+            genesis_slot: Slot::new(self.genesis_slot),
+            bls_withdrawal_prefix_byte: self.bls_withdrawal_prefix,
+            seconds_per_slot: self.seconds_per_slot,
+            min_attestation_inclusion_delay: self.min_attestation_inclusion_delay,
+            min_seed_lookahead: Epoch::new(self.min_seed_lookahead),
+            activation_exit_delay: self.activation_exit_delay,
+            min_validator_withdrawability_delay: Epoch::new(
+                self.min_validator_withdrawability_delay,
+            ),
+            persistent_committee_period: self.persistent_committee_period,
+            max_epochs_per_crosslink: self.max_epochs_per_crosslink,
+            min_epochs_to_inactivity_penalty: self.min_epochs_to_inactivity_penalty,
+            base_reward_factor: self.base_reward_factor,
+            whistleblower_reward_quotient: self.whistleblower_reward_quotient,
+            proposer_reward_quotient: self.proposer_reward_quotient,
+            inactivity_penalty_quotient: self.inactivity_penalty_quotient,
+            min_slashing_penalty_quotient: self.min_slashing_penalty_quotient,
+            domain_beacon_proposer: self.domain_beacon_proposer,
+            domain_randao: self.domain_randao,
+            domain_attestation: self.domain_attestation,
+            domain_deposit: self.domain_deposit,
+            domain_voluntary_exit: self.domain_voluntary_exit,
+            domain_transfer: self.domain_transfer,
+            ..chain_spec.clone()
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,6 +670,128 @@ mod tests {
         assert_eq!(int_to_bytes8(domain), expected);
     }
 
+    #[test]
+    fn fork_at_epoch() {
+        let mut spec = ChainSpec::minimal();
+        spec.fork_schedule = vec![
+            ScheduledFork {
+                epoch: Epoch::new(20),
+                version: [2; 4],
+            },
+            ScheduledFork {
+                epoch: Epoch::new(10),
+                version: [1; 4],
+            },
+        ];
+        let genesis_epoch = Epoch::new(0);
+
+        assert_eq!(
+            spec.fork_at_epoch(Epoch::new(9), genesis_epoch),
+            Fork::genesis(genesis_epoch)
+        );
+        assert_eq!(
+            spec.fork_at_epoch(Epoch::new(10), genesis_epoch),
+            Fork {
+                previous_version: [0; 4],
+                current_version: [1; 4],
+                epoch: Epoch::new(10),
+            }
+        );
+        assert_eq!(
+            spec.fork_at_epoch(Epoch::new(100), genesis_epoch),
+            Fork {
+                previous_version: [1; 4],
+                current_version: [2; 4],
+                epoch: Epoch::new(20),
+            }
+        );
+        assert_eq!(spec.fork_version_at_epoch(Epoch::new(15)), [1; 4]);
+        assert_eq!(spec.scheduled_fork(Epoch::new(20)).unwrap().version, [2; 4]);
+        assert_eq!(spec.scheduled_fork(Epoch::new(21)), None);
+        assert_eq!(spec.fork_versions(), vec![[0; 4], [2; 4], [1; 4]]);
+    }
+
+    #[test]
+    fn fork_schedule_toml_round_trip() {
+        let mut spec = ChainSpec::minimal();
+        spec.fork_schedule = vec![ScheduledFork {
+            epoch: Epoch::new(10),
+            version: [1, 0, 0, 0],
+        }];
+
+        let decoded: ChainSpec = toml::from_str(&toml::to_string(&spec).unwrap()).unwrap();
+
+        assert_eq!(decoded.fork_schedule, spec.fork_schedule);
+    }
+
+    #[test]
+    fn yaml_config_round_trip() {
+        for spec in &[ChainSpec::mainnet(), ChainSpec::interop()] {
+            let yaml_config = YamlConfig::from_spec::<MainnetEthSpec>(spec);
+            let yaml = serde_yaml::to_string(&yaml_config).unwrap();
+            let decoded: YamlConfig = serde_yaml::from_str(&yaml).unwrap();
+
+            assert_eq!(decoded, yaml_config);
+            assert_eq!(
+                decoded.apply_to_chain_spec::<MainnetEthSpec>(&ChainSpec::minimal()),
+                Ok(ChainSpec {
+                    boot_nodes: ChainSpec::minimal().boot_nodes,
+                    network_id: ChainSpec::minimal().network_id,
+                    ..spec.clone()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn yaml_config_incompatible_eth_spec() {
+        let yaml_config = YamlConfig::from_spec::<MainnetEthSpec>(&ChainSpec::mainnet());
+
+        assert!(yaml_config.validate_eth_spec::<MainnetEthSpec>().is_ok());
+        assert!(yaml_config.validate_eth_spec::<MinimalEthSpec>().is_err());
+        assert!(yaml_config
+            .apply_to_chain_spec::<MinimalEthSpec>(&ChainSpec::minimal())
+            .is_err());
+    }
+
+    #[test]
+    fn yaml_config_invalid_constant() {
+        let mut yaml_config = YamlConfig::from_spec::<MinimalEthSpec>(&ChainSpec::minimal());
+        yaml_config.seconds_per_slot = 0;
+
+        assert!(yaml_config
+            .apply_to_chain_spec::<MinimalEthSpec>(&ChainSpec::minimal())
+            .is_err());
+    }
+
+    #[test]
+    fn yaml_config_standard_format() {
+        let yaml = serde_yaml::to_string(&YamlConfig::from_spec::<MinimalEthSpec>(
+            &ChainSpec::minimal(),
+        ))
+        .unwrap()
+        .lines()
+        // The standard files encode the domains as unquoted, little-endian bytes.
+        .map(|line| match line {
+            line if line.starts_with("DOMAIN_RANDAO:") => "DOMAIN_RANDAO: 0x2a000000",
+            line => line,
+        })
+        // The standard files contain some constants which are not used.
+        .chain(std::iter::once(
+            "DEPOSIT_CONTRACT_ADDRESS: 0x1234567890123456789012345678901234567890",
+        ))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        let spec = serde_yaml::from_str::<YamlConfig>(&yaml)
+            .unwrap()
+            .apply_to_chain_spec::<MinimalEthSpec>(&ChainSpec::minimal())
+            .unwrap();
+
+        assert_eq!(spec.domain_randao, 42);
+        assert_eq!(spec.domain_attestation, 2);
+    }
+
     #[test]
     fn test_get_domain() {
         let spec = ChainSpec::mainnet();
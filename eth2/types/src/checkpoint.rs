@@ -4,7 +4,7 @@ use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
 use tree_hash::TreeHash;
-use tree_hash_derive::{SignedRoot, TreeHash};
+use tree_hash_derive::{SignedRoot, TreeHash, TreeHashProof};
 
 /// Casper FFG checkpoint, used in attestations.
 ///
@@ -21,6 +21,7 @@ use tree_hash_derive::{SignedRoot, TreeHash};
     Encode,
     Decode,
     TreeHash,
+    TreeHashProof,
     TestRandom,
     SignedRoot,
 )]
@@ -34,5 +35,4 @@ mod tests {
     use super::*;
 
     ssz_tests!(Checkpoint);
-
 }
@@ -1,4 +1,5 @@
 use crate::test_utils::TestRandom;
+use crate::utils::quoted_u64_var_list;
 use crate::{EthSpec, PublicKey};
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
@@ -11,6 +12,7 @@ use tree_hash_derive::TreeHash;
 #[serde(bound = "T: EthSpec")]
 pub struct CompactCommittee<T: EthSpec> {
     pub pubkeys: VariableList<PublicKey, T::MaxValidatorsPerCommittee>,
+    #[serde(with = "quoted_u64_var_list")]
     pub compact_validators: VariableList<u64, T::MaxValidatorsPerCommittee>,
 }
 
@@ -1,10 +1,11 @@
 use crate::test_utils::TestRandom;
+use crate::utils::quoted_u64;
 use crate::{Epoch, Hash256};
 
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
-use tree_hash_derive::TreeHash;
+use tree_hash_derive::{TreeHash, TreeHashProof};
 
 /// Specifies the block hash for a shard at an epoch.
 ///
@@ -21,9 +22,11 @@ use tree_hash_derive::TreeHash;
     Encode,
     Decode,
     TreeHash,
+    TreeHashProof,
     TestRandom,
 )]
 pub struct Crosslink {
+    #[serde(with = "quoted_u64")]
     pub shard: u64,
     pub parent_root: Hash256,
     // Crosslinking data
@@ -1,4 +1,5 @@
 use crate::test_utils::TestRandom;
+use crate::utils::quoted_u64;
 use crate::*;
 use bls::{PublicKeyBytes, SignatureBytes};
 use std::convert::From;
@@ -27,6 +28,7 @@ use tree_hash_derive::{SignedRoot, TreeHash};
 pub struct DepositData {
     pub pubkey: PublicKeyBytes,
     pub withdrawal_credentials: Hash256,
+    #[serde(with = "quoted_u64")]
     pub amount: u64,
     #[signed_root(skip_hashing)]
     pub signature: SignatureBytes,
@@ -54,6 +56,31 @@ impl DepositData {
 mod tests {
     use super::*;
 
+    #[test]
+    fn json_vector() {
+        let json = format!(
+            r#"{{"pubkey":"0x{}","withdrawal_credentials":"0x{}","amount":"32000000000","signature":"0x{}"}}"#,
+            "a1".repeat(48),
+            "00".repeat(31) + "2a",
+            "b2".repeat(96)
+        );
+        let deposit_data = DepositData {
+            pubkey: PublicKeyBytes::from_bytes(&[0xa1; 48]).unwrap(),
+            withdrawal_credentials: Hash256::from_low_u64_be(42),
+            amount: 32_000_000_000,
+            signature: SignatureBytes::from_bytes(&[0xb2; 96]).unwrap(),
+        };
+
+        assert_eq!(serde_json::to_string(&deposit_data).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<DepositData>(&json).unwrap(),
+            deposit_data
+        );
+
+        // Byte strings without the `0x` prefix are rejected.
+        assert!(serde_json::from_str::<DepositData>(&json.replace("0xa1", "a1")).is_err());
+    }
+
     ssz_tests!(DepositData);
 
 }
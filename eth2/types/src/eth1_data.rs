@@ -1,19 +1,31 @@
 use super::Hash256;
 use crate::test_utils::TestRandom;
+use crate::utils::quoted_u64;
 
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
-use tree_hash_derive::TreeHash;
+use tree_hash_derive::{TreeHash, TreeHashProof};
 
 /// Contains data obtained from the Eth1 chain.
 ///
 /// Spec v0.8.1
 #[derive(
-    Debug, PartialEq, Clone, Default, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom,
+    Debug,
+    PartialEq,
+    Clone,
+    Default,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    TreeHash,
+    TreeHashProof,
+    TestRandom,
 )]
 pub struct Eth1Data {
     pub deposit_root: Hash256,
+    #[serde(with = "quoted_u64")]
     pub deposit_count: u64,
     pub block_hash: Hash256,
 }
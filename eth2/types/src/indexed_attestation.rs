@@ -1,3 +1,4 @@
+use crate::utils::quoted_u64_var_list;
 use crate::{test_utils::TestRandom, AggregateSignature, AttestationData, EthSpec, VariableList};
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
@@ -25,7 +26,9 @@ use tree_hash_derive::{SignedRoot, TreeHash};
 #[serde(bound = "T: EthSpec")]
 pub struct IndexedAttestation<T: EthSpec> {
     /// Lists validator registry indices, not committee indices.
+    #[serde(with = "quoted_u64_var_list")]
     pub custody_bit_0_indices: VariableList<u64, T::MaxValidatorsPerCommittee>,
+    #[serde(with = "quoted_u64_var_list")]
     pub custody_bit_1_indices: VariableList<u64, T::MaxValidatorsPerCommittee>,
     pub data: AttestationData,
     #[signed_root(skip_hashing)]
@@ -51,7 +51,7 @@ pub use crate::beacon_block::BeaconBlock;
 pub use crate::beacon_block_body::BeaconBlockBody;
 pub use crate::beacon_block_header::BeaconBlockHeader;
 pub use crate::beacon_state::{Error as BeaconStateError, *};
-pub use crate::chain_spec::{ChainSpec, Domain};
+pub use crate::chain_spec::{ChainSpec, Domain, ScheduledFork, YamlConfig};
 pub use crate::checkpoint::Checkpoint;
 pub use crate::compact_committee::CompactCommittee;
 pub use crate::crosslink::Crosslink;
@@ -87,4 +87,7 @@ pub type AttesterMap = HashMap<(u64, u64), Vec<usize>>;
 pub type ProposerMap = HashMap<u64, usize>;
 
 pub use bls::{AggregatePublicKey, AggregateSignature, Keypair, PublicKey, SecretKey, Signature};
-pub use ssz_types::{typenum, typenum::Unsigned, BitList, BitVector, FixedVector, VariableList};
+pub use ssz_types::{
+    typenum, typenum::Unsigned, BitList, BitVector, FixedVector, PersistentList, PersistentVector,
+    VariableList,
+};
@@ -1,19 +1,33 @@
 use crate::test_utils::TestRandom;
+use crate::utils::quoted_u64;
 use crate::{AttestationData, BitList, EthSpec};
 
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
-use tree_hash_derive::TreeHash;
+use tree_hash_derive::{TreeHash, TreeHashProof};
 
 /// An attestation that has been included in the state but not yet fully processed.
 ///
 /// Spec v0.8.0
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    TreeHash,
+    TreeHashProof,
+    TestRandom,
+)]
 pub struct PendingAttestation<T: EthSpec> {
     pub aggregation_bits: BitList<T::MaxValidatorsPerCommittee>,
     pub data: AttestationData,
+    #[serde(with = "quoted_u64")]
     pub inclusion_delay: u64,
+    #[serde(with = "quoted_u64")]
     pub proposer_index: u64,
 }
 
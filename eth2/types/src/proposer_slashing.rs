@@ -1,5 +1,6 @@
 use super::BeaconBlockHeader;
 use crate::test_utils::TestRandom;
+use crate::utils::quoted_u64;
 
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
@@ -11,6 +12,7 @@ use tree_hash_derive::TreeHash;
 /// Spec v0.8.1
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
 pub struct ProposerSlashing {
+    #[serde(with = "quoted_u64")]
     pub proposer_index: u64,
     pub header_1: BeaconBlockHeader,
     pub header_2: BeaconBlockHeader,
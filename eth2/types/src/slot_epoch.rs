@@ -12,6 +12,7 @@
 
 use crate::slot_height::SlotHeight;
 use crate::test_utils::TestRandom;
+use crate::utils::quoted_u64;
 use rand::RngCore;
 use serde_derive::{Deserialize, Serialize};
 use slog;
@@ -24,10 +25,11 @@ use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, Sub, SubAssi
 
 #[derive(Eq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
 #[serde(transparent)]
-pub struct Slot(u64);
+pub struct Slot(#[serde(with = "quoted_u64")] u64);
 
 #[derive(Eq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
-pub struct Epoch(u64);
+#[serde(transparent)]
+pub struct Epoch(#[serde(with = "quoted_u64")] u64);
 
 impl_common!(Slot);
 impl_common!(Epoch);
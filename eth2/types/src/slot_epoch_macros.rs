@@ -238,6 +238,8 @@ macro_rules! impl_ssz {
             }
         }
 
+        impl tree_hash::TreeHashProof for $type {}
+
         impl TestRandom for $type {
             fn random_for_test(rng: &mut impl RngCore) -> Self {
                 $type::from(u64::random_for_test(rng))
@@ -1,5 +1,6 @@
 use super::Slot;
 use crate::test_utils::TestRandom;
+use crate::utils::quoted_u64;
 use bls::{PublicKey, Signature};
 use derivative::Derivative;
 
@@ -26,9 +27,13 @@ use tree_hash_derive::{SignedRoot, TreeHash};
 )]
 #[derivative(PartialEq, Eq, Hash)]
 pub struct Transfer {
+    #[serde(with = "quoted_u64")]
     pub sender: u64,
+    #[serde(with = "quoted_u64")]
     pub recipient: u64,
+    #[serde(with = "quoted_u64")]
     pub amount: u64,
+    #[serde(with = "quoted_u64")]
     pub fee: u64,
     pub slot: Slot,
     pub pubkey: PublicKey,
@@ -1,6 +1,9 @@
 use hex;
-use serde::de::Error;
-use serde::{Deserialize, Deserializer, Serializer};
+use serde::de::{Error, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ssz_types::typenum::Unsigned;
+use ssz_types::{FixedVector, PersistentList, VariableList};
+use std::fmt;
 
 pub const FORK_BYTES_LEN: usize = 4;
 pub const GRAFFITI_BYTES_LEN: usize = 32;
@@ -25,6 +28,39 @@ where
     serializer.serialize_str(&hex)
 }
 
+/// Deserializes a `u32` from a hex string of its 4 little-endian bytes (e.g., `0x01000000` is
+/// `1`).
+pub fn u32_from_hex_str<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: String = Deserialize::deserialize(deserializer)?;
+    let decoded: Vec<u8> = hex::decode(s.trim_start_matches("0x")).map_err(D::Error::custom)?;
+
+    if decoded.len() != 4 {
+        return Err(D::Error::custom(format!(
+            "Expected 4 bytes, got {}",
+            decoded.len()
+        )));
+    }
+
+    let mut array = [0; 4];
+    array.copy_from_slice(&decoded);
+
+    Ok(u32::from_le_bytes(array))
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // Serde requires the `int` to be a ref.
+pub fn u32_to_hex_str<S>(int: &u32, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut hex: String = "0x".to_string();
+    hex.push_str(&hex::encode(&int.to_le_bytes()));
+
+    serializer.serialize_str(&hex)
+}
+
 pub fn fork_from_hex_str<'de, D>(deserializer: D) -> Result<[u8; FORK_BYTES_LEN], D::Error>
 where
     D: Deserializer<'de>,
@@ -89,3 +125,241 @@ where
     }
     Ok(array)
 }
+
+/// Serializes a `u64` as a quoted decimal string (e.g., `"42"`), as per the standard JSON
+/// encoding of the consensus types. Unlike a JSON number, this does not lose precision when
+/// parsed by JavaScript.
+///
+/// Deserializes from either a quoted decimal string or a number, so the YAML test vectors (and
+/// existing config files) remain readable.
+///
+/// Use with `#[serde(with = "quoted_u64")]`.
+pub mod quoted_u64 {
+    use super::*;
+
+    #[allow(clippy::trivially_copy_pass_by_ref)] // Serde requires the `value` to be a ref.
+    pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        QuotedU64(*value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        QuotedU64::deserialize(deserializer).map(|quoted| quoted.0)
+    }
+}
+
+/// As per `quoted_u64`, for each value of a `VariableList<u64, N>`.
+///
+/// Use with `#[serde(with = "quoted_u64_var_list")]`.
+pub mod quoted_u64_var_list {
+    use super::*;
+
+    pub fn serialize<S, N>(list: &VariableList<u64, N>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        N: Unsigned,
+    {
+        serializer.collect_seq(list.iter().map(|value| QuotedU64(*value)))
+    }
+
+    pub fn deserialize<'de, D, N>(deserializer: D) -> Result<VariableList<u64, N>, D::Error>
+    where
+        D: Deserializer<'de>,
+        N: Unsigned,
+    {
+        let values = deserialize_quoted_u64s(deserializer)?;
+
+        VariableList::new(values).map_err(|e| D::Error::custom(format!("{:?}", e)))
+    }
+}
+
+/// As per `quoted_u64`, for each value of a `FixedVector<u64, N>`.
+///
+/// Use with `#[serde(with = "quoted_u64_fixed_vec")]`.
+pub mod quoted_u64_fixed_vec {
+    use super::*;
+
+    pub fn serialize<S, N>(vector: &FixedVector<u64, N>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        N: Unsigned,
+    {
+        serializer.collect_seq(vector.iter().map(|value| QuotedU64(*value)))
+    }
+
+    pub fn deserialize<'de, D, N>(deserializer: D) -> Result<FixedVector<u64, N>, D::Error>
+    where
+        D: Deserializer<'de>,
+        N: Unsigned,
+    {
+        let values = deserialize_quoted_u64s(deserializer)?;
+
+        FixedVector::new(values).map_err(|e| D::Error::custom(format!("{:?}", e)))
+    }
+}
+
+/// As per `quoted_u64`, for each value of a `PersistentList<u64, N>`.
+///
+/// Use with `#[serde(with = "quoted_u64_persistent_list")]`.
+pub mod quoted_u64_persistent_list {
+    use super::*;
+
+    pub fn serialize<S, N>(list: &PersistentList<u64, N>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        N: Unsigned,
+    {
+        serializer.collect_seq(list.iter().map(|value| QuotedU64(*value)))
+    }
+
+    pub fn deserialize<'de, D, N>(deserializer: D) -> Result<PersistentList<u64, N>, D::Error>
+    where
+        D: Deserializer<'de>,
+        N: Unsigned,
+    {
+        let values = deserialize_quoted_u64s(deserializer)?;
+
+        PersistentList::new(values).map_err(|e| D::Error::custom(format!("{:?}", e)))
+    }
+}
+
+fn deserialize_quoted_u64s<'de, D>(deserializer: D) -> Result<Vec<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let quoted: Vec<QuotedU64> = Deserialize::deserialize(deserializer)?;
+
+    Ok(quoted.into_iter().map(|quoted| quoted.0).collect())
+}
+
+/// A `u64` which is serialized as a quoted decimal string. See `quoted_u64`.
+struct QuotedU64(u64);
+
+impl Serialize for QuotedU64 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for QuotedU64 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_any(QuotedU64Visitor)
+            .map(QuotedU64)
+    }
+}
+
+struct QuotedU64Visitor;
+
+impl<'de> Visitor<'de> for QuotedU64Visitor {
+    type Value = u64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a u64 or a quoted decimal u64")
+    }
+
+    fn visit_u64<E: Error>(self, value: u64) -> Result<u64, E> {
+        Ok(value)
+    }
+
+    fn visit_i64<E: Error>(self, value: i64) -> Result<u64, E> {
+        if value >= 0 {
+            Ok(value as u64)
+        } else {
+            Err(E::custom(format!("{} is negative", value)))
+        }
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<u64, E> {
+        value
+            .parse()
+            .map_err(|_| E::custom(format!("{:?} is not a decimal u64", value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::{Deserialize, Serialize};
+    use ssz_types::typenum::U4;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Quoted {
+        #[serde(with = "quoted_u64")]
+        value: u64,
+        #[serde(with = "quoted_u64_var_list")]
+        list: VariableList<u64, U4>,
+        #[serde(with = "quoted_u64_fixed_vec")]
+        vector: FixedVector<u64, U4>,
+    }
+
+    #[test]
+    fn quoted_u64_json() {
+        let quoted = Quoted {
+            value: u64::max_value(),
+            list: VariableList::from(vec![1, 2]),
+            vector: FixedVector::from(vec![3, 4, 5, 6]),
+        };
+        let json =
+            r#"{"value":"18446744073709551615","list":["1","2"],"vector":["3","4","5","6"]}"#;
+
+        assert_eq!(serde_json::to_string(&quoted).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Quoted>(json).unwrap(), quoted);
+    }
+
+    #[test]
+    fn quoted_u64_accepts_numbers() {
+        let json = r#"{"value":42,"list":[1,"2"],"vector":[3,4,5,6]}"#;
+        let yaml = "value: 42\nlist: [1, 2]\nvector: [3, 4, 5, 6]";
+
+        let from_json: Quoted = serde_json::from_str(json).unwrap();
+        let from_yaml: Quoted = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(from_json, from_yaml);
+        assert_eq!(from_json.value, 42);
+        assert_eq!(&from_json.list[..], &[1, 2]);
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct QuotedPersistent {
+        #[serde(with = "quoted_u64_persistent_list")]
+        list: PersistentList<u64, U4>,
+    }
+
+    #[test]
+    fn quoted_u64_persistent_list_json() {
+        let quoted = QuotedPersistent {
+            list: PersistentList::from(vec![1, 2]),
+        };
+        let json = r#"{"list":["1","2"]}"#;
+
+        assert_eq!(serde_json::to_string(&quoted).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<QuotedPersistent>(json).unwrap(),
+            quoted
+        );
+        assert!(serde_json::from_str::<QuotedPersistent>(r#"{"list":[1,2,3,4,5]}"#).is_err());
+    }
+
+    #[test]
+    fn quoted_u64_rejects_invalid() {
+        assert!(serde_json::from_str::<Quoted>(
+            r#"{"value":"-1","list":[],"vector":["3","4","5","6"]}"#
+        )
+        .is_err());
+        // Too many values for the list.
+        assert!(serde_json::from_str::<Quoted>(
+            r#"{"value":"1","list":["1","2","3","4","5"],"vector":["3","4","5","6"]}"#
+        )
+        .is_err());
+        // Too few values for the vector.
+        assert!(
+            serde_json::from_str::<Quoted>(r#"{"value":"1","list":[],"vector":["3"]}"#).is_err()
+        );
+    }
+}
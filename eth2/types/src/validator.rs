@@ -1,17 +1,30 @@
+use crate::utils::quoted_u64;
 use crate::{test_utils::TestRandom, Epoch, Hash256, PublicKey};
 
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use test_random_derive::TestRandom;
-use tree_hash_derive::TreeHash;
+use tree_hash_derive::{TreeHash, TreeHashProof};
 
 /// Information about a `BeaconChain` validator.
 ///
 /// Spec v0.8.0
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode, TestRandom, TreeHash)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Encode,
+    Decode,
+    TestRandom,
+    TreeHash,
+    TreeHashProof,
+)]
 pub struct Validator {
     pub pubkey: PublicKey,
     pub withdrawal_credentials: Hash256,
+    #[serde(with = "quoted_u64")]
     pub effective_balance: u64,
     pub slashed: bool,
     pub activation_eligibility_epoch: Epoch,
@@ -1,3 +1,4 @@
+use crate::utils::quoted_u64;
 use crate::{test_utils::TestRandom, Epoch};
 use bls::Signature;
 
@@ -25,6 +26,7 @@ use tree_hash_derive::{SignedRoot, TreeHash};
 pub struct VoluntaryExit {
     /// Earliest epoch when voluntary exit can be processed.
     pub epoch: Epoch,
+    #[serde(with = "quoted_u64")]
     pub validator_index: u64,
     #[signed_root(skip_hashing)]
     pub signature: Signature,
@@ -0,0 +1,61 @@
+use bls::{verify_signature_sets, Keypair, Signature, SignatureSet};
+use criterion::{black_box, criterion_group, criterion_main, Benchmark, Criterion};
+use std::borrow::Cow;
+
+const DOMAIN: u64 = 42;
+
+fn bench_signature_sets(c: &mut Criterion, set_count: usize) {
+    let keypairs: Vec<Keypair> = (0..set_count).map(|_| Keypair::random()).collect();
+    let messages: Vec<Vec<u8>> = (0..set_count).map(|i| vec![i as u8; 32]).collect();
+    let signatures: Vec<Signature> = keypairs
+        .iter()
+        .zip(messages.iter())
+        .map(|(keypair, message)| Signature::new(message, DOMAIN, &keypair.sk))
+        .collect();
+
+    let sets: Vec<SignatureSet<'static>> = keypairs
+        .iter()
+        .zip(messages.iter())
+        .zip(signatures.iter())
+        .map(|((keypair, message), signature)| {
+            SignatureSet::single(
+                signature,
+                Cow::Owned(keypair.pk.clone()),
+                message.clone(),
+                DOMAIN,
+            )
+        })
+        .collect();
+
+    assert!(verify_signature_sets(&sets));
+
+    c.bench(
+        &format!("signature_sets/{}_sets", set_count),
+        Benchmark::new("verify_individually", move |b| {
+            b.iter(|| {
+                black_box(
+                    signatures
+                        .iter()
+                        .zip(messages.iter())
+                        .zip(keypairs.iter())
+                        .all(|((signature, message), keypair)| {
+                            signature.verify(message, DOMAIN, &keypair.pk)
+                        }),
+                )
+            })
+        })
+        .with_function("verify_signature_sets", move |b| {
+            b.iter(|| black_box(verify_signature_sets(&sets)))
+        })
+        .sample_size(10),
+    );
+}
+
+fn all_benches(c: &mut Criterion) {
+    bench_signature_sets(c, 1);
+    bench_signature_sets(c, 16);
+    bench_signature_sets(c, 128);
+}
+
+criterion_group!(benches, all_benches);
+criterion_main!(benches);
@@ -17,6 +17,11 @@ impl AggregatePublicKey {
         self.0.add(public_key.as_raw())
     }
 
+    /// Add (aggregate) another `AggregatePublicKey`.
+    pub fn add_aggregate(&mut self, aggregate_public_key: &AggregatePublicKey) {
+        self.0.point.add(&aggregate_public_key.0.point)
+    }
+
     /// Returns the underlying public key.
     pub fn as_raw(&self) -> &RawAggregatePublicKey {
         &self.0
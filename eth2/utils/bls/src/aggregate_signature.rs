@@ -29,6 +29,19 @@ impl AggregateSignature {
         }
     }
 
+    /// Instantiate an `AggregateSignature` containing only `signature`.
+    ///
+    /// The result is empty if `signature` is empty.
+    pub fn from_signature(signature: &Signature) -> Self {
+        if signature.is_empty() {
+            Self::empty_signature()
+        } else {
+            let mut aggregate_signature = Self::new();
+            aggregate_signature.add(signature);
+            aggregate_signature
+        }
+    }
+
     /// Add (aggregate) a signature to the `AggregateSignature`.
     pub fn add(&mut self, signature: &Signature) {
         if !self.is_empty {
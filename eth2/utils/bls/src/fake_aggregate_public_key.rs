@@ -25,6 +25,10 @@ impl FakeAggregatePublicKey {
         // No nothing.
     }
 
+    pub fn add_aggregate(&mut self, _aggregate_public_key: &FakeAggregatePublicKey) {
+        // No nothing.
+    }
+
     pub fn as_raw(&self) -> &FakeAggregatePublicKey {
         &self
     }
@@ -29,6 +29,12 @@ impl FakeAggregateSignature {
         }
     }
 
+    /// Instantiate an aggregate containing only `signature`. Fake signatures carry no data, so
+    /// the result is always all-zero's.
+    pub fn from_signature(_signature: &FakeSignature) -> Self {
+        Self::zero()
+    }
+
     /// Does glorious nothing.
     pub fn add(&mut self, _signature: &FakeSignature) {
         // Do nothing.
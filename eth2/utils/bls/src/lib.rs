@@ -7,11 +7,13 @@ mod keypair;
 mod public_key_bytes;
 mod secret_key;
 mod signature_bytes;
+mod signature_set;
 
 pub use crate::keypair::Keypair;
 pub use crate::public_key_bytes::PublicKeyBytes;
 pub use crate::secret_key::SecretKey;
 pub use crate::signature_bytes::SignatureBytes;
+pub use crate::signature_set::{verify_signature_sets, SignatureSet, SignedMessage};
 pub use milagro_bls::{compress_g2, hash_on_g2};
 
 #[cfg(feature = "fake_crypto")]
@@ -58,6 +58,8 @@ macro_rules! impl_tree_hash {
                 vector.tree_hash_root()
             }
         }
+
+        impl tree_hash::TreeHashProof for $type {}
     };
 }
 
@@ -148,7 +150,7 @@ macro_rules! bytes_struct {
             where
                 S: serde::ser::Serializer,
             {
-                serializer.serialize_str(&hex::encode(ssz::ssz_encode(self)))
+                serializer.serialize_str(&serde_hex::encode(ssz::ssz_encode(self)))
             }
         }
 
@@ -158,7 +160,7 @@ macro_rules! bytes_struct {
             where
                 D: serde::de::Deserializer<'de>,
             {
-                let bytes = deserializer.deserialize_str(serde_hex::HexVisitor)?;
+                let bytes = deserializer.deserialize_str(serde_hex::PrefixedHexVisitor)?;
                 let signature = Self::from_ssz_bytes(&bytes[..])
                     .map_err(|e| serde::de::Error::custom(format!("invalid ssz ({:?})", e)))?;
                 Ok(signature)
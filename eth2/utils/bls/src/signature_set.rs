@@ -0,0 +1,403 @@
+use super::{AggregateSignature, PublicKey, Signature};
+use std::borrow::Cow;
+
+#[cfg(not(feature = "fake_crypto"))]
+use super::AggregatePublicKey;
+#[cfg(not(feature = "fake_crypto"))]
+use rand::Rng;
+#[cfg(not(feature = "fake_crypto"))]
+use std::collections::BTreeMap;
+
+/// A message and the set of public keys which signed it.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "fake_crypto", allow(dead_code))]
+pub struct SignedMessage<'a> {
+    signing_keys: Vec<Cow<'a, PublicKey>>,
+    message: Vec<u8>,
+}
+
+impl<'a> SignedMessage<'a> {
+    pub fn new(signing_keys: Vec<Cow<'a, PublicKey>>, message: Vec<u8>) -> Self {
+        Self {
+            signing_keys,
+            message,
+        }
+    }
+
+    /// Aggregates all of the signing keys into a single key.
+    #[cfg(not(feature = "fake_crypto"))]
+    fn aggregate_public_key(&self) -> AggregatePublicKey {
+        let mut aggregate = AggregatePublicKey::new();
+        for key in &self.signing_keys {
+            aggregate.add(key);
+        }
+        aggregate
+    }
+}
+
+/// A signature across one or more messages, each signed by one or more public keys.
+///
+/// Many independent `SignatureSet`s may be verified at once via `verify_signature_sets`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "fake_crypto", allow(dead_code))]
+pub struct SignatureSet<'a> {
+    signature: Cow<'a, AggregateSignature>,
+    signed_messages: Vec<SignedMessage<'a>>,
+    domain: u64,
+}
+
+impl<'a> SignatureSet<'a> {
+    /// Instantiate a set where a single public key has signed a single message.
+    pub fn single(
+        signature: &Signature,
+        signing_key: Cow<'a, PublicKey>,
+        message: Vec<u8>,
+        domain: u64,
+    ) -> Self {
+        Self {
+            signature: Cow::Owned(AggregateSignature::from_signature(signature)),
+            signed_messages: vec![SignedMessage::new(vec![signing_key], message)],
+            domain,
+        }
+    }
+
+    /// Instantiate a set where `signature` is the aggregate of signatures across all of the
+    /// `signed_messages`.
+    pub fn new(
+        signature: &'a AggregateSignature,
+        signed_messages: Vec<SignedMessage<'a>>,
+        domain: u64,
+    ) -> Self {
+        Self {
+            signature: Cow::Borrowed(signature),
+            signed_messages,
+            domain,
+        }
+    }
+
+    /// Returns `true` if this set is valid.
+    pub fn is_valid(&self) -> bool {
+        verify_signature_sets(std::slice::from_ref(self))
+    }
+
+    /// Returns `false` if `self` can never be valid (i.e., it has an empty signature, no messages
+    /// or a message without any signing keys).
+    #[cfg(not(feature = "fake_crypto"))]
+    fn is_well_formed(&self) -> bool {
+        !self.signature.is_empty()
+            && !self.signed_messages.is_empty()
+            && self
+                .signed_messages
+                .iter()
+                .all(|signed_message| !signed_message.signing_keys.is_empty())
+    }
+}
+
+/// The combination of all `SignatureSet`s which share a domain.
+#[cfg(not(feature = "fake_crypto"))]
+struct Batch<'a> {
+    signature: AggregateSignature,
+    aggregate_public_keys: Vec<AggregatePublicKey>,
+    messages: Vec<&'a [u8]>,
+}
+
+#[cfg(not(feature = "fake_crypto"))]
+impl<'a> Batch<'a> {
+    fn new() -> Self {
+        Self {
+            signature: AggregateSignature::new(),
+            aggregate_public_keys: vec![],
+            messages: vec![],
+        }
+    }
+
+    /// Adds `set` to the batch, with its signature and signing keys multiplied by `scalar`.
+    fn add(&mut self, set: &'a SignatureSet, scalar: u64) {
+        self.signature.add_aggregate(&mul(
+            &*set.signature,
+            scalar,
+            AggregateSignature::add_aggregate,
+        ));
+
+        for signed_message in &set.signed_messages {
+            self.aggregate_public_keys.push(mul(
+                &signed_message.aggregate_public_key(),
+                scalar,
+                AggregatePublicKey::add_aggregate,
+            ));
+            self.messages.push(&signed_message.message);
+        }
+    }
+
+    /// Verifies every set in the batch with a single multi-pairing.
+    fn verify(&self, domain: u64) -> bool {
+        self.signature.verify_multiple(
+            &self.messages,
+            domain,
+            &self.aggregate_public_keys.iter().collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// Returns `point * scalar`, using `add` to add one point to another.
+///
+/// `scalar` must not be zero.
+#[cfg(not(feature = "fake_crypto"))]
+fn mul<T: Clone>(point: &T, scalar: u64, add: fn(&mut T, &T)) -> T {
+    debug_assert!(scalar != 0, "cannot multiply by zero");
+
+    // Double-and-add, starting from the most significant bit of `scalar`.
+    let mut result = point.clone();
+    for i in (0..63 - scalar.leading_zeros()).rev() {
+        let double = result.clone();
+        add(&mut result, &double);
+
+        if (scalar >> i) & 1 == 1 {
+            add(&mut result, point);
+        }
+    }
+    result
+}
+
+/// Returns a random, non-zero scalar.
+#[cfg(not(feature = "fake_crypto"))]
+fn random_scalar<R: Rng>(rng: &mut R) -> u64 {
+    loop {
+        let scalar = rng.gen();
+        if scalar != 0 {
+            return scalar;
+        }
+    }
+}
+
+/// Verify all of the `sets` at once, returning `true` only if every set is valid. Returns `true`
+/// if `sets` is empty.
+///
+/// The signature and signing keys of each set are multiplied by a random, non-zero 64-bit scalar
+/// before the sets are combined, so that an invalid set cannot be hidden by another invalid set
+/// which cancels it out. The combined sets are then checked with a single multi-pairing (and
+/// final exponentiation) for each distinct domain, instead of one for each set.
+#[cfg(not(feature = "fake_crypto"))]
+pub fn verify_signature_sets(sets: &[SignatureSet]) -> bool {
+    if !sets.iter().all(SignatureSet::is_well_formed) {
+        return false;
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut batches: BTreeMap<u64, Batch> = BTreeMap::new();
+
+    for set in sets {
+        // A lone set cannot be cancelled out by another, so it does not need to be randomized.
+        let scalar = if sets.len() == 1 {
+            1
+        } else {
+            random_scalar(&mut rng)
+        };
+
+        batches
+            .entry(set.domain)
+            .or_insert_with(Batch::new)
+            .add(set, scalar);
+    }
+
+    batches.iter().all(|(domain, batch)| batch.verify(*domain))
+}
+
+/// _Always_ returns `true`.
+#[cfg(feature = "fake_crypto")]
+pub fn verify_signature_sets(_sets: &[SignatureSet]) -> bool {
+    true
+}
+
+#[cfg(all(test, not(feature = "fake_crypto")))]
+mod tests {
+    use super::super::Keypair;
+    use super::*;
+
+    const DOMAIN: u64 = 42;
+
+    fn single_set(keypair: &Keypair, message: &[u8]) -> SignatureSet<'static> {
+        single_set_with_domain(keypair, message, DOMAIN)
+    }
+
+    fn single_set_with_domain(
+        keypair: &Keypair,
+        message: &[u8],
+        domain: u64,
+    ) -> SignatureSet<'static> {
+        let signature = Signature::new(message, domain, &keypair.sk);
+        SignatureSet::single(
+            &signature,
+            Cow::Owned(keypair.pk.clone()),
+            message.to_vec(),
+            domain,
+        )
+    }
+
+    #[test]
+    fn mul_matches_repeated_addition() {
+        let keypair = Keypair::random();
+        let signature =
+            AggregateSignature::from_signature(&Signature::new(&[1; 32], DOMAIN, &keypair.sk));
+
+        let mut expected = signature.clone();
+        for scalar in 1..20 {
+            assert_eq!(
+                mul(&signature, scalar, AggregateSignature::add_aggregate).as_bytes(),
+                expected.as_bytes(),
+                "scalar {}",
+                scalar
+            );
+            expected.add_aggregate(&signature);
+        }
+    }
+
+    #[test]
+    fn one_invalid_set_in_batch() {
+        let keypairs: Vec<Keypair> = (0..8).map(|_| Keypair::random()).collect();
+        let sets = |invalid: Option<usize>| {
+            keypairs
+                .iter()
+                .enumerate()
+                .map(|(i, keypair)| {
+                    // Spread the sets across two domains.
+                    let mut set =
+                        single_set_with_domain(keypair, &[i as u8; 32], DOMAIN + i as u64 % 2);
+                    if invalid == Some(i) {
+                        set.signed_messages[0].message = vec![255; 32];
+                    }
+                    set
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert!(verify_signature_sets(&sets(None)));
+        for i in 0..keypairs.len() {
+            assert!(!verify_signature_sets(&sets(Some(i))), "set {}", i);
+        }
+    }
+
+    /// Two invalid sets whose signatures sum to the sum of two valid signatures must not cancel
+    /// each other out.
+    #[test]
+    fn swapped_signatures() {
+        let keypairs: Vec<Keypair> = (0..2).map(|_| Keypair::random()).collect();
+        let messages: Vec<Vec<u8>> = vec![vec![1; 32], vec![2; 32]];
+        let signatures: Vec<Signature> = keypairs
+            .iter()
+            .zip(messages.iter())
+            .map(|(keypair, message)| Signature::new(message, DOMAIN, &keypair.sk))
+            .collect();
+
+        let set = |signature: &Signature, i: usize| {
+            SignatureSet::single(
+                signature,
+                Cow::Borrowed(&keypairs[i].pk),
+                messages[i].clone(),
+                DOMAIN,
+            )
+        };
+
+        assert!(verify_signature_sets(&[
+            set(&signatures[0], 0),
+            set(&signatures[1], 1)
+        ]));
+        assert!(!verify_signature_sets(&[
+            set(&signatures[1], 0),
+            set(&signatures[0], 1)
+        ]));
+    }
+
+    #[test]
+    fn single_sets() {
+        let keypairs: Vec<Keypair> = (0..8).map(|_| Keypair::random()).collect();
+        let mut sets: Vec<_> = keypairs
+            .iter()
+            .enumerate()
+            .map(|(i, keypair)| single_set(keypair, &[i as u8; 32]))
+            .collect();
+
+        assert!(sets.iter().all(SignatureSet::is_valid));
+        assert!(verify_signature_sets(&sets));
+
+        // Change the message of the last set.
+        sets[7].signed_messages[0].message = vec![0; 32];
+        assert!(!sets[7].is_valid());
+        assert!(!verify_signature_sets(&sets));
+    }
+
+    /// Assigns the first half of `keypairs` to the first message and the second half to the second.
+    fn signed_messages<'a>(
+        keypairs: &'a [Keypair],
+        messages: &[Vec<u8>],
+    ) -> Vec<SignedMessage<'a>> {
+        messages
+            .iter()
+            .zip(keypairs.chunks(keypairs.len() / messages.len()))
+            .map(|(message, keypairs)| {
+                SignedMessage::new(
+                    keypairs.iter().map(|kp| Cow::Borrowed(&kp.pk)).collect(),
+                    message.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn aggregate_set() {
+        let keypairs: Vec<Keypair> = (0..4).map(|_| Keypair::random()).collect();
+        let messages: Vec<Vec<u8>> = vec![vec![1; 32], vec![2; 32]];
+
+        // The first two keys sign the first message, the last two keys sign the second.
+        let mut signature = AggregateSignature::new();
+        for (i, keypair) in keypairs.iter().enumerate() {
+            signature.add(&Signature::new(&messages[i / 2], DOMAIN, &keypair.sk));
+        }
+
+        let valid = SignatureSet::new(&signature, signed_messages(&keypairs, &messages), DOMAIN);
+        let other = single_set(&keypairs[0], &[3; 32]);
+        assert!(verify_signature_sets(&[valid, other.clone()]));
+
+        let mut swapped_keypairs = keypairs.clone();
+        swapped_keypairs.swap(1, 2);
+        let invalid = SignatureSet::new(
+            &signature,
+            signed_messages(&swapped_keypairs, &messages),
+            DOMAIN,
+        );
+        assert!(!verify_signature_sets(&[invalid, other.clone()]));
+
+        let wrong_domain = SignatureSet::new(
+            &signature,
+            signed_messages(&keypairs, &messages),
+            DOMAIN + 1,
+        );
+        assert!(!verify_signature_sets(&[other, wrong_domain]));
+    }
+
+    #[test]
+    fn invalid_sets() {
+        let keypair = Keypair::random();
+
+        assert!(verify_signature_sets(&[]));
+
+        let empty_signature = AggregateSignature::empty_signature();
+        let empty = SignatureSet::new(
+            &empty_signature,
+            vec![SignedMessage::new(
+                vec![Cow::Borrowed(&keypair.pk)],
+                vec![0; 32],
+            )],
+            DOMAIN,
+        );
+        assert!(!empty.is_valid());
+
+        let mut no_keys = single_set(&keypair, &[0; 32]);
+        no_keys.signed_messages[0].signing_keys.clear();
+        assert!(!no_keys.is_valid());
+
+        let mut no_messages = single_set(&keypair, &[0; 32]);
+        no_messages.signed_messages.clear();
+        assert!(!no_messages.is_valid());
+    }
+}
@@ -111,14 +111,22 @@ impl Comparison {
         }
     }
 
-    pub fn from_slice<T: Debug + PartialEq<T>>(field_name: String, a: &[T], b: &[T]) -> Self {
+    /// Compares each of the values of `a` and `b`, which may be slices or any other collection
+    /// that can be iterated by reference.
+    pub fn from_slice<'a, T, I>(field_name: String, a: I, b: I) -> Self
+    where
+        T: Debug + PartialEq<T> + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        let a: Vec<&T> = a.into_iter().collect();
+        let b: Vec<&T> = b.into_iter().collect();
         let mut children = vec![];
 
         for i in 0..std::cmp::max(a.len(), b.len()) {
             children.push(FieldComparison::new(
                 format!("{:}", i),
-                &a.get(i),
-                &b.get(i),
+                &a.get(i).cloned(),
+                &b.get(i).cloned(),
             ));
         }
 
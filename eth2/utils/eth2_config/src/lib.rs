@@ -2,9 +2,19 @@ use clap::ArgMatches;
 use serde_derive::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::prelude::*;
+use std::io::ErrorKind;
 use std::path::PathBuf;
 use std::time::SystemTime;
-use types::ChainSpec;
+use types::{ChainSpec, MainnetEthSpec, MinimalEthSpec, ScheduledFork, YamlConfig};
+
+/// The file in a testnet directory containing the spec-constants YAML config.
+pub const TESTNET_CONFIG_FILENAME: &str = "config.yaml";
+/// The file in a testnet directory containing the SSZ-encoded genesis state.
+pub const TESTNET_GENESIS_STATE_FILENAME: &str = "genesis.ssz";
+/// The file in a testnet directory containing a YAML list of boot node ENRs.
+pub const TESTNET_BOOT_ENR_FILENAME: &str = "boot_enr.yaml";
+/// The file in a testnet directory containing a YAML list of `ScheduledFork`.
+pub const TESTNET_FORK_SCHEDULE_FILENAME: &str = "fork_schedule.yaml";
 
 /// The core configuration of a Lighthouse beacon node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,7 +61,14 @@ impl Eth2Config {
     ///
     /// Returns an error if arguments are obviously invalid. May succeed even if some values are
     /// invalid.
-    pub fn apply_cli_args(&mut self, args: &ArgMatches) -> Result<(), &'static str> {
+    pub fn apply_cli_args(&mut self, args: &ArgMatches) -> Result<(), String> {
+        // The testnet directory replaces the spec, so it must be applied before any flags which
+        // modify the spec.
+        if let Some(dir) = args.value_of("testnet-dir") {
+            let testnet_dir = Eth2TestnetDir::load(PathBuf::from(dir))?;
+            *self = testnet_dir.eth2_config()?;
+        }
+
         if args.is_present("recent-genesis") {
             self.spec.min_genesis_time = recent_genesis_time()
         }
@@ -60,6 +77,92 @@ impl Eth2Config {
     }
 }
 
+/// The contents of a directory which describes a testnet, containing:
+///
+/// - `config.yaml`: the spec-constants, in the format of the standard spec YAML files.
+/// - `genesis.ssz`: the SSZ-encoded genesis state (optional).
+/// - `boot_enr.yaml`: a YAML list of the ENRs of the boot nodes (optional).
+/// - `fork_schedule.yaml`: a YAML list of the forks to apply to the state (optional).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Eth2TestnetDir {
+    pub yaml_config: YamlConfig,
+    /// The path to the genesis state file, if it exists. The file is not read by `Self::load`.
+    pub genesis_state: Option<PathBuf>,
+    pub boot_enr: Vec<String>,
+    pub fork_schedule: Vec<ScheduledFork>,
+}
+
+impl Eth2TestnetDir {
+    /// Loads the testnet in `dir`, returning an error if the config is missing or invalid.
+    pub fn load(dir: PathBuf) -> Result<Self, String> {
+        let yaml_config = read_yaml(dir.join(TESTNET_CONFIG_FILENAME))?
+            .ok_or_else(|| format!("Testnet directory {:?} has no config file", dir))?;
+
+        let genesis_state = Some(dir.join(TESTNET_GENESIS_STATE_FILENAME)).filter(|p| p.exists());
+
+        let boot_enr = read_yaml(dir.join(TESTNET_BOOT_ENR_FILENAME))?.unwrap_or_default();
+
+        let fork_schedule =
+            read_yaml(dir.join(TESTNET_FORK_SCHEDULE_FILENAME))?.unwrap_or_default();
+
+        Ok(Self {
+            yaml_config,
+            genesis_state,
+            boot_enr,
+            fork_schedule,
+        })
+    }
+
+    /// Returns the title of the spec constants (i.e., the `EthSpec`) which are compatible with
+    /// the config.
+    pub fn spec_constants(&self) -> Result<String, String> {
+        self.eth2_config().map(|config| config.spec_constants)
+    }
+
+    /// Returns an `Eth2Config` with the spec described by the testnet.
+    ///
+    /// Returns an error if the config is invalid or is incompatible with all of the compiled
+    /// spec constants.
+    pub fn eth2_config(&self) -> Result<Eth2Config, String> {
+        let (mut config, result) = if self
+            .yaml_config
+            .validate_eth_spec::<MainnetEthSpec>()
+            .is_ok()
+        {
+            let config = Eth2Config::mainnet();
+            let spec = self
+                .yaml_config
+                .apply_to_chain_spec::<MainnetEthSpec>(&config.spec);
+            (config, spec)
+        } else {
+            let config = Eth2Config::minimal();
+            let spec = self
+                .yaml_config
+                .apply_to_chain_spec::<MinimalEthSpec>(&config.spec);
+            (config, spec)
+        };
+
+        config.spec = result.map_err(|e| format!("Invalid testnet config: {}", e))?;
+        config.spec.boot_nodes = self.boot_enr.clone();
+        config.spec.fork_schedule = self.fork_schedule.clone();
+
+        Ok(config)
+    }
+}
+
+/// Reads a YAML-encoded `T` from `path`, returning `None` if the file does not exist.
+///
+/// Returns an error if the file exists but cannot be opened or parsed.
+fn read_yaml<T: serde::de::DeserializeOwned>(path: PathBuf) -> Result<Option<T>, String> {
+    match File::open(&path) {
+        Ok(file) => serde_yaml::from_reader(file)
+            .map(Some)
+            .map_err(|e| format!("Unable to parse {:?}: {:?}", path, e)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("Unable to open {:?}: {:?}", path, e)),
+    }
+}
+
 /// Returns the system time, mod 30 minutes.
 ///
 /// Used for easily creating testnets.
@@ -111,3 +214,90 @@ where
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::tempdir;
+    use types::{ChainSpec, Epoch};
+
+    fn write_yaml<T: serde::Serialize>(path: &Path, item: &T) {
+        let file = File::create(path).expect("should create file");
+        serde_yaml::to_writer(file, item).expect("should write yaml");
+    }
+
+    #[test]
+    fn loads_testnet_dir() {
+        let dir = tempdir().unwrap();
+        let path = dir.path();
+
+        let yaml_config = YamlConfig::from_spec::<MinimalEthSpec>(&ChainSpec::minimal());
+        let boot_enr = vec!["enr:-Iu4QGuiaVXBEoi4kcLbsoPYX7GTK9ExOODTuqYBp9CyHN".to_string()];
+        let fork_schedule = vec![ScheduledFork {
+            epoch: Epoch::new(8),
+            version: [0, 0, 0, 1],
+        }];
+
+        write_yaml(&path.join(TESTNET_CONFIG_FILENAME), &yaml_config);
+        write_yaml(&path.join(TESTNET_BOOT_ENR_FILENAME), &boot_enr);
+        write_yaml(&path.join(TESTNET_FORK_SCHEDULE_FILENAME), &fork_schedule);
+
+        let testnet_dir = Eth2TestnetDir::load(path.to_path_buf()).expect("should load testnet");
+
+        assert_eq!(
+            testnet_dir,
+            Eth2TestnetDir {
+                yaml_config,
+                genesis_state: None,
+                boot_enr: boot_enr.clone(),
+                fork_schedule: fork_schedule.clone(),
+            }
+        );
+
+        let config = testnet_dir.eth2_config().expect("should build config");
+        assert_eq!(config.spec_constants, "minimal");
+        assert_eq!(config.spec.boot_nodes, boot_enr);
+        assert_eq!(config.spec.fork_schedule, fork_schedule);
+
+        fs::write(path.join(TESTNET_GENESIS_STATE_FILENAME), &[]).unwrap();
+        assert_eq!(
+            Eth2TestnetDir::load(path.to_path_buf())
+                .expect("should load testnet")
+                .genesis_state,
+            Some(path.join(TESTNET_GENESIS_STATE_FILENAME))
+        );
+    }
+
+    #[test]
+    fn optional_files_may_be_missing() {
+        let dir = tempdir().unwrap();
+        let path = dir.path();
+
+        assert!(Eth2TestnetDir::load(path.to_path_buf()).is_err());
+
+        write_yaml(
+            &path.join(TESTNET_CONFIG_FILENAME),
+            &YamlConfig::from_spec::<MinimalEthSpec>(&ChainSpec::minimal()),
+        );
+
+        let testnet_dir = Eth2TestnetDir::load(path.to_path_buf()).expect("should load testnet");
+        assert!(testnet_dir.boot_enr.is_empty());
+        assert!(testnet_dir.fork_schedule.is_empty());
+    }
+
+    #[test]
+    fn invalid_files_are_errors() {
+        let dir = tempdir().unwrap();
+        let path = dir.path();
+
+        write_yaml(
+            &path.join(TESTNET_CONFIG_FILENAME),
+            &YamlConfig::from_spec::<MinimalEthSpec>(&ChainSpec::minimal()),
+        );
+        fs::write(path.join(TESTNET_BOOT_ENR_FILENAME), "not: [a list").unwrap();
+
+        assert!(Eth2TestnetDir::load(path.to_path_buf()).is_err());
+    }
+}
@@ -7,6 +7,8 @@
 //! - `IncCounter`: used to represent an ideally ever-growing, never-shrinking integer (e.g.,
 //! number of block processing requests).
 //! - `IntGauge`: used to represent an varying integer (e.g., number of attestations per block).
+//! - `IntCounterVec` and `IntGaugeVec`: a family of `IntCounter` or `IntGauge`, distinguished by
+//! the values of their labels (e.g., a balance per validator).
 //!
 //! ## Important
 //!
@@ -55,7 +57,7 @@
 
 use prometheus::{HistogramOpts, HistogramTimer, Opts};
 
-pub use prometheus::{Histogram, IntCounter, IntGauge, Result};
+pub use prometheus::{Histogram, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Result};
 
 /// Collect all the metrics for reporting.
 pub fn gather() -> Vec<prometheus::proto::MetricFamily> {
@@ -89,6 +91,32 @@ pub fn try_create_histogram(name: &str, help: &str) -> Result<Histogram> {
     Ok(histogram)
 }
 
+/// Attempts to crate an `IntCounterVec`, returning `Err` if the registry does not accept the
+/// counter (potentially due to naming conflict).
+pub fn try_create_int_counter_vec(
+    name: &str,
+    help: &str,
+    label_names: &[&str],
+) -> Result<IntCounterVec> {
+    let opts = Opts::new(name, help);
+    let counter_vec = IntCounterVec::new(opts, label_names)?;
+    prometheus::register(Box::new(counter_vec.clone()))?;
+    Ok(counter_vec)
+}
+
+/// Attempts to crate an `IntGaugeVec`, returning `Err` if the registry does not accept the gauge
+/// (potentially due to naming conflict).
+pub fn try_create_int_gauge_vec(
+    name: &str,
+    help: &str,
+    label_names: &[&str],
+) -> Result<IntGaugeVec> {
+    let opts = Opts::new(name, help);
+    let gauge_vec = IntGaugeVec::new(opts, label_names)?;
+    prometheus::register(Box::new(gauge_vec.clone()))?;
+    Ok(gauge_vec)
+}
+
 /// Starts a timer for the given `Histogram`, stopping when it gets dropped or given to `stop_timer(..)`.
 pub fn start_timer(histogram: &Result<Histogram>) -> Option<HistogramTimer> {
     if let Ok(histogram) = histogram {
@@ -121,6 +149,30 @@ pub fn set_gauge(gauge: &Result<IntGauge>, value: i64) {
     }
 }
 
+/// Increments the counter with the given `label_values`, creating it if it does not exist.
+pub fn inc_counter_vec(counter_vec: &Result<IntCounterVec>, label_values: &[&str]) {
+    inc_counter_vec_by(counter_vec, label_values, 1)
+}
+
+/// Increments the counter with the given `label_values` by `value`, creating it if it does not
+/// exist.
+pub fn inc_counter_vec_by(counter_vec: &Result<IntCounterVec>, label_values: &[&str], value: i64) {
+    if let Ok(counter_vec) = counter_vec {
+        if let Ok(counter) = counter_vec.get_metric_with_label_values(label_values) {
+            counter.inc_by(value);
+        }
+    }
+}
+
+/// Sets the gauge with the given `label_values`, creating it if it does not exist.
+pub fn set_gauge_vec(gauge_vec: &Result<IntGaugeVec>, label_values: &[&str], value: i64) {
+    if let Ok(gauge_vec) = gauge_vec {
+        if let Ok(gauge) = gauge_vec.get_metric_with_label_values(label_values) {
+            gauge.set(value);
+        }
+    }
+}
+
 /// Sets the value of a `Histogram` manually.
 pub fn observe(histogram: &Result<Histogram>, value: f64) {
     if let Ok(histogram) = histogram {
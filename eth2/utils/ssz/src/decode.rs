@@ -156,6 +156,14 @@ impl<'a> SszDecoderBuilder<'a> {
         Ok(())
     }
 
+    /// Finalizes the builder, returning the slice of `bytes` which encodes each registered item,
+    /// in the order they were registered.
+    pub fn build_slices(mut self) -> Result<Vec<&'a [u8]>, DecodeError> {
+        self.finalize()?;
+
+        Ok(self.items)
+    }
+
     /// Finalizes the builder, returning a `SszDecoder` that may be used to instantiate objects.
     pub fn build(mut self) -> Result<SszDecoder<'a>, DecodeError> {
         self.finalize()?;
@@ -226,7 +234,7 @@ pub fn read_union_index(bytes: &[u8]) -> Result<usize, DecodeError> {
 
 /// Reads a `BYTES_PER_LENGTH_OFFSET`-byte length from `bytes`, where `bytes.len() >=
 /// BYTES_PER_LENGTH_OFFSET`.
-fn read_offset(bytes: &[u8]) -> Result<usize, DecodeError> {
+pub(crate) fn read_offset(bytes: &[u8]) -> Result<usize, DecodeError> {
     decode_offset(bytes.get(0..BYTES_PER_LENGTH_OFFSET).ok_or_else(|| {
         DecodeError::InvalidLengthPrefix {
             len: bytes.len(),
@@ -37,11 +37,14 @@
 mod decode;
 mod encode;
 mod macros;
+mod view;
 
 pub use decode::{
-    impls::decode_list_of_variable_length_items, Decode, DecodeError, SszDecoder, SszDecoderBuilder,
+    impls::decode_list_of_variable_length_items, read_union_index, Decode, DecodeError,
+    SszDecoder, SszDecoderBuilder,
 };
-pub use encode::{Encode, SszEncoder};
+pub use encode::{encode_union_index, Encode, SszEncoder};
+pub use view::{SszListView, SszListViewIter};
 
 /// The number of bytes used to represent an offset.
 pub const BYTES_PER_LENGTH_OFFSET: usize = 4;
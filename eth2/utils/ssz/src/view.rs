@@ -0,0 +1,228 @@
+use super::*;
+use crate::decode::read_offset;
+use std::marker::PhantomData;
+
+/// A borrowed view of the SSZ encoding of a list of `T`, which decodes elements only when they
+/// are accessed.
+///
+/// Instantiating the view validates the offsets of the list (if `T` is variable-length), but does
+/// not decode or copy any elements. Neither accessing nor iterating the elements allocates,
+/// except for any allocation required by `T::from_ssz_bytes`.
+///
+/// ## Example
+///
+/// ```rust
+/// use ssz::{Encode, SszListView};
+///
+/// let bytes = vec![1_u64, 2, 3].as_ssz_bytes();
+///
+/// let view: SszListView<u64> = SszListView::new(&bytes).unwrap();
+///
+/// assert_eq!(view.len(), 3);
+/// assert_eq!(view.get(1), Some(Ok(2)));
+/// assert_eq!(view.iter().map(Result::unwrap).sum::<u64>(), 6);
+/// ```
+pub struct SszListView<'a, T> {
+    bytes: &'a [u8],
+    len: usize,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, T> Clone for SszListView<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for SszListView<'a, T> {}
+
+impl<'a, T: Decode> SszListView<'a, T> {
+    /// Instantiate a view over `bytes`, which are assumed to be the SSZ encoding of a list of
+    /// `T`.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let len = if bytes.is_empty() {
+            0
+        } else if T::is_ssz_fixed_len() {
+            let item_len = T::ssz_fixed_len();
+
+            if bytes.len() % item_len != 0 {
+                return Err(DecodeError::InvalidByteLength {
+                    len: bytes.len(),
+                    expected: (bytes.len() / item_len + 1) * item_len,
+                });
+            }
+
+            bytes.len() / item_len
+        } else {
+            let first_offset = read_offset(bytes)?;
+            let len = first_offset / BYTES_PER_LENGTH_OFFSET;
+
+            // The fixed-length section must be a clean multiple of `BYTES_PER_LENGTH_OFFSET`.
+            if first_offset < BYTES_PER_LENGTH_OFFSET
+                || first_offset != len * BYTES_PER_LENGTH_OFFSET
+                || first_offset > bytes.len()
+            {
+                return Err(DecodeError::OutOfBoundsByte { i: first_offset });
+            }
+
+            // Offsets must be non-decreasing and within `bytes`.
+            let mut previous_offset = first_offset;
+            for i in 0..len {
+                let offset = read_offset(&bytes[i * BYTES_PER_LENGTH_OFFSET..])?;
+
+                if offset < previous_offset || offset > bytes.len() {
+                    return Err(DecodeError::OutOfBoundsByte { i: offset });
+                }
+
+                previous_offset = offset;
+            }
+
+            len
+        };
+
+        Ok(Self {
+            bytes,
+            len,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the number of elements in the list.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if the list does not contain any elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the SSZ bytes of the element at index `i`, if it exists.
+    pub fn get_bytes(&self, i: usize) -> Option<&'a [u8]> {
+        if i >= self.len {
+            return None;
+        }
+
+        if T::is_ssz_fixed_len() {
+            let item_len = T::ssz_fixed_len();
+            self.bytes.get(i * item_len..(i + 1) * item_len)
+        } else {
+            // The offsets were validated when `self` was instantiated.
+            let offset = |i: usize| read_offset(&self.bytes[i * BYTES_PER_LENGTH_OFFSET..]).ok();
+
+            let start = offset(i)?;
+            let end = if i + 1 == self.len {
+                self.bytes.len()
+            } else {
+                offset(i + 1)?
+            };
+
+            self.bytes.get(start..end)
+        }
+    }
+
+    /// Decodes the element at index `i`, if it exists.
+    pub fn get(&self, i: usize) -> Option<Result<T, DecodeError>> {
+        self.get_bytes(i).map(T::from_ssz_bytes)
+    }
+
+    /// Returns an iterator which decodes each element of the list, in order.
+    pub fn iter(&self) -> SszListViewIter<'a, T> {
+        SszListViewIter { view: *self, i: 0 }
+    }
+}
+
+/// An iterator which decodes the elements of a `SszListView`.
+pub struct SszListViewIter<'a, T> {
+    view: SszListView<'a, T>,
+    i: usize,
+}
+
+impl<'a, T: Decode> Iterator for SszListViewIter<'a, T> {
+    type Item = Result<T, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.view.get(self.i)?;
+        self.i += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.view.len() - self.i;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T: Decode> ExactSizeIterator for SszListViewIter<'a, T> {}
+
+impl<'a, T: Decode> IntoIterator for &SszListView<'a, T> {
+    type Item = Result<T, DecodeError>;
+    type IntoIter = SszListViewIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_len_items() {
+        let bytes = vec![1_u16, 2, 3].as_ssz_bytes();
+        let view: SszListView<u16> = SszListView::new(&bytes).unwrap();
+
+        assert_eq!(view.len(), 3);
+        assert_eq!(view.get_bytes(2), Some(&[3, 0][..]));
+        assert_eq!(view.get(3), None);
+        assert_eq!(
+            view.iter().collect::<Result<Vec<_>, _>>(),
+            Ok(vec![1, 2, 3])
+        );
+
+        assert!(SszListView::<u16>::new(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn variable_len_items() {
+        let vec: Vec<Vec<u8>> = vec![vec![1], vec![], vec![2, 3]];
+        let bytes = vec.as_ssz_bytes();
+        let view: SszListView<Vec<u8>> = SszListView::new(&bytes).unwrap();
+
+        assert_eq!(view.len(), 3);
+        assert_eq!(view.iter().len(), 3);
+        assert_eq!(view.get(1), Some(Ok(vec![])));
+        assert_eq!(view.get(3), None);
+        assert_eq!(view.iter().collect::<Result<Vec<_>, _>>(), Ok(vec));
+    }
+
+    #[test]
+    fn empty() {
+        let view: SszListView<Vec<u8>> = SszListView::new(&[]).unwrap();
+
+        assert!(view.is_empty());
+        assert_eq!(view.iter().next(), None);
+    }
+
+    #[test]
+    fn invalid_offsets() {
+        // First offset points into the offsets.
+        assert_eq!(
+            SszListView::<Vec<u8>>::new(&[2, 0, 0, 0, 1]).err(),
+            Some(DecodeError::OutOfBoundsByte { i: 2 })
+        );
+
+        // First offset is beyond the end of the bytes.
+        assert_eq!(
+            SszListView::<Vec<u8>>::new(&[8, 0, 0, 0]).err(),
+            Some(DecodeError::OutOfBoundsByte { i: 8 })
+        );
+
+        // Offsets are decreasing.
+        assert_eq!(
+            SszListView::<Vec<u8>>::new(&[8, 0, 0, 0, 7, 0, 0, 0]).err(),
+            Some(DecodeError::OutOfBoundsByte { i: 7 })
+        );
+    }
+}
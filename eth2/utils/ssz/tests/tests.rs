@@ -1,6 +1,6 @@
 use ethereum_types::H256;
 use ssz::{Decode, DecodeError, Encode};
-use ssz_derive::{Decode, Encode};
+use ssz_derive::{Decode, Encode, SszView};
 
 mod round_trip {
     use super::*;
@@ -377,3 +377,206 @@ mod round_trip {
         round_trip(vec);
     }
 }
+
+mod union {
+    use super::*;
+    use tree_hash::{merkle_root, mix_in_type, TreeHash};
+    use tree_hash_derive::TreeHash;
+
+    #[derive(Debug, PartialEq, Encode, Decode, TreeHash)]
+    enum Union {
+        None,
+        A(u16),
+        B(Vec<u8>),
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode, TreeHash)]
+    enum UnionWithoutNone {
+        A(u8),
+        B(u64),
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Container {
+        a: u8,
+        b: Union,
+        c: UnionWithoutNone,
+    }
+
+    #[test]
+    fn encoding() {
+        assert_eq!(Union::None.as_ssz_bytes(), vec![0, 0, 0, 0]);
+        assert_eq!(Union::A(258).as_ssz_bytes(), vec![1, 0, 0, 0, 2, 1]);
+        assert_eq!(Union::B(vec![]).as_ssz_bytes(), vec![2, 0, 0, 0]);
+        assert_eq!(Union::B(vec![7, 8]).as_ssz_bytes(), vec![2, 0, 0, 0, 7, 8]);
+        assert_eq!(UnionWithoutNone::A(9).as_ssz_bytes(), vec![0, 0, 0, 0, 9]);
+
+        assert!(!<Union as Encode>::is_ssz_fixed_len());
+        assert_eq!(
+            <Union as Encode>::ssz_fixed_len(),
+            ssz::BYTES_PER_LENGTH_OFFSET
+        );
+    }
+
+    #[test]
+    fn container_encoding() {
+        let container = Container {
+            a: 42,
+            b: Union::None,
+            c: UnionWithoutNone::B(1),
+        };
+
+        let bytes = vec![
+            //  | offset        | offset        | selector      | selector      | u64
+            42, 09, 00, 00, 00, 13, 00, 00, 00, 00, 00, 00, 00, 01, 00, 00, 00, 01, 00, 00, 00, 00,
+            00, 00, 00,
+        ];
+
+        assert_eq!(container.as_ssz_bytes(), bytes);
+        assert_eq!(Container::from_ssz_bytes(&bytes), Ok(container));
+    }
+
+    fn round_trip<T: Encode + Decode + std::fmt::Debug + PartialEq>(items: Vec<T>) {
+        for item in items {
+            let encoded = &item.as_ssz_bytes();
+            assert_eq!(T::from_ssz_bytes(&encoded), Ok(item));
+        }
+    }
+
+    #[test]
+    fn union_round_trip() {
+        round_trip(vec![
+            Union::None,
+            Union::A(0),
+            Union::A(u16::max_value()),
+            Union::B(vec![]),
+            Union::B(vec![1, 2, 3]),
+        ]);
+        round_trip(vec![UnionWithoutNone::A(1), UnionWithoutNone::B(2)]);
+    }
+
+    #[test]
+    fn invalid_selector() {
+        assert!(Union::from_ssz_bytes(&[3, 0, 0, 0]).is_err());
+        assert!(UnionWithoutNone::from_ssz_bytes(&[2, 0, 0, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn short_selector() {
+        assert_eq!(
+            Union::from_ssz_bytes(&[0, 0, 0]),
+            Err(DecodeError::InvalidByteLength {
+                len: 3,
+                expected: ssz::BYTES_PER_LENGTH_OFFSET
+            })
+        );
+    }
+
+    #[test]
+    fn none_with_value() {
+        assert!(Union::from_ssz_bytes(&[0, 0, 0, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn value_of_wrong_length() {
+        assert!(Union::from_ssz_bytes(&[1, 0, 0, 0, 1]).is_err());
+        assert!(UnionWithoutNone::from_ssz_bytes(&[0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn tree_hash() {
+        assert_eq!(Union::None.tree_hash_root(), mix_in_type(&[0; 32], 0));
+        assert_eq!(
+            Union::A(42).tree_hash_root(),
+            mix_in_type(&42_u16.tree_hash_root(), 1)
+        );
+        assert_eq!(
+            UnionWithoutNone::B(42).tree_hash_root(),
+            mix_in_type(&merkle_root(&42_u64.to_le_bytes(), 0), 1)
+        );
+        assert_ne!(
+            UnionWithoutNone::A(42).tree_hash_root(),
+            UnionWithoutNone::B(42).tree_hash_root()
+        );
+    }
+}
+
+mod view {
+    use super::*;
+    use ssz::SszListView;
+
+    #[derive(Debug, PartialEq, Encode, Decode, SszView)]
+    struct Inner {
+        a: u16,
+        b: Vec<u16>,
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode, SszView)]
+    struct Outer {
+        a: u64,
+        b: Vec<Inner>,
+        c: [u8; 4],
+        d: Inner,
+    }
+
+    fn outer() -> Outer {
+        Outer {
+            a: 42,
+            b: vec![
+                Inner { a: 1, b: vec![] },
+                Inner {
+                    a: 2,
+                    b: vec![3, 4],
+                },
+            ],
+            c: [5, 6, 7, 8],
+            d: Inner { a: 9, b: vec![10] },
+        }
+    }
+
+    #[test]
+    fn fields() {
+        let outer = outer();
+        let bytes = outer.as_ssz_bytes();
+        let view = OuterView::new(&bytes).unwrap();
+
+        assert_eq!(view.a(), Ok(42));
+        assert_eq!(view.a_bytes(), &42_u64.as_ssz_bytes()[..]);
+        assert_eq!(view.b(), Ok(outer.b));
+        assert_eq!(view.c(), Ok([5, 6, 7, 8]));
+        assert_eq!(view.d(), Ok(outer.d));
+    }
+
+    #[test]
+    fn nested_views() {
+        let bytes = outer().as_ssz_bytes();
+        let view = OuterView::new(&bytes).unwrap();
+
+        let inner = InnerView::new(view.d_bytes()).unwrap();
+        assert_eq!(inner.a(), Ok(9));
+
+        let list: SszListView<Inner> = SszListView::new(view.b_bytes()).unwrap();
+        assert_eq!(list.len(), 2);
+
+        let inner = InnerView::new(list.get_bytes(1).unwrap()).unwrap();
+        assert_eq!(inner.a(), Ok(2));
+        assert_eq!(
+            SszListView::<u16>::new(inner.b_bytes())
+                .unwrap()
+                .iter()
+                .collect::<Result<Vec<_>, _>>(),
+            Ok(vec![3, 4])
+        );
+    }
+
+    #[test]
+    fn invalid_offsets() {
+        let mut bytes = outer().as_ssz_bytes();
+
+        assert!(OuterView::new(&bytes[..12]).is_err());
+
+        // Point the offset of `b` beyond the offset of `d`.
+        bytes[8] = 255;
+        assert!(OuterView::new(&bytes).is_err());
+    }
+}
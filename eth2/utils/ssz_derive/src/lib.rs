@@ -61,10 +61,44 @@ fn should_skip_serializing(field: &syn::Field) -> bool {
     false
 }
 
-/// Implements `ssz::Encode` for some `struct`.
+/// Returns the identifier and type of each variant of an enum which represents an SSZ union,
+/// where the type is `None` for a unit variant.
+///
+/// # Panics
+/// Any variant which does not have exactly one unnamed field, except for a unit variant in the
+/// first position (the union's `None` option), will raise a panic at compile time.
+fn get_union_variants(enum_data: &syn::DataEnum) -> Vec<(&syn::Ident, Option<&syn::Type>)> {
+    let variants: Vec<_> = enum_data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(i, variant)| match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                (&variant.ident, Some(&fields.unnamed[0].ty))
+            }
+            syn::Fields::Unit if i == 0 => (&variant.ident, None),
+            syn::Fields::Unit => {
+                panic!("ssz_derive only supports a unit variant as the first variant of an enum.")
+            }
+            _ => panic!("ssz_derive only supports enum variants with exactly one unnamed field."),
+        })
+        .collect();
+
+    if variants.iter().all(|(_, ty)| ty.is_none()) {
+        panic!("ssz_derive requires an enum to have at least one variant with a field.");
+    }
+
+    variants
+}
+
+/// Implements `ssz::Encode` for some `struct` or `enum`.
 ///
 /// Fields are encoded in the order they are defined.
 ///
+/// An `enum` is encoded as an SSZ union, where the index of each variant is its selector. Each
+/// variant must have exactly one unnamed field, except the first which may be a unit variant
+/// representing the `None` option of the union.
+///
 /// ## Field attributes
 ///
 /// - `#[ssz(skip_serializing)]`: the field will not be serialized.
@@ -77,7 +111,8 @@ pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
 
     let struct_data = match &item.data {
         syn::Data::Struct(s) => s,
-        _ => panic!("ssz_derive only supports structs."),
+        syn::Data::Enum(e) => return ssz_encode_derive_enum(&item, e),
+        _ => panic!("ssz_derive only supports structs and enums."),
     };
 
     let field_idents = get_serializable_named_field_idents(&struct_data);
@@ -124,6 +159,45 @@ pub fn ssz_encode_derive(input: TokenStream) -> TokenStream {
     output.into()
 }
 
+/// Implements `ssz::Encode` for an `enum`, as an SSZ union.
+fn ssz_encode_derive_enum(item: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let arms: Vec<_> = get_union_variants(enum_data)
+        .into_iter()
+        .enumerate()
+        .map(|(selector, (ident, ty))| match ty {
+            Some(_) => quote! {
+                #name::#ident(ref inner) => {
+                    buf.append(&mut ssz::encode_union_index(#selector));
+                    ssz::Encode::ssz_append(inner, buf);
+                }
+            },
+            None => quote! {
+                #name::#ident => buf.append(&mut ssz::encode_union_index(#selector)),
+            },
+        })
+        .collect();
+
+    let output = quote! {
+        impl #impl_generics ssz::Encode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn ssz_append(&self, buf: &mut Vec<u8>) {
+                match *self {
+                    #(
+                        #arms
+                    )*
+                }
+            }
+        }
+    };
+    output.into()
+}
+
 /// Returns true if some field has an attribute declaring it should not be deserialized.
 ///
 /// The field attribute is: `#[ssz(skip_deserializing)]`
@@ -136,10 +210,13 @@ fn should_skip_deserializing(field: &syn::Field) -> bool {
     false
 }
 
-/// Implements `ssz::Decode` for some `struct`.
+/// Implements `ssz::Decode` for some `struct` or `enum`.
 ///
 /// Fields are decoded in the order they are defined.
 ///
+/// An `enum` is decoded as an SSZ union, see `ssz_encode_derive`. An error is returned if the
+/// selector does not match a variant, or if any bytes follow the selector of a unit variant.
+///
 /// ## Field attributes
 ///
 /// - `#[ssz(skip_deserializing)]`: during de-serialization the field will be instantiated from a
@@ -154,7 +231,8 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
 
     let struct_data = match &item.data {
         syn::Data::Struct(s) => s,
-        _ => panic!("ssz_derive only supports structs."),
+        syn::Data::Enum(e) => return ssz_decode_derive_enum(&item, e),
+        _ => panic!("ssz_derive only supports structs and enums."),
     };
 
     let mut register_types = vec![];
@@ -235,3 +313,170 @@ pub fn ssz_decode_derive(input: TokenStream) -> TokenStream {
     };
     output.into()
 }
+
+/// Implements `ssz::Decode` for an `enum`, as an SSZ union.
+fn ssz_decode_derive_enum(item: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let arms: Vec<_> = get_union_variants(enum_data)
+        .into_iter()
+        .enumerate()
+        .map(|(selector, (ident, ty))| match ty {
+            Some(ty) => quote! {
+                #selector => Ok(#name::#ident(<#ty as ssz::Decode>::from_ssz_bytes(value_bytes)?)),
+            },
+            None => quote! {
+                #selector if value_bytes.is_empty() => Ok(#name::#ident),
+                #selector => Err(ssz::DecodeError::BytesInvalid(format!(
+                    "{} bytes following the None selector of {}",
+                    value_bytes.len(),
+                    stringify!(#name)
+                ))),
+            },
+        })
+        .collect();
+
+    let output = quote! {
+        impl #impl_generics ssz::Decode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+                if bytes.len() < ssz::BYTES_PER_LENGTH_OFFSET {
+                    return Err(ssz::DecodeError::InvalidByteLength {
+                        len: bytes.len(),
+                        expected: ssz::BYTES_PER_LENGTH_OFFSET,
+                    });
+                }
+
+                let (index_bytes, value_bytes) = bytes.split_at(ssz::BYTES_PER_LENGTH_OFFSET);
+
+                match ssz::read_union_index(index_bytes)? {
+                    #(
+                        #arms
+                    )*
+                    selector => Err(ssz::DecodeError::BytesInvalid(format!(
+                        "{} is not a valid union selector for {}",
+                        selector,
+                        stringify!(#name)
+                    ))),
+                }
+            }
+        }
+    };
+    output.into()
+}
+
+/// Implements a borrowed, lazily-decoding view of the SSZ encoding of some `struct`.
+///
+/// For a `struct Foo`, generates a `struct FooView<'view>` with the same visibility and generics,
+/// which is instantiated with `FooView::new(&bytes)`. Instantiation only reads the offsets of the
+/// variable-length fields. Then, for each field `foo` of type `T`:
+///
+/// - `view.foo()` decodes and returns the field as a `Result<T, ssz::DecodeError>`.
+/// - `view.foo_bytes()` returns the SSZ bytes of the field, without decoding them. These may be
+/// used with `ssz::SszListView` to lazily read the elements of a list, or with the view of
+/// another container.
+///
+/// ## Field attributes
+///
+/// - `#[ssz(skip_deserializing)]`: the field is not part of the view.
+#[proc_macro_derive(SszView, attributes(ssz))]
+pub fn ssz_view_derive(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as DeriveInput);
+
+    let name = &item.ident;
+    let vis = &item.vis;
+    let (_, ty_generics, _) = item.generics.split_for_impl();
+
+    let struct_data = match &item.data {
+        syn::Data::Struct(s) => s,
+        _ => panic!("ssz_derive only supports views of structs."),
+    };
+
+    let view_name = syn::Ident::new(&format!("{}View", name), name.span());
+
+    let mut view_generics = item.generics.clone();
+    view_generics.params.insert(
+        0,
+        syn::GenericParam::Lifetime(syn::LifetimeDef::new(syn::Lifetime::new(
+            "'view",
+            name.span(),
+        ))),
+    );
+    let (view_impl_generics, view_ty_generics, where_clause) = view_generics.split_for_impl();
+
+    let mut register_types = vec![];
+    let mut accessors = vec![];
+
+    for field in &struct_data.fields {
+        let ident = match &field.ident {
+            Some(ref ident) => ident,
+            _ => panic!("ssz_derive only supports named struct fields."),
+        };
+
+        if should_skip_deserializing(field) {
+            continue;
+        }
+
+        let ty = &field.ty;
+        let index = accessors.len();
+        let bytes_ident = syn::Ident::new(&format!("{}_bytes", ident), ident.span());
+        let decode_doc = format!("Decodes the `{}` field.", ident);
+        let bytes_doc = format!("Returns the SSZ bytes of the `{}` field.", ident);
+
+        register_types.push(quote! {
+            builder.register_type::<#ty>()?;
+        });
+
+        accessors.push(quote! {
+            #[doc = #decode_doc]
+            pub fn #ident(&self) -> Result<#ty, ssz::DecodeError> {
+                <#ty as ssz::Decode>::from_ssz_bytes(self.fields[#index])
+            }
+
+            #[doc = #bytes_doc]
+            pub fn #bytes_ident(&self) -> &'view [u8] {
+                self.fields[#index]
+            }
+        });
+    }
+
+    let view_doc = format!(
+        "A borrowed view of the SSZ encoding of a `{}`, which decodes fields only when they are \
+         accessed.",
+        name
+    );
+
+    let output = quote! {
+        #[doc = #view_doc]
+        #vis struct #view_name #view_impl_generics #where_clause {
+            fields: Vec<&'view [u8]>,
+            _phantom: std::marker::PhantomData<#name #ty_generics>,
+        }
+
+        impl #view_impl_generics #view_name #view_ty_generics #where_clause {
+            /// Instantiate a view over `bytes`, reading only the offsets of the variable-length
+            /// fields.
+            pub fn new(bytes: &'view [u8]) -> Result<Self, ssz::DecodeError> {
+                let mut builder = ssz::SszDecoderBuilder::new(bytes);
+
+                #(
+                    #register_types
+                )*
+
+                Ok(Self {
+                    fields: builder.build_slices()?,
+                    _phantom: std::marker::PhantomData,
+                })
+            }
+
+            #(
+                #accessors
+            )*
+        }
+    };
+    output.into()
+}
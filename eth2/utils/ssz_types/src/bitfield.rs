@@ -606,6 +606,10 @@ impl<N: Unsigned + Clone> tree_hash::TreeHash for Bitfield<Fixed<N>> {
     }
 }
 
+impl<N: Unsigned + Clone> tree_hash::TreeHashProof for Bitfield<Variable<N>> {}
+
+impl<N: Unsigned + Clone> tree_hash::TreeHashProof for Bitfield<Fixed<N>> {}
+
 #[cfg(test)]
 mod bitvector {
     use super::*;
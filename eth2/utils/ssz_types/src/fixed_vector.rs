@@ -1,4 +1,4 @@
-use crate::tree_hash::vec_tree_hash_root;
+use crate::tree_hash::{vec_generalized_index, vec_tree_hash_nodes, vec_tree_hash_root};
 use crate::Error;
 use serde_derive::{Deserialize, Serialize};
 use std::marker::PhantomData;
@@ -146,6 +146,15 @@ impl<T, N: Unsigned> Deref for FixedVector<T, N> {
     }
 }
 
+impl<'a, T, N: Unsigned> IntoIterator for &'a FixedVector<T, N> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.vec.iter()
+    }
+}
+
 impl<T, N: Unsigned> tree_hash::TreeHash for FixedVector<T, N>
 where
     T: tree_hash::TreeHash,
@@ -163,7 +172,20 @@ where
     }
 
     fn tree_hash_root(&self) -> Vec<u8> {
-        vec_tree_hash_root::<T, N>(&self.vec)
+        vec_tree_hash_root::<T, N, _>(&self.vec)
+    }
+}
+
+impl<T, N: Unsigned> tree_hash::TreeHashProof for FixedVector<T, N>
+where
+    T: tree_hash::TreeHashProof,
+{
+    fn generalized_index(path: &[tree_hash::PathElement]) -> Result<u64, tree_hash::ProofError> {
+        vec_generalized_index::<T, N>(path)
+    }
+
+    fn tree_hash_nodes(&self, gindices: &[u64]) -> Result<Vec<Vec<u8>>, tree_hash::ProofError> {
+        vec_tree_hash_nodes::<T, N, _, _>(&self.vec, |i| self.vec.get(i), gindices)
     }
 }
 
@@ -4,6 +4,8 @@
 //! - `VariableList`: A heap-allocated list that cannot grow past a type-level maximum length.
 //! - `BitList`: A heap-allocated bitfield that with a type-level _maximum_ length.
 //! - `BitVector`: A heap-allocated bitfield that with a type-level _fixed__ length.
+//! - `PersistentVector`: A `FixedVector` with structural sharing between clones.
+//! - `PersistentList`: A `VariableList` with structural sharing between clones.
 //!
 //! These structs are required as SSZ serialization and Merklization rely upon type-level lengths
 //! for padding and verification.
@@ -40,11 +42,19 @@
 #[macro_use]
 mod bitfield;
 mod fixed_vector;
+mod persistent_list;
+mod persistent_tree;
+mod persistent_vector;
 mod tree_hash;
 mod variable_list;
 
 pub use bitfield::{BitList, BitVector, Bitfield};
 pub use fixed_vector::FixedVector;
+pub use persistent_list::PersistentList;
+pub use persistent_tree::{
+    Iter as PersistentIter, IterMut as PersistentIterMut, Slices as PersistentSlices,
+};
+pub use persistent_vector::PersistentVector;
 pub use typenum;
 pub use variable_list::VariableList;
 
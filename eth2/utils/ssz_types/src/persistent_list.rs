@@ -0,0 +1,453 @@
+use crate::persistent_tree::{Iter, IterMut, Slices, Tree};
+use crate::tree_hash::{vec_generalized_index, vec_tree_hash_nodes, vec_tree_hash_root};
+use crate::{Error, VariableList};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use typenum::Unsigned;
+
+/// Emulates a SSZ `List`, with structural sharing between clones.
+///
+/// An ordered, heap-allocated, variable-length, homogeneous collection of `T`, with no more than
+/// `N` values.
+///
+/// Has the same SSZ, tree hash and serde representation as `VariableList<T, N>`. However, this
+/// struct is backed by a persistent tree instead of a `Vec`: cloning it is `O(1)` and modifying
+/// a clone only copies the small part of the tree containing the modified value. The trade-off
+/// is that the values are not stored contiguously, so it cannot be dereferenced to a slice.
+///
+/// ## Example
+///
+/// ```
+/// use ssz_types::{PersistentList, typenum};
+///
+/// let mut list: PersistentList<u64, typenum::U4> = PersistentList::from(vec![1, 2, 3]);
+///
+/// // Clones share all of their values with the original.
+/// let mut clone = list.clone();
+///
+/// // Modifying a clone does not affect the original.
+/// clone[0] = 42;
+/// clone.push(4).unwrap();
+/// assert_eq!(list.to_vec(), vec![1, 2, 3]);
+/// assert_eq!(clone.to_vec(), vec![42, 2, 3, 4]);
+///
+/// // Push a value to if it _does_ exceed the maximum.
+/// assert!(clone.push(5).is_err());
+/// ```
+pub struct PersistentList<T, N> {
+    tree: Tree<T>,
+    _phantom: PhantomData<N>,
+}
+
+impl<T, N: Unsigned> PersistentList<T, N> {
+    /// Returns `Ok` if the given `vec` does not exceed the maximum length of `Self`. Otherwise
+    /// returns `Err`.
+    pub fn new(vec: Vec<T>) -> Result<Self, Error> {
+        if vec.len() <= N::to_usize() {
+            Ok(Self {
+                tree: Tree::from(vec),
+                _phantom: PhantomData,
+            })
+        } else {
+            Err(Error::OutOfBounds {
+                i: vec.len(),
+                len: Self::max_len(),
+            })
+        }
+    }
+
+    /// Create an empty list.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of values presently in `self`.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// True if `self` does not contain any values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the type-level maximum length.
+    pub fn max_len() -> usize {
+        N::to_usize()
+    }
+
+    /// Returns a reference to the value at index `i`, if it exists.
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.tree.get(i)
+    }
+
+    /// Returns an iterator over the values in `self`, in order.
+    pub fn iter(&self) -> Iter<T> {
+        self.tree.iter()
+    }
+
+    /// Returns an iterator over contiguous slices of the values in `self`, in order.
+    pub fn slices(&self) -> Slices<T> {
+        self.tree.slices()
+    }
+}
+
+impl<T: Clone, N: Unsigned> PersistentList<T, N> {
+    /// Returns a mutable reference to the value at index `i`, if it exists.
+    ///
+    /// Copies the part of the tree containing the value if it is shared with a clone.
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        self.tree.get_mut(i)
+    }
+
+    /// Returns an iterator which allows modifying each value in `self`, in order.
+    ///
+    /// Copies all parts of the tree which are shared with a clone, so `Self::get_mut` should be
+    /// preferred when only a few values are modified.
+    pub fn iter_mut(&mut self) -> IterMut<T> {
+        self.tree.iter_mut()
+    }
+
+    /// Shortens `self`, keeping the first `len` values and dropping the rest.
+    ///
+    /// Has no effect if `len` is greater than or equal to the length of `self`.
+    pub fn truncate(&mut self, len: usize) {
+        self.tree.truncate(len)
+    }
+
+    /// Removes the last value from `self` and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.tree.pop()
+    }
+
+    /// Appends `value` to the back of `self`.
+    ///
+    /// Returns `Err(())` when appending `value` would exceed the maximum length.
+    pub fn push(&mut self, value: T) -> Result<(), Error> {
+        if self.len() < Self::max_len() {
+            self.tree.push(value);
+            Ok(())
+        } else {
+            Err(Error::OutOfBounds {
+                i: self.len() + 1,
+                len: Self::max_len(),
+            })
+        }
+    }
+
+    /// Returns a `Vec` containing clones of all values in `self`.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.tree.to_vec()
+    }
+}
+
+impl<T, N: Unsigned> From<Vec<T>> for PersistentList<T, N> {
+    fn from(mut vec: Vec<T>) -> Self {
+        vec.truncate(N::to_usize());
+
+        Self {
+            tree: Tree::from(vec),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, N: Unsigned> From<VariableList<T, N>> for PersistentList<T, N> {
+    fn from(list: VariableList<T, N>) -> Self {
+        Self::from(Into::<Vec<T>>::into(list))
+    }
+}
+
+impl<T: Clone, N: Unsigned> From<PersistentList<T, N>> for VariableList<T, N> {
+    fn from(list: PersistentList<T, N>) -> Self {
+        VariableList::from(list.to_vec())
+    }
+}
+
+impl<T: Clone, N: Unsigned> Into<Vec<T>> for PersistentList<T, N> {
+    fn into(self) -> Vec<T> {
+        self.to_vec()
+    }
+}
+
+impl<T, N> Clone for PersistentList<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            tree: self.tree.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, N> Default for PersistentList<T, N> {
+    fn default() -> Self {
+        Self {
+            tree: Tree::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: PartialEq, N> PartialEq for PersistentList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tree == other.tree
+    }
+}
+
+impl<T: std::fmt::Debug, N> std::fmt::Debug for PersistentList<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.tree.fmt(f)
+    }
+}
+
+impl<T, N: Unsigned> Index<usize> for PersistentList<T, N> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, i: usize) -> &T {
+        let len = self.len();
+        self.get(i).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, i
+            )
+        })
+    }
+}
+
+impl<T: Clone, N: Unsigned> IndexMut<usize> for PersistentList<T, N> {
+    #[inline]
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let len = self.len();
+        self.get_mut(i).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, i
+            )
+        })
+    }
+}
+
+impl<'a, T, N: Unsigned> IntoIterator for &'a PersistentList<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: Clone, N: Unsigned> IntoIterator for &'a mut PersistentList<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T: Serialize, N> Serialize for PersistentList<T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.tree.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de>, N: Unsigned> Deserialize<'de> for PersistentList<T, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let vec = Vec::deserialize(deserializer)?;
+
+        Self::new(vec).map_err(|e| D::Error::custom(format!("PersistentList {:?}", e)))
+    }
+}
+
+impl<T, N: Unsigned> tree_hash::TreeHash for PersistentList<T, N>
+where
+    T: tree_hash::TreeHash,
+{
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::List
+    }
+
+    fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("List should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Vec<u8> {
+        let root = vec_tree_hash_root::<T, N, _>(self.iter());
+
+        tree_hash::mix_in_length(&root, self.len())
+    }
+}
+
+impl<T, N: Unsigned> tree_hash::TreeHashProof for PersistentList<T, N>
+where
+    T: tree_hash::TreeHashProof,
+{
+    fn generalized_index(path: &[tree_hash::PathElement]) -> Result<u64, tree_hash::ProofError> {
+        match path {
+            [] => Ok(1),
+            [tree_hash::PathElement::Length] => Ok(3),
+            _ => vec_generalized_index::<T, N>(path)
+                .map(|gindex| tree_hash::concat_generalized_indices(2, gindex)),
+        }
+    }
+
+    fn tree_hash_nodes(&self, gindices: &[u64]) -> Result<Vec<Vec<u8>>, tree_hash::ProofError> {
+        tree_hash::mixed_in_length_nodes(self.len(), gindices, |data_gindices| {
+            vec_tree_hash_nodes::<T, N, _, _>(self.iter(), |i| self.get(i), data_gindices)
+        })
+    }
+}
+
+impl<T, N: Unsigned> ssz::Encode for PersistentList<T, N>
+where
+    T: ssz::Encode,
+{
+    fn is_ssz_fixed_len() -> bool {
+        <VariableList<T, N> as ssz::Encode>::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        <VariableList<T, N> as ssz::Encode>::ssz_fixed_len()
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.tree.ssz_append(buf)
+    }
+}
+
+impl<T, N: Unsigned> ssz::Decode for PersistentList<T, N>
+where
+    T: ssz::Decode,
+{
+    fn is_ssz_fixed_len() -> bool {
+        <VariableList<T, N> as ssz::Decode>::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        <VariableList<T, N> as ssz::Decode>::ssz_fixed_len()
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+        VariableList::from_ssz_bytes(bytes).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ssz::*;
+    use tree_hash::{PathElement, TreeHash, TreeHashProof};
+    use tree_hash_derive::TreeHash;
+    use typenum::*;
+
+    #[test]
+    fn new() {
+        let vec = vec![42; 5];
+        let list: Result<PersistentList<u64, U4>, _> = PersistentList::new(vec.clone());
+        assert!(list.is_err());
+
+        let vec = vec![42; 4];
+        let list: Result<PersistentList<u64, U4>, _> = PersistentList::new(vec.clone());
+        assert!(list.is_ok());
+    }
+
+    #[test]
+    fn indexing() {
+        let mut list: PersistentList<u64, U1024> =
+            PersistentList::from((0..100).collect::<Vec<_>>());
+        let clone = list.clone();
+
+        list[99] = 42;
+        *list.get_mut(0).unwrap() = 7;
+
+        assert_eq!(list[99], 42);
+        assert_eq!(list[0], 7);
+        assert_eq!(list.get(100), None);
+        assert_eq!(clone[99], 99);
+        assert_eq!(clone[0], 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let list: PersistentList<u64, U4> = PersistentList::from(vec![1]);
+        let _ = list[1];
+    }
+
+    #[test]
+    fn truncate_and_pop() {
+        let mut list: PersistentList<u64, U1024> =
+            PersistentList::from((0..100).collect::<Vec<_>>());
+
+        list.truncate(200);
+        assert_eq!(list.len(), 100);
+        list.truncate(10);
+        assert_eq!(list.pop(), Some(9));
+        assert_eq!(list.to_vec(), (0..9).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn equal_to_variable_list() {
+        let vec: Vec<u64> = (0..1_000).collect();
+        let list: PersistentList<u64, U1024> = PersistentList::from(vec.clone());
+        let variable: VariableList<u64, U1024> = VariableList::from(vec.clone());
+
+        assert_eq!(list.as_ssz_bytes(), variable.as_ssz_bytes());
+        assert_eq!(list.tree_hash_root(), variable.tree_hash_root());
+        let paths: &[&[PathElement]] = &[&[PathElement::Index(517)], &[PathElement::Length]];
+        assert_eq!(list.merkle_proof(paths), variable.merkle_proof(paths));
+        assert_eq!(
+            serde_yaml::to_string(&list).unwrap(),
+            serde_yaml::to_string(&variable).unwrap()
+        );
+        let to_variable: VariableList<u64, U1024> = VariableList::from(list.clone());
+        assert_eq!(to_variable, variable);
+        let from_variable: PersistentList<u64, U1024> = PersistentList::from(variable);
+        assert_eq!(from_variable, list);
+    }
+
+    #[derive(Clone, Copy, TreeHash, Default)]
+    struct A {
+        a: u32,
+        b: u32,
+    }
+
+    #[test]
+    fn tree_hash_composite() {
+        let vec = vec![A { a: 1, b: 2 }; 100];
+        let list: PersistentList<A, U256> = PersistentList::from(vec.clone());
+        let variable: VariableList<A, U256> = VariableList::from(vec);
+
+        assert_eq!(list.tree_hash_root(), variable.tree_hash_root());
+    }
+
+    fn round_trip<T: Encode + Decode + std::fmt::Debug + PartialEq>(item: T) {
+        let encoded = &item.as_ssz_bytes();
+        assert_eq!(T::from_ssz_bytes(&encoded), Ok(item));
+    }
+
+    #[test]
+    fn ssz_round_trip() {
+        round_trip::<PersistentList<u16, U0>>(PersistentList::from(vec![]));
+        round_trip::<PersistentList<u16, U8>>(PersistentList::from(vec![1, 2, 3]));
+        round_trip::<PersistentList<Vec<u16>, U8>>(PersistentList::from(vec![
+            vec![1],
+            vec![],
+            vec![2, 3],
+        ]));
+    }
+
+    #[test]
+    fn ssz_decode_too_long() {
+        let bytes = VariableList::<u16, U8>::from(vec![1, 2, 3]).as_ssz_bytes();
+
+        assert!(PersistentList::<u16, U2>::from_ssz_bytes(&bytes).is_err());
+    }
+}
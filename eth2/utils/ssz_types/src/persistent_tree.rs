@@ -0,0 +1,582 @@
+use std::sync::Arc;
+
+/// The number of bits of an index which select a child at each level of the tree.
+const BITS_PER_LEVEL: usize = 5;
+/// The maximum number of children of each node.
+const BRANCHING_FACTOR: usize = 1 << BITS_PER_LEVEL;
+const MASK: usize = BRANCHING_FACTOR - 1;
+
+#[derive(Debug, Clone)]
+enum Node<T> {
+    Leaf(Vec<T>),
+    Branch(Vec<Arc<Node<T>>>),
+}
+
+impl<T> Node<T> {
+    /// Returns an empty node for the given `height` above the leaves.
+    fn empty(height: usize) -> Self {
+        if height == 0 {
+            Node::Leaf(Vec::with_capacity(BRANCHING_FACTOR))
+        } else {
+            Node::Branch(Vec::with_capacity(BRANCHING_FACTOR))
+        }
+    }
+}
+
+/// A persistent, ordered collection of `T` which is cheap to clone.
+///
+/// Values are stored in the leaves of a tree of reference-counted nodes. Cloning a `Tree` only
+/// clones the reference to its root. When a value is modified, only the nodes on the path from
+/// the root to that value are copied (if they are shared with another `Tree`), all other nodes
+/// remain shared.
+///
+/// The tree is always left-packed: all leaves except the last are full.
+pub struct Tree<T> {
+    root: Arc<Node<T>>,
+    /// The number of levels of branches above the leaves.
+    height: usize,
+    len: usize,
+}
+
+impl<T> Tree<T> {
+    /// Returns the number of values in `self`.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns a reference to the value at index `i`, if it exists.
+    pub fn get(&self, i: usize) -> Option<&T> {
+        if i >= self.len {
+            return None;
+        }
+
+        let mut node = &*self.root;
+        for level in (1..=self.height).rev() {
+            node = match node {
+                Node::Branch(children) => &children[(i >> (level * BITS_PER_LEVEL)) & MASK],
+                Node::Leaf(_) => unreachable!("Leaf above the bottom of the tree"),
+            };
+        }
+
+        match node {
+            Node::Leaf(values) => values.get(i & MASK),
+            Node::Branch(_) => unreachable!("Branch at the bottom of the tree"),
+        }
+    }
+
+    /// Returns an iterator over the values in `self`, in order.
+    pub fn iter(&self) -> Iter<T> {
+        match &*self.root {
+            Node::Leaf(values) => Iter {
+                stack: vec![],
+                leaf: Some(values.iter()),
+                remaining: self.len,
+            },
+            Node::Branch(children) => Iter {
+                stack: vec![children.iter()],
+                leaf: None,
+                remaining: self.len,
+            },
+        }
+    }
+
+    /// Returns an iterator over contiguous slices of the values in `self`, in order.
+    ///
+    /// Each slice is a leaf of the tree. All slices except the last contain `BRANCHING_FACTOR`
+    /// values.
+    pub fn slices(&self) -> Slices<T> {
+        match &*self.root {
+            Node::Leaf(values) => Slices {
+                stack: vec![],
+                leaf: Some(&values[..]),
+            },
+            Node::Branch(children) => Slices {
+                stack: vec![children.iter()],
+                leaf: None,
+            },
+        }
+    }
+
+    /// Returns the maximum number of values that may be stored without increasing the height
+    /// of the tree.
+    fn capacity(&self) -> usize {
+        1 << ((self.height + 1) * BITS_PER_LEVEL)
+    }
+}
+
+impl<T: Clone> Tree<T> {
+    /// Returns a mutable reference to the value at index `i`, if it exists.
+    ///
+    /// Copies any nodes on the path to the value which are shared with another `Tree`.
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.len {
+            return None;
+        }
+
+        let mut node = Arc::make_mut(&mut self.root);
+        for level in (1..=self.height).rev() {
+            node = match node {
+                Node::Branch(children) => {
+                    Arc::make_mut(&mut children[(i >> (level * BITS_PER_LEVEL)) & MASK])
+                }
+                Node::Leaf(_) => unreachable!("Leaf above the bottom of the tree"),
+            };
+        }
+
+        match node {
+            Node::Leaf(values) => values.get_mut(i & MASK),
+            Node::Branch(_) => unreachable!("Branch at the bottom of the tree"),
+        }
+    }
+
+    /// Appends `value` to the end of `self`.
+    ///
+    /// Copies any nodes on the path to the new value which are shared with another `Tree`.
+    pub fn push(&mut self, value: T) {
+        if self.len == self.capacity() {
+            let old_root = std::mem::replace(&mut self.root, Arc::new(Node::empty(1)));
+            self.root = Arc::new(Node::Branch(vec![old_root]));
+            self.height += 1;
+        }
+
+        let i = self.len;
+        let mut node = Arc::make_mut(&mut self.root);
+        for level in (1..=self.height).rev() {
+            node = match node {
+                Node::Branch(children) => {
+                    let child = (i >> (level * BITS_PER_LEVEL)) & MASK;
+                    if child == children.len() {
+                        children.push(Arc::new(Node::empty(level - 1)));
+                    }
+                    Arc::make_mut(&mut children[child])
+                }
+                Node::Leaf(_) => unreachable!("Leaf above the bottom of the tree"),
+            };
+        }
+
+        match node {
+            Node::Leaf(values) => values.push(value),
+            Node::Branch(_) => unreachable!("Branch at the bottom of the tree"),
+        }
+
+        self.len += 1;
+    }
+
+    /// Shortens `self`, keeping the first `len` values and dropping the rest.
+    ///
+    /// Has no effect if `len` is greater than or equal to the length of `self`. Copies any nodes
+    /// on the path to the new last value which are shared with another `Tree`.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        if len == 0 {
+            *self = Self::default();
+            return;
+        }
+
+        let last = len - 1;
+        let mut node = Arc::make_mut(&mut self.root);
+        for level in (1..=self.height).rev() {
+            node = match node {
+                Node::Branch(children) => {
+                    let child = (last >> (level * BITS_PER_LEVEL)) & MASK;
+                    children.truncate(child + 1);
+                    Arc::make_mut(&mut children[child])
+                }
+                Node::Leaf(_) => unreachable!("Leaf above the bottom of the tree"),
+            };
+        }
+
+        match node {
+            Node::Leaf(values) => values.truncate((last & MASK) + 1),
+            Node::Branch(_) => unreachable!("Branch at the bottom of the tree"),
+        }
+
+        self.len = len;
+
+        // Remove any levels which are no longer required, so that the height is the same as a
+        // tree built from the remaining values.
+        while self.height > 0 && self.len <= 1 << (self.height * BITS_PER_LEVEL) {
+            let child = match Arc::make_mut(&mut self.root) {
+                Node::Branch(children) => children.swap_remove(0),
+                Node::Leaf(_) => unreachable!("Leaf above the bottom of the tree"),
+            };
+            self.root = child;
+            self.height -= 1;
+        }
+    }
+
+    /// Removes the last value from `self` and returns it, or `None` if it is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.get(self.len.checked_sub(1)?).cloned();
+        self.truncate(self.len - 1);
+        value
+    }
+
+    /// Returns an iterator which allows modifying each value in `self`, in order.
+    ///
+    /// Copies all nodes which are shared with another `Tree` as they are reached, so
+    /// `Self::get_mut` should be preferred when only a few values are modified.
+    pub fn iter_mut(&mut self) -> IterMut<T> {
+        let remaining = self.len;
+
+        match Arc::make_mut(&mut self.root) {
+            Node::Leaf(values) => IterMut {
+                stack: vec![],
+                leaf: Some(values.iter_mut()),
+                remaining,
+            },
+            Node::Branch(children) => IterMut {
+                stack: vec![children.iter_mut()],
+                leaf: None,
+                remaining,
+            },
+        }
+    }
+
+    /// Returns a `Vec` containing clones of all values in `self`.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> From<Vec<T>> for Tree<T> {
+    /// Builds the tree from the bottom up, without cloning any values.
+    fn from(vec: Vec<T>) -> Self {
+        let len = vec.len();
+
+        let mut nodes = group(vec.into_iter(), Node::Leaf);
+        let mut height = 0;
+
+        while nodes.len() > 1 {
+            nodes = group(nodes.into_iter(), Node::Branch);
+            height += 1;
+        }
+
+        Self {
+            root: nodes.pop().unwrap_or_else(|| Arc::new(Node::empty(0))),
+            height,
+            len,
+        }
+    }
+}
+
+/// Splits `items` into nodes of at most `BRANCHING_FACTOR` items.
+fn group<T, U, I, F>(mut items: I, node: F) -> Vec<Arc<Node<T>>>
+where
+    I: Iterator<Item = U>,
+    F: Fn(Vec<U>) -> Node<T>,
+{
+    let mut nodes = vec![];
+
+    loop {
+        let chunk: Vec<U> = items.by_ref().take(BRANCHING_FACTOR).collect();
+
+        if chunk.is_empty() {
+            break nodes;
+        }
+
+        nodes.push(Arc::new(node(chunk)));
+    }
+}
+
+impl<T> Clone for Tree<T> {
+    /// Only clones the reference to the root of the tree, all nodes are shared.
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            height: self.height,
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Default for Tree<T> {
+    fn default() -> Self {
+        Self::from(vec![])
+    }
+}
+
+impl<T: PartialEq> PartialEq for Tree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Tree<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: ssz::Encode> Tree<T> {
+    /// Appends the SSZ encoding of the values of `self` to `buf`, as per `Vec<T>`.
+    pub fn ssz_append(&self, buf: &mut Vec<u8>) {
+        if T::is_ssz_fixed_len() {
+            buf.reserve(T::ssz_fixed_len() * self.len());
+
+            for item in self.iter() {
+                item.ssz_append(buf);
+            }
+        } else {
+            let mut encoder = ssz::SszEncoder::list(buf, self.len() * ssz::BYTES_PER_LENGTH_OFFSET);
+
+            for item in self.iter() {
+                encoder.append(item);
+            }
+
+            encoder.finalize();
+        }
+    }
+}
+
+/// An iterator over the values of a `Tree`.
+pub struct Iter<'a, T> {
+    /// Iterators over the remaining children of each branch between the root and the current
+    /// leaf.
+    stack: Vec<std::slice::Iter<'a, Arc<Node<T>>>>,
+    /// An iterator over the remaining values of the current leaf, if any.
+    leaf: Option<std::slice::Iter<'a, T>>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(value) = self.leaf.as_mut().and_then(Iterator::next) {
+                self.remaining -= 1;
+                return Some(value);
+            }
+
+            match self.stack.last_mut()?.next().map(|node| &**node) {
+                Some(Node::Leaf(values)) => self.leaf = Some(values.iter()),
+                Some(Node::Branch(children)) => self.stack.push(children.iter()),
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// An iterator over the values of a `Tree`, which allows modifying each value.
+pub struct IterMut<'a, T> {
+    /// Iterators over the remaining children of each branch between the root and the current
+    /// leaf.
+    stack: Vec<std::slice::IterMut<'a, Arc<Node<T>>>>,
+    /// An iterator over the remaining values of the current leaf, if any.
+    leaf: Option<std::slice::IterMut<'a, T>>,
+    remaining: usize,
+}
+
+impl<'a, T: Clone> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        loop {
+            if let Some(value) = self.leaf.as_mut().and_then(Iterator::next) {
+                self.remaining -= 1;
+                return Some(value);
+            }
+
+            match self.stack.last_mut()?.next().map(Arc::make_mut) {
+                Some(Node::Leaf(values)) => self.leaf = Some(values.iter_mut()),
+                Some(Node::Branch(children)) => self.stack.push(children.iter_mut()),
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T: Clone> ExactSizeIterator for IterMut<'a, T> {}
+
+/// An iterator over the leaves of a `Tree`, as slices of values.
+pub struct Slices<'a, T> {
+    /// Iterators over the remaining children of each branch between the root and the next leaf.
+    stack: Vec<std::slice::Iter<'a, Arc<Node<T>>>>,
+    /// The root of the tree, if it is a leaf which has not yet been returned.
+    leaf: Option<&'a [T]>,
+}
+
+impl<'a, T> Iterator for Slices<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        if let Some(values) = self.leaf.take() {
+            return Some(values).filter(|values| !values.is_empty());
+        }
+
+        loop {
+            match self.stack.last_mut()?.next().map(|node| &**node) {
+                Some(Node::Leaf(values)) => return Some(&values[..]),
+                Some(Node::Branch(children)) => self.stack.push(children.iter()),
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_vec_and_iter() {
+        for len in &[0, 1, 31, 32, 33, 1023, 1024, 1025, 40_000] {
+            let vec: Vec<usize> = (0..*len).collect();
+            let tree = Tree::from(vec.clone());
+
+            assert_eq!(tree.len(), *len);
+            assert_eq!(tree.iter().len(), *len);
+            assert_eq!(tree.to_vec(), vec);
+            assert_eq!(tree.get(*len), None);
+
+            for i in (0..*len).step_by(7) {
+                assert_eq!(tree.get(i), Some(&i));
+            }
+        }
+    }
+
+    #[test]
+    fn push_matches_from_vec() {
+        let mut tree = Tree::default();
+
+        for i in 0..2_000 {
+            tree.push(i);
+            assert_eq!(tree.len(), i + 1);
+            assert_eq!(tree.get(i), Some(&i));
+        }
+
+        assert_eq!(tree, Tree::from((0..2_000).collect::<Vec<_>>()));
+    }
+
+    #[test]
+    fn clones_share_unmodified_nodes() {
+        let original = Tree::from((0..5_000).collect::<Vec<usize>>());
+        let mut clone = original.clone();
+
+        assert!(Arc::ptr_eq(&original.root, &clone.root));
+
+        *clone.get_mut(0).unwrap() = 42;
+        clone.push(5_000);
+
+        assert_eq!(original.get(0), Some(&0));
+        assert_eq!(original.len(), 5_000);
+        assert_eq!(clone.get(0), Some(&42));
+        assert_eq!(clone.get(5_000), Some(&5_000));
+
+        // Only the first and last branches of the root were copied.
+        match (&*original.root, &*clone.root) {
+            (Node::Branch(original), Node::Branch(clone)) => {
+                assert!(!Arc::ptr_eq(&original[0], &clone[0]));
+                assert!(Arc::ptr_eq(&original[2], &clone[2]));
+                assert!(!Arc::ptr_eq(&original[4], &clone[4]));
+            }
+            _ => panic!("Root should be a branch"),
+        }
+    }
+
+    #[test]
+    fn truncate_matches_from_vec() {
+        for len in &[0, 1, 31, 32, 33, 1023, 1024, 1025, 5_000] {
+            for new_len in &[0, 1, 32, 33, 1024, 1025, 4_999] {
+                let mut tree = Tree::from((0..*len).collect::<Vec<usize>>());
+                tree.truncate(*new_len);
+
+                let expected = Tree::from((0..(*len).min(*new_len)).collect::<Vec<usize>>());
+                assert_eq!(tree, expected);
+                assert_eq!(tree.height, expected.height);
+
+                // The tree remains left-packed when values are pushed after truncation.
+                tree.push(42);
+                assert_eq!(tree.get(tree.len() - 1), Some(&42));
+                assert_eq!(
+                    tree.slices().count(),
+                    (tree.len() + MASK) / BRANCHING_FACTOR
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn truncate_and_pop_do_not_modify_clones() {
+        let original = Tree::from((0..100).collect::<Vec<usize>>());
+        let mut clone = original.clone();
+
+        assert_eq!(clone.pop(), Some(99));
+        clone.truncate(40);
+        assert_eq!(clone.pop(), Some(39));
+
+        assert_eq!(clone.to_vec(), (0..39).collect::<Vec<_>>());
+        assert_eq!(original.to_vec(), (0..100).collect::<Vec<_>>());
+
+        let mut empty: Tree<usize> = Tree::default();
+        assert_eq!(empty.pop(), None);
+    }
+
+    #[test]
+    fn iter_mut_copies_shared_nodes() {
+        let original = Tree::from((0..1_000).collect::<Vec<usize>>());
+        let mut clone = original.clone();
+
+        assert_eq!(clone.iter_mut().len(), 1_000);
+        for value in clone.iter_mut() {
+            *value *= 2;
+        }
+
+        assert_eq!(
+            clone.to_vec(),
+            (0..1_000).map(|i| i * 2).collect::<Vec<_>>()
+        );
+        assert_eq!(original.to_vec(), (0..1_000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn slices() {
+        let tree: Tree<usize> = Tree::default();
+        assert_eq!(tree.slices().count(), 0);
+
+        let vec: Vec<usize> = (0..1_000).collect();
+        let tree = Tree::from(vec.clone());
+        let slices: Vec<&[usize]> = tree.slices().collect();
+
+        assert_eq!(slices.len(), 32);
+        assert!(slices[..31].iter().all(|s| s.len() == BRANCHING_FACTOR));
+        assert_eq!(slices.concat(), vec);
+    }
+
+    #[test]
+    fn get_mut_copies_shared_leaf_only() {
+        let original = Tree::from((0..100).collect::<Vec<usize>>());
+        let mut clone = original.clone();
+
+        *clone.get_mut(99).unwrap() = 0;
+
+        match (&*original.root, &*clone.root) {
+            (Node::Branch(original), Node::Branch(clone)) => {
+                assert!(Arc::ptr_eq(&original[0], &clone[0]));
+                assert!(!Arc::ptr_eq(&original[3], &clone[3]));
+            }
+            _ => panic!("Root should be a branch"),
+        }
+        assert_eq!(original.get(99), Some(&99));
+    }
+}
@@ -0,0 +1,373 @@
+use crate::persistent_tree::{Iter, IterMut, Slices, Tree};
+use crate::tree_hash::{vec_generalized_index, vec_tree_hash_nodes, vec_tree_hash_root};
+use crate::{Error, FixedVector};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use typenum::Unsigned;
+
+/// Emulates a SSZ `Vector`, with structural sharing between clones.
+///
+/// An ordered, heap-allocated, fixed-length, homogeneous collection of `T`, with `N` values.
+///
+/// Has the same SSZ, tree hash and serde representation as `FixedVector<T, N>`. However, this
+/// struct is backed by a persistent tree instead of a `Vec`: cloning it is `O(1)` and modifying
+/// a clone only copies the small part of the tree containing the modified value. The trade-off
+/// is that the values are not stored contiguously, so it cannot be dereferenced to a slice.
+///
+/// ## Example
+///
+/// ```
+/// use ssz_types::{PersistentVector, typenum};
+///
+/// // Missing values are created using `std::default::Default`.
+/// let vector: PersistentVector<u64, typenum::U4> = PersistentVector::from(vec![1, 2]);
+/// assert_eq!(vector.to_vec(), vec![1, 2, 0, 0]);
+///
+/// // Modifying a clone does not affect the original.
+/// let mut clone = vector.clone();
+/// clone[3] = 42;
+/// assert_eq!(vector[3], 0);
+/// assert_eq!(clone[3], 42);
+/// ```
+pub struct PersistentVector<T, N> {
+    tree: Tree<T>,
+    _phantom: PhantomData<N>,
+}
+
+impl<T, N: Unsigned> PersistentVector<T, N> {
+    /// Returns `Ok` if the given `vec` equals the fixed length of `Self`. Otherwise returns
+    /// `Err`.
+    pub fn new(vec: Vec<T>) -> Result<Self, Error> {
+        if vec.len() == Self::capacity() {
+            Ok(Self {
+                tree: Tree::from(vec),
+                _phantom: PhantomData,
+            })
+        } else {
+            Err(Error::OutOfBounds {
+                i: vec.len(),
+                len: Self::capacity(),
+            })
+        }
+    }
+
+    /// Create a new vector filled with clones of `elem`.
+    pub fn from_elem(elem: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            tree: Tree::from(vec![elem; N::to_usize()]),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Identical to `self.capacity`, returns the type-level constant length.
+    ///
+    /// Exists for compatibility with `Vec`.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// True if the type-level constant length of `self` is zero.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the type-level constant length.
+    pub fn capacity() -> usize {
+        N::to_usize()
+    }
+
+    /// Returns a reference to the value at index `i`, if it exists.
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.tree.get(i)
+    }
+
+    /// Returns an iterator over the values in `self`, in order.
+    pub fn iter(&self) -> Iter<T> {
+        self.tree.iter()
+    }
+
+    /// Returns an iterator over contiguous slices of the values in `self`, in order.
+    pub fn slices(&self) -> Slices<T> {
+        self.tree.slices()
+    }
+}
+
+impl<T: Clone, N: Unsigned> PersistentVector<T, N> {
+    /// Returns a mutable reference to the value at index `i`, if it exists.
+    ///
+    /// Copies the part of the tree containing the value if it is shared with a clone.
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut T> {
+        self.tree.get_mut(i)
+    }
+
+    /// Returns an iterator which allows modifying each value in `self`, in order.
+    ///
+    /// Copies all parts of the tree which are shared with a clone, so `Self::get_mut` should be
+    /// preferred when only a few values are modified.
+    pub fn iter_mut(&mut self) -> IterMut<T> {
+        self.tree.iter_mut()
+    }
+
+    /// Returns a `Vec` containing clones of all values in `self`.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.tree.to_vec()
+    }
+}
+
+impl<T: Default, N: Unsigned> From<Vec<T>> for PersistentVector<T, N> {
+    fn from(mut vec: Vec<T>) -> Self {
+        vec.resize_with(Self::capacity(), Default::default);
+
+        Self {
+            tree: Tree::from(vec),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Default, N: Unsigned> From<FixedVector<T, N>> for PersistentVector<T, N> {
+    fn from(vector: FixedVector<T, N>) -> Self {
+        Self::from(Into::<Vec<T>>::into(vector))
+    }
+}
+
+impl<T: Clone + Default, N: Unsigned> From<PersistentVector<T, N>> for FixedVector<T, N> {
+    fn from(vector: PersistentVector<T, N>) -> Self {
+        FixedVector::from(vector.to_vec())
+    }
+}
+
+impl<T: Clone, N: Unsigned> Into<Vec<T>> for PersistentVector<T, N> {
+    fn into(self) -> Vec<T> {
+        self.to_vec()
+    }
+}
+
+impl<T, N> Clone for PersistentVector<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            tree: self.tree.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, N> Default for PersistentVector<T, N> {
+    fn default() -> Self {
+        Self {
+            tree: Tree::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: PartialEq, N> PartialEq for PersistentVector<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tree == other.tree
+    }
+}
+
+impl<T: std::fmt::Debug, N> std::fmt::Debug for PersistentVector<T, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.tree.fmt(f)
+    }
+}
+
+impl<T, N: Unsigned> Index<usize> for PersistentVector<T, N> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, i: usize) -> &T {
+        let len = self.len();
+        self.get(i).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, i
+            )
+        })
+    }
+}
+
+impl<T: Clone, N: Unsigned> IndexMut<usize> for PersistentVector<T, N> {
+    #[inline]
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        let len = self.len();
+        self.get_mut(i).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, i
+            )
+        })
+    }
+}
+
+impl<'a, T, N: Unsigned> IntoIterator for &'a PersistentVector<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: Clone, N: Unsigned> IntoIterator for &'a mut PersistentVector<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T: Serialize, N> Serialize for PersistentVector<T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.tree.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de>, N: Unsigned> Deserialize<'de> for PersistentVector<T, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let vec = Vec::deserialize(deserializer)?;
+
+        Self::new(vec).map_err(|e| D::Error::custom(format!("PersistentVector {:?}", e)))
+    }
+}
+
+impl<T, N: Unsigned> tree_hash::TreeHash for PersistentVector<T, N>
+where
+    T: tree_hash::TreeHash,
+{
+    fn tree_hash_type() -> tree_hash::TreeHashType {
+        tree_hash::TreeHashType::Vector
+    }
+
+    fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+        unreachable!("Vector should never be packed.")
+    }
+
+    fn tree_hash_packing_factor() -> usize {
+        unreachable!("Vector should never be packed.")
+    }
+
+    fn tree_hash_root(&self) -> Vec<u8> {
+        vec_tree_hash_root::<T, N, _>(self.iter())
+    }
+}
+
+impl<T, N: Unsigned> tree_hash::TreeHashProof for PersistentVector<T, N>
+where
+    T: tree_hash::TreeHashProof,
+{
+    fn generalized_index(path: &[tree_hash::PathElement]) -> Result<u64, tree_hash::ProofError> {
+        vec_generalized_index::<T, N>(path)
+    }
+
+    fn tree_hash_nodes(&self, gindices: &[u64]) -> Result<Vec<Vec<u8>>, tree_hash::ProofError> {
+        vec_tree_hash_nodes::<T, N, _, _>(self.iter(), |i| self.get(i), gindices)
+    }
+}
+
+impl<T, N: Unsigned> ssz::Encode for PersistentVector<T, N>
+where
+    T: ssz::Encode,
+{
+    fn is_ssz_fixed_len() -> bool {
+        <FixedVector<T, N> as ssz::Encode>::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        <FixedVector<T, N> as ssz::Encode>::ssz_fixed_len()
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        self.tree.ssz_append(buf)
+    }
+}
+
+impl<T, N: Unsigned> ssz::Decode for PersistentVector<T, N>
+where
+    T: ssz::Decode + Default,
+{
+    fn is_ssz_fixed_len() -> bool {
+        <FixedVector<T, N> as ssz::Decode>::is_ssz_fixed_len()
+    }
+
+    fn ssz_fixed_len() -> usize {
+        <FixedVector<T, N> as ssz::Decode>::ssz_fixed_len()
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
+        FixedVector::from_ssz_bytes(bytes).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ssz::*;
+    use tree_hash::{PathElement, TreeHash, TreeHashProof};
+    use typenum::*;
+
+    #[test]
+    fn new() {
+        let vec = vec![42; 3];
+        let vector: Result<PersistentVector<u64, U4>, _> = PersistentVector::new(vec.clone());
+        assert!(vector.is_err());
+
+        let vec = vec![42; 4];
+        let vector: Result<PersistentVector<u64, U4>, _> = PersistentVector::new(vec.clone());
+        assert!(vector.is_ok());
+    }
+
+    #[test]
+    fn indexing() {
+        let mut vector: PersistentVector<u64, U8192> = PersistentVector::from(vec![1, 2]);
+        let clone = vector.clone();
+
+        vector[1] = 3;
+        vector[8191] = 4;
+
+        assert_eq!(vector.len(), 8192);
+        assert_eq!(vector[1], 3);
+        assert_eq!(vector[8191], 4);
+        assert_eq!(vector.get(8192), None);
+        assert_eq!(clone[1], 2);
+        assert_eq!(clone[8191], 0);
+    }
+
+    #[test]
+    fn equal_to_fixed_vector() {
+        let vec: Vec<u64> = (0..1_000).collect();
+        let vector: PersistentVector<u64, U1024> = PersistentVector::from(vec.clone());
+        let fixed: FixedVector<u64, U1024> = FixedVector::from(vec.clone());
+
+        assert_eq!(vector.as_ssz_bytes(), fixed.as_ssz_bytes());
+        assert_eq!(vector.tree_hash_root(), fixed.tree_hash_root());
+        let paths: &[&[PathElement]] = &[&[PathElement::Index(3)], &[PathElement::Index(999)]];
+        assert_eq!(vector.merkle_proof(paths), fixed.merkle_proof(paths));
+        assert_eq!(
+            serde_yaml::to_string(&vector).unwrap(),
+            serde_yaml::to_string(&fixed).unwrap()
+        );
+        let to_fixed: FixedVector<u64, U1024> = FixedVector::from(vector.clone());
+        assert_eq!(to_fixed, fixed);
+        let from_fixed: PersistentVector<u64, U1024> = PersistentVector::from(fixed);
+        assert_eq!(from_fixed, vector);
+    }
+
+    #[test]
+    fn ssz_round_trip() {
+        let vector: PersistentVector<u16, U8> = PersistentVector::from(vec![42; 8]);
+        let encoded = vector.as_ssz_bytes();
+
+        assert_eq!(
+            PersistentVector::<u16, U8>::from_ssz_bytes(&encoded),
+            Ok(vector)
+        );
+    }
+}
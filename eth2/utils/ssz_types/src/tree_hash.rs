@@ -1,41 +1,120 @@
-use tree_hash::{merkle_root, TreeHash, TreeHashType, BYTES_PER_CHUNK};
+use tree_hash::{
+    concat_generalized_indices, merkle_root, merkle_tree_nodes, tree_depth, PathElement,
+    ProofError, TreeHash, TreeHashProof, TreeHashType, BYTES_PER_CHUNK,
+};
 use typenum::Unsigned;
 
 /// A helper function providing common functionality between the `TreeHash` implementations for
-/// `FixedVector` and `VariableList`.
-pub fn vec_tree_hash_root<T, N>(vec: &[T]) -> Vec<u8>
+/// `FixedVector`, `VariableList`, `PersistentVector` and `PersistentList`.
+pub fn vec_tree_hash_root<'a, T, N, I>(items: I) -> Vec<u8>
 where
-    T: TreeHash,
+    T: TreeHash + 'a,
     N: Unsigned,
+    I: IntoIterator<Item = &'a T>,
+    I::IntoIter: ExactSizeIterator,
 {
-    let (leaves, minimum_chunk_count) = match T::tree_hash_type() {
+    merkle_root(&vec_tree_hash_leaves(items), minimum_chunk_count::<T, N>())
+}
+
+/// A helper function providing common functionality between the `TreeHashProof::generalized_index`
+/// implementations for `FixedVector`, `VariableList`, `PersistentVector` and `PersistentList`.
+///
+/// Returns the generalized index relative to the root of the tree of the values (i.e., without
+/// the length mixed in).
+pub fn vec_generalized_index<T, N>(path: &[PathElement]) -> Result<u64, ProofError>
+where
+    T: TreeHashProof,
+    N: Unsigned,
+{
+    let first_leaf: u64 = 1 << tree_depth(minimum_chunk_count::<T, N>());
+
+    match path.split_first() {
+        None => Ok(1),
+        Some((PathElement::Index(i), rest)) if *i < N::to_usize() => match T::tree_hash_type() {
+            TreeHashType::Basic if rest.is_empty() => {
+                Ok(first_leaf + (i / T::tree_hash_packing_factor()) as u64)
+            }
+            TreeHashType::Basic => Err(ProofError::InvalidPath(format!("{:?}", rest[0]))),
+            TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => Ok(
+                concat_generalized_indices(first_leaf + *i as u64, T::generalized_index(rest)?),
+            ),
+        },
+        Some((element, _)) => Err(ProofError::InvalidPath(format!("{:?}", element))),
+    }
+}
+
+/// A helper function providing common functionality between the `TreeHashProof::tree_hash_nodes`
+/// implementations for `FixedVector`, `VariableList`, `PersistentVector` and `PersistentList`.
+///
+/// `get` must return the value at the given index of `items`. The `gindices` are relative to the
+/// root of the tree of the values (i.e., without the length mixed in).
+pub fn vec_tree_hash_nodes<'a, T, N, I, G>(
+    items: I,
+    get: G,
+    gindices: &[u64],
+) -> Result<Vec<Vec<u8>>, ProofError>
+where
+    T: TreeHashProof + 'a,
+    N: Unsigned,
+    I: IntoIterator<Item = &'a T>,
+    I::IntoIter: ExactSizeIterator,
+    G: Fn(usize) -> Option<&'a T>,
+{
+    merkle_tree_nodes(
+        || vec_tree_hash_leaves(items),
+        tree_depth(minimum_chunk_count::<T, N>()),
+        gindices,
+        |i, sub_gindices| match T::tree_hash_type() {
+            TreeHashType::Basic => None,
+            TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => {
+                get(i).map(|item| item.tree_hash_nodes(sub_gindices))
+            }
+        },
+    )
+}
+
+/// Returns the concatenated leaves of the tree of `items`, without any padding.
+fn vec_tree_hash_leaves<'a, T, I>(items: I) -> Vec<u8>
+where
+    T: TreeHash + 'a,
+    I: IntoIterator<Item = &'a T>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let items = items.into_iter();
+
+    match T::tree_hash_type() {
         TreeHashType::Basic => {
             let mut leaves =
-                Vec::with_capacity((BYTES_PER_CHUNK / T::tree_hash_packing_factor()) * vec.len());
+                Vec::with_capacity((BYTES_PER_CHUNK / T::tree_hash_packing_factor()) * items.len());
 
-            for item in vec {
+            for item in items {
                 leaves.append(&mut item.tree_hash_packed_encoding());
             }
 
-            let values_per_chunk = T::tree_hash_packing_factor();
-            let minimum_chunk_count = (N::to_usize() + values_per_chunk - 1) / values_per_chunk;
-
-            (leaves, minimum_chunk_count)
+            leaves
         }
         TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => {
-            let mut leaves = Vec::with_capacity(vec.len() * BYTES_PER_CHUNK);
+            let mut leaves = Vec::with_capacity(items.len() * BYTES_PER_CHUNK);
 
-            for item in vec {
+            for item in items {
                 leaves.append(&mut item.tree_hash_root())
             }
 
-            let minimum_chunk_count = N::to_usize();
-
-            (leaves, minimum_chunk_count)
+            leaves
         }
-    };
+    }
+}
 
-    merkle_root(&leaves, minimum_chunk_count)
+/// Returns the number of leaves in the tree of a list or vector of `T` with a maximum length of
+/// `N`.
+fn minimum_chunk_count<T: TreeHash, N: Unsigned>() -> usize {
+    match T::tree_hash_type() {
+        TreeHashType::Basic => {
+            let values_per_chunk = T::tree_hash_packing_factor();
+            (N::to_usize() + values_per_chunk - 1) / values_per_chunk
+        }
+        TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => N::to_usize(),
+    }
 }
 
 /// A helper function providing common functionality for finding the Merkle root of some bytes that
@@ -1,4 +1,4 @@
-use crate::tree_hash::vec_tree_hash_root;
+use crate::tree_hash::{vec_generalized_index, vec_tree_hash_nodes, vec_tree_hash_root};
 use crate::Error;
 use serde_derive::{Deserialize, Serialize};
 use std::marker::PhantomData;
@@ -190,12 +190,32 @@ where
     }
 
     fn tree_hash_root(&self) -> Vec<u8> {
-        let root = vec_tree_hash_root::<T, N>(&self.vec);
+        let root = vec_tree_hash_root::<T, N, _>(&self.vec);
 
         tree_hash::mix_in_length(&root, self.len())
     }
 }
 
+impl<T, N: Unsigned> tree_hash::TreeHashProof for VariableList<T, N>
+where
+    T: tree_hash::TreeHashProof,
+{
+    fn generalized_index(path: &[tree_hash::PathElement]) -> Result<u64, tree_hash::ProofError> {
+        match path {
+            [] => Ok(1),
+            [tree_hash::PathElement::Length] => Ok(3),
+            _ => vec_generalized_index::<T, N>(path)
+                .map(|gindex| tree_hash::concat_generalized_indices(2, gindex)),
+        }
+    }
+
+    fn tree_hash_nodes(&self, gindices: &[u64]) -> Result<Vec<Vec<u8>>, tree_hash::ProofError> {
+        tree_hash::mixed_in_length_nodes(self.len(), gindices, |data_gindices| {
+            vec_tree_hash_nodes::<T, N, _, _>(&self.vec, |i| self.vec.get(i), data_gindices)
+        })
+    }
+}
+
 impl<T, N: Unsigned> ssz::Encode for VariableList<T, N>
 where
     T: ssz::Encode,
@@ -236,8 +256,8 @@ where
 mod test {
     use super::*;
     use ssz::*;
-    use tree_hash::{merkle_root, TreeHash};
-    use tree_hash_derive::TreeHash;
+    use tree_hash::{merkle_root, PathElement, ProofError, TreeHash, TreeHashProof};
+    use tree_hash_derive::{TreeHash, TreeHashProof};
     use typenum::*;
 
     #[test]
@@ -348,7 +368,7 @@ mod test {
         assert_eq!(fixed.tree_hash_root(), root_with_length(&source, 16));
     }
 
-    #[derive(Clone, Copy, TreeHash, Default)]
+    #[derive(Clone, Copy, TreeHash, TreeHashProof, Default)]
     struct A {
         a: u32,
         b: u32,
@@ -419,4 +439,41 @@ mod test {
             );
         }
     }
+    #[test]
+    fn merkle_proofs() {
+        let list: VariableList<u16, U64> = VariableList::from((0..40).collect::<Vec<_>>());
+        let root = list.tree_hash_root();
+
+        // Element 17 is in the second of four chunks of 16 values.
+        let path = [PathElement::Index(17)];
+        assert_eq!(VariableList::<u16, U64>::generalized_index(&path), Ok(9));
+        let proof = list.merkle_proof(&[&path, &[PathElement::Length]]).unwrap();
+        let chunk: Vec<u8> = (16..32_u16)
+            .flat_map(|v| v.to_le_bytes().to_vec())
+            .collect();
+        assert_eq!(proof.leaves[0].as_bytes(), &chunk[..]);
+        assert_eq!(proof.leaves[1].as_bytes()[0], 40);
+        assert_eq!(proof.root().unwrap().as_bytes(), &root[..]);
+
+        assert!(list.merkle_proof(&[&[PathElement::Index(64)]]).is_err());
+        assert!(list
+            .merkle_proof(&[&[PathElement::Index(1), PathElement::Length]])
+            .is_err());
+
+        let list: VariableList<A, U8> = VariableList::from(vec![A { a: 1, b: 2 }; 3]);
+        let root = list.tree_hash_root();
+
+        let path = [PathElement::Index(2), PathElement::Field("b")];
+        let proof = list.merkle_proof(&[&path]).unwrap();
+        assert_eq!(proof.gindices, vec![37]);
+        assert_eq!(proof.leaves[0].as_bytes(), &2_u32.tree_hash_root()[..]);
+        assert_eq!(proof.root().unwrap().as_bytes(), &root[..]);
+
+        // Element 3 is within the maximum length, but not the length of the list. The error refers
+        // to the generalized index within the tree of the values.
+        assert_eq!(
+            list.merkle_proof(&[&[PathElement::Index(3), PathElement::Field("a")]]),
+            Err(ProofError::InvalidGeneralizedIndex(22))
+        );
+    }
 }
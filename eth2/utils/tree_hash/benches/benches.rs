@@ -3,6 +3,7 @@ extern crate lazy_static;
 
 use criterion::Criterion;
 use criterion::{black_box, criterion_group, criterion_main, Benchmark};
+use tree_hash::{merkleize_padded, merkleize_standard, BYTES_PER_CHUNK};
 use types::test_utils::{generate_deterministic_keypairs, TestingBeaconStateBuilder};
 use types::{BeaconState, EthSpec, Keypair, MainnetEthSpec, MinimalEthSpec};
 
@@ -48,7 +49,34 @@ fn bench_suite<T: EthSpec>(c: &mut Criterion, spec_desc: &str, validator_count:
     );
 }
 
+fn bench_merkleize(c: &mut Criterion, chunks: usize) {
+    let bytes: Vec<u8> = (0..chunks * BYTES_PER_CHUNK).map(|i| i as u8).collect();
+
+    // The parallel implementation must produce the same root as the reference implementation.
+    assert_eq!(
+        merkleize_padded(&bytes, 0),
+        merkleize_standard(&bytes)[0..BYTES_PER_CHUNK].to_vec()
+    );
+
+    let padded_bytes = bytes.clone();
+
+    c.bench(
+        &format!("merkleize/{}_chunks", chunks),
+        Benchmark::new("merkleize_padded", move |b| {
+            b.iter(|| black_box(merkleize_padded(&padded_bytes, 0)))
+        })
+        .with_function("merkleize_standard", move |b| {
+            b.iter(|| black_box(merkleize_standard(&bytes)))
+        })
+        .sample_size(10),
+    );
+}
+
 fn all_benches(c: &mut Criterion) {
+    bench_merkleize(c, 1 << 12);
+    bench_merkleize(c, 1 << 16);
+    bench_merkleize(c, 300_000);
+
     bench_suite::<MinimalEthSpec>(c, "minimal", 100_000);
     bench_suite::<MinimalEthSpec>(c, "minimal", 300_000);
 
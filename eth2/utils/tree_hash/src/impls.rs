@@ -30,6 +30,13 @@ impl_for_bitsize!(u32, 32);
 impl_for_bitsize!(u64, 64);
 impl_for_bitsize!(usize, 64);
 
+impl TreeHashProof for u8 {}
+impl TreeHashProof for u16 {}
+impl TreeHashProof for u32 {}
+impl TreeHashProof for u64 {}
+impl TreeHashProof for usize {}
+impl TreeHashProof for bool {}
+
 impl TreeHash for bool {
     fn tree_hash_type() -> TreeHashType {
         TreeHashType::Basic
@@ -73,6 +80,9 @@ macro_rules! impl_for_u8_array {
 impl_for_u8_array!(4);
 impl_for_u8_array!(32);
 
+impl TreeHashProof for [u8; 4] {}
+impl TreeHashProof for [u8; 32] {}
+
 impl TreeHash for H256 {
     fn tree_hash_type() -> TreeHashType {
         TreeHashType::Vector
@@ -91,6 +101,8 @@ impl TreeHash for H256 {
     }
 }
 
+impl TreeHashProof for H256 {}
+
 // TODO: this implementation always panics, it only exists to allow us to compile whilst
 // refactoring tree hash. Should be removed.
 macro_rules! impl_for_list {
@@ -2,11 +2,18 @@
 extern crate lazy_static;
 
 pub mod impls;
+mod merkleize_cached;
 mod merkleize_padded;
 mod merkleize_standard;
+mod proof;
 
+pub use merkleize_cached::TreeHashCache;
 pub use merkleize_padded::merkleize_padded;
 pub use merkleize_standard::merkleize_standard;
+pub use proof::{
+    concat_generalized_indices, generalized_index_depth, get_helper_indices, merkle_tree_nodes,
+    mixed_in_length_nodes, tree_depth, MerkleProof, PathElement, ProofError, TreeHashProof,
+};
 
 pub const BYTES_PER_CHUNK: usize = 32;
 pub const HASHSIZE: usize = 32;
@@ -30,6 +37,13 @@ pub fn mix_in_length(root: &[u8], length: usize) -> Vec<u8> {
     merkleize_padded::hash_concat(root, &length_bytes)
 }
 
+/// Returns the node created by hashing `root` and `selector`.
+///
+/// Used in `TreeHash` for inserting the selector of a union above the root of its value.
+pub fn mix_in_type(root: &[u8], selector: usize) -> Vec<u8> {
+    mix_in_length(root, selector)
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum TreeHashType {
     Basic,
@@ -52,6 +66,26 @@ pub trait SignedRoot: TreeHash {
     fn signed_root(&self) -> Vec<u8>;
 }
 
+/// Provides access to the tree hash root of each field of a container, in the order the fields
+/// are hashed by `TreeHash::tree_hash_root`.
+pub trait TreeHashFields: TreeHash {
+    /// The names of the hashed fields, in the order they are hashed.
+    fn tree_hash_field_names() -> &'static [&'static str];
+
+    /// Returns the tree hash root of each hashed field, in the order they are hashed.
+    ///
+    /// `cached_root` is called with the name of each field first. If it returns `Some(root)`,
+    /// `root` is used instead of hashing the field.
+    fn tree_hash_field_roots_with<F>(&self, cached_root: F) -> Vec<Vec<u8>>
+    where
+        F: FnMut(&'static str) -> Option<Vec<u8>>;
+
+    /// Returns the tree hash root of each hashed field, in the order they are hashed.
+    fn tree_hash_field_roots(&self) -> Vec<Vec<u8>> {
+        self.tree_hash_field_roots_with(|_| None)
+    }
+}
+
 #[macro_export]
 macro_rules! tree_hash_ssz_encoding_as_vector {
     ($type: ident) => {
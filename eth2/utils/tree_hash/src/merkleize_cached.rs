@@ -0,0 +1,195 @@
+use super::merkleize_padded::{get_zero_hash, hash_concat};
+use super::BYTES_PER_CHUNK;
+
+/// A Merkle tree of fixed depth which stores all of its internal nodes, so that the root may be
+/// re-calculated by only hashing the nodes above the leaves which have changed.
+///
+/// The tree is padded with zero-value leaves to `2^depth` leaves, the same as `merkleize_padded`.
+/// Padding nodes are never stored.
+///
+/// ## Memory Performance
+///
+/// Roughly `2 * n * BYTES_PER_CHUNK` bytes are stored, where `n` is the number of leaves with
+/// values.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TreeHashCache {
+    /// The number of layers below the root.
+    depth: usize,
+    /// `layers[0]` contains the leaves, `layers[depth]` contains the root. Each layer is the
+    /// concatenation of all of its non-padding nodes.
+    layers: Vec<Vec<u8>>,
+}
+
+impl TreeHashCache {
+    /// Instantiate an empty tree which will be padded to `min_leaves` leaves, the same as
+    /// `merkleize_padded(.., min_leaves)`.
+    pub fn new(min_leaves: usize) -> Self {
+        let depth = min_leaves.next_power_of_two().trailing_zeros() as usize;
+
+        Self {
+            depth,
+            layers: vec![vec![]; depth + 1],
+        }
+    }
+
+    /// Returns the number of leaves with values.
+    pub fn len(&self) -> usize {
+        self.layers[0].len() / BYTES_PER_CHUNK
+    }
+
+    /// Returns `true` if no leaves have values.
+    pub fn is_empty(&self) -> bool {
+        self.layers[0].is_empty()
+    }
+
+    /// Returns the leaf at index `i`, if it has a value.
+    pub fn leaf(&self, i: usize) -> Option<&[u8]> {
+        self.layers[0].get(i * BYTES_PER_CHUNK..(i + 1) * BYTES_PER_CHUNK)
+    }
+
+    /// Replaces the leaves of the tree with `leaves` and returns the new root.
+    ///
+    /// Only the nodes above leaves which differ from the previous call are re-hashed. Leaves
+    /// beyond the end of `leaves` are removed.
+    ///
+    /// ## Panics
+    ///
+    /// If there are more leaves than the tree is able to hold.
+    pub fn recalculate_merkle_root<I>(&mut self, leaves: I) -> Vec<u8>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let old_len = self.len();
+        let mut dirty = vec![];
+        let mut num_leaves = 0;
+
+        for (i, leaf) in leaves.into_iter().enumerate() {
+            let leaf = leaf.as_ref();
+            assert_eq!(leaf.len(), BYTES_PER_CHUNK, "Leaves must be a single chunk");
+
+            if i < old_len {
+                let existing = &mut self.layers[0][i * BYTES_PER_CHUNK..(i + 1) * BYTES_PER_CHUNK];
+                if existing != leaf {
+                    existing.copy_from_slice(leaf);
+                    dirty.push(i);
+                }
+            } else {
+                self.layers[0].extend_from_slice(leaf);
+                dirty.push(i);
+            }
+
+            num_leaves = i + 1;
+        }
+
+        assert!(
+            num_leaves <= 1 << self.depth,
+            "Tree exceeds its maximum number of leaves"
+        );
+
+        if num_leaves == 0 {
+            self.layers.iter_mut().for_each(Vec::clear);
+            return get_zero_hash(self.depth).to_vec();
+        }
+
+        if num_leaves < old_len {
+            // The right-most node at each height may now have a padding node as a child.
+            self.layers[0].truncate(num_leaves * BYTES_PER_CHUNK);
+            dirty.push(num_leaves - 1);
+        }
+
+        for height in 1..=self.depth {
+            let (children, parents) = self.layers.split_at_mut(height);
+            let children = &children[height - 1];
+            let parents = &mut parents[0];
+
+            let num_children = children.len() / BYTES_PER_CHUNK;
+            let num_parents = (num_children + 1) / 2;
+            parents.resize(num_parents * BYTES_PER_CHUNK, 0);
+
+            dirty = dirty.into_iter().map(|i| i / 2).collect();
+            dirty.dedup();
+
+            for &parent in &dirty {
+                let left_index = parent * 2;
+                let left =
+                    &children[left_index * BYTES_PER_CHUNK..(left_index + 1) * BYTES_PER_CHUNK];
+                let right = children
+                    .get((left_index + 1) * BYTES_PER_CHUNK..(left_index + 2) * BYTES_PER_CHUNK)
+                    .unwrap_or_else(|| get_zero_hash(height - 1));
+
+                parents[parent * BYTES_PER_CHUNK..(parent + 1) * BYTES_PER_CHUNK]
+                    .copy_from_slice(&hash_concat(left, right));
+            }
+        }
+
+        self.layers[self.depth].clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::merkleize_padded;
+
+    fn leaves(values: &[u64]) -> Vec<Vec<u8>> {
+        values
+            .iter()
+            .map(|value| {
+                let mut leaf = value.to_le_bytes().to_vec();
+                leaf.resize(BYTES_PER_CHUNK, 0);
+                leaf
+            })
+            .collect()
+    }
+
+    fn reference_root(leaves: &[Vec<u8>], min_leaves: usize) -> Vec<u8> {
+        merkleize_padded(&leaves.concat(), min_leaves)
+    }
+
+    #[test]
+    fn matches_merkleize_padded() {
+        let min_leaves = 16;
+        let mut cache = TreeHashCache::new(min_leaves);
+
+        for values in &[
+            vec![],
+            vec![1],
+            vec![1, 2, 3],
+            vec![1, 2, 4],
+            vec![5, 2, 4, 6, 7, 8, 9],
+            vec![5, 2, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18],
+            vec![5, 2],
+            vec![5],
+            vec![],
+            vec![3, 3, 3, 3, 3],
+        ] {
+            let leaves = leaves(values);
+            assert_eq!(
+                cache.recalculate_merkle_root(&leaves),
+                reference_root(&leaves, min_leaves),
+                "values: {:?}",
+                values
+            );
+            assert_eq!(cache.len(), values.len());
+        }
+    }
+
+    #[test]
+    fn pads_to_next_power_of_two() {
+        let leaves = leaves(&[1, 2, 3]);
+        let mut cache = TreeHashCache::new(5);
+
+        assert_eq!(
+            cache.recalculate_merkle_root(&leaves),
+            reference_root(&leaves, 5)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_when_full() {
+        let mut cache = TreeHashCache::new(2);
+        cache.recalculate_merkle_root(&leaves(&[1, 2, 3]));
+    }
+}
@@ -1,5 +1,6 @@
 use super::BYTES_PER_CHUNK;
 use eth2_hashing::hash;
+use rayon::prelude::*;
 
 /// The size of the cache that stores padding nodes for a given height.
 ///
@@ -8,6 +9,14 @@ use eth2_hashing::hash;
 /// It is set to 48 as we expect it to be sufficiently high that we won't exceed it.
 pub const MAX_TREE_DEPTH: usize = 48;
 
+/// Trees with fewer leaf chunks (with values) than this are always hashed on a single thread.
+///
+/// Below this size the overhead of dispatching work to the thread pool outweighs the gains.
+const PARALLEL_THRESHOLD: usize = 1 << 13;
+
+/// The minimum number of leaf chunks in a subtree that is hashed as a single unit of work.
+const MIN_SUBTREE_LEAVES: usize = 1 << 10;
+
 lazy_static! {
     /// Cached zero hashes where `ZERO_HASHES[i]` is the hash of a Merkle tree with 2^i zero leaves.
     static ref ZERO_HASHES: Vec<Vec<u8>> = {
@@ -49,6 +58,14 @@ lazy_static! {
 ///
 /// _Note: there are some minor memory overheads, including a handful of usizes and a list of
 /// `MAX_TREE_DEPTH` hashes as `lazy_static` constants._
+///
+/// ## Parallelism
+///
+/// If there are at least `PARALLEL_THRESHOLD` leaves with values (and more than one thread is
+/// available), the leaves are split into equally-sized subtrees which are hashed in parallel
+/// using `rayon`. The subtree size adapts to the number of threads, but is never smaller than
+/// `MIN_SUBTREE_LEAVES`. The roots of the subtrees are then hashed into the final root on the
+/// calling thread.
 pub fn merkleize_padded(bytes: &[u8], min_leaves: usize) -> Vec<u8> {
     // If the bytes are just one chunk or less, pad to one chunk and return without hashing.
     if bytes.len() <= BYTES_PER_CHUNK && min_leaves <= 1 {
@@ -65,12 +82,6 @@ pub fn merkleize_padded(bytes: &[u8], min_leaves: usize) -> Vec<u8> {
     // The number of leaves that can be made directly from `bytes`.
     let leaves_with_values = (bytes.len() + (BYTES_PER_CHUNK - 1)) / BYTES_PER_CHUNK;
 
-    // The number of parents that have at least one non-padding leaf.
-    //
-    // Since there is more than one node in this tree (see prior assertion), there should always be
-    // one or more initial parent nodes.
-    let initial_parents_with_values = std::cmp::max(1, next_even_number(leaves_with_values) / 2);
-
     // The number of leaves in the full tree (including padding nodes).
     let num_leaves = std::cmp::max(leaves_with_values, min_leaves).next_power_of_two();
 
@@ -81,6 +92,68 @@ pub fn merkleize_padded(bytes: &[u8], min_leaves: usize) -> Vec<u8> {
 
     assert!(height >= 2, "The tree should have two or more heights");
 
+    if leaves_with_values >= PARALLEL_THRESHOLD && rayon::current_num_threads() > 1 {
+        let subtree_leaves = subtree_leaves(leaves_with_values, rayon::current_num_threads());
+
+        merkleize_subtrees(bytes, subtree_leaves, height)
+    } else {
+        merkleize_serial(bytes, leaves_with_values, height)
+    }
+}
+
+/// Returns the number of leaves in each of the subtrees that `leaves_with_values` leaves should be
+/// split into when hashing with `threads` threads.
+///
+/// Aims for a handful of subtrees per thread, so that the work stays balanced if some threads are
+/// slower than others.
+fn subtree_leaves(leaves_with_values: usize, threads: usize) -> usize {
+    std::cmp::max(
+        MIN_SUBTREE_LEAVES,
+        (leaves_with_values / (threads * 4)).next_power_of_two(),
+    )
+}
+
+/// Merkleize `bytes` by hashing subtrees of `subtree_leaves` leaves in parallel, padding the root
+/// of the result out to a tree with `height` levels.
+///
+/// `subtree_leaves` must be a power of two, larger than one and the tree must have more than
+/// `subtree_leaves` leaves.
+fn merkleize_subtrees(bytes: &[u8], subtree_leaves: usize, height: usize) -> Vec<u8> {
+    assert!(
+        subtree_leaves.is_power_of_two() && subtree_leaves > 1,
+        "Subtrees must have a power-of-two number of leaves"
+    );
+
+    let subtree_height = subtree_leaves.trailing_zeros() as usize + 1;
+
+    assert!(
+        subtree_height < height,
+        "Subtrees must be smaller than the tree"
+    );
+
+    // Hash each subtree, padding the right-most subtree with zero leaves.
+    let roots: Vec<u8> = bytes
+        .par_chunks(subtree_leaves * BYTES_PER_CHUNK)
+        .map(|subtree| {
+            let leaves_with_values = (subtree.len() + (BYTES_PER_CHUNK - 1)) / BYTES_PER_CHUNK;
+            merkleize_serial(subtree, leaves_with_values, subtree_height)
+        })
+        .collect::<Vec<_>>()
+        .concat();
+
+    // The subtree roots are the nodes at the height directly below the root of each subtree.
+    merkleize_chunks(ChunkStore(roots), subtree_height - 1, height)
+}
+
+/// Merkleize `bytes` on a single thread, padding the root of the result out to a tree with
+/// `height` levels.
+fn merkleize_serial(bytes: &[u8], leaves_with_values: usize, height: usize) -> Vec<u8> {
+    // The number of parents that have at least one non-padding leaf.
+    //
+    // Since there is more than one node in this tree (`height >= 2`), there should always be one
+    // or more initial parent nodes.
+    let initial_parents_with_values = std::cmp::max(1, next_even_number(leaves_with_values) / 2);
+
     // A buffer/scratch-space used for storing each round of hashes at each height.
     //
     // This buffer is kept as small as possible; it will shrink so it never stores a padding node.
@@ -120,15 +193,23 @@ pub fn merkleize_padded(bytes: &[u8], min_leaves: usize) -> Vec<u8> {
             .expect("Buffer should always have capacity for parent nodes")
     }
 
+    merkleize_chunks(chunks, 1, height)
+}
+
+/// Hash the nodes in `chunks`, which are at `first_height` in a tree with `height` levels, until
+/// only the root remains.
+///
+/// The leaves of the tree are at height `0`.
+fn merkleize_chunks(mut chunks: ChunkStore, first_height: usize, height: usize) -> Vec<u8> {
     // Iterate through all heights above the leaf nodes and either (a) hash two children or, (b)
     // hash a left child and a right padding node.
     //
-    // Skip the 0'th height because the leaves have already been processed. Skip the highest-height
-    // in the tree as it is the root does not require hashing.
+    // Skip the heights below `first_height` because they have already been processed. Skip the
+    // highest-height in the tree as it is the root does not require hashing.
     //
     // The padding nodes for each height are cached via `lazy static` to simulate non-adjacent
     // padding nodes (i.e., avoid doing unnecessary hashing).
-    for height in 1..height - 1 {
+    for height in first_height..height - 1 {
         let child_nodes = chunks.len();
         let parent_nodes = next_even_number(child_nodes) / 2;
 
@@ -228,7 +309,7 @@ impl ChunkStore {
 }
 
 /// Returns a cached padding node for a given height.
-fn get_zero_hash(height: usize) -> &'static [u8] {
+pub(crate) fn get_zero_hash(height: usize) -> &'static [u8] {
     if height <= MAX_TREE_DEPTH {
         &ZERO_HASHES[height]
     } else {
@@ -346,6 +427,75 @@ mod test {
         common_tests!(random_bytes);
     }
 
+    mod parallel {
+        use super::*;
+
+        fn bytes(chunks: usize) -> Vec<u8> {
+            (0..chunks * BYTES_PER_CHUNK).map(|i| i as u8).collect()
+        }
+
+        #[test]
+        fn around_threshold() {
+            for &chunks in &[
+                PARALLEL_THRESHOLD - 1,
+                PARALLEL_THRESHOLD,
+                PARALLEL_THRESHOLD + 1,
+                PARALLEL_THRESHOLD * 2 + 7,
+            ] {
+                test_against_reference(&bytes(chunks), 0);
+                test_against_reference(&bytes(chunks), PARALLEL_THRESHOLD * 8);
+            }
+        }
+
+        #[test]
+        fn partial_chunk() {
+            let mut input = bytes(PARALLEL_THRESHOLD * 3);
+            input.truncate(input.len() - 5);
+
+            test_against_reference(&input, 0);
+        }
+
+        #[test]
+        fn small_subtrees() {
+            for chunks in 3..64 {
+                let input = bytes(chunks);
+                let num_leaves = chunks.next_power_of_two();
+                let height = num_leaves.trailing_zeros() as usize + 1;
+
+                let mut subtree_leaves = 2;
+                while subtree_leaves < chunks {
+                    assert_eq!(
+                        merkleize_subtrees(&input, subtree_leaves, height),
+                        merkleize_serial(&input, chunks, height),
+                        "chunks: {}, subtree_leaves: {}",
+                        chunks,
+                        subtree_leaves
+                    );
+
+                    // A taller tree than is required to hold the leaves.
+                    assert_eq!(
+                        merkleize_subtrees(&input, subtree_leaves, height + 2),
+                        merkleize_serial(&input, chunks, height + 2),
+                    );
+
+                    subtree_leaves *= 2;
+                }
+            }
+        }
+
+        #[test]
+        fn adaptive_subtree_size() {
+            assert_eq!(subtree_leaves(PARALLEL_THRESHOLD, 2), MIN_SUBTREE_LEAVES);
+            assert_eq!(subtree_leaves(1 << 20, 1), 1 << 18);
+            assert_eq!(subtree_leaves(1 << 20, 16), 1 << 14);
+            assert_eq!(subtree_leaves((1 << 21) - 1, 16), 1 << 15);
+
+            for threads in 1..64 {
+                assert!(subtree_leaves(PARALLEL_THRESHOLD, threads) < PARALLEL_THRESHOLD);
+            }
+        }
+    }
+
     fn test_against_reference(input: &[u8], min_nodes: usize) {
         let mut reference_input = input.to_vec();
         reference_input.resize(
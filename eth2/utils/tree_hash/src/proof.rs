@@ -0,0 +1,413 @@
+use super::merkleize_padded::{get_zero_hash, hash_concat};
+use super::{merkleize_padded, mix_in_length, TreeHash, BYTES_PER_CHUNK};
+use ethereum_types::H256;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// A single step along the path from the root of some SSZ object to one of its nodes.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PathElement<'a> {
+    /// The field of a container with the given name.
+    Field(&'a str),
+    /// The element of a list or vector at the given index.
+    ///
+    /// For lists and vectors of basic types, this refers to the chunk which contains the element.
+    Index(usize),
+    /// The length of a list, which is mixed into its root.
+    Length,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ProofError {
+    /// The path does not refer to a node of the type (e.g., an unknown field name or an index
+    /// which exceeds the maximum length).
+    InvalidPath(String),
+    /// The generalized index does not refer to a node in the tree of the value (e.g., a node
+    /// below a packed chunk, or below an element beyond the length of a list).
+    ///
+    /// The index is relative to the root of the innermost tree in which it was found to be
+    /// invalid.
+    InvalidGeneralizedIndex(u64),
+}
+
+/// Allows for generating Merkle proofs for the nodes in the tree hash of a value.
+///
+/// Nodes are identified by their generalized index, as per the Eth 2.0 SSZ spec: the root has
+/// index `1` and the children of the node at `i` are at `2 * i` and `2 * i + 1`.
+pub trait TreeHashProof: TreeHash {
+    /// Returns the generalized index of the node at `path`, relative to the root of `Self`.
+    ///
+    /// The default implementation is for types which are a single leaf, such as basic types.
+    fn generalized_index(path: &[PathElement]) -> Result<u64, ProofError> {
+        match path.first() {
+            None => Ok(1),
+            Some(element) => Err(ProofError::InvalidPath(format!("{:?}", element))),
+        }
+    }
+
+    /// Returns the node at each of `gindices`, relative to the root of `self`.
+    ///
+    /// The default implementation is for types which are a single leaf, such as basic types.
+    fn tree_hash_nodes(&self, gindices: &[u64]) -> Result<Vec<Vec<u8>>, ProofError> {
+        gindices
+            .iter()
+            .map(|&gindex| match gindex {
+                1 => Ok(self.tree_hash_root()),
+                _ => Err(ProofError::InvalidGeneralizedIndex(gindex)),
+            })
+            .collect()
+    }
+
+    /// Returns a proof of the nodes at each of `paths`.
+    ///
+    /// Providing a single path produces a regular Merkle branch, providing several produces a
+    /// multiproof which shares the nodes common to each branch.
+    fn merkle_proof(&self, paths: &[&[PathElement]]) -> Result<MerkleProof, ProofError> {
+        let gindices = paths
+            .iter()
+            .map(|path| Self::generalized_index(path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.merkle_proof_for_generalized_indices(&gindices)
+    }
+
+    /// Returns a proof of the nodes at each of `gindices`.
+    fn merkle_proof_for_generalized_indices(
+        &self,
+        gindices: &[u64],
+    ) -> Result<MerkleProof, ProofError> {
+        let helper_indices = get_helper_indices(gindices);
+
+        let mut leaves = self.tree_hash_nodes(&[gindices, &helper_indices].concat())?;
+        let proof = leaves.split_off(gindices.len());
+
+        Ok(MerkleProof {
+            gindices: gindices.to_vec(),
+            leaves: leaves.iter().map(|node| H256::from_slice(node)).collect(),
+            proof: proof.iter().map(|node| H256::from_slice(node)).collect(),
+        })
+    }
+}
+
+/// A proof that each of the `leaves` is the node at the corresponding generalized index in
+/// `gindices` of a tree.
+///
+/// The `proof` contains the nodes at `get_helper_indices(gindices)`, as per the `multiproof` of
+/// the SSZ spec.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MerkleProof {
+    pub gindices: Vec<u64>,
+    pub leaves: Vec<H256>,
+    pub proof: Vec<H256>,
+}
+
+impl MerkleProof {
+    /// Returns the root of the tree determined by the leaves and proof, or `None` if the proof
+    /// is malformed or inconsistent.
+    pub fn root(&self) -> Option<H256> {
+        let helper_indices = get_helper_indices(&self.gindices);
+
+        if self.leaves.len() != self.gindices.len() || self.proof.len() != helper_indices.len() {
+            return None;
+        }
+
+        let mut nodes: HashMap<u64, H256> = HashMap::new();
+        for (&gindex, &node) in self
+            .gindices
+            .iter()
+            .chain(helper_indices.iter())
+            .zip(self.leaves.iter().chain(self.proof.iter()))
+        {
+            if gindex == 0
+                || nodes
+                    .insert(gindex, node)
+                    .map_or(false, |prev| prev != node)
+            {
+                return None;
+            }
+        }
+
+        let mut gindices: Vec<u64> = nodes.keys().cloned().collect();
+        gindices.sort_by(|a, b| b.cmp(a));
+
+        let mut i = 0;
+        while i < gindices.len() {
+            let gindex = gindices[i];
+
+            if gindex > 1 {
+                if let (Some(left), Some(right)) =
+                    (nodes.get(&(gindex & !1)), nodes.get(&(gindex | 1)))
+                {
+                    let parent = H256::from_slice(&hash_concat(left.as_bytes(), right.as_bytes()));
+
+                    match nodes.get(&(gindex / 2)) {
+                        // A leaf which is an ancestor of another leaf must be consistent with it.
+                        Some(existing) if *existing != parent => return None,
+                        Some(_) => (),
+                        None => {
+                            nodes.insert(gindex / 2, parent);
+                            gindices.push(gindex / 2);
+                        }
+                    }
+                }
+            }
+
+            i += 1;
+        }
+
+        nodes.get(&1).cloned()
+    }
+
+    /// Returns `true` if the proof shows that each of the `leaves` is in the tree with `root`.
+    pub fn verify(&self, root: H256) -> bool {
+        self.root() == Some(root)
+    }
+}
+
+/// Returns the generalized indices of the nodes required to prove the nodes at `gindices`,
+/// sorted in decreasing order.
+pub fn get_helper_indices(gindices: &[u64]) -> Vec<u64> {
+    let mut branch_indices = BTreeSet::new();
+    let mut path_indices = HashSet::new();
+
+    for &gindex in gindices {
+        let mut gindex = gindex;
+        while gindex > 1 {
+            branch_indices.insert(gindex ^ 1);
+            path_indices.insert(gindex);
+            gindex /= 2;
+        }
+    }
+
+    branch_indices
+        .into_iter()
+        .rev()
+        .filter(|gindex| !path_indices.contains(gindex))
+        .collect()
+}
+
+/// Returns the depth of the node at `gindex` below the root.
+pub fn generalized_index_depth(gindex: u64) -> usize {
+    63 - gindex.leading_zeros() as usize
+}
+
+/// Returns the generalized index of the node which is at `b` below the node at `a`, where `a` is
+/// relative to the root of the whole tree.
+pub fn concat_generalized_indices(a: u64, b: u64) -> u64 {
+    let depth = generalized_index_depth(b);
+
+    (a << depth) | (b ^ (1 << depth))
+}
+
+/// Returns the depth of the tree produced by `merkleize_padded` for `num_leaves` leaves.
+pub fn tree_depth(num_leaves: usize) -> usize {
+    num_leaves.next_power_of_two().trailing_zeros() as usize
+}
+
+/// Returns the node at each of `gindices` in a Merkle tree of the given `depth`, with the
+/// leaves `chunks()` padded with zero chunks.
+///
+/// Nodes below the leaves are found with `descend(i, sub_gindices)`, where `sub_gindices` are
+/// relative to leaf `i`. It returns `None` if leaf `i` has no children.
+pub fn merkle_tree_nodes<C, F>(
+    chunks: C,
+    depth: usize,
+    gindices: &[u64],
+    mut descend: F,
+) -> Result<Vec<Vec<u8>>, ProofError>
+where
+    C: FnOnce() -> Vec<u8>,
+    F: FnMut(usize, &[u64]) -> Option<Result<Vec<Vec<u8>>, ProofError>>,
+{
+    let mut nodes = vec![vec![]; gindices.len()];
+
+    // Only compute the leaves if some node is in this tree.
+    let mut chunks = Some(chunks);
+    let mut leaves: Option<Vec<u8>> = None;
+
+    // Maps each leaf to the positions in `gindices` of the nodes below it, and their
+    // generalized indices relative to that leaf.
+    let mut below_leaves: BTreeMap<usize, (Vec<usize>, Vec<u64>)> = BTreeMap::new();
+
+    for (position, &gindex) in gindices.iter().enumerate() {
+        if gindex == 0 {
+            return Err(ProofError::InvalidGeneralizedIndex(gindex));
+        }
+
+        let node_depth = generalized_index_depth(gindex);
+
+        if node_depth <= depth {
+            let leaves = leaves.get_or_insert_with(|| (chunks.take().expect("Taken once"))());
+
+            let width = 1 << (depth - node_depth);
+            let start = (gindex - (1 << node_depth)) as usize * width * BYTES_PER_CHUNK;
+
+            nodes[position] = if start < leaves.len() {
+                let end = std::cmp::min(start + width * BYTES_PER_CHUNK, leaves.len());
+                merkleize_padded(&leaves[start..end], width)
+            } else {
+                get_zero_hash(depth - node_depth).to_vec()
+            };
+        } else {
+            let sub_depth = node_depth - depth;
+            let leaf = ((gindex >> sub_depth) - (1 << depth)) as usize;
+            let sub_gindex = (gindex & ((1 << sub_depth) - 1)) | (1 << sub_depth);
+
+            let entry = below_leaves.entry(leaf).or_default();
+            entry.0.push(position);
+            entry.1.push(sub_gindex);
+        }
+    }
+
+    for (leaf, (positions, sub_gindices)) in below_leaves {
+        let sub_nodes = descend(leaf, &sub_gindices)
+            .ok_or_else(|| ProofError::InvalidGeneralizedIndex(gindices[positions[0]]))??;
+
+        for (position, node) in positions.into_iter().zip(sub_nodes) {
+            nodes[position] = node;
+        }
+    }
+
+    Ok(nodes)
+}
+
+/// Returns the node at each of `gindices` in the tree of a list, which mixes `length` into the
+/// root of its data tree.
+///
+/// The nodes of the data tree are found with `data_nodes(sub_gindices)`, where `sub_gindices` are
+/// relative to the root of the data tree.
+pub fn mixed_in_length_nodes<F>(
+    length: usize,
+    gindices: &[u64],
+    data_nodes: F,
+) -> Result<Vec<Vec<u8>>, ProofError>
+where
+    F: FnOnce(&[u64]) -> Result<Vec<Vec<u8>>, ProofError>,
+{
+    enum Node {
+        Root(usize),
+        Length,
+        Data(usize),
+    }
+
+    let mut length_chunk = length.to_le_bytes().to_vec();
+    length_chunk.resize(BYTES_PER_CHUNK, 0);
+
+    let mut data_gindices = vec![];
+    let mut node_for = |data_gindex: u64| {
+        data_gindices.push(data_gindex);
+        data_gindices.len() - 1
+    };
+
+    let nodes = gindices
+        .iter()
+        .map(|&gindex| match gindex {
+            0 => Err(ProofError::InvalidGeneralizedIndex(gindex)),
+            1 => Ok(Node::Root(node_for(1))),
+            3 => Ok(Node::Length),
+            _ => {
+                let depth = generalized_index_depth(gindex);
+                let below_data = 1 << (depth - 1);
+
+                if gindex & below_data == 0 {
+                    Ok(Node::Data(node_for(below_data | (gindex ^ (1 << depth)))))
+                } else {
+                    Err(ProofError::InvalidGeneralizedIndex(gindex))
+                }
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let data = if data_gindices.is_empty() {
+        vec![]
+    } else {
+        data_nodes(&data_gindices)?
+    };
+
+    Ok(nodes
+        .into_iter()
+        .map(|node| match node {
+            Node::Root(i) => mix_in_length(&data[i], length),
+            Node::Length => length_chunk.clone(),
+            Node::Data(i) => data[i].clone(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::merkle_root;
+
+    #[test]
+    fn generalized_indices() {
+        assert_eq!(tree_depth(0), 0);
+        assert_eq!(tree_depth(1), 0);
+        assert_eq!(tree_depth(5), 3);
+        assert_eq!(generalized_index_depth(1), 0);
+        assert_eq!(generalized_index_depth(7), 2);
+        assert_eq!(concat_generalized_indices(1, 5), 5);
+        assert_eq!(concat_generalized_indices(5, 1), 5);
+        assert_eq!(concat_generalized_indices(3, 5), 13);
+        assert_eq!(get_helper_indices(&[9]), vec![8, 5, 3]);
+        assert_eq!(get_helper_indices(&[9, 14]), vec![15, 8, 6, 5]);
+    }
+
+    fn chunks(n: u8) -> Vec<u8> {
+        (1..=n).flat_map(|i| vec![i; BYTES_PER_CHUNK]).collect()
+    }
+
+    #[test]
+    fn tree_nodes_and_proofs() {
+        let leaves = chunks(5);
+        let root = H256::from_slice(&merkle_root(&leaves, 0));
+        let nodes = |gindices: &[u64]| {
+            merkle_tree_nodes(|| leaves.clone(), 3, gindices, |_, _| None)
+                .map(|nodes| nodes.iter().map(|n| H256::from_slice(n)).collect())
+        };
+
+        assert_eq!(nodes(&[1]), Ok(vec![root]));
+        assert_eq!(
+            nodes(&[12, 13, 15]).unwrap(),
+            vec![
+                H256::from_slice(&[5; 32]),
+                H256::zero(),
+                H256::from_slice(get_zero_hash(0)),
+            ]
+        );
+        assert_eq!(nodes(&[16]), Err(ProofError::InvalidGeneralizedIndex(16)));
+
+        for gindices in &[vec![8], vec![12, 9], vec![4, 15, 10], vec![2, 8]] {
+            let helper_indices = get_helper_indices(gindices);
+            let mut proof = MerkleProof {
+                gindices: gindices.clone(),
+                leaves: nodes(gindices).unwrap(),
+                proof: nodes(&helper_indices).unwrap(),
+            };
+
+            assert!(proof.verify(root));
+
+            proof.leaves[0] = H256::repeat_byte(42);
+            assert!(!proof.verify(root));
+        }
+    }
+
+    #[test]
+    fn mixed_in_length() {
+        let leaves = chunks(3);
+        let root = mix_in_length(&merkle_root(&leaves, 4), 3);
+        let nodes = mixed_in_length_nodes(3, &[1, 3, 2, 5, 9], |gindices| {
+            merkle_tree_nodes(|| leaves.clone(), 2, gindices, |_, _| None)
+        })
+        .unwrap();
+
+        assert_eq!(nodes[0], root);
+        assert_eq!(nodes[1][0..8].to_vec(), 3_u64.to_le_bytes().to_vec());
+        assert_eq!(nodes[2], merkle_root(&leaves, 4));
+        assert_eq!(nodes[3], merkle_root(&leaves[64..], 2));
+        assert_eq!(nodes[4], vec![2; 32]);
+        assert_eq!(
+            mixed_in_length_nodes(3, &[7], |_| Ok(vec![])),
+            Err(ProofError::InvalidGeneralizedIndex(7))
+        );
+    }
+}
@@ -27,6 +27,17 @@ fn get_hashable_named_field_idents<'a>(struct_data: &'a syn::DataStruct) -> Vec<
         .collect()
 }
 
+/// Returns a Vec of `syn::Type` for each named field in the struct, in the same order as
+/// `get_hashable_named_field_idents`.
+fn get_hashable_named_field_types(struct_data: &syn::DataStruct) -> Vec<&syn::Type> {
+    struct_data
+        .fields
+        .iter()
+        .filter(|f| !should_skip_hashing(f))
+        .map(|f| &f.ty)
+        .collect()
+}
+
 /// Returns true if some field has an attribute declaring it should not be hashed.
 ///
 /// The field attribute is: `#[tree_hash(skip_hashing)]`
@@ -37,9 +48,13 @@ fn should_skip_hashing(field: &syn::Field) -> bool {
         .any(|attr| attr.into_token_stream().to_string() == "# [ tree_hash ( skip_hashing ) ]")
 }
 
-/// Implements `tree_hash::TreeHash` for some `struct`.
+/// Implements `tree_hash::TreeHash` for some `struct` or `enum`.
 ///
 /// Fields are hashed in the order they are defined.
+///
+/// An `enum` is hashed as an SSZ union: the root of the value of the variant has the index of
+/// the variant mixed in. Each variant must have exactly one unnamed field, except the first which
+/// may be a unit variant representing the `None` option of the union.
 #[proc_macro_derive(TreeHash, attributes(tree_hash))]
 pub fn tree_hash_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
@@ -49,7 +64,8 @@ pub fn tree_hash_derive(input: TokenStream) -> TokenStream {
 
     let struct_data = match &item.data {
         syn::Data::Struct(s) => s,
-        _ => panic!("tree_hash_derive only supports structs."),
+        syn::Data::Enum(e) => return tree_hash_derive_enum(&item, e),
+        _ => panic!("tree_hash_derive only supports structs and enums."),
     };
 
     let idents = get_hashable_named_field_idents(&struct_data);
@@ -82,6 +98,185 @@ pub fn tree_hash_derive(input: TokenStream) -> TokenStream {
     output.into()
 }
 
+/// Implements `tree_hash::TreeHash` for an `enum`, as an SSZ union.
+///
+/// # Panics
+/// Any variant which does not have exactly one unnamed field, except for a unit variant in the
+/// first position, will raise a panic at compile time.
+fn tree_hash_derive_enum(item: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let arms: Vec<_> = enum_data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(selector, variant)| {
+            let ident = &variant.ident;
+
+            match &variant.fields {
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+                    #name::#ident(ref inner) => {
+                        tree_hash::mix_in_type(&tree_hash::TreeHash::tree_hash_root(inner), #selector)
+                    }
+                },
+                syn::Fields::Unit if selector == 0 => quote! {
+                    #name::#ident => tree_hash::mix_in_type(&[0; tree_hash::HASHSIZE], #selector),
+                },
+                syn::Fields::Unit => panic!(
+                    "tree_hash_derive only supports a unit variant as the first variant of an enum."
+                ),
+                _ => panic!(
+                    "tree_hash_derive only supports enum variants with exactly one unnamed field."
+                ),
+            }
+        })
+        .collect();
+
+    let output = quote! {
+        impl #impl_generics tree_hash::TreeHash for #name #ty_generics #where_clause {
+            fn tree_hash_type() -> tree_hash::TreeHashType {
+                tree_hash::TreeHashType::Container
+            }
+
+            fn tree_hash_packed_encoding(&self) -> Vec<u8> {
+                unreachable!("Enum should never be packed.")
+            }
+
+            fn tree_hash_packing_factor() -> usize {
+                unreachable!("Enum should never be packed.")
+            }
+
+            fn tree_hash_root(&self) -> Vec<u8> {
+                match *self {
+                    #(
+                        #arms
+                    )*
+                }
+            }
+        }
+    };
+    output.into()
+}
+
+/// Implements `tree_hash::TreeHashProof` for some `struct`.
+///
+/// Fields are named in paths by their identifier. Each hashed field must also implement
+/// `tree_hash::TreeHashProof`.
+#[proc_macro_derive(TreeHashProof, attributes(tree_hash))]
+pub fn tree_hash_proof_derive(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as DeriveInput);
+
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let struct_data = match &item.data {
+        syn::Data::Struct(s) => s,
+        _ => panic!("tree_hash_derive only supports structs."),
+    };
+
+    let idents = &get_hashable_named_field_idents(struct_data);
+    let types = get_hashable_named_field_types(struct_data);
+    let names: Vec<String> = idents.iter().map(|ident| ident.to_string()).collect();
+    let indices_a: Vec<u64> = (0..idents.len() as u64).collect();
+    let indices_b: Vec<usize> = (0..idents.len()).collect();
+    let num_leaves = idents.len();
+
+    let output = quote! {
+        impl #impl_generics tree_hash::TreeHashProof for #name #ty_generics #where_clause {
+            fn generalized_index(
+                path: &[tree_hash::PathElement]
+            ) -> Result<u64, tree_hash::ProofError> {
+                let first_leaf: u64 = 1 << tree_hash::tree_depth(#num_leaves);
+
+                match path.split_first() {
+                    None => Ok(1),
+                    Some((tree_hash::PathElement::Field(field), rest)) => match *field {
+                        #(
+                            #names => Ok(tree_hash::concat_generalized_indices(
+                                first_leaf + #indices_a,
+                                <#types as tree_hash::TreeHashProof>::generalized_index(rest)?,
+                            )),
+                        )*
+                        _ => Err(tree_hash::ProofError::InvalidPath(format!("{:?}", path[0]))),
+                    },
+                    Some((element, _)) => Err(tree_hash::ProofError::InvalidPath(format!("{:?}", element))),
+                }
+            }
+
+            fn tree_hash_nodes(&self, gindices: &[u64]) -> Result<Vec<Vec<u8>>, tree_hash::ProofError> {
+                tree_hash::merkle_tree_nodes(
+                    || {
+                        let mut leaves = Vec::with_capacity(#num_leaves * tree_hash::HASHSIZE);
+
+                        #(
+                            leaves.append(&mut self.#idents.tree_hash_root());
+                        )*
+
+                        leaves
+                    },
+                    tree_hash::tree_depth(#num_leaves),
+                    gindices,
+                    |leaf, sub_gindices| match leaf {
+                        #(
+                            #indices_b => Some(tree_hash::TreeHashProof::tree_hash_nodes(&self.#idents, sub_gindices)),
+                        )*
+                        _ => None,
+                    },
+                )
+            }
+        }
+    };
+    output.into()
+}
+
+/// Implements `tree_hash::TreeHashFields` for some `struct`.
+///
+/// Fields are named by their identifier and listed in the order they are defined, excluding
+/// those marked `#[tree_hash(skip_hashing)]`.
+#[proc_macro_derive(TreeHashFields, attributes(tree_hash))]
+pub fn tree_hash_fields_derive(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as DeriveInput);
+
+    let name = &item.ident;
+    let (impl_generics, ty_generics, where_clause) = &item.generics.split_for_impl();
+
+    let struct_data = match &item.data {
+        syn::Data::Struct(s) => s,
+        _ => panic!("tree_hash_derive only supports structs."),
+    };
+
+    let idents = &get_hashable_named_field_idents(struct_data);
+    let names_a: Vec<String> = idents.iter().map(|ident| ident.to_string()).collect();
+    let names_b = names_a.clone();
+    let num_fields = idents.len();
+
+    let output = quote! {
+        impl #impl_generics tree_hash::TreeHashFields for #name #ty_generics #where_clause {
+            fn tree_hash_field_names() -> &'static [&'static str] {
+                &[#(#names_a),*]
+            }
+
+            fn tree_hash_field_roots_with<F>(&self, mut cached_root: F) -> Vec<Vec<u8>>
+            where
+                F: FnMut(&'static str) -> Option<Vec<u8>>,
+            {
+                let mut roots = Vec::with_capacity(#num_fields);
+
+                #(
+                    roots.push(
+                        cached_root(#names_b)
+                            .unwrap_or_else(|| tree_hash::TreeHash::tree_hash_root(&self.#idents)),
+                    );
+                )*
+
+                roots
+            }
+        }
+    };
+    output.into()
+}
+
 #[proc_macro_derive(SignedRoot, attributes(signed_root))]
 pub fn tree_hash_signed_root_derive(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveInput);
@@ -11,6 +11,7 @@ mod epoch_processing_crosslinks;
 mod epoch_processing_final_updates;
 mod epoch_processing_justification_and_finalization;
 mod epoch_processing_registry_updates;
+mod epoch_processing_rewards_and_penalties;
 mod epoch_processing_slashings;
 mod genesis_initialization;
 mod genesis_validity;
@@ -37,6 +38,7 @@ pub use epoch_processing_crosslinks::*;
 pub use epoch_processing_final_updates::*;
 pub use epoch_processing_justification_and_finalization::*;
 pub use epoch_processing_registry_updates::*;
+pub use epoch_processing_rewards_and_penalties::*;
 pub use epoch_processing_slashings::*;
 pub use genesis_initialization::*;
 pub use genesis_validity::*;
@@ -0,0 +1,116 @@
+use super::*;
+use crate::case_result::{compare_beacon_state_results_without_caches, compare_result_detailed};
+use compare_fields_derive::CompareFields;
+use serde_derive::Deserialize;
+use state_processing::per_epoch_processing::{
+    apply_rewards::process_rewards_and_penalties, errors::EpochProcessingError,
+    validator_statuses::ValidatorStatuses, winning_root::winning_root, WinningRootHashSet,
+};
+use types::{BeaconState, ChainSpec, EthSpec, RelativeEpoch, Unsigned};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct EpochProcessingRewardsAndPenalties<E: EthSpec> {
+    pub description: String,
+    pub pre: BeaconState<E>,
+    pub post: Option<BeaconState<E>>,
+}
+
+/// The net change to each validator's balance across some state transition.
+///
+/// Used to report exactly which validators received incorrect rewards or penalties, rather than
+/// dumping the entire `balances` list.
+#[derive(Debug, PartialEq, CompareFields)]
+struct BalanceDeltas {
+    #[compare_fields(as_slice)]
+    deltas: Vec<i64>,
+}
+
+impl BalanceDeltas {
+    fn new<E: EthSpec>(pre: &BeaconState<E>, post: &BeaconState<E>) -> Self {
+        let deltas = post
+            .balances
+            .iter()
+            .zip(pre.balances.iter())
+            .map(|(post, pre)| post.wrapping_sub(*pre) as i64)
+            .collect();
+
+        Self { deltas }
+    }
+}
+
+impl<E: EthSpec> YamlDecode for EpochProcessingRewardsAndPenalties<E> {
+    fn yaml_decode(yaml: &str) -> Result<Self, Error> {
+        Ok(serde_yaml::from_str(yaml).unwrap())
+    }
+}
+
+impl<E: EthSpec> Case for EpochProcessingRewardsAndPenalties<E> {
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn result(&self, _case_index: usize) -> Result<(), Error> {
+        let mut state = self.pre.clone();
+        let mut expected = self.post.clone();
+
+        let spec = &E::default_spec();
+
+        let mut result = (|| {
+            // Processing requires the epoch cache.
+            state.build_all_caches(spec)?;
+
+            let mut validator_statuses = ValidatorStatuses::new(&state, spec)?;
+            validator_statuses.process_attestations(&state, spec)?;
+
+            let winning_root_for_shards = previous_epoch_winning_roots(&state, spec)?;
+
+            process_rewards_and_penalties(
+                &mut state,
+                &mut validator_statuses,
+                &winning_root_for_shards,
+                spec,
+            )
+            .map(|_| state)
+        })();
+
+        // Compare the balance deltas first, so a failure lists the individual validators that
+        // were incorrectly rewarded or penalized.
+        if let (Ok(result), Some(expected)) = (&result, &expected) {
+            if result.balances != expected.balances {
+                compare_result_detailed::<_, ()>(
+                    &Ok(BalanceDeltas::new(&self.pre, result)),
+                    &Some(BalanceDeltas::new(&self.pre, expected)),
+                )?;
+            }
+        }
+
+        compare_beacon_state_results_without_caches(&mut result, &mut expected)
+    }
+}
+
+/// Returns the winning crosslink roots for each shard in the previous epoch of `state`.
+///
+/// The test vectors provide a state that has already had crosslinks processed, so the winning
+/// roots are computed directly from the state (as the spec does in `get_crosslink_deltas`),
+/// instead of by re-running `process_crosslinks`.
+fn previous_epoch_winning_roots<E: EthSpec>(
+    state: &BeaconState<E>,
+    spec: &ChainSpec,
+) -> Result<WinningRootHashSet, EpochProcessingError> {
+    let mut winning_root_for_shards = WinningRootHashSet::new();
+
+    let relative_epoch = RelativeEpoch::Previous;
+    let epoch = relative_epoch.into_epoch(state.current_epoch());
+
+    for offset in 0..state.get_committee_count(relative_epoch)? {
+        let shard =
+            (state.get_epoch_start_shard(relative_epoch)? + offset) % E::ShardCount::to_u64();
+
+        if let Some(winning_root) = winning_root(state, shard, epoch, spec)? {
+            winning_root_for_shards.insert(shard, winning_root);
+        }
+    }
+
+    Ok(winning_root_for_shards)
+}
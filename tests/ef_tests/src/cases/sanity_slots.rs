@@ -1,7 +1,7 @@
 use super::*;
 use crate::case_result::compare_beacon_state_results_without_caches;
 use serde_derive::Deserialize;
-use state_processing::per_slot_processing;
+use state_processing::{fast_forward_state, per_slot_processing};
 use types::{BeaconState, EthSpec};
 
 #[derive(Debug, Clone, Deserialize)]
@@ -32,6 +32,17 @@ impl<E: EthSpec> Case for SanitySlots<E> {
         // Processing requires the epoch cache.
         state.build_all_caches(spec).unwrap();
 
+        // The skip-slot optimized path must produce an identical state.
+        let mut fast_forward_result = {
+            let mut state = state.clone();
+            let target_slot = state.slot + self.slots as u64;
+            fast_forward_state(&mut state, target_slot, None, spec).map(|_| state)
+        };
+        compare_beacon_state_results_without_caches(
+            &mut fast_forward_result,
+            &mut expected.clone(),
+        )?;
+
         let mut result = (0..self.slots)
             .try_for_each(|_| per_slot_processing(&mut state, spec))
             .map(|_| state);
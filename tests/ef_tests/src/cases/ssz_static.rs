@@ -101,6 +101,7 @@ where
         + Encode
         + PartialEq<T>
         + serde::de::DeserializeOwned
+        + serde::Serialize
         + TreeHash
         + TestRandom,
 {
@@ -123,5 +124,11 @@ where
     let tree_hash_root = Hash256::from_slice(&decoded.tree_hash_root());
     compare_result::<Hash256, Error>(&Ok(tree_hash_root), &Some(expected_root))?;
 
+    // Verify the value round-trips through the standard JSON encoding.
+    let json = serde_json::to_string(&tc.value)
+        .map_err(|e| Error::FailedToParseTest(format!("{:?}", e)))?;
+    let json_decode_result = serde_json::from_str::<T>(&json);
+    compare_result(&json_decode_result, &Some(tc.value.clone()))?;
+
     Ok(())
 }
@@ -120,6 +120,12 @@ impl Doc {
             ("epoch_processing", "justification_and_finalization", "mainnet") => {
                 run_test::<EpochProcessingJustificationAndFinalization<MainnetEthSpec>>(self)
             }
+            ("epoch_processing", "rewards_and_penalties", "minimal") => {
+                run_test::<EpochProcessingRewardsAndPenalties<MinimalEthSpec>>(self)
+            }
+            ("epoch_processing", "rewards_and_penalties", "mainnet") => {
+                run_test::<EpochProcessingRewardsAndPenalties<MainnetEthSpec>>(self)
+            }
             ("epoch_processing", "slashings", "minimal") => {
                 run_test::<EpochProcessingSlashings<MinimalEthSpec>>(self)
             }
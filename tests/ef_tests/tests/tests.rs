@@ -188,6 +188,15 @@ fn epoch_processing_registry_updates() {
         });
 }
 
+#[test]
+fn epoch_processing_rewards_and_penalties() {
+    yaml_files_in_test_dir(&Path::new("epoch_processing").join("rewards_and_penalties"))
+        .into_par_iter()
+        .for_each(|file| {
+            Doc::assert_tests_pass(file);
+        });
+}
+
 #[test]
 fn epoch_processing_slashings() {
     yaml_files_in_test_dir(&Path::new("epoch_processing").join("slashings"))
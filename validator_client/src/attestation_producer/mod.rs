@@ -30,7 +30,7 @@ impl From<BeaconNodeError> for Error {
 /// This struct contains the logic for requesting and signing beacon attestations for a validator. The
 /// validator can abstractly sign via the Signer trait object.
 pub struct AttestationProducer<'a, B: BeaconNodeAttestation, S: Signer, E: EthSpec> {
-    /// The current fork.
+    /// The fork at the epoch of the attestation.
     pub fork: Fork,
     /// The attestation duty to perform.
     pub duty: AttestationDuty,
@@ -39,7 +39,7 @@ pub enum ValidatorEvent {
 /// This struct contains the logic for requesting and signing beacon blocks for a validator. The
 /// validator can abstractly sign via the Signer trait object.
 pub struct BlockProducer<'a, B: BeaconNodeBlock, S: Signer, E: EthSpec> {
-    /// The current fork.
+    /// The fork at the epoch of the block.
     pub fork: Fork,
     /// The current slot to produce a block for.
     pub slot: Slot,
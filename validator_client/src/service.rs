@@ -43,8 +43,8 @@ const TIME_DELAY_FROM_SLOT: Duration = Duration::from_millis(100);
 /// duties.
 //TODO: Generalize the BeaconNode types to use testing
 pub struct Service<B: BeaconNodeDuties + 'static, S: Signer + 'static, E: EthSpec> {
-    /// The node's current fork version we are processing on.
-    fork: Fork,
+    /// The epoch at genesis, from which the fork at any epoch is derived.
+    genesis_epoch: Epoch,
     /// The slot clock for this service.
     slot_clock: SystemTimeSlotClock,
     /// The current slot we are processing.
@@ -130,7 +130,7 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static, E: EthSpec> Service<B,
         let mut current_version: [u8; 4] = [0; 4];
         previous_version.copy_from_slice(&proto_fork.get_previous_version()[..4]);
         current_version.copy_from_slice(&proto_fork.get_current_version()[..4]);
-        let fork = Fork {
+        let node_fork = Fork {
             previous_version,
             current_version,
             epoch: Epoch::from(proto_fork.get_epoch()),
@@ -183,6 +183,20 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static, E: EthSpec> Service<B,
         };
 
         let slots_per_epoch = E::slots_per_epoch();
+        let genesis_epoch = genesis_slot.epoch(slots_per_epoch);
+
+        // Duties are signed with the fork at their epoch, as given by the fork schedule.
+        let expected_fork = eth2_config
+            .spec
+            .fork_at_epoch(current_slot.epoch(slots_per_epoch), genesis_epoch);
+        if node_fork != expected_fork {
+            warn!(
+                log,
+                "Beacon node fork does not match the fork schedule";
+                "node_fork" => format!("{:?}", node_fork),
+                "expected_fork" => format!("{:?}", expected_fork),
+            );
+        }
 
         // TODO: keypairs are randomly generated; they should be loaded from a file or generated.
         // https://github.com/sigp/lighthouse/issues/160
@@ -205,7 +219,7 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static, E: EthSpec> Service<B,
         let spec = Arc::new(eth2_config.spec);
 
         Ok(Service {
-            fork,
+            genesis_epoch,
             slot_clock,
             current_slot,
             slots_per_epoch,
@@ -334,6 +348,12 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static, E: EthSpec> Service<B,
         //});
     }
 
+    /// Returns the fork at the epoch of `slot`, according to the fork schedule.
+    fn fork_at_slot(&self, slot: Slot) -> Fork {
+        self.spec
+            .fork_at_epoch(slot.epoch(self.slots_per_epoch), self.genesis_epoch)
+    }
+
     /// If there are any duties to process, spawn a separate thread and perform required actions.
     fn process_duties(&mut self) {
         if let Some(work) = self.duties_manager.get_current_work(self.current_slot) {
@@ -342,8 +362,8 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static, E: EthSpec> Service<B,
                     // we need to produce a block
                     // spawns a thread to produce a beacon block
                     let signers = self.duties_manager.signers.clone(); // this is an arc
-                    let fork = self.fork.clone();
                     let slot = self.current_slot;
+                    let fork = self.fork_at_slot(slot);
                     let spec = self.spec.clone();
                     let beacon_node = self.beacon_block_client.clone();
                     let log = self.log.clone();
@@ -367,7 +387,8 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static, E: EthSpec> Service<B,
                     // we need to produce an attestation
                     // spawns a thread to produce and sign an attestation
                     let signers = self.duties_manager.signers.clone(); // this is an arc
-                    let fork = self.fork.clone();
+                    let duty = work_type.attestation_duty.expect("Should never be none");
+                    let fork = self.fork_at_slot(duty.slot);
                     let spec = self.spec.clone();
                     let beacon_node = self.attestation_client.clone();
                     let log = self.log.clone();
@@ -377,7 +398,7 @@ impl<B: BeaconNodeDuties + 'static, S: Signer + 'static, E: EthSpec> Service<B,
                         let signer = &signers[signer_index];
                         let mut attestation_producer = AttestationProducer {
                             fork,
-                            duty: work_type.attestation_duty.expect("Should never be none"),
+                            duty,
                             spec,
                             beacon_node,
                             signer,